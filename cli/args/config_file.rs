@@ -25,6 +25,9 @@ use std::fmt;
 use std::path::Path;
 use std::path::PathBuf;
 
+/// Filenames that Deno will recognize when discovering config.
+const CONFIG_FILE_NAMES: [&str; 2] = ["deno.json", "deno.jsonc"];
+
 pub type MaybeImportsResult =
   Result<Option<Vec<(ModuleSpecifier, Vec<String>)>>, AnyError>;
 
@@ -344,6 +347,7 @@ struct SerializedLintConfig {
   pub rules: LintRulesConfig,
   pub files: SerializedFilesConfig,
   pub report: Option<String>,
+  pub plugins: Vec<String>,
 }
 
 impl SerializedLintConfig {
@@ -351,10 +355,20 @@ impl SerializedLintConfig {
     self,
     config_file_specifier: &ModuleSpecifier,
   ) -> Result<LintConfig, AnyError> {
+    let config_dir = specifier_parent(config_file_specifier);
     Ok(LintConfig {
       rules: self.rules,
       files: self.files.into_resolved(config_file_specifier)?,
       report: self.report,
+      plugins: self
+        .plugins
+        .iter()
+        .map(|p| {
+          config_dir
+            .join(p)
+            .with_context(|| format!("Invalid lint plugin specifier \"{p}\""))
+        })
+        .collect::<Result<Vec<_>, _>>()?,
     })
   }
 }
@@ -364,6 +378,8 @@ pub struct LintConfig {
   pub rules: LintRulesConfig,
   pub files: FilesConfig,
   pub report: Option<String>,
+  /// Modules exporting lint rules that run in addition to the built-in ones.
+  pub plugins: Vec<ModuleSpecifier>,
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -454,6 +470,46 @@ pub struct BenchConfig {
   pub files: FilesConfig,
 }
 
+/// A task of the configuration file. A task is either a command or an object
+/// which may also declare the tasks it depends on, its working directory, its
+/// environment variables, a description and the inputs and outputs used to
+/// cache it.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct TaskConfig {
+  pub command: Option<String>,
+  pub dependencies: Vec<String>,
+  /// The working directory, relative to the configuration file.
+  pub cwd: Option<String>,
+  pub env: BTreeMap<String, String>,
+  pub description: Option<String>,
+  /// Globs of the files the task reads, relative to the configuration file.
+  /// The task is skipped when they haven't changed since it last succeeded.
+  pub inputs: Vec<String>,
+  /// The files and directories the task writes, relative to the
+  /// configuration file, which are restored when the task is skipped.
+  pub outputs: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+enum SerializedTaskConfig {
+  Command(String),
+  Config(TaskConfig),
+}
+
+impl From<SerializedTaskConfig> for TaskConfig {
+  fn from(config: SerializedTaskConfig) -> Self {
+    match config {
+      SerializedTaskConfig::Command(command) => TaskConfig {
+        command: Some(command),
+        ..Default::default()
+      },
+      SerializedTaskConfig::Config(config) => config,
+    }
+  }
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(untagged)]
 pub enum LockConfig {
@@ -474,12 +530,123 @@ pub struct ConfigFileJson {
   pub test: Option<Value>,
   pub bench: Option<Value>,
   pub lock: Option<Value>,
+  pub env_file: Option<String>,
+  pub workspaces: Option<Vec<String>>,
+}
+
+impl ConfigFileJson {
+  /// Merges the configuration of the workspace root into the configuration of
+  /// one of its members. Values of the member take precedence.
+  ///
+  /// Import map addresses are made absolute so that they still point to the
+  /// same location when used from the root's import map. The `files` of tool
+  /// configurations are relative to the root and so are not inherited.
+  fn inherit_from(
+    &mut self,
+    specifier: &ModuleSpecifier,
+    root: &ConfigFileJson,
+    root_specifier: &ModuleSpecifier,
+  ) {
+    fn merge_objects(root: &Option<Value>, member: &mut Option<Value>) {
+      if let Some(root) = root {
+        let mut merged = root.clone();
+        if let Some(member) = member {
+          json_merge(&mut merged, member);
+        }
+        *member = Some(merged);
+      }
+    }
+
+    fn inherit_tool_config(root: &Option<Value>, member: &mut Option<Value>) {
+      if member.is_none() {
+        *member = root.clone().map(|mut config| {
+          if let Some(config) = config.as_object_mut() {
+            config.remove("files");
+          }
+          config
+        });
+      }
+    }
+
+    let root_imports = root
+      .imports
+      .as_ref()
+      .map(|imports| resolve_import_map_addresses(imports, root_specifier));
+    self.imports = self
+      .imports
+      .as_ref()
+      .map(|imports| resolve_import_map_addresses(imports, specifier));
+    merge_objects(&root_imports, &mut self.imports);
+    self.scopes = self.scopes.as_ref().map(|scopes| {
+      let mut resolved = serde_json::Map::new();
+      if let Some(scopes) = scopes.as_object() {
+        for (scope, imports) in scopes {
+          let scope = specifier
+            .join(scope)
+            .map(|url| url.to_string())
+            .unwrap_or_else(|_| scope.clone());
+          resolved
+            .insert(scope, resolve_import_map_addresses(imports, specifier));
+        }
+      }
+      resolved.into()
+    });
+
+    if self.env_file.is_none() {
+      self.env_file = root.env_file.as_ref().map(|env_file| {
+        root_specifier
+          .join(env_file)
+          .map(|url| url.to_string())
+          .unwrap_or_else(|_| env_file.clone())
+      });
+    }
+    merge_objects(&root.compiler_options, &mut self.compiler_options);
+    merge_objects(&root.tasks, &mut self.tasks);
+    inherit_tool_config(&root.lint, &mut self.lint);
+    inherit_tool_config(&root.fmt, &mut self.fmt);
+    inherit_tool_config(&root.test, &mut self.test);
+    inherit_tool_config(&root.bench, &mut self.bench);
+  }
+}
+
+/// Resolves the relative addresses (`./`, `../` and `/`) of an import map
+/// `imports` object against the specifier of the config file declaring them.
+fn resolve_import_map_addresses(
+  imports: &Value,
+  base: &ModuleSpecifier,
+) -> Value {
+  match imports.as_object() {
+    Some(imports) => imports
+      .iter()
+      .map(|(key, address)| {
+        let address = match address.as_str() {
+          Some(text)
+            if text.starts_with("./")
+              || text.starts_with("../")
+              || text.starts_with('/') =>
+          {
+            base
+              .join(text)
+              .map(|url| Value::String(url.to_string()))
+              .unwrap_or_else(|_| address.clone())
+          }
+          _ => address.clone(),
+        };
+        (key.clone(), address)
+      })
+      .collect::<serde_json::Map<_, _>>()
+      .into(),
+    None => imports.clone(),
+  }
 }
 
 #[derive(Clone, Debug)]
 pub struct ConfigFile {
   pub specifier: ModuleSpecifier,
   pub json: ConfigFileJson,
+  /// The configuration of each member when this file declares a workspace,
+  /// already merged with this configuration.
+  pub workspace_members: Vec<ConfigFile>,
 }
 
 impl ConfigFile {
@@ -521,9 +688,6 @@ impl ConfigFile {
     start: &Path,
     checked: &mut HashSet<PathBuf>,
   ) -> Result<Option<ConfigFile>, AnyError> {
-    /// Filenames that Deno will recognize when discovering config.
-    const CONFIG_FILE_NAMES: [&str; 2] = ["deno.json", "deno.jsonc"];
-
     for ancestor in start.ancestors() {
       if checked.insert(ancestor.to_path_buf()) {
         for config_filename in CONFIG_FILE_NAMES {
@@ -531,7 +695,7 @@ impl ConfigFile {
           match ConfigFile::read(&f) {
             Ok(cf) => {
               log::debug!("Config file found at '{}'", f.display());
-              return Ok(Some(cf));
+              return Ok(Some(Self::resolve_workspace_member(cf, ancestor)?));
             }
             Err(e) => {
               if let Some(ioerr) = e.downcast_ref::<std::io::Error>() {
@@ -556,6 +720,50 @@ impl ConfigFile {
     Ok(None)
   }
 
+  /// When the config file found in `dir` is listed as a member by the config
+  /// file of a parent directory, returns the member merged with that
+  /// workspace root instead.
+  fn resolve_workspace_member(
+    config_file: ConfigFile,
+    dir: &Path,
+  ) -> Result<ConfigFile, AnyError> {
+    if config_file.json.workspaces.is_some() {
+      return Ok(config_file);
+    }
+    for ancestor in dir.ancestors().skip(1) {
+      for config_filename in CONFIG_FILE_NAMES {
+        let path = ancestor.join(config_filename);
+        if !path.is_file() {
+          continue;
+        }
+        // only the closest config file is considered and one that can't be
+        // read is not a workspace root we need to report errors for
+        let mut root = match ModuleSpecifier::from_file_path(&path)
+          .map_err(|_| anyhow!("Invalid config file path"))
+          .and_then(|specifier| {
+            Self::from_specifier_without_members(&specifier)
+          }) {
+          Ok(root) if root.json.workspaces.is_some() => root,
+          _ => return Ok(config_file),
+        };
+        root.load_workspace_members()?;
+        let config_dir = specifier_parent(&config_file.specifier);
+        log::debug!(
+          "Config file is a member of the workspace at '{}'",
+          path.display()
+        );
+        return Ok(
+          root
+            .workspace_members
+            .into_iter()
+            .find(|member| specifier_parent(&member.specifier) == config_dir)
+            .unwrap_or(config_file),
+        );
+      }
+    }
+    Ok(config_file)
+  }
+
   pub fn read(path_ref: impl AsRef<Path>) -> Result<Self, AnyError> {
     let path = Path::new(path_ref.as_ref());
     let config_file = if path.is_absolute() {
@@ -598,6 +806,14 @@ impl ConfigFile {
   }
 
   pub fn from_specifier(specifier: &ModuleSpecifier) -> Result<Self, AnyError> {
+    let mut config_file = Self::from_specifier_without_members(specifier)?;
+    config_file.load_workspace_members()?;
+    Ok(config_file)
+  }
+
+  fn from_specifier_without_members(
+    specifier: &ModuleSpecifier,
+  ) -> Result<Self, AnyError> {
     let config_path = specifier_to_file_path(specifier)?;
     let config_text = match std::fs::read_to_string(config_path) {
       Ok(text) => text,
@@ -637,9 +853,58 @@ impl ConfigFile {
     Ok(Self {
       specifier: specifier.to_owned(),
       json,
+      workspace_members: Vec::new(),
     })
   }
 
+  /// Reads the config file of every directory listed in `workspaces` and
+  /// merges it with this configuration.
+  fn load_workspace_members(&mut self) -> Result<(), AnyError> {
+    let members = match &self.json.workspaces {
+      Some(members) => members.clone(),
+      None => return Ok(()),
+    };
+    let config_dir = specifier_parent(&self.specifier);
+    let mut workspace_members = Vec::with_capacity(members.len());
+    for member in members {
+      let member_dir = config_dir
+        .join(&format!("{}/", member.trim_end_matches('/')))
+        .with_context(|| format!("Invalid workspace member \"{member}\""))?;
+      let maybe_member_specifier = CONFIG_FILE_NAMES
+        .iter()
+        .filter_map(|name| member_dir.join(name).ok())
+        .find(|specifier| {
+          specifier_to_file_path(specifier)
+            .map(|path| path.is_file())
+            .unwrap_or(false)
+        });
+      let member_specifier = match maybe_member_specifier {
+        Some(specifier) => specifier,
+        None => bail!(
+          "Could not find a deno.json or deno.jsonc file in workspace member \"{}\" of {}",
+          member,
+          self.specifier
+        ),
+      };
+      let mut member_config =
+        Self::from_specifier_without_members(&member_specifier)?;
+      if member_config.json.workspaces.is_some() {
+        bail!(
+          "Nested workspaces are not supported. Workspace member {} declares \"workspaces\".",
+          member_specifier
+        );
+      }
+      member_config.json.inherit_from(
+        &member_specifier,
+        &self.json,
+        &self.specifier,
+      );
+      workspace_members.push(member_config);
+    }
+    self.workspace_members = workspace_members;
+    Ok(())
+  }
+
   /// Returns true if the configuration indicates that JavaScript should be
   /// type checked, otherwise false.
   pub fn get_check_js(&self) -> bool {
@@ -675,14 +940,35 @@ impl ConfigFile {
     if let Some(imports) = &self.json.imports {
       value.insert("imports".to_string(), imports.clone());
     }
-    if let Some(scopes) = &self.json.scopes {
-      value.insert("scopes".to_string(), scopes.clone());
+    let mut scopes = match &self.json.scopes {
+      Some(Value::Object(scopes)) => scopes.clone(),
+      _ => serde_json::Map::new(),
+    };
+    // the imports of each member apply to the modules in its directory
+    for member in &self.workspace_members {
+      if let Some(Value::Object(member_scopes)) = &member.json.scopes {
+        scopes.extend(member_scopes.clone());
+      }
+      if let Some(imports) = &member.json.imports {
+        scopes.insert(
+          specifier_parent(&member.specifier).to_string(),
+          imports.clone(),
+        );
+      }
+    }
+    if self.json.scopes.is_some() || !scopes.is_empty() {
+      value.insert("scopes".to_string(), scopes.into());
     }
     value.into()
   }
 
   pub fn is_an_import_map(&self) -> bool {
-    self.json.imports.is_some() || self.json.scopes.is_some()
+    self.json.imports.is_some()
+      || self.json.scopes.is_some()
+      || self
+        .workspace_members
+        .iter()
+        .any(|member| member.is_an_import_map())
   }
 
   pub fn to_fmt_config(&self) -> Result<Option<FmtConfig>, AnyError> {
@@ -726,18 +1012,17 @@ impl ConfigFile {
   }
 
   /// Return any tasks that are defined in the configuration file as a sequence
-  /// of JSON objects providing the name of the task and the arguments of the
-  /// task in a detail field.
+  /// of JSON objects providing the name of the task and its description, or
+  /// the command of the task when it has none, in a detail field.
   pub fn to_lsp_tasks(&self) -> Option<Value> {
-    let value = self.json.tasks.clone()?;
-    let tasks: BTreeMap<String, String> = serde_json::from_value(value).ok()?;
+    let tasks = self.to_tasks_config().ok()??;
     Some(
       tasks
         .into_iter()
-        .map(|(key, value)| {
+        .map(|(key, task)| {
           json!({
             "name": key,
-            "detail": value,
+            "detail": task.description.or(task.command).unwrap_or_default(),
           })
         })
         .collect(),
@@ -746,12 +1031,17 @@ impl ConfigFile {
 
   pub fn to_tasks_config(
     &self,
-  ) -> Result<Option<BTreeMap<String, String>>, AnyError> {
+  ) -> Result<Option<BTreeMap<String, TaskConfig>>, AnyError> {
     if let Some(config) = self.json.tasks.clone() {
-      let tasks_config: BTreeMap<String, String> =
+      let tasks_config: BTreeMap<String, SerializedTaskConfig> =
         serde_json::from_value(config)
           .context("Failed to parse \"tasks\" configuration")?;
-      Ok(Some(tasks_config))
+      Ok(Some(
+        tasks_config
+          .into_iter()
+          .map(|(name, task)| (name, task.into()))
+          .collect(),
+      ))
     } else {
       Ok(None)
     }
@@ -801,7 +1091,7 @@ impl ConfigFile {
 
   pub fn resolve_tasks_config(
     &self,
-  ) -> Result<BTreeMap<String, String>, AnyError> {
+  ) -> Result<BTreeMap<String, TaskConfig>, AnyError> {
     let maybe_tasks_config = self.to_tasks_config()?;
     if let Some(tasks_config) = maybe_tasks_config {
       for key in tasks_config.keys() {
@@ -816,12 +1106,46 @@ impl ConfigFile {
           bail!("Configuration file task names must start with an alphabetic character. Task: {}", key);
         }
       }
+      for (key, task) in &tasks_config {
+        if task.command.is_none() && task.dependencies.is_empty() {
+          bail!("Configuration file task \"{}\" must have a command or dependencies", key);
+        }
+        if task.inputs.is_empty() && !task.outputs.is_empty() {
+          bail!(
+            "Configuration file task \"{}\" has outputs but no inputs",
+            key
+          );
+        }
+        for dependency in &task.dependencies {
+          if !tasks_config.contains_key(dependency) {
+            bail!(
+              "Task \"{}\" depends on unknown task \"{}\"",
+              key,
+              dependency
+            );
+          }
+        }
+      }
       Ok(tasks_config)
     } else {
       bail!("No tasks found in configuration file")
     }
   }
 
+  /// Returns the path of the `.env` file of the `envFile` option, which is
+  /// relative to the configuration file.
+  pub fn to_env_file_path(&self) -> Result<Option<PathBuf>, AnyError> {
+    match &self.json.env_file {
+      Some(env_file) => {
+        let specifier = self.specifier.join(env_file).with_context(|| {
+          format!("Invalid \"envFile\" configuration: {env_file}")
+        })?;
+        Ok(Some(specifier_to_file_path(&specifier)?))
+      }
+      None => Ok(None),
+    }
+  }
+
   pub fn to_lock_config(&self) -> Result<Option<LockConfig>, AnyError> {
     if let Some(config) = self.json.lock.clone() {
       let lock_config: LockConfig = serde_json::from_value(config)
@@ -1049,7 +1373,8 @@ mod tests {
         "rules": {
           "tags": ["recommended"],
           "include": ["ban-untagged-todo"]
-        }
+        },
+        "plugins": ["./lint/plugin.ts"]
       },
       "fmt": {
         "files": {
@@ -1104,6 +1429,10 @@ mod tests {
       Some(vec!["recommended".to_string()])
     );
     assert!(lint_config.rules.exclude.is_none());
+    assert_eq!(
+      lint_config.plugins,
+      vec![ModuleSpecifier::parse("file:///deno/lint/plugin.ts").unwrap()]
+    );
 
     let fmt_config = config_file
       .to_fmt_config()
@@ -1121,12 +1450,12 @@ mod tests {
 
     let tasks_config = config_file.to_tasks_config().unwrap().unwrap();
     assert_eq!(
-      tasks_config["build"],
-      "deno run --allow-read --allow-write build.ts",
+      tasks_config["build"].command.as_deref(),
+      Some("deno run --allow-read --allow-write build.ts"),
     );
     assert_eq!(
-      tasks_config["server"],
-      "deno run --allow-net --allow-read server.ts"
+      tasks_config["server"].command.as_deref(),
+      Some("deno run --allow-net --allow-read server.ts")
     );
   }
 
@@ -1222,6 +1551,97 @@ mod tests {
       .is_none());
   }
 
+  #[test]
+  fn read_workspace() {
+    let testdata = test_util::testdata_path();
+    let config_file = ConfigFile::read(testdata.join("workspaces/deno.json"))
+      .expect("Failed to load config file");
+    assert_eq!(config_file.workspace_members.len(), 2);
+    let root_dir = specifier_parent(&config_file.specifier);
+
+    let member_a = &config_file.workspace_members[0];
+    assert_eq!(member_a.specifier, root_dir.join("a/deno.json").unwrap());
+    let tasks = member_a.to_tasks_config().unwrap().unwrap();
+    assert_eq!(tasks["hello"].command.as_deref(), Some("echo hello from a"));
+    assert_eq!(
+      tasks["root"].command.as_deref(),
+      Some("echo only in the root")
+    );
+    assert_eq!(
+      member_a.json.imports,
+      Some(json!({
+        "shared/": root_dir.join("shared/").unwrap().to_string(),
+        "greeting": root_dir.join("a/greeting.ts").unwrap().to_string(),
+      }))
+    );
+
+    // the lint config is inherited without the root's files
+    let member_b = &config_file.workspace_members[1];
+    assert_eq!(member_b.specifier, root_dir.join("b/deno.jsonc").unwrap());
+    let lint_config = member_b.to_lint_config().unwrap().unwrap();
+    assert_eq!(
+      lint_config.rules.exclude,
+      Some(vec!["no-unused-vars".into()])
+    );
+    assert!(lint_config.files.exclude.is_empty());
+
+    let import_map = config_file.to_import_map_value();
+    assert_eq!(
+      import_map["scopes"][root_dir.join("a/").unwrap().as_str()]["greeting"],
+      json!(root_dir.join("a/greeting.ts").unwrap().to_string())
+    );
+  }
+
+  #[test]
+  fn discover_from_workspace_member() {
+    let testdata = test_util::testdata_path();
+    let start = testdata.join("workspaces/a/mod.ts");
+    let mut checked = HashSet::new();
+    let config_file = ConfigFile::discover_from(&start, &mut checked)
+      .unwrap()
+      .unwrap();
+    assert!(config_file
+      .specifier
+      .as_str()
+      .ends_with("workspaces/a/deno.json"));
+    // merged with the workspace root
+    let tasks = config_file.to_tasks_config().unwrap().unwrap();
+    assert_eq!(
+      tasks["root"].command.as_deref(),
+      Some("echo only in the root")
+    );
+  }
+
+  #[test]
+  fn workspace_member_inherit_from_root() {
+    let config_dir = ModuleSpecifier::parse("file:///deno/").unwrap();
+    let config_specifier = config_dir.join("deno.json").unwrap();
+    let mut root =
+      ConfigFile::new(r#"{ "imports": { "a": "./a.ts" } }"#, &config_specifier)
+        .unwrap();
+    let member_specifier = config_dir.join("member/deno.json").unwrap();
+    let mut member = ConfigFile::new(
+      r#"{ "imports": { "b": "../b.ts", "c": "https://deno.land/c.ts" }, "fmt": { "files": { "include": ["src/"] } } }"#,
+      &member_specifier,
+    )
+    .unwrap();
+    root.json.fmt = Some(json!({ "options": { "useTabs": true } }));
+    member
+      .json
+      .inherit_from(&member_specifier, &root.json, &root.specifier);
+    assert_eq!(
+      member.json.imports,
+      Some(json!({
+        "a": "file:///deno/a.ts",
+        "b": "file:///deno/b.ts",
+        "c": "https://deno.land/c.ts",
+      }))
+    );
+    // the member's own tool configuration is not merged with the root's
+    let fmt_config = member.to_fmt_config().unwrap().unwrap();
+    assert_eq!(fmt_config.options.use_tabs, None);
+  }
+
   #[test]
   fn discover_from_malformed() {
     let testdata = test_util::testdata_path();
@@ -1231,6 +1651,20 @@ mod tests {
     assert!(err.to_string().contains("Unable to parse config file"));
   }
 
+  #[test]
+  fn env_file_path() {
+    let config_text = r#"{ "envFile": "./config/.env" }"#;
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    assert_eq!(
+      config_file.to_env_file_path().unwrap(),
+      Some(PathBuf::from("/deno/config/.env"))
+    );
+    let config_file = ConfigFile::new("{}", &config_specifier).unwrap();
+    assert_eq!(config_file.to_env_file_path().unwrap(), None);
+  }
+
   #[test]
   fn tasks_no_tasks() {
     run_task_error_test(r#"{}"#, "No tasks found in configuration file");
@@ -1281,6 +1715,93 @@ mod tests {
     );
   }
 
+  #[test]
+  fn task_objects() {
+    let config_text = r#"{
+      "tasks": {
+        "build": {
+          "command": "deno run build.ts",
+          "dependencies": ["clean"],
+          "cwd": "scripts",
+          "env": { "MODE": "release" },
+          "description": "Builds the project",
+          "inputs": ["src/**/*.ts"],
+          "outputs": ["dist"]
+        },
+        "clean": "rm -rf dist",
+        "all": { "dependencies": ["build"] }
+      }
+    }"#;
+    let config_dir = ModuleSpecifier::parse("file:///deno/").unwrap();
+    let config_specifier = config_dir.join("deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    let tasks = config_file.resolve_tasks_config().unwrap();
+    assert_eq!(
+      tasks["build"],
+      TaskConfig {
+        command: Some("deno run build.ts".to_string()),
+        dependencies: vec!["clean".to_string()],
+        cwd: Some("scripts".to_string()),
+        env: BTreeMap::from([("MODE".to_string(), "release".to_string())]),
+        description: Some("Builds the project".to_string()),
+        inputs: vec!["src/**/*.ts".to_string()],
+        outputs: vec!["dist".to_string()],
+      }
+    );
+    assert_eq!(
+      tasks["clean"],
+      TaskConfig {
+        command: Some("rm -rf dist".to_string()),
+        ..Default::default()
+      }
+    );
+    assert_eq!(tasks["all"].command, None);
+    assert_eq!(
+      config_file.to_lsp_tasks().unwrap(),
+      json!([
+        { "name": "all", "detail": "" },
+        { "name": "build", "detail": "Builds the project" },
+        { "name": "clean", "detail": "rm -rf dist" },
+      ])
+    );
+  }
+
+  #[test]
+  fn task_without_command() {
+    run_task_error_test(
+      r#"{
+        "tasks": {
+          "build": { "description": "Builds the project" }
+        }
+      }"#,
+      "Configuration file task \"build\" must have a command or dependencies",
+    );
+  }
+
+  #[test]
+  fn task_outputs_without_inputs() {
+    run_task_error_test(
+      r#"{
+        "tasks": {
+          "build": { "command": "deno task bundle", "outputs": ["dist"] }
+        }
+      }"#,
+      "Configuration file task \"build\" has outputs but no inputs",
+    );
+  }
+
+  #[test]
+  fn task_unknown_dependency() {
+    run_task_error_test(
+      r#"{
+        "tasks": {
+          "build": { "command": "deno test", "dependencies": ["clean"] }
+        }
+      }"#,
+      "Task \"build\" depends on unknown task \"clean\"",
+    );
+  }
+
   fn run_task_error_test(config_text: &str, expected_error: &str) {
     let config_dir = ModuleSpecifier::parse("file:///deno/").unwrap();
     let config_specifier = config_dir.join("tsconfig.json").unwrap();
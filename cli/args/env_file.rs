@@ -0,0 +1,342 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use std::iter::Peekable;
+use std::path::Path;
+use std::str::Chars;
+
+/// Reads and parses a `.env` file. References to variables are expanded with
+/// the environment variables of the process.
+pub fn load_env_file(path: &Path) -> Result<Vec<(String, String)>, AnyError> {
+  let text = std::fs::read_to_string(path).with_context(|| {
+    format!("Failed to read env file \"{}\"", path.display())
+  })?;
+  parse_env_file(&text, |name| std::env::var(name).ok())
+    .with_context(|| format!("Failed to parse env file \"{}\"", path.display()))
+}
+
+/// Sets the environment variables which aren't already set in the
+/// environment of the process, which takes precedence over `.env` files.
+/// Reading them from JavaScript still requires `--allow-env`.
+pub fn set_env_vars(env_vars: &[(String, String)]) {
+  for (name, value) in env_vars {
+    if std::env::var_os(name).is_none() {
+      std::env::set_var(name, value);
+    }
+  }
+}
+
+/// Parses the `NAME=value` lines of a `.env` file, which may start with
+/// `export`. Single quoted values are taken literally, double quoted values
+/// support escapes like `\n`, and both may span multiple lines. `#` starts a
+/// comment outside of quotes. `$NAME` and `${NAME}` in values which aren't
+/// single quoted expand to the value of the variable from `get_var` or from
+/// an earlier line, or to nothing.
+pub fn parse_env_file(
+  text: &str,
+  get_var: impl Fn(&str) -> Option<String>,
+) -> Result<Vec<(String, String)>, AnyError> {
+  let mut parser = EnvFileParser {
+    chars: text.chars().peekable(),
+    line: 1,
+    env_vars: Vec::new(),
+    get_var,
+  };
+  parser.parse()?;
+  Ok(parser.env_vars)
+}
+
+struct EnvFileParser<'a, TGetVar: Fn(&str) -> Option<String>> {
+  chars: Peekable<Chars<'a>>,
+  line: usize,
+  env_vars: Vec<(String, String)>,
+  get_var: TGetVar,
+}
+
+impl<'a, TGetVar: Fn(&str) -> Option<String>> EnvFileParser<'a, TGetVar> {
+  fn parse(&mut self) -> Result<(), AnyError> {
+    loop {
+      while matches!(self.chars.peek(), Some(c) if c.is_whitespace()) {
+        self.next();
+      }
+      match self.chars.peek() {
+        None => return Ok(()),
+        Some('#') => {
+          self.skip_line();
+          continue;
+        }
+        Some(_) => {}
+      }
+
+      let line = self.line;
+      let mut name = self.read_name();
+      if name == "export" && matches!(self.chars.peek(), Some(' ' | '\t')) {
+        self.skip_spaces();
+        name = self.read_name();
+      }
+      if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        bail!("Invalid variable name at line {}", line);
+      }
+      self.skip_spaces();
+      if self.chars.peek() != Some(&'=') {
+        bail!("Expected \"=\" after \"{}\" at line {}", name, line);
+      }
+      self.next();
+      self.skip_spaces();
+
+      let value = match self.chars.peek() {
+        Some('\'') => {
+          self.next();
+          self.read_single_quoted(&name, line)?
+        }
+        Some('"') => {
+          self.next();
+          self.read_double_quoted(&name, line)?
+        }
+        _ => self.read_unquoted()?,
+      };
+      self.skip_spaces();
+      match self.chars.peek() {
+        None | Some('\n' | '\r') => {}
+        Some('#') => self.skip_line(),
+        Some(_) => bail!(
+          "Unexpected characters after the value of \"{}\" at line {}",
+          name,
+          self.line
+        ),
+      }
+
+      // a later definition of a variable replaces an earlier one
+      self.env_vars.retain(|(n, _)| *n != name);
+      self.env_vars.push((name, value));
+    }
+  }
+
+  fn next(&mut self) -> Option<char> {
+    let c = self.chars.next();
+    if c == Some('\n') {
+      self.line += 1;
+    }
+    c
+  }
+
+  fn skip_spaces(&mut self) {
+    while matches!(self.chars.peek(), Some(' ' | '\t')) {
+      self.next();
+    }
+  }
+
+  fn skip_line(&mut self) {
+    while let Some(c) = self.next() {
+      if c == '\n' {
+        break;
+      }
+    }
+  }
+
+  fn read_name(&mut self) -> String {
+    let mut name = String::new();
+    while let Some(c) = self.chars.peek().copied() {
+      if c.is_ascii_alphanumeric() || c == '_' {
+        name.push(c);
+        self.next();
+      } else {
+        break;
+      }
+    }
+    name
+  }
+
+  fn read_single_quoted(
+    &mut self,
+    name: &str,
+    line: usize,
+  ) -> Result<String, AnyError> {
+    let mut value = String::new();
+    loop {
+      match self.next() {
+        Some('\'') => return Ok(value),
+        Some(c) => value.push(c),
+        None => {
+          bail!("Unterminated quoted value of \"{}\" at line {}", name, line)
+        }
+      }
+    }
+  }
+
+  fn read_double_quoted(
+    &mut self,
+    name: &str,
+    line: usize,
+  ) -> Result<String, AnyError> {
+    let mut value = String::new();
+    loop {
+      match self.next() {
+        Some('"') => return Ok(value),
+        Some('\\') => match self.next() {
+          Some('n') => value.push('\n'),
+          Some('r') => value.push('\r'),
+          Some('t') => value.push('\t'),
+          Some(c @ ('"' | '\\' | '$')) => value.push(c),
+          Some(c) => {
+            value.push('\\');
+            value.push(c);
+          }
+          None => {
+            bail!("Unterminated quoted value of \"{}\" at line {}", name, line)
+          }
+        },
+        Some('$') => value.push_str(&self.read_reference()?),
+        Some(c) => value.push(c),
+        None => {
+          bail!("Unterminated quoted value of \"{}\" at line {}", name, line)
+        }
+      }
+    }
+  }
+
+  fn read_unquoted(&mut self) -> Result<String, AnyError> {
+    let mut value = String::new();
+    while let Some(c) = self.chars.peek().copied() {
+      // a `#` only starts a comment at the start of the value or after a space
+      if c == '\n'
+        || c == '#'
+          && (value.is_empty()
+            || value.ends_with(|c: char| c == ' ' || c == '\t'))
+      {
+        break;
+      }
+      self.next();
+      if c == '$' {
+        value.push_str(&self.read_reference()?);
+      } else {
+        value.push(c);
+      }
+    }
+    Ok(value.trim_end().to_string())
+  }
+
+  /// Reads the name of the variable referenced after a `$` and returns its
+  /// value.
+  fn read_reference(&mut self) -> Result<String, AnyError> {
+    let name = if self.chars.peek() == Some(&'{') {
+      let line = self.line;
+      self.next();
+      let name = self.read_name();
+      if self.next() != Some('}') {
+        bail!("Unterminated variable reference at line {}", line);
+      }
+      name
+    } else {
+      let name = self.read_name();
+      if name.is_empty() {
+        return Ok("$".to_string());
+      }
+      name
+    };
+    Ok(
+      (self.get_var)(&name)
+        .or_else(|| {
+          self
+            .env_vars
+            .iter()
+            .rev()
+            .find(|(n, _)| *n == name)
+            .map(|(_, value)| value.clone())
+        })
+        .unwrap_or_default(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn parse(text: &str) -> Result<Vec<(String, String)>, AnyError> {
+    parse_env_file(text, |name| (name == "HOME").then(|| "/home/deno".into()))
+  }
+
+  fn env_vars(env_vars: &[(&str, &str)]) -> Vec<(String, String)> {
+    env_vars
+      .iter()
+      .map(|(name, value)| (name.to_string(), value.to_string()))
+      .collect()
+  }
+
+  #[test]
+  fn parses_env_file() {
+    let text = r#"
+# a comment
+A=1
+export B = two words  # a comment
+C=
+D=a#b
+E='single $A'
+F="double\n\"quoted\" $A"
+G="multi
+line"
+H='multi
+line' # a comment
+A=3
+"#;
+    assert_eq!(
+      parse(text).unwrap(),
+      env_vars(&[
+        ("B", "two words"),
+        ("C", ""),
+        ("D", "a#b"),
+        ("E", "single $A"),
+        ("F", "double\n\"quoted\" 1"),
+        ("G", "multi\nline"),
+        ("H", "multi\nline"),
+        ("A", "3"),
+      ])
+    );
+  }
+
+  #[test]
+  fn expands_variables() {
+    let text = r#"
+DIR=$HOME/deno
+CACHE=${DIR}/cache
+PRICE="\$5 $"
+MISSING=a${MISSING}b
+"#;
+    assert_eq!(
+      parse(text).unwrap(),
+      env_vars(&[
+        ("DIR", "/home/deno/deno"),
+        ("CACHE", "/home/deno/deno/cache"),
+        ("PRICE", "$5 $"),
+        ("MISSING", "ab"),
+      ])
+    );
+  }
+
+  #[test]
+  fn parses_crlf_line_endings() {
+    assert_eq!(
+      parse("A=1\r\nB=\"2\"\r\n").unwrap(),
+      env_vars(&[("A", "1"), ("B", "2")])
+    );
+  }
+
+  #[test]
+  fn errors() {
+    let error = |text: &str| parse(text).unwrap_err().to_string();
+    assert_eq!(error("A=1\n1A=2"), "Invalid variable name at line 2");
+    assert_eq!(error("A 1"), "Expected \"=\" after \"A\" at line 1");
+    assert_eq!(
+      error("A=1\nB=\"2\n"),
+      "Unterminated quoted value of \"B\" at line 2"
+    );
+    assert_eq!(
+      error("A='1' 2"),
+      "Unexpected characters after the value of \"A\" at line 1"
+    );
+    assert_eq!(error("A=${B"), "Unterminated variable reference at line 1");
+  }
+}
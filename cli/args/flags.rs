@@ -1,6 +1,7 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 use clap::Arg;
+use clap::ArgGroup;
 use clap::ArgMatches;
 use clap::ColorChoice;
 use clap::Command;
@@ -14,6 +15,7 @@ use once_cell::sync::Lazy;
 use std::env;
 use std::net::SocketAddr;
 use std::num::NonZeroU32;
+use std::num::NonZeroU64;
 use std::num::NonZeroU8;
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
@@ -54,6 +56,9 @@ pub struct FileFlags {
 pub struct BenchFlags {
   pub files: FileFlags,
   pub filter: Option<String>,
+  pub json: bool,
+  pub baseline: Option<String>,
+  pub regression_threshold: Option<u32>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -78,6 +83,7 @@ pub struct CompileFlags {
   pub output: Option<PathBuf>,
   pub args: Vec<String>,
   pub target: Option<String>,
+  pub include: Vec<String>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -92,12 +98,13 @@ pub struct CoverageFlags {
   pub include: Vec<String>,
   pub exclude: Vec<String>,
   pub lcov: bool,
+  pub html: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DocSourceFileFlag {
   Builtin,
-  Path(String),
+  Paths(Vec<String>),
 }
 
 impl Default for DocSourceFileFlag {
@@ -110,10 +117,17 @@ impl Default for DocSourceFileFlag {
 pub struct DocFlags {
   pub private: bool,
   pub json: bool,
+  pub html: Option<DocHtmlFlag>,
+  pub lint: bool,
   pub source_file: DocSourceFileFlag,
   pub filter: Option<String>,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DocHtmlFlag {
+  pub output: PathBuf,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct EvalFlags {
   pub print: bool,
@@ -142,9 +156,18 @@ pub struct InitFlags {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct InfoFlags {
   pub json: bool,
+  pub format: Option<InfoGraphFormat>,
+  pub sizes: bool,
+  pub why: Option<String>,
   pub file: Option<String>,
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InfoGraphFormat {
+  Dot,
+  Mermaid,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct InstallFlags {
   pub module_url: String,
@@ -169,6 +192,9 @@ pub struct LintFlags {
   pub maybe_rules_exclude: Option<Vec<String>>,
   pub json: bool,
   pub compact: bool,
+  pub sarif: bool,
+  pub github: bool,
+  pub fix: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -195,6 +221,20 @@ pub struct TaskFlags {
   pub task: String,
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TestReporterConfig {
+  Pretty,
+  Dot,
+  Junit,
+  Tap,
+}
+
+impl Default for TestReporterConfig {
+  fn default() -> Self {
+    TestReporterConfig::Pretty
+  }
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct TestFlags {
   pub doc: bool,
@@ -206,6 +246,21 @@ pub struct TestFlags {
   pub shuffle: Option<u64>,
   pub concurrent_jobs: Option<NonZeroUsize>,
   pub trace_ops: bool,
+  pub reporter: TestReporterConfig,
+  pub junit_path: Option<String>,
+  pub shard: Option<TestShard>,
+  pub shard_timings: Option<String>,
+  pub update_snapshots: bool,
+  pub timeout: Option<NonZeroU64>,
+  pub retries: usize,
+}
+
+/// One of the shards selected with `--shard=<index>/<total>`, where `index`
+/// starts at 1.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TestShard {
+  pub index: usize,
+  pub total: usize,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -329,6 +384,7 @@ pub struct Flags {
   pub node_modules_dir: bool,
   pub coverage_dir: Option<String>,
   pub enable_testing_features: bool,
+  pub env_file: Option<String>,
   pub ignore: Vec<PathBuf>,
   pub import_map_path: Option<String>,
   pub inspect_brk: Option<SocketAddr>,
@@ -342,6 +398,9 @@ pub struct Flags {
   pub no_lock: bool,
   pub no_npm: bool,
   pub no_prompt: bool,
+  pub permission_audit: Option<PathBuf>,
+  pub permissions_learn: bool,
+  pub permissions_learn_config: Option<PathBuf>,
   pub reload: bool,
   pub seed: Option<u64>,
   pub unstable: bool,
@@ -732,6 +791,39 @@ fn bench_subcommand<'a>() -> Command<'a> {
         .takes_value(true)
         .help("Run benchmarks with this string or pattern in the bench name"),
     )
+    .arg(
+      Arg::new("json")
+        .long("json")
+        .help("Output benchmark results in JSON format to stdout")
+        .takes_value(false),
+    )
+    .arg(
+      Arg::new("baseline")
+        .long("baseline")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("FILE")
+        .value_hint(ValueHint::FilePath)
+        .help("Compare results against a previous run saved with --json")
+        .long_help(
+          "Compare results against a previous run saved with --json. The \
+process fails if the average time of a benchmark increased by more than the \
+regression threshold.",
+        ),
+    )
+    .arg(
+      Arg::new("regression-threshold")
+        .long("regression-threshold")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("PERCENT")
+        .requires("baseline")
+        .help("Allowed increase of the average time compared to the baseline. Defaults to 10.")
+        .validator(|val: &str| match val.parse::<u32>() {
+          Ok(_) => Ok(()),
+          Err(_) => Err("regression-threshold should be a whole number of percent".to_string()),
+        }),
+    )
     .arg(
       Arg::new("files")
         .help("List of file names to run")
@@ -754,7 +846,12 @@ and report results to standard output:
 Directory arguments are expanded to all contained files matching the \
 glob {*_,*.,}bench.{js,mjs,ts,mts,jsx,tsx}:
 
-  deno bench src/",
+  deno bench src/
+
+Save results and fail later runs that are more than 5% slower:
+
+  deno bench --json > baseline.json
+  deno bench --baseline=baseline.json --regression-threshold=5",
     )
 }
 
@@ -845,6 +942,7 @@ Unless --reload is specified, this command will not re-download already cached d
 fn compile_subcommand<'a>() -> Command<'a> {
   runtime_args(Command::new("compile"), true, false)
     .trailing_var_arg(true)
+    .arg(env_file_arg())
     .arg(script_arg().required(true))
     .arg(
       Arg::new("output")
@@ -866,17 +964,39 @@ fn compile_subcommand<'a>() -> Command<'a> {
           "aarch64-apple-darwin",
         ]),
     )
+    .arg(
+      Arg::new("include")
+        .long("include")
+        .help("Glob of asset files to embed in the executable")
+        .long_help(
+          "Glob of asset files to embed in the executable. The files keep \
+their paths relative to the current directory and are mounted in the current \
+directory of the executable when it starts, where they can be read with \
+'Deno.readFile', 'Deno.stat' or 'fetch'. Can be specified multiple times.",
+        )
+        .takes_value(true)
+        .number_of_values(1)
+        .multiple_occurrences(true)
+        .value_name("glob")
+        .value_hint(ValueHint::AnyPath),
+    )
     .about("UNSTABLE: Compile the script into a self contained executable")
     .long_about(
       "UNSTABLE: Compiles the given script into a self contained executable.
 
   deno compile -A https://deno.land/std/http/file_server.ts
   deno compile --output color_util https://deno.land/std/examples/colors.ts
+  deno compile --include \"static/**\" --include templates/index.html server.ts
 
 Any flags passed which affect runtime behavior, such as '--unstable', \
 '--allow-*', '--v8-flags', etc. are encoded into the output executable and \
 used at runtime as if they were passed to a similar 'deno run' command.
 
+The variables of the .env file given with '--env-file' are set when the \
+executable starts. They are stored unencrypted in the executable, so don't \
+embed secrets in executables that are shared. The 'envFile' of the config file \
+is not embedded.
+
 The executable name is inferred by default: Attempt to take the file stem of \
 the URL path. The above example would become 'file_server'. If the file stem \
 is something generic like 'main', 'mod', 'index' or 'cli', and the path has no \
@@ -943,6 +1063,10 @@ Write a report using the lcov format:
 Generate html reports from lcov:
 
   genhtml -o html_cov cov.lcov
+
+Write a browsable html report to cov_profile/html/:
+
+  deno coverage --html cov_profile/
 ",
     )
     .arg(
@@ -982,15 +1106,24 @@ Generate html reports from lcov:
         .help("Output coverage report in lcov format")
         .takes_value(false),
     )
+    .arg(
+      Arg::new("html")
+        .long("html")
+        .help("Output coverage report as a static html site")
+        .takes_value(false),
+    )
+    .group(ArgGroup::new("report-format").args(&["lcov", "html"]))
     .arg(
       Arg::new("output")
-        .requires("lcov")
+        .requires("report-format")
         .long("output")
-        .help("Output file (defaults to stdout) for lcov")
+        .help("Output file (defaults to stdout) for lcov, or output directory for html")
         .long_help(
           "Exports the coverage report in lcov format to the given file. \
     Filename should be passed along with '=' For example '--output=foo.lcov' \
-    If no --output arg is specified then the report is written to stdout.",
+    If no --output arg is specified then the report is written to stdout. \
+    When used with --html, the report is written to the given directory \
+    instead of the 'html' directory inside the first coverage directory.",
         )
         .takes_value(true)
         .require_equals(true)
@@ -1024,6 +1157,15 @@ Output documentation in JSON format:
 
     deno doc --json ./path/to/module.ts
 
+Generate a static HTML documentation site for one or more modules:
+
+    deno doc --html --output=./docs/ ./path/to/module.ts ./path/to/other.ts
+
+Check that the exported symbols of one or more modules are documented, with
+explicit return types and without references to non-exported types:
+
+    deno doc --lint ./path/to/module.ts ./path/to/other.ts
+
 Target a specific symbol:
 
     deno doc ./path/to/module.ts MyClass.someField
@@ -1041,6 +1183,30 @@ Show documentation for runtime built-ins:
         .help("Output documentation in JSON format")
         .takes_value(false),
     )
+    .arg(
+      Arg::new("html")
+        .long("html")
+        .help("Output documentation as a static HTML site")
+        .takes_value(false)
+        .conflicts_with("json"),
+    )
+    .arg(
+      Arg::new("output")
+        .long("output")
+        .help("Directory to write the HTML documentation to [default: ./docs/]")
+        .takes_value(true)
+        .requires("html")
+        .value_hint(ValueHint::DirPath),
+    )
+    .arg(
+      Arg::new("lint")
+        .long("lint")
+        .help("Check the documentation of the exported symbols")
+        .takes_value(false)
+        .conflicts_with_all(&["json", "html", "private"])
+        .requires("source_file"),
+    )
+    .group(ArgGroup::new("multiple_modules").args(&["html", "lint"]))
     .arg(
       Arg::new("private")
         .long("private")
@@ -1064,6 +1230,16 @@ Show documentation for runtime built-ins:
         .required(false)
         .conflicts_with("json"),
     )
+    // With `--html` or `--lint`, the filter and these are more modules to
+    // document.
+    .arg(
+      Arg::new("more_source_files")
+        .takes_value(true)
+        .multiple_values(true)
+        .requires("multiple_modules")
+        .hide(true)
+        .value_hint(ValueHint::FilePath),
+    )
 }
 
 fn eval_subcommand<'a>() -> Command<'a> {
@@ -1264,6 +1440,22 @@ type: JavaScript, TypeScript, or JSON.
 emit: Local path of compiled source code. (TypeScript only.)
 dependencies: Dependency tree of the source file.
 
+Export the module graph, including npm packages, to Graphviz DOT or Mermaid:
+
+  deno info --format=dot main.ts | dot -Tsvg > graph.svg
+  deno info --format=mermaid main.ts
+
+List the transitive size of every dependency and the modules and npm packages
+that are in the graph with more than one version:
+
+  deno info --sizes main.ts
+
+Show every chain of imports from the module to a module or npm package, with
+the import map entries that the imports were resolved with:
+
+  deno info --why=https://deno.land/std@0.170.0/path/mod.ts main.ts
+  deno info --why=npm:chalk main.ts
+
 Without any additional arguments, 'deno info' shows:
 
 DENO_DIR: Directory containing Deno-managed files.
@@ -1290,6 +1482,34 @@ TypeScript compiler cache: Subdirectory containing TS compiler output.",
         .help("UNSTABLE: Outputs the information in JSON format")
         .takes_value(false),
     )
+    .arg(
+      Arg::new("format")
+        .long("format")
+        .help("Output the module graph in Graphviz DOT or Mermaid format")
+        .takes_value(true)
+        .require_equals(true)
+        .possible_values(["dot", "mermaid"])
+        .requires("file")
+        .conflicts_with("json"),
+    )
+    .arg(
+      Arg::new("sizes")
+        .long("sizes")
+        .help("List the transitive size of each dependency and duplicated dependencies")
+        .takes_value(false)
+        .requires("file")
+        .conflicts_with_all(&["json", "format"]),
+    )
+    .arg(
+      Arg::new("why")
+        .long("why")
+        .help("Show the import chains to a module or npm package")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("SPECIFIER_OR_PACKAGE")
+        .requires("file")
+        .conflicts_with_all(&["json", "format", "sizes"]),
+    )
 }
 
 fn install_subcommand<'a>() -> Command<'a> {
@@ -1410,6 +1630,11 @@ Print result as JSON:
 
   deno lint --json
 
+Fix the problems of rules that support it, like prefer-const and
+no-extra-semi:
+
+  deno lint --fix
+
 Read from stdin:
 
   cat file.ts | deno lint -
@@ -1430,6 +1655,14 @@ Names of rules to ignore must be specified after ignore comment.
 Ignore linting a file by adding an ignore comment at the top of the file:
 
   // deno-lint-ignore-file
+
+Add rules from JavaScript/TypeScript plugins with \"lint.plugins\" in the
+config file. A plugin default exports its name and rules, whose codes are
+<plugin name>/<rule name>:
+
+  {
+    \"lint\": { \"plugins\": [\"./lint_plugin.ts\"] }
+  }
 ",
     )
     .arg(Arg::new("rules").long("rules").help("List available rules"))
@@ -1484,6 +1717,26 @@ Ignore linting a file by adding an ignore comment at the top of the file:
         .takes_value(false)
         .conflicts_with("json"),
     )
+    .arg(
+      Arg::new("fix")
+        .long("fix")
+        .help("Fix any linting errors for rules that support it")
+        .takes_value(false),
+    )
+    .arg(
+      Arg::new("sarif")
+        .long("sarif")
+        .help("Output lint result in SARIF 2.1 format")
+        .takes_value(false)
+        .conflicts_with_all(&["json", "compact"]),
+    )
+    .arg(
+      Arg::new("github")
+        .long("github")
+        .help("Output lint result as GitHub Actions workflow commands")
+        .takes_value(false)
+        .conflicts_with_all(&["json", "compact", "sarif"]),
+    )
     .arg(
       Arg::new("files")
         .takes_value(true)
@@ -1527,6 +1780,8 @@ fn run_subcommand<'a>() -> Command<'a> {
         .conflicts_with("inspect-brk"),
     )
     .arg(no_clear_screen_arg())
+    .arg(permissions_learn_arg())
+    .arg(env_file_arg())
     .trailing_var_arg(true)
     .arg(script_arg().required(true))
     .about("Run a JavaScript or TypeScript program")
@@ -1560,6 +1815,7 @@ fn task_subcommand<'a>() -> Command<'a> {
   Command::new("task")
     .trailing_var_arg(true)
     .arg(config_arg())
+    .arg(env_file_arg())
     .arg(
       Arg::new("cwd")
         .long("cwd")
@@ -1588,6 +1844,8 @@ fn task_subcommand<'a>() -> Command<'a> {
 fn test_subcommand<'a>() -> Command<'a> {
   runtime_args(Command::new("test"), true, true)
     .trailing_var_arg(true)
+    .arg(permissions_learn_arg())
+    .arg(env_file_arg())
     .arg(
       Arg::new("ignore")
         .long("ignore")
@@ -1606,7 +1864,7 @@ fn test_subcommand<'a>() -> Command<'a> {
     .arg(
       Arg::new("trace-ops")
         .long("trace-ops")
-        .help("Enable tracing of async ops. Useful when debugging leaking ops in test, but impacts test execution time.")
+        .help("Enable tracing of async ops and resources. Useful when debugging leaking ops and resources in test, but impacts test execution time.")
         .takes_value(false),
     )
     .arg(
@@ -1657,6 +1915,30 @@ fn test_subcommand<'a>() -> Command<'a> {
           Err(_) => Err("Shuffle seed should be a number".to_string()),
         }),
     )
+    .arg(
+      Arg::new("timeout")
+        .long("timeout")
+        .value_name("MS")
+        .help("Fail tests which take longer than MS milliseconds, unless they set their own 'timeout' option")
+        .require_equals(true)
+        .takes_value(true)
+        .validator(|val: &str| match val.parse::<NonZeroU64>() {
+          Ok(_) => Ok(()),
+          Err(_) => Err("timeout should be a non zero number of milliseconds".to_string()),
+        }),
+    )
+    .arg(
+      Arg::new("retry")
+        .long("retry")
+        .value_name("N")
+        .help("Retry failing tests up to N times, unless they set their own 'retries' option. Tests which pass on a retry are reported as flaky")
+        .require_equals(true)
+        .takes_value(true)
+        .validator(|val: &str| match val.parse::<usize>() {
+          Ok(_) => Ok(()),
+          Err(_) => Err("retry should be a number".to_string()),
+        }),
+    )
     .arg(
       Arg::new("coverage")
         .long("coverage")
@@ -1689,6 +1971,48 @@ fn test_subcommand<'a>() -> Command<'a> {
           Err(_) => Err("jobs should be a non zero unsigned integer".to_string()),
         }),
     )
+    .arg(
+      Arg::new("reporter")
+        .long("reporter")
+        .takes_value(true)
+        .require_equals(true)
+        .possible_values(["pretty", "dot", "junit", "tap"])
+        .help("Select reporter to use. Defaults to 'pretty'."),
+    )
+    .arg(
+      Arg::new("junit-path")
+        .long("junit-path")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("PATH")
+        .value_hint(ValueHint::FilePath)
+        .help("Write a JUnit XML test report to PATH. Use '-' to write to stdout."),
+    )
+    .arg(
+      Arg::new("update-snapshots")
+        .long("update-snapshots")
+        .help("Update the snapshots of 't.assertSnapshot()' and remove obsolete ones."),
+    )
+    .arg(
+      Arg::new("shard")
+        .long("shard")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("INDEX/TOTAL")
+        .validator(|val: &str| parse_test_shard(val).map(|_| ()))
+        .conflicts_with("watch")
+        .help("Only run the test modules of shard INDEX out of TOTAL shards, for example '--shard=1/3'."),
+    )
+    .arg(
+      Arg::new("shard-timings")
+        .long("shard-timings")
+        .takes_value(true)
+        .require_equals(true)
+        .requires("shard")
+        .value_name("PATH")
+        .value_hint(ValueHint::FilePath)
+        .help("Balance the shards by the durations of the test modules in the JUnit XML report of a previous run."),
+    )
     .arg(
       Arg::new("files")
         .help("List of file names to run")
@@ -1963,6 +2287,15 @@ fn permission_args(app: Command) -> Command {
         .long("no-prompt")
         .help("Always throw if required permission wasn't passed"),
     )
+    .arg(
+      Arg::new("permission-audit")
+        .long("permission-audit")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("FILE")
+        .help("Write a JSON line for every permission check to the given file")
+        .value_hint(ValueHint::FilePath),
+    )
 }
 
 fn runtime_args(
@@ -2178,6 +2511,39 @@ fn no_clear_screen_arg<'a>() -> Arg<'a> {
     .help("Do not clear terminal screen when under watch mode")
 }
 
+fn permissions_learn_arg<'a>() -> Arg<'a> {
+  Arg::new("permissions-learn")
+    .long("permissions-learn")
+    .value_name("CONFIG_FILE")
+    .min_values(0)
+    .takes_value(true)
+    .require_equals(true)
+    .conflicts_with_all(&[
+      "allow-read",
+      "allow-write",
+      "allow-net",
+      "allow-env",
+      "allow-sys",
+      "allow-run",
+      "allow-ffi",
+      "allow-hrtime",
+      "allow-all",
+    ])
+    .help("Grant all permissions and print the minimal permission flags needed")
+    .long_help(
+      "Grant all permissions, record every permission that is checked and print
+the minimal --allow-* flags needed to run the program on exit. Permission
+checks of module imports are not recorded.
+
+If a config file is given, the flags are also written into its \"permissions\"
+field. The file is created if it doesn't exist. Comments in an existing file
+are not preserved.
+
+  deno run --permissions-learn=deno.json main.ts",
+    )
+    .value_hint(ValueHint::FilePath)
+}
+
 fn no_check_arg<'a>() -> Arg<'a> {
   Arg::new("no-check")
     .takes_value(true)
@@ -2239,6 +2605,18 @@ fn lock_arg<'a>() -> Arg<'a> {
     .value_hint(ValueHint::FilePath)
 }
 
+fn env_file_arg<'a>() -> Arg<'a> {
+  Arg::new("env-file")
+    .long("env-file")
+    .value_name("FILE")
+    .help("Load environment variables from a .env file. If value is not provided, defaults to \".env\" in the current working directory.")
+    .takes_value(true)
+    .min_values(0)
+    .max_values(1)
+    .require_equals(true)
+    .value_hint(ValueHint::FilePath)
+}
+
 fn lock_write_arg<'a>() -> Arg<'a> {
   Arg::new("lock-write")
     .long("lock-write")
@@ -2327,6 +2705,11 @@ fn bench_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   };
 
   let filter = matches.value_of("filter").map(String::from);
+  let json = matches.is_present("json");
+  let baseline = matches.value_of("baseline").map(String::from);
+  let regression_threshold = matches
+    .value_of("regression-threshold")
+    .map(|value| value.parse().unwrap());
 
   if matches.is_present("script_arg") {
     let script_arg: Vec<String> = matches
@@ -2355,6 +2738,9 @@ fn bench_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   flags.subcommand = DenoSubcommand::Bench(BenchFlags {
     files: FileFlags { include, ignore },
     filter,
+    json,
+    baseline,
+    regression_threshold,
   });
 }
 
@@ -2407,6 +2793,7 @@ fn check_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
 fn compile_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   flags.type_check_mode = TypeCheckMode::Local;
   runtime_args_parse(flags, matches, true, false);
+  env_file_arg_parse(flags, matches);
 
   let mut script: Vec<String> = matches
     .values_of("script_arg")
@@ -2418,12 +2805,17 @@ fn compile_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   let source_file = script[0].to_string();
   let output = matches.value_of("output").map(PathBuf::from);
   let target = matches.value_of("target").map(String::from);
+  let include = matches
+    .values_of("include")
+    .map(|values| values.map(String::from).collect())
+    .unwrap_or_default();
 
   flags.subcommand = DenoSubcommand::Compile(CompileFlags {
     source_file,
     output,
     args,
     target,
+    include,
   });
 }
 
@@ -2474,6 +2866,7 @@ fn coverage_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     None => vec![],
   };
   let lcov = matches.is_present("lcov");
+  let html = matches.is_present("html");
   let output = matches.value_of("output").map(PathBuf::from);
   flags.subcommand = DenoSubcommand::Coverage(CoverageFlags {
     files: FileFlags {
@@ -2484,6 +2877,7 @@ fn coverage_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     include,
     exclude,
     lcov,
+    html,
   });
 }
 
@@ -2491,22 +2885,42 @@ fn doc_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   import_map_arg_parse(flags, matches);
   reload_arg_parse(flags, matches);
 
+  let html = matches.is_present("html").then(|| DocHtmlFlag {
+    output: matches
+      .value_of("output")
+      .map(PathBuf::from)
+      .unwrap_or_else(|| PathBuf::from("./docs/")),
+  });
+  let lint = matches.is_present("lint");
+  let multiple_modules = html.is_some() || lint;
+  let mut filter = matches.value_of("filter").map(String::from);
   let source_file = matches
     .value_of("source_file")
     .map(|value| {
       if value == "--builtin" {
         DocSourceFileFlag::Builtin
       } else {
-        DocSourceFileFlag::Path(value.to_string())
+        let mut paths = vec![value.to_string()];
+        if multiple_modules {
+          paths.extend(filter.take());
+          if let Some(more) = matches.values_of("more_source_files") {
+            paths.extend(more.map(String::from));
+          }
+        }
+        DocSourceFileFlag::Paths(paths)
       }
     })
     .unwrap_or_default();
+  if multiple_modules {
+    filter = None;
+  }
   let private = matches.is_present("private");
   let json = matches.is_present("json");
-  let filter = matches.value_of("filter").map(String::from);
   flags.subcommand = DenoSubcommand::Doc(DocFlags {
     source_file,
     json,
+    html,
+    lint,
     filter,
     private,
   });
@@ -2623,9 +3037,19 @@ fn info_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   ca_file_arg_parse(flags, matches);
   local_npm_args_parse(flags, matches);
   let json = matches.is_present("json");
+  let format = match matches.value_of("format") {
+    Some("dot") => Some(InfoGraphFormat::Dot),
+    Some("mermaid") => Some(InfoGraphFormat::Mermaid),
+    _ => None,
+  };
+  let sizes = matches.is_present("sizes");
+  let why = matches.value_of("why").map(String::from);
   flags.subcommand = DenoSubcommand::Info(InfoFlags {
     file: matches.value_of("file").map(|f| f.to_string()),
     json,
+    format,
+    sizes,
+    why,
   });
 }
 
@@ -2691,6 +3115,9 @@ fn lint_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
 
   let json = matches.is_present("json");
   let compact = matches.is_present("compact");
+  let sarif = matches.is_present("sarif");
+  let github = matches.is_present("github");
+  let fix = matches.is_present("fix");
   flags.subcommand = DenoSubcommand::Lint(LintFlags {
     files: FileFlags {
       include: files,
@@ -2703,6 +3130,9 @@ fn lint_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
 
     json,
     compact,
+    sarif,
+    github,
+    fix,
   });
 }
 
@@ -2740,6 +3170,8 @@ fn run_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   }
 
   watch_arg_parse(flags, matches, true);
+  permissions_learn_arg_parse(flags, matches);
+  env_file_arg_parse(flags, matches);
   flags.subcommand = DenoSubcommand::Run(RunFlags { script });
 }
 
@@ -2753,6 +3185,7 @@ fn task_parse(
   } else {
     ConfigFlag::Discover
   };
+  env_file_arg_parse(flags, matches);
 
   let mut task_flags = TaskFlags {
     cwd: None,
@@ -2782,6 +3215,14 @@ fn task_parse(
           flags.config_flag = ConfigFlag::Disabled;
           index += 1;
         }
+        "--env-file" => {
+          flags.env_file = Some(".env".to_string());
+          index += 1;
+        }
+        arg if arg.starts_with("--env-file=") => {
+          flags.env_file = Some(arg["--env-file=".len()..].to_string());
+          index += 1;
+        }
         "-q" | "--quiet" => {
           flags.log_level = Some(Level::Error);
           index += 1;
@@ -2808,9 +3249,11 @@ fn task_parse(
 fn test_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   flags.type_check_mode = TypeCheckMode::Local;
   runtime_args_parse(flags, matches, true, true);
+  env_file_arg_parse(flags, matches);
   // NOTE: `deno test` always uses `--no-prompt`, tests shouldn't ever do
   // interactive prompts, unless done by user code
   flags.no_prompt = true;
+  permissions_learn_arg_parse(flags, matches);
 
   let ignore = match matches.values_of("ignore") {
     Some(f) => f.map(PathBuf::from).collect(),
@@ -2891,6 +3334,24 @@ fn test_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     Vec::new()
   };
 
+  let reporter = match matches.value_of("reporter") {
+    Some("dot") => TestReporterConfig::Dot,
+    Some("junit") => TestReporterConfig::Junit,
+    Some("tap") => TestReporterConfig::Tap,
+    _ => TestReporterConfig::Pretty,
+  };
+  let junit_path = matches.value_of("junit-path").map(String::from);
+  let shard = matches
+    .value_of("shard")
+    .map(|val| parse_test_shard(val).unwrap());
+  let shard_timings = matches.value_of("shard-timings").map(String::from);
+  let update_snapshots = matches.is_present("update-snapshots");
+  let timeout = matches.value_of("timeout").map(|val| val.parse().unwrap());
+  let retries = matches
+    .value_of("retry")
+    .map(|val| val.parse().unwrap())
+    .unwrap_or(0);
+
   flags.coverage_dir = matches.value_of("coverage").map(String::from);
   watch_arg_parse(flags, matches, false);
   flags.subcommand = DenoSubcommand::Test(TestFlags {
@@ -2903,9 +3364,30 @@ fn test_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     allow_none,
     concurrent_jobs,
     trace_ops,
+    reporter,
+    junit_path,
+    shard,
+    shard_timings,
+    update_snapshots,
+    timeout,
+    retries,
   });
 }
 
+fn parse_test_shard(val: &str) -> Result<TestShard, String> {
+  let error = || {
+    "shard should be INDEX/TOTAL, where INDEX is between 1 and TOTAL"
+      .to_string()
+  };
+  let (index, total) = val.split_once('/').ok_or_else(error)?;
+  let index = index.parse::<usize>().map_err(|_| error())?;
+  let total = total.parse::<usize>().map_err(|_| error())?;
+  if index == 0 || index > total {
+    return Err(error());
+  }
+  Ok(TestShard { index, total })
+}
+
 fn types_parse(flags: &mut Flags, _matches: &clap::ArgMatches) {
   flags.subcommand = DenoSubcommand::Types;
 }
@@ -3044,6 +3526,9 @@ fn permission_args_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   if matches.is_present("no-prompt") {
     flags.no_prompt = true;
   }
+  if let Some(path) = matches.value_of("permission-audit") {
+    flags.permission_audit = Some(PathBuf::from(path));
+  }
 }
 fn unsafely_ignore_certificate_errors_parse(
   flags: &mut Flags,
@@ -3168,6 +3653,14 @@ fn seed_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
   }
 }
 
+fn permissions_learn_arg_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
+  if matches.is_present("permissions-learn") {
+    flags.permissions_learn = true;
+    flags.permissions_learn_config =
+      matches.value_of("permissions-learn").map(PathBuf::from);
+  }
+}
+
 fn no_check_arg_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   if let Some(cache_type) = matches.value_of("no-check") {
     match cache_type {
@@ -3217,6 +3710,13 @@ fn lock_arg_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   }
 }
 
+fn env_file_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
+  if matches.is_present("env-file") {
+    let env_file = matches.value_of("env-file").unwrap_or(".env");
+    flags.env_file = Some(env_file.to_string());
+  }
+}
+
 fn config_args_parse(flags: &mut Flags, matches: &ArgMatches) {
   flags.config_flag = if matches.is_present("no-config") {
     ConfigFlag::Disabled
@@ -3522,6 +4022,88 @@ mod tests {
     );
   }
 
+  #[test]
+  fn permission_audit() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--permission-audit=audit.jsonl",
+      "gist.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "gist.ts".to_string(),
+        }),
+        permission_audit: Some(PathBuf::from("audit.jsonl")),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn permissions_learn() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--permissions-learn", "gist.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "gist.ts".to_string(),
+        }),
+        permissions_learn: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--permissions-learn=deno.json",
+      "gist.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "gist.ts".to_string(),
+        }),
+        permissions_learn: true,
+        permissions_learn_config: Some(PathBuf::from("deno.json")),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--permissions-learn"]);
+    let flags = r.unwrap();
+    assert!(flags.permissions_learn);
+    assert!(matches!(flags.subcommand, DenoSubcommand::Test(_)));
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--permissions-learn",
+      "--allow-read",
+      "gist.ts"
+    ]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--permissions-learn",
+      "--allow-all"
+    ]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec!["deno", "fmt", "--permissions-learn"]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec!["deno", "bench", "--permissions-learn"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn allow_all() {
     let r = flags_from_vec(svec!["deno", "run", "--allow-all", "gist.ts"]);
@@ -3889,6 +4471,9 @@ mod tests {
           maybe_rules_exclude: None,
           json: false,
           compact: false,
+          sarif: false,
+          github: false,
+          fix: false,
         }),
         ..Flags::default()
       }
@@ -3918,6 +4503,9 @@ mod tests {
           maybe_rules_exclude: None,
           json: false,
           compact: false,
+          sarif: false,
+          github: false,
+          fix: false,
         }),
         watch: Some(vec![]),
         ..Flags::default()
@@ -3949,6 +4537,9 @@ mod tests {
           maybe_rules_exclude: None,
           json: false,
           compact: false,
+          sarif: false,
+          github: false,
+          fix: false,
         }),
         watch: Some(vec![]),
         no_clear_screen: true,
@@ -3975,6 +4566,9 @@ mod tests {
           maybe_rules_exclude: None,
           json: false,
           compact: false,
+          sarif: false,
+          github: false,
+          fix: false,
         }),
         ..Flags::default()
       }
@@ -3995,6 +4589,9 @@ mod tests {
           maybe_rules_exclude: None,
           json: false,
           compact: false,
+          sarif: false,
+          github: false,
+          fix: false,
         }),
         ..Flags::default()
       }
@@ -4021,6 +4618,9 @@ mod tests {
           maybe_rules_exclude: Some(svec!["no-const-assign"]),
           json: false,
           compact: false,
+          sarif: false,
+          github: false,
+          fix: false,
         }),
         ..Flags::default()
       }
@@ -4041,6 +4641,9 @@ mod tests {
           maybe_rules_exclude: None,
           json: true,
           compact: false,
+          sarif: false,
+          github: false,
+          fix: false,
         }),
         ..Flags::default()
       }
@@ -4068,6 +4671,9 @@ mod tests {
           maybe_rules_exclude: None,
           json: true,
           compact: false,
+          sarif: false,
+          github: false,
+          fix: false,
         }),
         config_flag: ConfigFlag::Path("Deno.jsonc".to_string()),
         ..Flags::default()
@@ -4096,21 +4702,96 @@ mod tests {
           maybe_rules_exclude: None,
           json: false,
           compact: true,
+          sarif: false,
+          github: false,
+          fix: false,
         }),
         config_flag: ConfigFlag::Path("Deno.jsonc".to_string()),
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn types() {
-    let r = flags_from_vec(svec!["deno", "types"]);
+    let r = flags_from_vec(svec!["deno", "lint", "--sarif", "script_1.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Types,
-        ..Flags::default()
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec![PathBuf::from("script_1.ts")],
+            ignore: vec![],
+          },
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: false,
+          compact: false,
+          sarif: true,
+          github: false,
+          fix: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "lint", "--github", "script_1.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec![PathBuf::from("script_1.ts")],
+            ignore: vec![],
+          },
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: false,
+          compact: false,
+          sarif: false,
+          github: true,
+          fix: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "lint", "--fix", "script_1.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec![PathBuf::from("script_1.ts")],
+            ignore: vec![],
+          },
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: false,
+          compact: false,
+          sarif: false,
+          github: false,
+          fix: true,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "lint", "--sarif", "--github"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn types() {
+    let r = flags_from_vec(svec!["deno", "types"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Types,
+        ..Flags::default()
       }
     );
   }
@@ -4175,6 +4856,9 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: false,
+          format: None,
+          sizes: false,
+          why: None,
           file: Some("script.ts".to_string()),
         }),
         ..Flags::default()
@@ -4187,6 +4871,9 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: false,
+          format: None,
+          sizes: false,
+          why: None,
           file: Some("script.ts".to_string()),
         }),
         reload: true,
@@ -4200,6 +4887,9 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: true,
+          format: None,
+          sizes: false,
+          why: None,
           file: Some("script.ts".to_string()),
         }),
         ..Flags::default()
@@ -4212,6 +4902,9 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: false,
+          format: None,
+          sizes: false,
+          why: None,
           file: None
         }),
         ..Flags::default()
@@ -4224,6 +4917,9 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: true,
+          format: None,
+          sizes: false,
+          why: None,
           file: None
         }),
         ..Flags::default()
@@ -4236,6 +4932,9 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: false,
+          format: None,
+          sizes: false,
+          why: None,
           file: None
         }),
         config_flag: ConfigFlag::Path("tsconfig.json".to_owned()),
@@ -4244,6 +4943,65 @@ mod tests {
     );
   }
 
+  #[test]
+  fn info_format_sizes_and_why() {
+    let r =
+      flags_from_vec(svec!["deno", "info", "--format=mermaid", "main.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: false,
+          format: Some(InfoGraphFormat::Mermaid),
+          sizes: false,
+          why: None,
+          file: Some("main.ts".to_string()),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "info", "--sizes", "main.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: false,
+          format: None,
+          sizes: true,
+          why: None,
+          file: Some("main.ts".to_string()),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "info", "--why=npm:chalk", "main.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: false,
+          format: None,
+          sizes: false,
+          why: Some("npm:chalk".to_string()),
+          file: Some("main.ts".to_string()),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "info", "--format=svg", "main.ts"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "info", "--format=dot"]);
+    assert!(r.is_err());
+    let r =
+      flags_from_vec(svec!["deno", "info", "--sizes", "--json", "main.ts"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "info", "--why=npm:chalk"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn tsconfig() {
     let r =
@@ -4965,6 +5723,9 @@ mod tests {
         subcommand: DenoSubcommand::Info(InfoFlags {
           file: Some("script.ts".to_string()),
           json: false,
+          format: None,
+          sizes: false,
+          why: None,
         }),
         import_map_path: Some("import_map.json".to_owned()),
         ..Flags::default()
@@ -5004,9 +5765,11 @@ mod tests {
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Doc(DocFlags {
-          source_file: DocSourceFileFlag::Path("script.ts".to_owned()),
+          source_file: DocSourceFileFlag::Paths(svec!["script.ts"]),
           private: false,
           json: false,
+          html: None,
+          lint: false,
           filter: None,
         }),
         import_map_path: Some("import_map.json".to_owned()),
@@ -5656,6 +6419,13 @@ mod tests {
           shuffle: None,
           concurrent_jobs: None,
           trace_ops: true,
+          reporter: Default::default(),
+          junit_path: None,
+          shard: None,
+          shard_timings: None,
+          update_snapshots: false,
+          timeout: None,
+          retries: 0,
         }),
         unstable: true,
         no_prompt: true,
@@ -5710,6 +6480,38 @@ mod tests {
     );
   }
 
+  #[test]
+  fn run_with_env_file() {
+    let r = flags_from_vec(svec!["deno", "run", "--env-file", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        env_file: Some(".env".to_string()),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--env-file=.env.local",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        env_file: Some(".env.local".to_string()),
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn test_with_concurrent_jobs() {
     let r = flags_from_vec(svec!["deno", "test", "--jobs=4"]);
@@ -5729,6 +6531,13 @@ mod tests {
           },
           concurrent_jobs: Some(NonZeroUsize::new(4).unwrap()),
           trace_ops: false,
+          reporter: Default::default(),
+          junit_path: None,
+          shard: None,
+          shard_timings: None,
+          update_snapshots: false,
+          timeout: None,
+          retries: 0,
         }),
         type_check_mode: TypeCheckMode::Local,
         no_prompt: true,
@@ -5759,6 +6568,13 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          reporter: Default::default(),
+          junit_path: None,
+          shard: None,
+          shard_timings: None,
+          update_snapshots: false,
+          timeout: None,
+          retries: 0,
         }),
         type_check_mode: TypeCheckMode::Local,
         no_prompt: true,
@@ -5793,6 +6609,13 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          reporter: Default::default(),
+          junit_path: None,
+          shard: None,
+          shard_timings: None,
+          update_snapshots: false,
+          timeout: None,
+          retries: 0,
         }),
         no_prompt: true,
         type_check_mode: TypeCheckMode::Local,
@@ -5821,6 +6644,13 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          reporter: Default::default(),
+          junit_path: None,
+          shard: None,
+          shard_timings: None,
+          update_snapshots: false,
+          timeout: None,
+          retries: 0,
         }),
         no_prompt: true,
         watch: None,
@@ -5830,6 +6660,105 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_reporter() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--reporter=tap",
+      "--junit-path=report.xml"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          reporter: TestReporterConfig::Tap,
+          junit_path: Some("report.xml".to_string()),
+          ..TestFlags::default()
+        }),
+        no_prompt: true,
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=xml"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_shard() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--shard=2/3",
+      "--shard-timings=report.xml"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          shard: Some(TestShard { index: 2, total: 3 }),
+          shard_timings: Some("report.xml".to_string()),
+          ..TestFlags::default()
+        }),
+        no_prompt: true,
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    for shard in ["0/3", "4/3", "1", "a/b"] {
+      let r = flags_from_vec(svec!["deno", "test", format!("--shard={shard}")]);
+      assert!(r.is_err(), "{shard}");
+    }
+    let r = flags_from_vec(svec!["deno", "test", "--shard-timings=report.xml"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "test", "--shard=1/2", "--watch"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_timeout_and_retry() {
+    let r =
+      flags_from_vec(svec!["deno", "test", "--timeout=5000", "--retry=2"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          timeout: NonZeroU64::new(5000),
+          retries: 2,
+          ..TestFlags::default()
+        }),
+        no_prompt: true,
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--timeout=0"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "test", "--retry=-1"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_update_snapshots() {
+    let r = flags_from_vec(svec!["deno", "test", "--update-snapshots"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          update_snapshots: true,
+          ..TestFlags::default()
+        }),
+        no_prompt: true,
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn test_watch() {
     let r = flags_from_vec(svec!["deno", "test", "--watch"]);
@@ -5849,6 +6778,13 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          reporter: Default::default(),
+          junit_path: None,
+          shard: None,
+          shard_timings: None,
+          update_snapshots: false,
+          timeout: None,
+          retries: 0,
         }),
         no_prompt: true,
         type_check_mode: TypeCheckMode::Local,
@@ -5876,6 +6812,13 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          reporter: Default::default(),
+          junit_path: None,
+          shard: None,
+          shard_timings: None,
+          update_snapshots: false,
+          timeout: None,
+          retries: 0,
         }),
         no_prompt: true,
         type_check_mode: TypeCheckMode::Local,
@@ -5905,6 +6848,13 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          reporter: Default::default(),
+          junit_path: None,
+          shard: None,
+          shard_timings: None,
+          update_snapshots: false,
+          timeout: None,
+          retries: 0,
         }),
         watch: Some(vec![]),
         type_check_mode: TypeCheckMode::Local,
@@ -5993,6 +6943,9 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: false,
+          format: None,
+          sizes: false,
+          why: None,
           file: Some("https://example.com".to_string()),
         }),
         ca_data: Some(CaData::File("example.crt".to_owned())),
@@ -6010,7 +6963,9 @@ mod tests {
         subcommand: DenoSubcommand::Doc(DocFlags {
           private: false,
           json: true,
-          source_file: DocSourceFileFlag::Path("path/to/module.ts".to_string()),
+          html: None,
+          lint: false,
+          source_file: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
           filter: None,
         }),
         ..Flags::default()
@@ -6029,7 +6984,9 @@ mod tests {
         subcommand: DenoSubcommand::Doc(DocFlags {
           private: false,
           json: false,
-          source_file: DocSourceFileFlag::Path("path/to/module.ts".to_string()),
+          html: None,
+          lint: false,
+          source_file: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
           filter: Some("SomeClass.someField".to_string()),
         }),
         ..Flags::default()
@@ -6043,6 +7000,8 @@ mod tests {
         subcommand: DenoSubcommand::Doc(DocFlags {
           private: false,
           json: false,
+          html: None,
+          lint: false,
           source_file: Default::default(),
           filter: None,
         }),
@@ -6057,6 +7016,8 @@ mod tests {
         subcommand: DenoSubcommand::Doc(DocFlags {
           private: false,
           json: false,
+          html: None,
+          lint: false,
           source_file: DocSourceFileFlag::Builtin,
           filter: Some("Deno.Listener".to_string()),
         }),
@@ -6072,12 +7033,90 @@ mod tests {
         subcommand: DenoSubcommand::Doc(DocFlags {
           private: true,
           json: false,
-          source_file: DocSourceFileFlag::Path("path/to/module.js".to_string()),
+          html: None,
+          lint: false,
+          source_file: DocSourceFileFlag::Paths(svec!["path/to/module.js"]),
+          filter: None,
+        }),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn doc_html() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--html",
+      "--output=out/",
+      "a.ts",
+      "b.ts",
+      "c.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: false,
+          html: Some(DocHtmlFlag {
+            output: PathBuf::from("out/"),
+          }),
+          lint: false,
+          source_file: DocSourceFileFlag::Paths(svec!["a.ts", "b.ts", "c.ts"]),
+          filter: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "doc", "--html", "a.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: false,
+          html: Some(DocHtmlFlag {
+            output: PathBuf::from("./docs/"),
+          }),
+          lint: false,
+          source_file: DocSourceFileFlag::Paths(svec!["a.ts"]),
           filter: None,
         }),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "doc", "--html", "--json", "a.ts"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "doc", "a.ts", "A", "b.ts"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn doc_lint() {
+    let r = flags_from_vec(svec!["deno", "doc", "--lint", "a.ts", "b.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: false,
+          html: None,
+          lint: true,
+          source_file: DocSourceFileFlag::Paths(svec!["a.ts", "b.ts"]),
+          filter: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "doc", "--lint"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "doc", "--lint", "--json", "a.ts"]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -6142,6 +7181,7 @@ mod tests {
           output: None,
           args: vec![],
           target: None,
+          include: vec![],
         }),
         type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
@@ -6161,6 +7201,7 @@ mod tests {
           output: Some(PathBuf::from("colors")),
           args: svec!["foo", "bar"],
           target: None,
+          include: vec![],
         }),
         import_map_path: Some("import_map.json".to_string()),
         no_remote: true,
@@ -6182,6 +7223,33 @@ mod tests {
     );
   }
 
+  #[test]
+  fn compile_with_include() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--include",
+      "static/**",
+      "--include=templates/index.html",
+      "server.ts",
+      "--include",
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "server.ts".to_string(),
+          output: None,
+          args: svec!["--include"],
+          target: None,
+          include: svec!["static/**", "templates/index.html"],
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn coverage() {
     let r = flags_from_vec(svec!["deno", "coverage", "foo.json"]);
@@ -6197,6 +7265,7 @@ mod tests {
           include: vec![r"^file:".to_string()],
           exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
           lcov: false,
+          html: false,
         }),
         ..Flags::default()
       }
@@ -6223,12 +7292,47 @@ mod tests {
           include: vec![r"^file:".to_string()],
           exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
           lcov: true,
+          html: false,
           output: Some(PathBuf::from("foo.lcov")),
         }),
         ..Flags::default()
       }
     );
   }
+  #[test]
+  fn coverage_with_html_and_out_dir() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "coverage",
+      "--html",
+      "--output=cov_html",
+      "foo.json"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Coverage(CoverageFlags {
+          files: FileFlags {
+            include: vec![PathBuf::from("foo.json")],
+            ignore: vec![],
+          },
+          include: vec![r"^file:".to_string()],
+          exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+          lcov: false,
+          html: true,
+          output: Some(PathBuf::from("cov_html")),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "coverage", "--lcov", "--html", "foo.json"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "coverage", "--output=x", "foo.json"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn location_with_bad_scheme() {
     #[rustfmt::skip]
@@ -6512,6 +7616,23 @@ mod tests {
     );
   }
 
+  #[test]
+  fn task_subcommand_env_file() {
+    let r =
+      flags_from_vec(svec!["deno", "task", "--env-file=.env.local", "build"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: None,
+          task: "build".to_string(),
+        }),
+        env_file: Some(".env.local".to_string()),
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn task_subcommand_config_short() {
     let r = flags_from_vec(svec!["deno", "task", "-c", "deno.jsonc"]);
@@ -6560,6 +7681,9 @@ mod tests {
             include: vec![PathBuf::from("dir1/"), PathBuf::from("dir2/")],
             ignore: vec![],
           },
+          json: false,
+          baseline: None,
+          regression_threshold: None,
         }),
         unstable: true,
         type_check_mode: TypeCheckMode::Local,
@@ -6572,6 +7696,34 @@ mod tests {
     );
   }
 
+  #[test]
+  fn bench_json_with_baseline() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "bench",
+      "--json",
+      "--baseline=baseline.json",
+      "--regression-threshold=5"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Bench(BenchFlags {
+          json: true,
+          baseline: Some("baseline.json".to_string()),
+          regression_threshold: Some(5),
+          ..BenchFlags::default()
+        }),
+        no_prompt: true,
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "bench", "--regression-threshold=5"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn bench_watch() {
     let r = flags_from_vec(svec!["deno", "bench", "--watch"]);
@@ -6584,6 +7736,9 @@ mod tests {
             include: vec![],
             ignore: vec![],
           },
+          json: false,
+          baseline: None,
+          regression_threshold: None,
         }),
         no_prompt: true,
         type_check_mode: TypeCheckMode::Local,
@@ -1,6 +1,7 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 mod config_file;
+mod env_file;
 mod flags;
 mod flags_allow_net;
 mod import_map;
@@ -17,10 +18,13 @@ pub use config_file::FmtOptionsConfig;
 pub use config_file::JsxImportSourceConfig;
 pub use config_file::LintRulesConfig;
 pub use config_file::ProseWrap;
+pub use config_file::TaskConfig;
 pub use config_file::TsConfig;
 pub use config_file::TsConfigForEmit;
 pub use config_file::TsConfigType;
 pub use config_file::TsTypeLib;
+pub use env_file::load_env_file;
+pub use env_file::set_env_vars;
 pub use flags::*;
 pub use lockfile::Lockfile;
 pub use lockfile::LockfileError;
@@ -46,13 +50,18 @@ use std::env;
 use std::io::BufReader;
 use std::io::Cursor;
 use std::net::SocketAddr;
+use std::num::NonZeroU64;
 use std::num::NonZeroUsize;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::cache::DenoDir;
 use crate::file_fetcher::FileFetcher;
 use crate::util::fs::canonicalize_path_maybe_not_exists;
+use crate::util::fs::resolve_from_cwd;
+use crate::util::path::specifier_parent;
+use crate::util::path::specifier_to_file_path;
 use crate::version;
 
 use self::config_file::FmtConfig;
@@ -105,6 +114,11 @@ impl CacheSetting {
 pub struct BenchOptions {
   pub files: FilesConfig,
   pub filter: Option<String>,
+  pub json: bool,
+  pub baseline: Option<String>,
+  /// Allowed increase in percent of the average time of a bench compared to
+  /// the baseline.
+  pub regression_threshold: u32,
 }
 
 impl BenchOptions {
@@ -119,6 +133,9 @@ impl BenchOptions {
         Some(bench_flags.files),
       ),
       filter: bench_flags.filter,
+      json: bench_flags.json,
+      baseline: bench_flags.baseline,
+      regression_threshold: bench_flags.regression_threshold.unwrap_or(10),
     })
   }
 }
@@ -130,9 +147,17 @@ pub struct FmtOptions {
   pub ext: String,
   pub options: FmtOptionsConfig,
   pub files: FilesConfig,
+  /// The options of each workspace member, keyed by the member's directory.
+  pub workspace_members: Vec<(PathBuf, FmtOptions)>,
 }
 
 impl FmtOptions {
+  /// Returns the options that apply to the file, which are those of the
+  /// workspace member containing it, if any.
+  pub fn for_path(&self, path: &Path) -> &FmtOptions {
+    find_workspace_member(&self.workspace_members, path).unwrap_or(self)
+  }
+
   pub fn resolve(
     maybe_fmt_config: Option<FmtConfig>,
     mut maybe_fmt_flags: Option<FmtFlags>,
@@ -166,6 +191,7 @@ impl FmtOptions {
         maybe_config_files,
         maybe_fmt_flags.map(|f| f.files),
       ),
+      workspace_members: Vec::new(),
     })
   }
 }
@@ -222,9 +248,31 @@ pub struct TestOptions {
   pub shuffle: Option<u64>,
   pub concurrent_jobs: NonZeroUsize,
   pub trace_ops: bool,
+  pub reporter: TestReporterConfig,
+  pub junit_path: Option<String>,
+  pub shard: Option<TestShard>,
+  /// A JUnit report of a previous run used to balance the shards.
+  pub shard_timings: Option<String>,
+  pub update_snapshots: bool,
+  /// The `test.files` configuration of each workspace member, keyed by the
+  /// member's directory.
+  pub workspace_members: Vec<(PathBuf, FilesConfig)>,
 }
 
 impl TestOptions {
+  /// Returns false if the specifier is excluded by the configuration of the
+  /// workspace member containing it.
+  pub fn matches_workspace_member(&self, specifier: &ModuleSpecifier) -> bool {
+    let path = match specifier_to_file_path(specifier) {
+      Ok(path) => path,
+      Err(_) => return true,
+    };
+    match find_workspace_member(&self.workspace_members, &path) {
+      Some(files) => files.matches_specifier(specifier),
+      None => true,
+    }
+  }
+
   pub fn resolve(
     maybe_test_config: Option<TestConfig>,
     maybe_test_flags: Option<TestFlags>,
@@ -246,6 +294,12 @@ impl TestOptions {
       no_run: test_flags.no_run,
       shuffle: test_flags.shuffle,
       trace_ops: test_flags.trace_ops,
+      reporter: test_flags.reporter,
+      junit_path: test_flags.junit_path,
+      shard: test_flags.shard,
+      shard_timings: test_flags.shard_timings,
+      update_snapshots: test_flags.update_snapshots,
+      workspace_members: Vec::new(),
     })
   }
 }
@@ -255,6 +309,8 @@ pub enum LintReporterKind {
   Pretty,
   Json,
   Compact,
+  Sarif,
+  Github,
 }
 
 impl Default for LintReporterKind {
@@ -268,10 +324,20 @@ pub struct LintOptions {
   pub rules: LintRulesConfig,
   pub files: FilesConfig,
   pub is_stdin: bool,
+  pub fix: bool,
   pub reporter_kind: LintReporterKind,
+  pub plugins: Vec<ModuleSpecifier>,
+  /// The options of each workspace member, keyed by the member's directory.
+  pub workspace_members: Vec<(PathBuf, LintOptions)>,
 }
 
 impl LintOptions {
+  /// Returns the options that apply to the file, which are those of the
+  /// workspace member containing it, if any.
+  pub fn for_path(&self, path: &Path) -> &LintOptions {
+    find_workspace_member(&self.workspace_members, path).unwrap_or(self)
+  }
+
   pub fn resolve(
     maybe_lint_config: Option<LintConfig>,
     mut maybe_lint_flags: Option<LintFlags>,
@@ -294,6 +360,10 @@ impl LintOptions {
           Some(LintReporterKind::Json)
         } else if lint_flags.compact {
           Some(LintReporterKind::Compact)
+        } else if lint_flags.sarif {
+          Some(LintReporterKind::Sarif)
+        } else if lint_flags.github {
+          Some(LintReporterKind::Github)
         } else {
           None
         }
@@ -306,6 +376,8 @@ impl LintOptions {
           Some("json") => Some(LintReporterKind::Json),
           Some("compact") => Some(LintReporterKind::Compact),
           Some("pretty") => Some(LintReporterKind::Pretty),
+          Some("sarif") => Some(LintReporterKind::Sarif),
+          Some("github") => Some(LintReporterKind::Github),
           Some(_) => {
             bail!("Invalid lint report type in config file")
           }
@@ -314,6 +386,8 @@ impl LintOptions {
       }
     }
 
+    let fix = maybe_lint_flags.as_ref().map(|f| f.fix).unwrap_or(false);
+
     let (
       maybe_file_flags,
       maybe_rules_tags,
@@ -330,11 +404,16 @@ impl LintOptions {
       })
       .unwrap_or_default();
 
-    let (maybe_config_files, maybe_config_rules) =
-      maybe_lint_config.map(|c| (c.files, c.rules)).unzip();
+    let (maybe_config_files, maybe_config_rules, plugins) =
+      match maybe_lint_config {
+        Some(c) => (Some(c.files), Some(c.rules), c.plugins),
+        None => (None, None, Vec::new()),
+      };
     Ok(Self {
       reporter_kind: maybe_reporter_kind.unwrap_or_default(),
       is_stdin,
+      fix,
+      plugins,
       files: resolve_files(maybe_config_files, Some(maybe_file_flags)),
       rules: resolve_lint_rules_options(
         maybe_config_rules,
@@ -342,10 +421,74 @@ impl LintOptions {
         maybe_rules_include,
         maybe_rules_exclude,
       ),
+      workspace_members: Vec::new(),
     })
   }
 }
 
+fn resolve_env_file(
+  flags: &Flags,
+  maybe_config_file: Option<&ConfigFile>,
+) -> Result<Option<PathBuf>, AnyError> {
+  if let Some(env_file) = &flags.env_file {
+    Ok(Some(resolve_from_cwd(Path::new(env_file))?))
+  } else if let Some(config_file) = maybe_config_file {
+    config_file.to_env_file_path()
+  } else {
+    Ok(None)
+  }
+}
+
+/// Resolves the options of `deno run`, `deno task` and `deno test` and sets
+/// the environment variables of their `.env` file, if any, which aren't
+/// already set. Setting environment variables isn't thread safe, so this must
+/// be called before the async runtime is built. The options are returned so
+/// the subcommand doesn't resolve the config file again.
+pub fn resolve_options_with_env_file(
+  flags: &Flags,
+) -> Result<Option<CliOptions>, AnyError> {
+  if !matches!(
+    flags.subcommand,
+    DenoSubcommand::Run(_) | DenoSubcommand::Task(_) | DenoSubcommand::Test(_)
+  ) {
+    return Ok(None);
+  }
+  let cli_options = CliOptions::from_flags(flags.clone())?;
+  if let Some(path) = cli_options.resolve_env_file()? {
+    set_env_vars(&load_env_file(&path)?);
+  }
+  Ok(Some(cli_options))
+}
+
+/// Resolves some options for each member of the workspace declared by the
+/// config file, keyed by the member's directory.
+pub fn resolve_workspace_members<T>(
+  config_file: &ConfigFile,
+  resolve: impl Fn(&ConfigFile) -> Result<T, AnyError>,
+) -> Result<Vec<(PathBuf, T)>, AnyError> {
+  config_file
+    .workspace_members
+    .iter()
+    .map(|member| {
+      let dir = specifier_to_file_path(&specifier_parent(&member.specifier))?;
+      Ok((dir, resolve(member)?))
+    })
+    .collect()
+}
+
+/// Finds the value of the innermost workspace member directory containing
+/// the path.
+fn find_workspace_member<'a, T>(
+  members: &'a [(PathBuf, T)],
+  path: &Path,
+) -> Option<&'a T> {
+  members
+    .iter()
+    .filter(|(dir, _)| path.starts_with(dir))
+    .max_by_key(|(dir, _)| dir.components().count())
+    .map(|(_, value)| value)
+}
+
 fn resolve_lint_rules_options(
   maybe_lint_rules_config: Option<LintRulesConfig>,
   mut maybe_rules_tags: Option<Vec<String>>,
@@ -663,13 +806,26 @@ impl CliOptions {
       .map(|host| InspectorServer::new(host, version::get_user_agent()))
   }
 
+  /// Resolves the `.env` file from the `--env-file` flag, which is relative
+  /// to the current working directory, or from the `envFile` option of the
+  /// configuration file.
+  pub fn resolve_env_file(&self) -> Result<Option<PathBuf>, AnyError> {
+    resolve_env_file(&self.flags, self.maybe_config_file.as_ref())
+  }
+
+  /// The `.env` file given with `--env-file`, ignoring the `envFile` of the
+  /// config file.
+  pub fn resolve_env_file_flag(&self) -> Result<Option<PathBuf>, AnyError> {
+    resolve_env_file(&self.flags, None)
+  }
+
   pub fn maybe_lock_file(&self) -> Option<Arc<Mutex<Lockfile>>> {
     self.maybe_lockfile.clone()
   }
 
   pub fn resolve_tasks_config(
     &self,
-  ) -> Result<BTreeMap<String, String>, AnyError> {
+  ) -> Result<BTreeMap<String, TaskConfig>, AnyError> {
     if let Some(config_file) = &self.maybe_config_file {
       config_file.resolve_tasks_config()
     } else {
@@ -728,7 +884,21 @@ impl CliOptions {
     } else {
       None
     };
-    LintOptions::resolve(maybe_lint_config, Some(lint_flags))
+    let mut lint_options =
+      LintOptions::resolve(maybe_lint_config, Some(lint_flags.clone()))?;
+    if let Some(config_file) = &self.maybe_config_file {
+      lint_options.workspace_members =
+        resolve_workspace_members(config_file, |member| {
+          // the files to lint are collected from the root, so only the rule
+          // flags apply to the members
+          let member_flags = LintFlags {
+            files: Default::default(),
+            ..lint_flags.clone()
+          };
+          LintOptions::resolve(member.to_lint_config()?, Some(member_flags))
+        })?;
+    }
+    Ok(lint_options)
   }
 
   pub fn resolve_test_options(
@@ -740,7 +910,20 @@ impl CliOptions {
     } else {
       None
     };
-    TestOptions::resolve(maybe_test_config, Some(test_flags))
+    let mut test_options =
+      TestOptions::resolve(maybe_test_config, Some(test_flags))?;
+    if let Some(config_file) = &self.maybe_config_file {
+      test_options.workspace_members =
+        resolve_workspace_members(config_file, |member| {
+          Ok(
+            member
+              .to_test_config()?
+              .map(|c| c.files)
+              .unwrap_or_default(),
+          )
+        })?;
+    }
+    Ok(test_options)
   }
 
   pub fn resolve_bench_options(
@@ -857,6 +1040,22 @@ impl CliOptions {
   }
 
   pub fn permissions_options(&self) -> PermissionsOptions {
+    if self.flags.permissions_learn {
+      // Everything is granted so the program runs to completion while the
+      // checked permissions are recorded. The `--allow-*` flags conflict with
+      // learning, so there are none to honor.
+      return PermissionsOptions {
+        allow_env: Some(vec![]),
+        allow_hrtime: true,
+        allow_net: Some(vec![]),
+        allow_ffi: Some(vec![]),
+        allow_read: Some(vec![]),
+        allow_run: Some(vec![]),
+        allow_sys: Some(vec![]),
+        allow_write: Some(vec![]),
+        prompt: false,
+      };
+    }
     PermissionsOptions {
       allow_env: self.flags.allow_env.clone(),
       allow_hrtime: self.flags.allow_hrtime,
@@ -896,6 +1095,23 @@ impl CliOptions {
     }
   }
 
+  /// The timeout in milliseconds of the tests which don't set their own.
+  pub fn test_timeout(&self) -> Option<NonZeroU64> {
+    match self.sub_command() {
+      DenoSubcommand::Test(flags) => flags.timeout,
+      _ => None,
+    }
+  }
+
+  /// How many times failing tests which don't set their own number of
+  /// retries are retried.
+  pub fn test_retries(&self) -> usize {
+    match self.sub_command() {
+      DenoSubcommand::Test(flags) => flags.retries,
+      _ => 0,
+    }
+  }
+
   pub fn type_check_mode(&self) -> TypeCheckMode {
     self.flags.type_check_mode
   }
@@ -915,6 +1131,14 @@ impl CliOptions {
   pub fn watch_paths(&self) -> &Option<Vec<PathBuf>> {
     &self.flags.watch
   }
+
+  pub fn has_permission(&self) -> bool {
+    self.flags.has_permission()
+  }
+
+  pub fn has_permission_in_argv(&self) -> bool {
+    self.flags.has_permission_in_argv()
+  }
 }
 
 fn resolve_import_map_specifier(
@@ -1118,4 +1342,29 @@ mod test {
     let actual = actual.unwrap();
     assert_eq!(actual, None);
   }
+
+  #[test]
+  fn find_workspace_member_innermost() {
+    let testdata = test_util::testdata_path();
+    let config_file = ConfigFile::read(testdata.join("workspaces/deno.json"))
+      .expect("Failed to load config file");
+    let members = resolve_workspace_members(&config_file, |member| {
+      Ok(member.specifier.clone())
+    })
+    .unwrap();
+    let root_dir = specifier_parent(&config_file.specifier);
+    let workspaces = testdata.join("workspaces");
+    assert_eq!(
+      find_workspace_member(&members, &workspaces.join("b/mod.ts")),
+      Some(&root_dir.join("b/deno.jsonc").unwrap())
+    );
+    assert_eq!(
+      find_workspace_member(&members, &workspaces.join("a/sub/mod.ts")),
+      Some(&root_dir.join("a/deno.json").unwrap())
+    );
+    assert_eq!(
+      find_workspace_member(&members, &workspaces.join("shared/name.ts")),
+      None
+    );
+  }
 }
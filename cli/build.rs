@@ -320,6 +320,7 @@ fn create_cli_snapshot(snapshot_path: PathBuf, files: Vec<PathBuf>) {
     deno_cache::init::<SqliteBackedCache>(None),
     deno_websocket::init::<PermissionsContainer>("".to_owned(), None, None),
     deno_webstorage::init(None),
+    deno_kv::init::<PermissionsContainer>(None, false), // No --unstable
     deno_crypto::init(None),
     deno_webgpu::init(false),
     deno_broadcast_channel::init(
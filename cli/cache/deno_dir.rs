@@ -64,6 +64,18 @@ impl DenoDir {
     self.root.join("lint_incremental_cache_v1")
   }
 
+  /// Path for the incremental cache used for tasks.
+  pub fn task_incremental_cache_db_file_path(&self) -> PathBuf {
+    // bump this version name to invalidate the entire cache
+    self.root.join("task_incremental_cache_v1")
+  }
+
+  /// Path to the copies of the outputs of cached tasks.
+  pub fn task_outputs_path(&self) -> PathBuf {
+    // bump this version name to invalidate the entire cache
+    self.root.join("task_outputs_v1")
+  }
+
   /// Path for caching swc dependency analysis.
   pub fn dep_analysis_db_file_path(&self) -> PathBuf {
     // bump this version name to invalidate the entire cache
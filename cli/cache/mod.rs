@@ -2,6 +2,7 @@
 
 use crate::errors::get_error_class_name;
 use crate::file_fetcher::FileFetcher;
+use crate::graph_util::rewrite_import_attributes;
 use crate::npm;
 
 use deno_core::futures;
@@ -144,10 +145,15 @@ impl Loader for FetchCacher {
             Err(err)
           },
           |file| {
+            let content =
+              match rewrite_import_attributes(&file.source, file.media_type) {
+                Some(source) => source.into(),
+                None => file.source,
+              };
             Ok(Some(LoadResponse::Module {
               specifier: file.specifier,
               maybe_headers: file.maybe_headers,
-              content: file.source,
+              content,
             }))
           },
         )
@@ -629,6 +629,29 @@ impl FileFetcher {
     }
   }
 
+  /// A synchronous way to retrieve the bytes of a source file that has been
+  /// fetched, as the source of a `File` is decoded text. Local files are read
+  /// from disk and remote files from the HTTP cache.
+  pub fn get_source_bytes(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Result<Vec<u8>, AnyError> {
+    if specifier.scheme() == "data" {
+      let (bytes, _) = DataUrl::process(specifier.as_str())
+        .map_err(|e| uri_error(format!("{e:?}")))?
+        .decode_to_vec()
+        .map_err(|e| uri_error(format!("{e:?}")))?;
+      return Ok(bytes);
+    }
+    let local = self.get_local_path(specifier).ok_or_else(|| {
+      custom_error(
+        "NotFound",
+        format!("Unable to load source bytes of \"{specifier}\"."),
+      )
+    })?;
+    Ok(fs::read(local)?)
+  }
+
   /// Insert a temporary module into the in memory cache for the file fetcher.
   pub fn insert_cached(&self, file: File) -> Option<File> {
     self.cache.insert(file.specifier.clone(), file)
@@ -8,6 +8,7 @@ use crate::cache;
 use crate::cache::TypeCheckCache;
 use crate::colors;
 use crate::errors::get_error_class_name;
+use crate::file_fetcher::FileFetcher;
 use crate::npm::resolve_graph_npm_info;
 use crate::npm::NpmPackageReference;
 use crate::npm::NpmPackageReq;
@@ -15,6 +16,10 @@ use crate::proc_state::ProcState;
 use crate::resolver::CliResolver;
 use crate::tools::check;
 
+use deno_ast::swc::parser::token::Keyword;
+use deno_ast::swc::parser::token::Token;
+use deno_ast::swc::parser::token::Word;
+use deno_ast::TokenOrComment;
 use deno_core::anyhow::bail;
 use deno_core::error::custom_error;
 use deno_core::error::AnyError;
@@ -50,6 +55,10 @@ pub enum ModuleEntry {
     checked_libs: HashSet<TsTypeLib>,
     maybe_types: Option<Resolved>,
   },
+  /// A module which isn't analyzed and is loaded by the runtime from its
+  /// source bytes: a WebAssembly module or a module imported with a `"text"`
+  /// or `"bytes"` type assertion.
+  Raw(MediaType),
   Error(ModuleGraphError),
   Redirect(ModuleSpecifier),
 }
@@ -156,7 +165,10 @@ impl GraphData {
           self.modules.insert(specifier.clone(), module_entry);
         }
         Err(error) => {
-          let module_entry = ModuleEntry::Error(error.clone());
+          let module_entry = match raw_module_media_type(error) {
+            Some(media_type) => ModuleEntry::Raw(media_type),
+            None => ModuleEntry::Error(error.clone()),
+          };
           self.modules.insert(specifier.clone(), module_entry);
         }
       }
@@ -276,7 +288,7 @@ impl GraphData {
             }
           }
         }
-        ModuleEntry::Error(_) => {}
+        ModuleEntry::Raw(_) | ModuleEntry::Error(_) => {}
         ModuleEntry::Redirect(specifier) => {
           if !seen.contains(specifier) {
             seen.insert(specifier);
@@ -465,6 +477,22 @@ impl GraphData {
   }
 }
 
+/// Returns the media type of the module if the error is for a module which
+/// deno_graph doesn't support, but the runtime loads as a raw module.
+fn raw_module_media_type(error: &ModuleGraphError) -> Option<MediaType> {
+  match error {
+    ModuleGraphError::UnsupportedMediaType(_, MediaType::Wasm) => {
+      Some(MediaType::Wasm)
+    }
+    ModuleGraphError::UnsupportedImportAssertionType(specifier, kind)
+      if kind == "text" || kind == "bytes" =>
+    {
+      Some(MediaType::from(specifier))
+    }
+    _ => None,
+  }
+}
+
 impl From<&ModuleGraph> for GraphData {
   fn from(graph: &ModuleGraph) -> Self {
     let mut graph_data = GraphData::default();
@@ -484,25 +512,156 @@ pub fn graph_valid(
     .unwrap()
 }
 
+/// Returns the modules of the graph which deno_graph doesn't parse, but the
+/// runtime loads as raw modules, with their media types.
+pub fn graph_raw_modules(
+  graph: &ModuleGraph,
+) -> Vec<(&ModuleSpecifier, MediaType)> {
+  graph
+    .specifiers()
+    .filter_map(|(specifier, result)| match result {
+      Err(error) => raw_module_media_type(error).map(|m| (specifier, m)),
+      Ok(_) => None,
+    })
+    .collect()
+}
+
 /// Checks the lockfile against the graph and and exits on errors.
 pub fn graph_lock_or_exit(graph: &ModuleGraph, lockfile: &mut Lockfile) {
   for module in graph.modules() {
     if let Some(source) = &module.maybe_source {
-      if !lockfile.check_or_insert_remote(module.specifier.as_str(), source) {
-        let err = format!(
-          concat!(
-            "The source code is invalid, as it does not match the expected hash in the lock file.\n",
-            "  Specifier: {}\n",
-            "  Lock file: {}",
-          ),
-          module.specifier,
-          lockfile.filename.display(),
-        );
-        log::error!("{} {}", colors::red("error:"), err);
-        std::process::exit(10);
+      lock_or_exit(&module.specifier, source.as_bytes(), lockfile);
+    }
+  }
+}
+
+/// Checks the lockfile against the contents of the raw modules in the graph,
+/// which deno_graph has no source for, and exits on errors.
+pub fn graph_raw_modules_lock_or_exit(
+  graph: &ModuleGraph,
+  file_fetcher: &FileFetcher,
+  lockfile: &mut Lockfile,
+) -> Result<(), AnyError> {
+  for (specifier, _) in graph_raw_modules(graph) {
+    if matches!(specifier.scheme(), "http" | "https") {
+      let bytes = file_fetcher.get_source_bytes(specifier)?;
+      lock_or_exit(specifier, &bytes, lockfile);
+    }
+  }
+  Ok(())
+}
+
+fn lock_or_exit(
+  specifier: &ModuleSpecifier,
+  source: &[u8],
+  lockfile: &mut Lockfile,
+) {
+  if !lockfile.check_or_insert_remote(specifier.as_str(), source) {
+    let err = format!(
+      concat!(
+        "The source code is invalid, as it does not match the expected hash in the lock file.\n",
+        "  Specifier: {}\n",
+        "  Lock file: {}",
+      ),
+      specifier,
+      lockfile.filename.display(),
+    );
+    log::error!("{} {}", colors::red("error:"), err);
+    std::process::exit(10);
+  }
+}
+
+/// Rewrites the `with` keyword of import attributes to `assert`, because the
+/// parser and V8 only support the import assertions syntax. Returns `None` if
+/// the source has no import attributes.
+pub fn rewrite_import_attributes(
+  source: &str,
+  media_type: MediaType,
+) -> Option<String> {
+  match media_type {
+    MediaType::JavaScript
+    | MediaType::Jsx
+    | MediaType::Mjs
+    | MediaType::Cjs
+    | MediaType::TypeScript
+    | MediaType::Mts
+    | MediaType::Cts
+    | MediaType::Dts
+    | MediaType::Dmts
+    | MediaType::Dcts
+    | MediaType::Tsx => {}
+    _ => return None,
+  }
+  if !source.contains("with") {
+    return None;
+  }
+  let tokens = deno_ast::lex(source, media_type)
+    .into_iter()
+    .filter_map(|item| match item.inner {
+      TokenOrComment::Token(token) => Some((token, item.range)),
+      TokenOrComment::Comment { .. } => None,
+    })
+    .collect::<Vec<_>>();
+  let mut ranges = Vec::new();
+  for (i, (token, range)) in tokens.iter().enumerate() {
+    if !is_keyword(token, Keyword::With) {
+      continue;
+    }
+    let prev = |n: usize| i.checked_sub(n).map(|i| &tokens[i].0);
+    let next = tokens.get(i + 1).map(|(token, _)| token);
+    // `import "./a.json" with {` and `from "./a.json" with {`
+    let is_static = matches!(next, Some(Token::LBrace))
+      && matches!(prev(1), Some(Token::Str { .. }))
+      && match prev(2) {
+        Some(Token::Word(Word::Ident(ident))) => *ident == *"from",
+        Some(token) => is_keyword(token, Keyword::Import),
+        None => false,
+      };
+    // `import("./a.json", { with: {`
+    let is_dynamic = matches!(next, Some(Token::Colon))
+      && matches!(prev(1), Some(Token::LBrace))
+      && matches!(prev(2), Some(Token::Comma))
+      && is_import_call_argument(&tokens[..i - 2]);
+    if is_static || is_dynamic {
+      ranges.push(range.clone());
+    }
+  }
+  if ranges.is_empty() {
+    return None;
+  }
+  let mut source = source.to_string();
+  for range in ranges.into_iter().rev() {
+    source.replace_range(range, "assert");
+  }
+  Some(source)
+}
+
+/// Returns whether the tokens end inside the arguments of an `import()` call.
+fn is_import_call_argument(tokens: &[(Token, std::ops::Range<usize>)]) -> bool {
+  let mut depth = 0;
+  for (i, (token, _)) in tokens.iter().enumerate().rev() {
+    match token {
+      Token::RParen | Token::RBracket | Token::RBrace => depth += 1,
+      Token::LParen | Token::LBracket | Token::LBrace | Token::DollarLBrace
+        if depth > 0 =>
+      {
+        depth -= 1
+      }
+      Token::LParen => {
+        return i > 0 && is_keyword(&tokens[i - 1].0, Keyword::Import);
       }
+      Token::LBracket | Token::LBrace | Token::DollarLBrace => return false,
+      _ => {}
     }
   }
+  false
+}
+
+fn is_keyword(token: &Token, keyword: Keyword) -> bool {
+  match token {
+    Token::Word(Word::Keyword(k)) => *k == keyword,
+    _ => false,
+  }
 }
 
 pub async fn create_graph_and_maybe_check(
@@ -552,7 +711,9 @@ pub async fn create_graph_and_maybe_check(
     .add_package_reqs(graph_data.npm_package_reqs().clone())
     .await?;
   if let Some(lockfile) = &ps.lockfile {
-    graph_lock_or_exit(&graph, &mut lockfile.lock());
+    let mut lockfile = lockfile.lock();
+    graph_lock_or_exit(&graph, &mut lockfile);
+    graph_raw_modules_lock_or_exit(&graph, &ps.file_fetcher, &mut lockfile)?;
   }
 
   if ps.options.type_check_mode() != TypeCheckMode::None {
@@ -688,6 +849,8 @@ mod test {
   use deno_graph::SpecifierError;
 
   use crate::graph_util::get_resolution_error_bare_node_specifier;
+  use crate::graph_util::rewrite_import_attributes;
+  use deno_graph::MediaType;
 
   #[test]
   fn import_map_node_resolution_error() {
@@ -727,4 +890,43 @@ mod test {
       assert_eq!(get_resolution_error_bare_node_specifier(&err), output,);
     }
   }
+
+  #[test]
+  fn rewrite_import_attributes_to_assertions() {
+    let source = r#"import data from "./data.json" with { type: "json" };
+import "./side_effect.json" with { type: "json" };
+export { default as text } from "./text.txt" with { type: "text" };
+const bytes = await import("./data.bin", { with: { type: "bytes" } });
+// import a from "./a.json" with { type: "json" };
+with (data) {}
+const options = { with: 1 };
+f("./a.json", { with: { type: "json" } });
+"#;
+    assert_eq!(
+      rewrite_import_attributes(source, MediaType::JavaScript).unwrap(),
+      r#"import data from "./data.json" assert { type: "json" };
+import "./side_effect.json" assert { type: "json" };
+export { default as text } from "./text.txt" assert { type: "text" };
+const bytes = await import("./data.bin", { assert: { type: "bytes" } });
+// import a from "./a.json" with { type: "json" };
+with (data) {}
+const options = { with: 1 };
+f("./a.json", { with: { type: "json" } });
+"#
+    );
+    assert_eq!(
+      rewrite_import_attributes(
+        r#"import data from "./data.json" assert { type: "json" };"#,
+        MediaType::TypeScript
+      ),
+      None
+    );
+    assert_eq!(
+      rewrite_import_attributes(
+        r#"import data from "./a.json" with { type: "json" };"#,
+        MediaType::Json
+      ),
+      None
+    );
+  }
 }
@@ -4,10 +4,19 @@
 import { primordials } from '../../core/00_primordials.js';
 import * as core from '../../core/01_core.js';
 import * as ops from '../../ops/index.js';
-import { Console } from '../../ext/console/02_console.js';
+import { Console, inspect } from '../../ext/console/02_console.js';
 import { serializePermissions } from '../../runtime/js/10_permissions.js';
-import { assert } from '../../ext/web/00_infra.js';
+import { assert, AssertionError } from '../../ext/web/00_infra.js';
 import { setExitHandler } from '../../runtime/js/30_os.js';
+import {
+  clearTimeout,
+  FakeClock,
+  installFakeClock,
+  restoreFakeClock,
+  runAllFakeTimers,
+  setTimeout,
+  tickFakeClock,
+} from '../../ext/web/02_timers.js';
 
 const {
   ArrayFrom,
@@ -16,20 +25,29 @@ const {
   ArrayPrototypeMap,
   ArrayPrototypePush,
   ArrayPrototypeShift,
+  ArrayPrototypeSome,
   ArrayPrototypeSort,
   BigInt,
   DateNow,
+  DatePrototype,
+  DatePrototypeGetTime,
   Error,
   FunctionPrototype,
   Map,
+  MapPrototypeDelete,
   MapPrototypeGet,
   MapPrototypeHas,
   MapPrototypeSet,
   MathCeil,
+  MathMin,
+  NumberIsFinite,
+  NumberIsInteger,
   ObjectKeys,
   ObjectPrototypeHasOwnProperty,
   ObjectPrototypeIsPrototypeOf,
   Promise,
+  PromisePrototypeThen,
+  PromiseRace,
   SafeArrayIterator,
   Set,
   SymbolToStringTag,
@@ -140,6 +158,11 @@ const OP_DETAILS = {
 // completed ops after the test is the same as number of dispatched
 // ops. Note that "unref" ops are ignored since in nature that are
 // optional.
+// Async ops which were still pending when a test timed out, by op name. Such
+// tests keep running in the background, so their ops may complete during a
+// later test and must not be reported by that test's op sanitizer.
+const timedOutOps = new Map();
+
 function assertOps(fn) {
   /** @param desc {TestDescription | TestStepDescription} */
   return async function asyncOpSanitizer(desc: TestDescription | TestStepDescription) {
@@ -165,7 +188,7 @@ function assertOps(fn) {
     const dispatchedDiff = post.opsDispatchedAsync - pre.opsDispatchedAsync;
     const completedDiff = post.opsCompletedAsync - pre.opsCompletedAsync;
 
-    if (dispatchedDiff === completedDiff) return;
+    if (dispatchedDiff === completedDiff && timedOutOps.size === 0) return;
 
     const details = [];
     for (const key in post.ops) {
@@ -177,34 +200,30 @@ function assertOps(fn) {
       const postOp = post.ops[key];
       const dispatchedDiff = postOp.opsDispatchedAsync -
         preOp.opsDispatchedAsync;
-      const completedDiff = postOp.opsCompletedAsync -
+      let completedDiff = postOp.opsCompletedAsync -
         preOp.opsCompletedAsync;
 
-      if (dispatchedDiff > completedDiff) {
-        const [name, hint] = OP_DETAILS[key] || [key, null];
-        const count = dispatchedDiff - completedDiff;
-        let message = `${count} async operation${
-          count === 1 ? "" : "s"
-        } to ${name} ${
-          count === 1 ? "was" : "were"
-        } started in this test, but never completed.`;
-        if (hint) {
-          message += ` This is often caused by not ${hint}.`;
+      const timedOutCount = MapPrototypeGet(timedOutOps, key) ?? 0;
+      if (completedDiff > dispatchedDiff && timedOutCount > 0) {
+        const count = MathMin(completedDiff - dispatchedDiff, timedOutCount);
+        completedDiff -= count;
+        if (count === timedOutCount) {
+          MapPrototypeDelete(timedOutOps, key);
+        } else {
+          MapPrototypeSet(timedOutOps, key, timedOutCount - count);
         }
-        const traces = [];
-        for (const [id, { opName, stack }] of postTraces) {
-          if (opName !== key) continue;
-          if (MapPrototypeHas(preTraces, id)) continue;
-          ArrayPrototypePush(traces, stack);
-        }
-        if (traces.length === 1) {
-          message += " The operation was started here:\n";
-          message += traces[0];
-        } else if (traces.length > 1) {
-          message += " The operations were started here:\n";
-          message += ArrayPrototypeJoin(traces, "\n\n");
-        }
-        ArrayPrototypePush(details, message);
+      }
+
+      if (dispatchedDiff > completedDiff) {
+        ArrayPrototypePush(
+          details,
+          pendingOpMessage(
+            key,
+            dispatchedDiff - completedDiff,
+            preTraces,
+            postTraces,
+          ),
+        );
       } else if (dispatchedDiff < completedDiff) {
         const [name, hint] = OP_DETAILS[key] || [key, null];
         const count = completedDiff - dispatchedDiff;
@@ -220,6 +239,8 @@ function assertOps(fn) {
       }
     }
 
+    if (details.length === 0) return;
+
     let msg = `Test case is leaking async ops.
 
 - ${ArrayPrototypeJoin(details, "\n - ")}`;
@@ -235,6 +256,80 @@ function assertOps(fn) {
   };
 }
 
+/**
+ * Describes the async ops of the given kind which were started after the
+ * `preTraces` were taken and haven't completed.
+ */
+function pendingOpMessage(key, count, preTraces, postTraces) {
+  const [name, hint] = OP_DETAILS[key] || [key, null];
+  let message = `${count} async operation${
+    count === 1 ? "" : "s"
+  } to ${name} ${
+    count === 1 ? "was" : "were"
+  } started in this test, but never completed.`;
+  if (hint) {
+    message += ` This is often caused by not ${hint}.`;
+  }
+  const traces = [];
+  for (const [id, { opName, stack }] of postTraces) {
+    if (opName !== key) continue;
+    if (MapPrototypeHas(preTraces, id)) continue;
+    ArrayPrototypePush(traces, stack);
+  }
+  if (traces.length === 1) {
+    message += " The operation was started here:\n";
+    message += traces[0];
+  } else if (traces.length > 1) {
+    message += " The operations were started here:\n";
+    message += ArrayPrototypeJoin(traces, "\n\n");
+  }
+  return message;
+}
+
+/**
+ * The error of a test which didn't finish within its timeout, listing the
+ * async ops started by the test which are still pending. Those ops are
+ * recorded in `timedOutOps` so that later tests don't report them.
+ */
+function timeoutError(timeout, pre, preTraces) {
+  const post = core.metrics();
+  const postTraces = new Map(core.opCallTraces);
+  const details = [];
+  for (const key in post.ops) {
+    if (!ObjectPrototypeHasOwnProperty(post.ops, key)) {
+      continue;
+    }
+    const preOp = pre.ops[key] ??
+      { opsDispatchedAsync: 0, opsCompletedAsync: 0 };
+    const postOp = post.ops[key];
+    const count = (postOp.opsDispatchedAsync - preOp.opsDispatchedAsync) -
+      (postOp.opsCompletedAsync - preOp.opsCompletedAsync);
+    if (count > 0) {
+      MapPrototypeSet(
+        timedOutOps,
+        key,
+        (MapPrototypeGet(timedOutOps, key) ?? 0) + count,
+      );
+      ArrayPrototypePush(
+        details,
+        pendingOpMessage(key, count, preTraces, postTraces),
+      );
+    }
+  }
+
+  let msg = `Test timed out after ${timeout}ms.`;
+  if (details.length > 0) {
+    msg += ` It was waiting for these async ops:
+
+- ${ArrayPrototypeJoin(details, "\n- ")}`;
+    if (!core.isOpCallTracingEnabled()) {
+      msg +=
+        `\n\nTo get more details where the ops were started, run again with --trace-ops flag.`;
+    }
+  }
+  return new Error(msg);
+}
+
 function prettyResourceNames(name) {
   switch (name) {
     case "fsFile":
@@ -390,6 +485,8 @@ function assertResources(fn) {
     ]);
 
     const details = [];
+    let traces = null;
+    let untraced = false;
     for (const resource of allResources) {
       const preResource = pre[resource];
       const postResource = post[resource];
@@ -398,8 +495,15 @@ function assertResources(fn) {
       if (preResource === undefined) {
         const [name, action1, action2] = prettyResourceNames(postResource);
         const hint = resourceCloseHint(postResource);
-        const detail =
+        let detail =
           `${name} (rid ${resource}) was ${action1} during the test, but not ${action2} during the test. ${hint}`;
+        traces ??= core.resourceTraces();
+        const trace = traces[resource];
+        if (trace !== undefined) {
+          detail += ` The resource was created here:\n${trace}`;
+        } else {
+          untraced = true;
+        }
         ArrayPrototypePush(details, detail);
       } else {
         const [name, action1, action2] = prettyResourceNames(preResource);
@@ -409,12 +513,16 @@ function assertResources(fn) {
       }
     }
 
-    const message = `Test case is leaking ${details.length} resource${
+    let message = `Test case is leaking ${details.length} resource${
       details.length === 1 ? "" : "s"
     }:
 
 - ${details.join("\n - ")}
 `;
+    if (untraced && !core.isOpCallTracingEnabled()) {
+      message +=
+        `\nTo get more details where resources were leaked, run again with --trace-ops flag.`;
+    }
     assert(details.length === 0, message);
   };
 }
@@ -442,6 +550,28 @@ function assertExit(fn, isTest) {
   };
 }
 
+// Wrap test function so that the time faked with `t.useFakeTime()` is
+// restored when the test or step ends, before the sanitizers run, which rely
+// on real timers.
+function restoreFakeTime(fn) {
+  /** @param desc {TestDescription | TestStepDescription} */
+  return async function fakeTimeRestorer(desc: TestDescription | TestStepDescription) {
+    try {
+      await fn(desc);
+    } finally {
+      restoreTestFakeClock(desc);
+    }
+  };
+}
+
+/** @param desc {TestDescription | TestStepDescription} */
+function restoreTestFakeClock(desc: TestDescription | TestStepDescription) {
+  const clock = MapPrototypeGet(testStates, desc.id).fakeClock;
+  if (clock !== undefined) {
+    restoreFakeClock(clock);
+  }
+}
+
 function assertTestStepScopes(fn) {
   /** @param desc {TestDescription | TestStepDescription} */
   return async function testStepSanitizer(desc: TestDescription | TestStepDescription) {
@@ -569,6 +699,8 @@ export type BenchFunction = (...args: any[]) => any;
  *   sanitizeResources: boolean,
  *   sanitizeExit: boolean,
  *   permissions: PermissionOptions,
+ *   timeout?: number,
+ *   retries?: number,
  * }} TestDescription
  */
 export interface TestDescription {
@@ -584,6 +716,8 @@ export interface TestDescription {
   sanitizeResources: boolean;
   sanitizeExit: boolean;
   permissions: Deno.PermissionOptions;
+  timeout?: number;
+  retries?: number;
 }
 /**
  * @typedef {{
@@ -622,12 +756,14 @@ export interface TestStepDescription {
  *   context: Deno.TestContext,
  *   children: TestStepDescription[],
  *   finalized: boolean,
+ *   fakeClock?: FakeClock,
  * }} TestState
  */
 export interface TestState {
   context: Deno.TestContext;
   children: TestStepDescription[];
   finalized: boolean;
+  fakeClock?: FakeClock;
 }
 /**
  * @typedef {{
@@ -639,6 +775,7 @@ export interface TestState {
  *   elapsed: number | null,
  *   reportedWait: boolean,
  *   reportedResult: boolean,
+ *   fakeClock?: FakeClock,
  * }} TestStepState
  */
 export interface TestStepState {
@@ -650,6 +787,7 @@ export interface TestStepState {
   elapsed: number | null;
   reportedWait: boolean;
   reportedResult: boolean;
+  fakeClock?: FakeClock;
 }
 /**
  * @typedef {{
@@ -682,6 +820,8 @@ const testDescs: TestDescription[] = [];
 const testStates: Map<number, TestState | TestStepState> = new Map();
 /** @type {BenchDescription[]} */
 const benchDescs: BenchDescription[] = [];
+/** @type {Map<number, number>} */
+const snapshotCounts: Map<number, number> = new Map();
 let isTestSubcommand = false;
 let isBenchSubcommand = false;
 
@@ -775,6 +915,21 @@ export function test(
     testDesc = { ...defaults, ...nameOrFnOrOptions, fn, name };
   }
 
+  if (
+    testDesc.timeout != null &&
+    !(typeof testDesc.timeout === "number" && testDesc.timeout > 0)
+  ) {
+    throw new TypeError(
+      "The 'timeout' option must be a positive number of milliseconds.",
+    );
+  }
+  if (
+    testDesc.retries != null &&
+    !(NumberIsInteger(testDesc.retries) && testDesc.retries >= 0)
+  ) {
+    throw new TypeError("The 'retries' option must be a non-negative integer.");
+  }
+
   // Delete this prop in case the user passed it. It's used to detect steps.
   delete testDesc.parent;
   testDesc.fn = wrapTestFnWithSanitizers(testDesc.fn, testDesc);
@@ -904,13 +1059,35 @@ export function bench(
   ArrayPrototypePush(benchDescs, benchDesc);
 }
 
-async function runTest(desc) {
+async function runTest(desc, timeout) {
   if (desc.ignore) {
     return "ignored";
   }
 
+  const pre = core.metrics();
+  const preTraces = new Map(core.opCallTraces);
+  // the timer is started before the test, so that the op sanitizer doesn't
+  // consider it to be leaked by the test
+  let timeoutId = null;
+  const timeoutPromise = timeout == null ? null : new Promise((resolve) => {
+    timeoutId = setTimeout(() => {
+      timeoutId = null;
+      resolve(true);
+    }, timeout);
+  });
+
   try {
-    await desc.fn(desc);
+    if (timeoutPromise === null) {
+      await desc.fn(desc);
+    } else {
+      const timedOut = await PromiseRace([
+        PromisePrototypeThen(desc.fn(desc), () => false),
+        timeoutPromise,
+      ]);
+      if (timedOut) {
+        throw timeoutError(timeout, pre, preTraces);
+      }
+    }
     const failCount = failedChildStepsCount(desc);
     return failCount === 0 ? "ok" : {
       "failed": core.destructureError(
@@ -924,6 +1101,13 @@ async function runTest(desc) {
       "failed": core.destructureError(error),
     };
   } finally {
+    // a test which timed out may still be running with a fake clock
+    restoreTestFakeClock(desc);
+    if (timeoutId !== null) {
+      clearTimeout(timeoutId);
+      // let the cancelled timer complete before the next test starts
+      await opSanitizerDelay();
+    }
     const state = MapPrototypeGet(testStates, desc.id);
     state.finalized = true;
     // ensure the children report their result
@@ -933,6 +1117,27 @@ async function runTest(desc) {
   }
 }
 
+/**
+ * Runs a test up to `1 + retries` times until it passes. The failed attempts
+ * are reported with `retry` events, so that a test which eventually passes is
+ * reported as flaky.
+ */
+async function runTestWithRetries(desc, timeout, retries) {
+  for (let attempt = 1;; attempt++) {
+    const result = await runTest(desc, desc.timeout ?? timeout);
+    if (typeof result === "string" || attempt > (desc.retries ?? retries)) {
+      return result;
+    }
+    ops.op_dispatch_test_event({ retry: [desc.id, attempt] });
+    MapPrototypeSet(testStates, desc.id, {
+      context: createTestContext(desc),
+      children: [],
+      finalized: false,
+    });
+    MapPrototypeDelete(snapshotCounts, desc.id);
+  }
+}
+
 function compareMeasurements(a, b) {
   if (a > b) return 1;
   if (a < b) return -1;
@@ -1124,6 +1329,8 @@ export function enableBench() {
 
 export async function runTests({
   shuffle = null,
+  timeout = null,
+  retries = 0,
 } = {}) {
   core.setMacrotaskCallback(handleOpSanitizerDelayMacrotask);
 
@@ -1163,18 +1370,25 @@ export async function runTests({
     }
   }
 
+  let stopped = false;
   for (const desc of filtered) {
     if (ops.op_tests_should_stop()) {
+      stopped = true;
       break;
     }
     ops.op_dispatch_test_event({ wait: desc.id });
     const earlier = DateNow();
-    const result = await runTest(desc);
+    const result = await runTestWithRetries(desc, timeout, retries);
     const elapsed = DateNow() - earlier;
     ops.op_dispatch_test_event({
       result: [desc.id, result, elapsed],
     });
   }
+
+  // snapshots which weren't asserted are only obsolete if every test ran
+  const complete = !stopped && filtered.length === testDescs.length &&
+    !ArrayPrototypeSome(filtered, (desc) => desc.ignore);
+  ops.op_save_snapshots(complete);
 }
 
 export async function runBenchmarks() {
@@ -1307,6 +1521,35 @@ function shouldSkipSanitizers(desc) {
   }
 }
 
+/**
+ * The name of the next snapshot of a test or step, which is its full name
+ * followed by the number of the assertion.
+ * @param desc {TestDescription | TestStepDescription}
+ */
+function snapshotName(desc: TestDescription | TestStepDescription) {
+  let name = desc.name;
+  let current = desc;
+  while ("parent" in current) {
+    current = current.parent;
+    name = `${current.name} > ${name}`;
+  }
+  const count = (MapPrototypeGet(snapshotCounts, desc.id) ?? 0) + 1;
+  MapPrototypeSet(snapshotCounts, desc.id, count);
+  return `${name} ${count}`;
+}
+
+/** @param value {unknown} */
+function serializeSnapshot(value: unknown): string {
+  return inspect(value, {
+    depth: Infinity,
+    sorted: true,
+    trailingComma: true,
+    compact: false,
+    iterableLimit: Infinity,
+    strAbbreviateSize: Infinity,
+  });
+}
+
 /** @param desc {TestDescription | TestStepDescription} */
 function createTestContext(desc: TestDescription | TestStepDescription) {
   let parent;
@@ -1338,6 +1581,58 @@ function createTestContext(desc: TestDescription | TestStepDescription) {
      * File Uri of the test code.
      */
     origin: desc.origin,
+    /**
+     * @param actual {unknown}
+     */
+    assertSnapshot(actual: unknown) {
+      const message = ops.op_assert_snapshot(
+        snapshotName(desc),
+        serializeSnapshot(actual),
+      );
+      if (message !== null) {
+        throw new AssertionError(message);
+      }
+    },
+    /**
+     * @param start {number | Date | undefined}
+     */
+    useFakeTime(start?: number | Date): Deno.FakeTime {
+      let dateNow;
+      if (start === undefined) {
+        dateNow = DateNow();
+      } else if (ObjectPrototypeIsPrototypeOf(DatePrototype, start)) {
+        dateNow = DatePrototypeGetTime(start);
+      } else {
+        dateNow = start;
+      }
+      if (typeof dateNow !== "number" || !NumberIsFinite(dateNow)) {
+        throw new TypeError(
+          "The start time must be a finite number or a valid Date.",
+        );
+      }
+      const clock = installFakeClock(dateNow);
+      MapPrototypeGet(testStates, desc.id).fakeClock = clock;
+      return {
+        [SymbolToStringTag]: "Deno.FakeTime",
+        get now() {
+          return clock.now + clock.dateOffset;
+        },
+        tick(ms = 0) {
+          if (typeof ms !== "number" || !NumberIsFinite(ms) || ms < 0) {
+            throw new TypeError(
+              "The time to advance must be a non-negative number.",
+            );
+          }
+          tickFakeClock(clock, ms);
+        },
+        runAll() {
+          runAllFakeTimers(clock);
+        },
+        restore() {
+          restoreFakeClock(clock);
+        },
+      };
+    },
     /**
      * @param nameOrFnOrOptions {string | TestStepDefinition | ((t: TestContext) => void | Promise<void>)}
      * @param maybeFn {((t: TestContext) => void | Promise<void>) | undefined}
@@ -1481,7 +1776,7 @@ function wrapTestFnWithSanitizers<T = Function>(testFn: T, opts: {
     sanitizeResources: boolean;
     sanitizeExit: boolean;
   }): T {
-  testFn = assertTestStepScopes(testFn) as T;
+  testFn = restoreFakeTime(assertTestStepScopes(testFn)) as T;
 
   if (opts.sanitizeOps) {
     testFn = assertOps(testFn) as T;
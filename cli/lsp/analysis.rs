@@ -3,9 +3,12 @@
 use super::diagnostics::DenoDiagnostic;
 use super::documents::Documents;
 use super::language_server;
+use super::text::LineIndex;
 use super::tsc;
 
 use crate::tools::lint::create_linter;
+use crate::tools::lint::get_lint_fix;
+use crate::tools::lint::lint_with_plugins;
 
 use deno_ast::SourceRange;
 use deno_ast::SourceRangedForSpanned;
@@ -122,9 +125,22 @@ fn as_lsp_range(range: &deno_lint::diagnostic::Range) -> Range {
 pub fn get_lint_references(
   parsed_source: &deno_ast::ParsedSource,
   lint_rules: Vec<Arc<dyn LintRule>>,
+  lint_plugins: &[ModuleSpecifier],
 ) -> Result<Vec<Reference>, AnyError> {
   let linter = create_linter(parsed_source.media_type(), lint_rules);
-  let lint_diagnostics = linter.lint_with_ast(parsed_source);
+  let mut lint_diagnostics = linter.lint_with_ast(parsed_source);
+  if let Err(err) = lint_with_plugins(
+    lint_plugins,
+    parsed_source,
+    parsed_source.specifier(),
+    &mut lint_diagnostics,
+  ) {
+    lsp_log!(
+      "Failed to run lint plugins on \"{}\": {:#}",
+      parsed_source.specifier(),
+      err
+    );
+  }
 
   Ok(
     lint_diagnostics
@@ -372,6 +388,84 @@ impl CodeActionCollection {
     Ok(())
   }
 
+  /// Add the fix of a lint diagnostic, if the rule has one.
+  pub fn add_deno_lint_fix_action(
+    &mut self,
+    specifier: &ModuleSpecifier,
+    diagnostic: &lsp::Diagnostic,
+    line_index: &LineIndex,
+    maybe_parsed_source: Option<deno_ast::ParsedSource>,
+  ) {
+    let parsed_source = match maybe_parsed_source {
+      Some(parsed_source) => parsed_source,
+      None => return,
+    };
+    // The client's positions are in UTF-16 code units and may be stale, so
+    // they are converted with the line index and checked against the text.
+    let byte_index = |position: Position| {
+      let offset = line_index.offset(position).ok()?;
+      let next_line_offset = line_index
+        .offset(Position {
+          line: position.line + 1,
+          character: 0,
+        })
+        .ok()?;
+      (offset <= next_line_offset).then(|| u32::from(offset) as usize)
+    };
+    let range = match (
+      byte_index(diagnostic.range.start),
+      byte_index(diagnostic.range.end),
+    ) {
+      (Some(start), Some(end)) => start..end,
+      _ => return,
+    };
+    let code = code_as_string(&diagnostic.code);
+    let fix =
+      match get_lint_fix(&parsed_source, &code, &diagnostic.message, range) {
+        Some(fix) => fix,
+        None => return,
+      };
+
+    let text_info = parsed_source.text_info();
+    let position = |byte_index: usize| {
+      let start = text_info.range().start;
+      let line = text_info.line_index(start + byte_index);
+      let line_start = text_info.line_start(line).as_byte_index(start);
+      Position {
+        line: line as u32,
+        character: text_info.text_str()[line_start..byte_index]
+          .encode_utf16()
+          .count() as u32,
+      }
+    };
+    let mut changes = HashMap::new();
+    changes.insert(
+      specifier.clone(),
+      vec![lsp::TextEdit {
+        new_text: fix.new_text,
+        range: Range {
+          start: position(fix.range.start),
+          end: position(fix.range.end),
+        },
+      }],
+    );
+    let fix_action = lsp::CodeAction {
+      title: fix.description,
+      kind: Some(lsp::CodeActionKind::QUICKFIX),
+      diagnostics: Some(vec![diagnostic.clone()]),
+      command: None,
+      is_preferred: Some(true),
+      disabled: None,
+      data: None,
+      edit: Some(lsp::WorkspaceEdit {
+        changes: Some(changes),
+        change_annotations: None,
+        document_changes: None,
+      }),
+    };
+    self.actions.push(CodeActionKind::DenoLint(fix_action));
+  }
+
   pub fn add_deno_lint_ignore_action(
     &mut self,
     specifier: &ModuleSpecifier,
@@ -796,6 +890,66 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_add_deno_lint_fix_action() {
+    let text = "let s = \"\u{1F600}\";;\n";
+    let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+      specifier: "file:///a.ts".to_string(),
+      text_info: SourceTextInfo::from_string(text.to_string()),
+      media_type: deno_ast::MediaType::TypeScript,
+      capture_tokens: true,
+      scope_analysis: false,
+      maybe_syntax: None,
+    })
+    .unwrap();
+    let line_index = LineIndex::new(text);
+    let specifier = ModuleSpecifier::parse("file:///a.ts").unwrap();
+    let diagnostic = |start: u32, end: u32| lsp::Diagnostic {
+      range: Range {
+        start: Position {
+          line: 0,
+          character: start,
+        },
+        end: Position {
+          line: 0,
+          character: end,
+        },
+      },
+      code: Some(lsp::NumberOrString::String("no-extra-semi".to_string())),
+      source: Some("deno-lint".to_string()),
+      message: "Unnecessary semicolon.".to_string(),
+      ..Default::default()
+    };
+
+    // the emoji is two UTF-16 code units and four bytes
+    let mut actions = CodeActionCollection::default();
+    actions.add_deno_lint_fix_action(
+      &specifier,
+      &diagnostic(13, 14),
+      &line_index,
+      Some(parsed_source.clone()),
+    );
+    assert_eq!(actions.actions.len(), 1);
+    let edit = match &actions.actions[0] {
+      CodeActionKind::DenoLint(action) => {
+        action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&specifier][0]
+          .clone()
+      }
+      _ => unreachable!(),
+    };
+    assert_eq!(edit.new_text, "");
+    assert_eq!(edit.range, diagnostic(13, 14).range);
+
+    let mut actions = CodeActionCollection::default();
+    actions.add_deno_lint_fix_action(
+      &specifier,
+      &diagnostic(40, 41),
+      &line_index,
+      Some(parsed_source),
+    );
+    assert!(actions.actions.is_empty());
+  }
+
   #[test]
   fn test_as_lsp_range() {
     let fixture = deno_lint::diagnostic::Range {
@@ -18,6 +18,7 @@ use crate::graph_util::enhanced_resolution_error_message;
 use crate::node;
 use crate::npm::NpmPackageReference;
 use crate::tools::lint::get_configured_rules;
+use crate::util::path::specifier_to_file_path;
 
 use deno_ast::MediaType;
 use deno_core::anyhow::anyhow;
@@ -455,7 +456,6 @@ async fn generate_lint_diagnostics(
 ) -> DiagnosticVec {
   let documents = snapshot.documents.documents(true, true);
   let workspace_settings = config.settings.workspace.clone();
-  let lint_rules = get_configured_rules(lint_options.rules.clone());
   let mut diagnostics_vec = Vec::new();
   if workspace_settings.lint {
     for document in documents {
@@ -472,13 +472,18 @@ async fn generate_lint_diagnostics(
       }
 
       let version = document.maybe_lsp_version();
+      let lint_options = match specifier_to_file_path(document.specifier()) {
+        Ok(path) => lint_options.for_path(&path),
+        Err(_) => lint_options,
+      };
+      let lint_rules = get_configured_rules(lint_options.rules.clone());
       diagnostics_vec.push((
         document.specifier().clone(),
         version,
         generate_document_lint_diagnostics(
           config,
           lint_options,
-          lint_rules.clone(),
+          lint_rules,
           &document,
         ),
       ));
@@ -501,9 +506,11 @@ fn generate_document_lint_diagnostics(
   }
   match document.maybe_parsed_source() {
     Some(Ok(parsed_source)) => {
-      if let Ok(references) =
-        analysis::get_lint_references(&parsed_source, lint_rules)
-      {
+      if let Ok(references) = analysis::get_lint_references(
+        &parsed_source,
+        lint_rules,
+        &lint_options.plugins,
+      ) {
         references
           .into_iter()
           .map(|r| r.to_diagnostic())
@@ -58,6 +58,7 @@ use super::tsc::TsServer;
 use super::urls;
 use crate::args::get_root_cert_store;
 use crate::args::resolve_import_map_from_specifier;
+use crate::args::resolve_workspace_members;
 use crate::args::CaData;
 use crate::args::CacheSetting;
 use crate::args::CliOptions;
@@ -766,18 +767,30 @@ impl Inner {
     self.lint_options = Default::default();
 
     if let Some(config_file) = self.get_config_file()? {
-      let lint_options = config_file
-        .to_lint_config()
-        .and_then(|maybe_lint_config| {
+      let resolve_lint_options = |config_file: &ConfigFile| {
+        config_file.to_lint_config().and_then(|maybe_lint_config| {
           LintOptions::resolve(maybe_lint_config, None)
         })
+      };
+      let resolve_fmt_options = |config_file: &ConfigFile| {
+        config_file.to_fmt_config().and_then(|maybe_fmt_config| {
+          FmtOptions::resolve(maybe_fmt_config, None)
+        })
+      };
+      let lint_options = resolve_lint_options(&config_file)
+        .and_then(|mut lint_options| {
+          lint_options.workspace_members =
+            resolve_workspace_members(&config_file, resolve_lint_options)?;
+          Ok(lint_options)
+        })
         .map_err(|err| {
           anyhow!("Unable to update lint configuration: {:?}", err)
         })?;
-      let fmt_options = config_file
-        .to_fmt_config()
-        .and_then(|maybe_fmt_config| {
-          FmtOptions::resolve(maybe_fmt_config, None)
+      let fmt_options = resolve_fmt_options(&config_file)
+        .and_then(|mut fmt_options| {
+          fmt_options.workspace_members =
+            resolve_workspace_members(&config_file, resolve_fmt_options)?;
+          Ok(fmt_options)
         })
         .map_err(|err| {
           anyhow!("Unable to update formatter configuration: {:?}", err)
@@ -1252,13 +1265,14 @@ impl Inner {
     })?;
 
     // skip formatting any files ignored by the config file
-    if !self.fmt_options.files.matches_specifier(&specifier) {
+    let fmt_options = self.fmt_options.for_path(&file_path);
+    if !fmt_options.files.matches_specifier(&specifier) {
       return Ok(None);
     }
 
     let format_result = match document.maybe_parsed_source() {
       Some(Ok(parsed_source)) => {
-        format_parsed_source(&parsed_source, &self.fmt_options.options)
+        format_parsed_source(&parsed_source, &fmt_options.options)
       }
       Some(Err(err)) => Err(anyhow!("{}", err)),
       None => {
@@ -1271,7 +1285,7 @@ impl Inner {
           .map(|ext| file_path.with_extension(ext))
           .unwrap_or(file_path);
         // it's not a js/ts file, so attempt to format its contents
-        format_file(&file_path, &document.content(), &self.fmt_options.options)
+        format_file(&file_path, &document.content(), &fmt_options.options)
       }
     };
 
@@ -1479,17 +1493,25 @@ impl Inner {
               error!("{}", err);
               LspError::internal_error()
             })?,
-          Some("deno-lint") => code_actions
-            .add_deno_lint_ignore_action(
+          Some("deno-lint") => {
+            code_actions.add_deno_lint_fix_action(
               &specifier,
               diagnostic,
-              asset_or_doc.document().map(|d| d.text_info()),
+              &asset_or_doc.line_index(),
               asset_or_doc.maybe_parsed_source().and_then(|r| r.ok()),
-            )
-            .map_err(|err| {
-              error!("Unable to fix lint error: {}", err);
-              LspError::internal_error()
-            })?,
+            );
+            code_actions
+              .add_deno_lint_ignore_action(
+                &specifier,
+                diagnostic,
+                asset_or_doc.document().map(|d| d.text_info()),
+                asset_or_doc.maybe_parsed_source().and_then(|r| r.ok()),
+              )
+              .map_err(|err| {
+                error!("Unable to fix lint error: {}", err);
+                LspError::internal_error()
+              })?
+          }
           _ => (),
         }
       }
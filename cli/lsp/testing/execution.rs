@@ -165,7 +165,7 @@ async fn test_specifier(
       &ps,
       specifier.clone(),
       PermissionsContainer::new(permissions),
-      vec![ops::testing::init(sender, fail_fast_tracker, filter)],
+      vec![ops::testing::init(sender, fail_fast_tracker, filter, false)],
       Stdio {
         stdin: StdioPipe::Inherit,
         stdout,
@@ -445,6 +445,9 @@ impl TestRun {
                 duration,
               );
             }
+            test::TestEvent::Retry(_, _) => {}
+            // snapshots aren't updated when testing from the editor
+            test::TestEvent::SnapshotsUpdated(_) => {}
           }
         }
 
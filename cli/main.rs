@@ -27,6 +27,8 @@ mod version;
 mod worker;
 
 use crate::args::flags_from_vec;
+use crate::args::resolve_options_with_env_file;
+use crate::args::set_env_vars;
 use crate::args::DenoSubcommand;
 use crate::args::Flags;
 use crate::proc_state::ProcState;
@@ -45,7 +47,16 @@ use deno_runtime::tokio_util::run_local;
 use std::env;
 use std::path::PathBuf;
 
-async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
+async fn run_subcommand(
+  flags: Flags,
+  maybe_cli_options: Option<CliOptions>,
+) -> Result<i32, AnyError> {
+  // the options of the subcommands that load a `.env` file were resolved
+  // before the runtime was built
+  let cli_options = move |flags: Flags| match maybe_cli_options {
+    Some(cli_options) => Ok(cli_options),
+    None => CliOptions::from_flags(flags),
+  };
   match flags.subcommand.clone() {
     DenoSubcommand::Bench(bench_flags) => {
       let cli_options = CliOptions::from_flags(flags)?;
@@ -128,14 +139,15 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
       tools::repl::run(flags, repl_flags).await
     }
     DenoSubcommand::Run(run_flags) => {
+      let cli_options = cli_options(flags)?;
       if run_flags.is_stdin() {
-        tools::run::run_from_stdin(flags).await
+        tools::run::run_from_stdin(cli_options).await
       } else {
-        tools::run::run_script(flags, run_flags).await
+        tools::run::run_script(cli_options, run_flags).await
       }
     }
     DenoSubcommand::Task(task_flags) => {
-      tools::task::execute_script(flags, task_flags).await
+      tools::task::execute_script(cli_options(flags)?, task_flags).await
     }
     DenoSubcommand::Test(test_flags) => {
       if let Some(ref coverage_dir) = flags.coverage_dir {
@@ -148,7 +160,7 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
           PathBuf::from(coverage_dir).canonicalize()?,
         );
       }
-      let cli_options = CliOptions::from_flags(flags)?;
+      let cli_options = cli_options(flags)?;
       let test_options = cli_options.resolve_test_options(test_flags)?;
 
       if cli_options.watch_paths().is_some() {
@@ -241,33 +253,51 @@ pub fn main() {
 
   let args: Vec<String> = env::args().collect();
 
-  let future = async move {
-    let standalone_res =
-      match standalone::extract_standalone(args.clone()).await {
-        Ok(Some((metadata, eszip))) => standalone::run(eszip, metadata).await,
-        Ok(None) => Ok(()),
-        Err(err) => Err(err),
-      };
+  let maybe_standalone =
+    unwrap_or_exit(run_local(standalone::extract_standalone(args.clone())));
+  if let Some((metadata, eszip)) = maybe_standalone {
+    set_env_vars(&metadata.env_vars);
     // TODO(bartlomieju): doesn't handle exit code set by the runtime properly
-    unwrap_or_exit(standalone_res);
+    unwrap_or_exit(run_local(standalone::run(eszip, metadata)));
+  }
 
-    let flags = match flags_from_vec(args) {
-      Ok(flags) => flags,
-      Err(err @ clap::Error { .. })
-        if err.kind() == clap::ErrorKind::DisplayHelp
-          || err.kind() == clap::ErrorKind::DisplayVersion =>
-      {
-        err.print().unwrap();
-        std::process::exit(0);
-      }
-      Err(err) => unwrap_or_exit(Err(AnyError::from(err))),
-    };
+  let flags = match flags_from_vec(args) {
+    Ok(flags) => flags,
+    Err(err @ clap::Error { .. })
+      if err.kind() == clap::ErrorKind::DisplayHelp
+        || err.kind() == clap::ErrorKind::DisplayVersion =>
+    {
+      err.print().unwrap();
+      std::process::exit(0);
+    }
+    Err(err) => unwrap_or_exit(Err(AnyError::from(err))),
+  };
 
-    init_v8_flags(&flags.v8_flags, get_v8_flags_from_env());
+  init_v8_flags(&flags.v8_flags, get_v8_flags_from_env());
 
-    util::logger::init(flags.log_level);
+  util::logger::init(flags.log_level);
 
-    run_subcommand(flags).await
+  // Setting environment variables isn't thread safe, so the variables of the
+  // `.env` file are set before the runtime that runs the subcommand is built.
+  let maybe_cli_options = unwrap_or_exit(resolve_options_with_env_file(&flags));
+
+  let future = async move {
+    if let Some(path) = &flags.permission_audit {
+      unwrap_or_exit(deno_runtime::permissions::set_permission_audit_log(path));
+    }
+    let permissions_learn = flags.permissions_learn;
+    if permissions_learn {
+      deno_runtime::permissions::start_learning_permissions(
+        flags.permissions_learn_config.clone(),
+      );
+    }
+
+    let result = run_subcommand(flags, maybe_cli_options).await;
+    if permissions_learn {
+      // Also printed by `Deno.exit()`, which doesn't return here.
+      deno_runtime::permissions::print_learned_permissions();
+    }
+    result
   };
 
   let exit_code = unwrap_or_exit(run_local(future));
@@ -28,8 +28,15 @@ use std::pin::Pin;
 use std::rc::Rc;
 use std::str;
 
+enum ModuleCode {
+  Text(String),
+  /// The source of a raw module, like a WebAssembly module, which isn't
+  /// necessarily valid UTF-8.
+  Bytes(Vec<u8>),
+}
+
 struct ModuleCodeSource {
-  pub code: String,
+  pub code: ModuleCode,
   pub found_url: ModuleSpecifier,
   pub media_type: MediaType,
 }
@@ -80,7 +87,9 @@ impl CliModuleLoader {
   ) -> Result<ModuleCodeSource, AnyError> {
     if specifier.as_str() == "node:module" {
       return Ok(ModuleCodeSource {
-        code: deno_runtime::deno_node::MODULE_ES_SHIM.to_string(),
+        code: ModuleCode::Text(
+          deno_runtime::deno_node::MODULE_ES_SHIM.to_string(),
+        ),
         found_url: specifier.to_owned(),
         media_type: MediaType::JavaScript,
       });
@@ -123,7 +132,15 @@ impl CliModuleLoader {
         self.ps.parsed_source_cache.free(specifier);
 
         Ok(ModuleCodeSource {
-          code,
+          code: ModuleCode::Text(code),
+          found_url,
+          media_type: *media_type,
+        })
+      }
+      Some(ModuleEntry::Raw(media_type)) => {
+        let bytes = self.ps.file_fetcher.get_source_bytes(&found_url)?;
+        Ok(ModuleCodeSource {
+          code: ModuleCode::Bytes(bytes),
           found_url,
           media_type: *media_type,
         })
@@ -181,28 +198,31 @@ impl CliModuleLoader {
         )?
       };
       ModuleCodeSource {
-        code,
+        code: ModuleCode::Text(code),
         found_url: specifier.clone(),
         media_type: MediaType::from(specifier),
       }
     } else {
       self.load_prepared_module(specifier, maybe_referrer)?
     };
-    let code = if self.ps.options.is_inspecting() {
+    let code = match code_source.code {
       // we need the code with the source map in order for
       // it to work with --inspect or --inspect-brk
-      code_source.code
-    } else {
+      ModuleCode::Text(code) if self.ps.options.is_inspecting() => {
+        code.into_bytes()
+      }
       // reduce memory and throw away the source map
       // because we don't need it
-      code_without_source_map(code_source.code)
+      ModuleCode::Text(code) => code_without_source_map(code).into_bytes(),
+      ModuleCode::Bytes(bytes) => bytes,
     };
     Ok(ModuleSource {
-      code: code.into_bytes().into_boxed_slice(),
+      code: code.into_boxed_slice(),
       module_url_specified: specifier.to_string(),
       module_url_found: code_source.found_url.to_string(),
       module_type: match code_source.media_type {
         MediaType::Json => ModuleType::Json,
+        MediaType::Wasm => ModuleType::Wasm,
         _ => ModuleType::JavaScript,
       },
     })
@@ -286,8 +306,10 @@ impl SourceMapGetter for CliModuleLoader {
       "wasm" | "file" | "http" | "https" | "data" | "blob" => (),
       _ => return None,
     }
-    let source = self.load_prepared_module(&specifier, None).ok()?;
-    source_map_from_code(&source.code)
+    match self.load_prepared_module(&specifier, None).ok()?.code {
+      ModuleCode::Text(code) => source_map_from_code(&code),
+      ModuleCode::Bytes(_) => None,
+    }
   }
 
   fn get_source_line(
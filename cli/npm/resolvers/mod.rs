@@ -222,6 +222,11 @@ impl NpmPackageResolver {
     Ok(path)
   }
 
+  /// The registry API that the packages are resolved with.
+  pub fn api(&self) -> &RealNpmRegistryApi {
+    &self.api
+  }
+
   /// Attempts to get the package size in bytes.
   pub fn package_size(
     &self,
@@ -1,6 +1,7 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 use crate::tools::test::FailFastTracker;
+use crate::tools::test::SnapshotFile;
 use crate::tools::test::TestDescription;
 use crate::tools::test::TestEvent;
 use crate::tools::test::TestEventSender;
@@ -29,6 +30,7 @@ pub fn init(
   sender: TestEventSender,
   fail_fast_tracker: FailFastTracker,
   filter: TestFilter,
+  update_snapshots: bool,
 ) -> Extension {
   Extension::builder("deno_test")
     .ops(vec![
@@ -39,11 +41,17 @@ pub fn init(
       op_register_test_step::decl(),
       op_dispatch_test_event::decl(),
       op_tests_should_stop::decl(),
+      op_assert_snapshot::decl(),
+      op_save_snapshots::decl(),
     ])
     .state(move |state| {
       state.put(sender.clone());
       state.put(fail_fast_tracker.clone());
       state.put(filter.clone());
+      state.put(SnapshotState {
+        update: update_snapshots,
+        maybe_file: None,
+      });
       Ok(())
     })
     .build()
@@ -205,3 +213,47 @@ fn op_dispatch_test_event(
 fn op_tests_should_stop(state: &mut OpState) -> bool {
   state.borrow::<FailFastTracker>().should_stop()
 }
+
+/// The snapshots of the test module, which are read on the first snapshot
+/// assertion.
+struct SnapshotState {
+  update: bool,
+  maybe_file: Option<SnapshotFile>,
+}
+
+/// Compares the serialized value with its snapshot and returns the error
+/// message if it doesn't match.
+#[op]
+fn op_assert_snapshot(
+  state: &mut OpState,
+  name: String,
+  actual: String,
+) -> Result<Option<String>, AnyError> {
+  let specifier = state.borrow::<ModuleSpecifier>().clone();
+  let snapshot_state = state.borrow_mut::<SnapshotState>();
+  if snapshot_state.maybe_file.is_none() {
+    snapshot_state.maybe_file =
+      Some(SnapshotFile::read(&specifier, snapshot_state.update)?);
+  }
+  let file = snapshot_state.maybe_file.as_mut().unwrap();
+  Ok(file.assert(name, actual))
+}
+
+/// Writes the updated snapshots once the tests of the module finished and
+/// reports the changes. `complete` is whether every test of the module ran.
+#[op]
+fn op_save_snapshots(
+  state: &mut OpState,
+  complete: bool,
+) -> Result<(), AnyError> {
+  let snapshot_state = state.borrow_mut::<SnapshotState>();
+  let file = match snapshot_state.maybe_file.as_mut() {
+    Some(file) => file,
+    None => return Ok(()),
+  };
+  if let Some(changes) = file.save(complete)? {
+    let mut sender = state.borrow::<TestEventSender>().clone();
+    sender.send(TestEvent::SnapshotsUpdated(changes)).ok();
+  }
+  Ok(())
+}
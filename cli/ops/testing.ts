@@ -2,3 +2,12 @@ export const op_tests_should_stop = () => {
   console.warn("Not implemented: ops.op_tests_should_stop");
   return false;
 }
+
+export const op_assert_snapshot = (name: string, actual: string): string | null => {
+  console.warn("Not implemented: ops.op_assert_snapshot");
+  return null;
+}
+
+export const op_save_snapshots = (complete: boolean) => {
+  console.warn("Not implemented: ops.op_save_snapshots");
+}
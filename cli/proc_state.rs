@@ -18,6 +18,7 @@ use crate::cache::TypeCheckCache;
 use crate::emit::emit_parsed_source;
 use crate::file_fetcher::FileFetcher;
 use crate::graph_util::graph_lock_or_exit;
+use crate::graph_util::graph_raw_modules_lock_or_exit;
 use crate::graph_util::GraphData;
 use crate::graph_util::ModuleEntry;
 use crate::http_util::HttpClient;
@@ -120,11 +121,9 @@ impl ProcState {
   }
 
   pub async fn build_for_file_watcher(
-    flags: Flags,
+    cli_options: Arc<CliOptions>,
     files_to_watch_sender: tokio::sync::mpsc::UnboundedSender<Vec<PathBuf>>,
   ) -> Result<Self, AnyError> {
-    // resolve the config each time
-    let cli_options = Arc::new(CliOptions::from_flags(flags)?);
     let ps =
       Self::build_with_sender(cli_options, Some(files_to_watch_sender.clone()))
         .await?;
@@ -402,7 +401,13 @@ impl ProcState {
 
     // If there is a lockfile, validate the integrity of all the modules.
     if let Some(lockfile) = &self.lockfile {
-      graph_lock_or_exit(&graph, &mut lockfile.lock());
+      let mut lockfile = lockfile.lock();
+      graph_lock_or_exit(&graph, &mut lockfile);
+      graph_raw_modules_lock_or_exit(
+        &graph,
+        &self.file_fetcher,
+        &mut lockfile,
+      )?;
     }
 
     // Determine any modules that have already been emitted this session and
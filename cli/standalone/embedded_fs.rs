@@ -0,0 +1,566 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde::Deserialize;
+use deno_core::serde::Serialize;
+use deno_runtime::fs::File;
+use deno_runtime::fs::FileSystem;
+use deno_runtime::fs::FileTime;
+use deno_runtime::fs::FsDirEntry;
+use deno_runtime::fs::FsFileType;
+use deno_runtime::fs::FsStat;
+use deno_runtime::fs::OpenOptions;
+use deno_runtime::fs::RealFs;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::io;
+use std::io::ErrorKind;
+use std::io::SeekFrom;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// The files included with `deno compile --include`, as stored in the
+/// metadata of the executable. Their contents follow the eszip archive.
+///
+/// The files are stored relative to the directory `deno compile` ran in and
+/// mounted in the current directory of the executable when it starts.
+#[derive(Deserialize, Serialize)]
+pub struct EmbeddedFiles {
+  /// The position of the contents of the files in the executable.
+  pub pos: u64,
+  pub entries: Vec<EmbeddedFileEntry>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct EmbeddedFileEntry {
+  /// The relative path, separated by `/`.
+  pub path: String,
+  /// The offset of the contents from [`EmbeddedFiles::pos`].
+  pub offset: u64,
+  pub len: u64,
+}
+
+/// A read-only file system with the embedded files, layered on top of the
+/// real file system. Paths that are not embedded use the real file system.
+pub struct EmbeddedFs {
+  files: HashMap<PathBuf, Arc<Vec<u8>>>,
+  /// The directories containing embedded files and their children.
+  dirs: HashMap<PathBuf, BTreeMap<String, bool>>,
+  /// The current directory, while it is an embedded directory which does
+  /// not exist on disk.
+  embedded_cwd: Mutex<Option<PathBuf>>,
+  real_fs: RealFs,
+}
+
+impl EmbeddedFs {
+  /// Mounts the files, given with their relative paths, in `root`.
+  pub fn new(root: &Path, files: Vec<(String, Vec<u8>)>) -> Self {
+    let root = normalize_path(root);
+    let mut fs = Self {
+      files: HashMap::new(),
+      dirs: HashMap::new(),
+      embedded_cwd: Mutex::new(None),
+      real_fs: RealFs,
+    };
+    fs.dirs.insert(root.clone(), BTreeMap::new());
+    for (relative_path, data) in files {
+      let path = normalize_path(&root.join(relative_path));
+      // add all the directories between the root and the file
+      let mut child = path.as_path();
+      let mut is_file = true;
+      while let Some(parent) = child.parent() {
+        if !parent.starts_with(&root) {
+          break;
+        }
+        if let Some(name) = child.file_name() {
+          fs.dirs
+            .entry(parent.to_path_buf())
+            .or_default()
+            .insert(name.to_string_lossy().to_string(), is_file);
+        }
+        is_file = false;
+        child = parent;
+      }
+      fs.files.insert(path, Arc::new(data));
+    }
+    fs
+  }
+
+  fn resolve(&self, path: &Path) -> PathBuf {
+    if path.is_absolute() {
+      normalize_path(path)
+    } else {
+      match self.cwd() {
+        Ok(cwd) => normalize_path(&cwd.join(path)),
+        Err(_) => normalize_path(path),
+      }
+    }
+  }
+
+  /// Returns the path to pass to the real file system, which resolves
+  /// relative paths against the current directory of the process rather
+  /// than an embedded current directory.
+  fn on_disk<'a>(&self, path: &'a Path) -> Cow<'a, Path> {
+    match &*self.embedded_cwd.lock() {
+      Some(cwd) if path.is_relative() => {
+        Cow::Owned(normalize_path(&cwd.join(path)))
+      }
+      _ => Cow::Borrowed(path),
+    }
+  }
+
+  fn lookup(&self, path: &Path) -> Option<Embedded> {
+    let path = self.resolve(path);
+    if let Some(data) = self.files.get(&path) {
+      Some(Embedded::File(path, data.clone()))
+    } else if self.dirs.contains_key(&path) {
+      Some(Embedded::Dir(path))
+    } else {
+      None
+    }
+  }
+
+  fn is_embedded(&self, path: &Path) -> bool {
+    self.lookup(path).is_some()
+  }
+}
+
+enum Embedded {
+  File(PathBuf, Arc<Vec<u8>>),
+  Dir(PathBuf),
+}
+
+impl Embedded {
+  fn stat(&self) -> FsStat {
+    match self {
+      Embedded::File(_, data) => FsStat {
+        is_file: true,
+        size: data.len() as u64,
+        mode: 0o100444,
+        nlink: 1,
+        ..Default::default()
+      },
+      Embedded::Dir(_) => FsStat {
+        is_directory: true,
+        mode: 0o040555,
+        nlink: 1,
+        ..Default::default()
+      },
+    }
+  }
+}
+
+/// Lexically removes `.` and `..` components.
+fn normalize_path(path: &Path) -> PathBuf {
+  let mut normalized = PathBuf::new();
+  for component in path.components() {
+    match component {
+      Component::CurDir => {}
+      Component::ParentDir => {
+        normalized.pop();
+      }
+      _ => normalized.push(component),
+    }
+  }
+  normalized
+}
+
+fn read_only_error() -> io::Error {
+  io::Error::new(ErrorKind::PermissionDenied, "Read-only file system")
+}
+
+impl FileSystem for EmbeddedFs {
+  fn cwd(&self) -> io::Result<PathBuf> {
+    match &*self.embedded_cwd.lock() {
+      Some(cwd) => Ok(cwd.clone()),
+      None => self.real_fs.cwd(),
+    }
+  }
+
+  fn chdir(&self, path: &Path) -> io::Result<()> {
+    match self.lookup(path) {
+      Some(Embedded::Dir(path)) => {
+        // embedded directories may not exist on disk
+        let embedded_cwd = match self.real_fs.chdir(&path) {
+          Ok(()) => None,
+          Err(_) => Some(path),
+        };
+        *self.embedded_cwd.lock() = embedded_cwd;
+        Ok(())
+      }
+      Some(Embedded::File(..)) => {
+        Err(io::Error::new(ErrorKind::Other, "Not a directory"))
+      }
+      None => {
+        self.real_fs.chdir(&self.on_disk(path))?;
+        *self.embedded_cwd.lock() = None;
+        Ok(())
+      }
+    }
+  }
+
+  fn tmp_dir(&self) -> io::Result<PathBuf> {
+    self.real_fs.tmp_dir()
+  }
+
+  fn umask(&self, mask: Option<u32>) -> Result<u32, AnyError> {
+    self.real_fs.umask(mask)
+  }
+
+  fn open(
+    &self,
+    path: &Path,
+    options: OpenOptions,
+  ) -> io::Result<Box<dyn File>> {
+    match self.lookup(path) {
+      Some(_) if options.write || options.append || options.create_new => {
+        Err(read_only_error())
+      }
+      Some(Embedded::File(_, data)) => {
+        Ok(Box::new(EmbeddedFile { data, position: 0 }))
+      }
+      Some(Embedded::Dir(_)) => {
+        Err(io::Error::new(ErrorKind::Other, "Is a directory"))
+      }
+      None => self.real_fs.open(&self.on_disk(path), options),
+    }
+  }
+
+  fn mkdir(&self, path: &Path, recursive: bool, mode: u32) -> io::Result<()> {
+    match self.lookup(path) {
+      Some(Embedded::Dir(_)) if recursive => Ok(()),
+      Some(_) => Err(read_only_error()),
+      None => self.real_fs.mkdir(&self.on_disk(path), recursive, mode),
+    }
+  }
+
+  fn chmod(&self, path: &Path, mode: u32) -> Result<(), AnyError> {
+    if self.is_embedded(path) {
+      return Err(read_only_error().into());
+    }
+    self.real_fs.chmod(&self.on_disk(path), mode)
+  }
+
+  fn chown(
+    &self,
+    path: &Path,
+    uid: Option<u32>,
+    gid: Option<u32>,
+  ) -> Result<(), AnyError> {
+    if self.is_embedded(path) {
+      return Err(read_only_error().into());
+    }
+    self.real_fs.chown(&self.on_disk(path), uid, gid)
+  }
+
+  fn remove(&self, path: &Path, recursive: bool) -> io::Result<()> {
+    if self.is_embedded(path) {
+      return Err(read_only_error());
+    }
+    self.real_fs.remove(&self.on_disk(path), recursive)
+  }
+
+  fn copy_file(&self, from: &Path, to: &Path) -> io::Result<()> {
+    if self.is_embedded(to) {
+      return Err(read_only_error());
+    }
+    match self.lookup(from) {
+      Some(Embedded::File(_, data)) => self.real_fs.write_file(
+        &self.on_disk(to),
+        OpenOptions::write(true, false, false, None),
+        &data,
+      ),
+      Some(Embedded::Dir(_)) => {
+        Err(io::Error::new(ErrorKind::Other, "Is a directory"))
+      }
+      None => self
+        .real_fs
+        .copy_file(&self.on_disk(from), &self.on_disk(to)),
+    }
+  }
+
+  fn stat(&self, path: &Path) -> io::Result<FsStat> {
+    match self.lookup(path) {
+      Some(embedded) => Ok(embedded.stat()),
+      None => self.real_fs.stat(&self.on_disk(path)),
+    }
+  }
+
+  fn lstat(&self, path: &Path) -> io::Result<FsStat> {
+    match self.lookup(path) {
+      Some(embedded) => Ok(embedded.stat()),
+      None => self.real_fs.lstat(&self.on_disk(path)),
+    }
+  }
+
+  fn realpath(&self, path: &Path) -> io::Result<PathBuf> {
+    match self.lookup(path) {
+      Some(Embedded::File(path, _)) | Some(Embedded::Dir(path)) => Ok(path),
+      None => self.real_fs.realpath(&self.on_disk(path)),
+    }
+  }
+
+  fn read_dir(&self, path: &Path) -> io::Result<Vec<FsDirEntry>> {
+    match self.lookup(path) {
+      Some(Embedded::Dir(path)) => {
+        // Show the files on disk next to the embedded ones.
+        let mut entries = self.real_fs.read_dir(&path).unwrap_or_default();
+        entries.retain(|entry| !self.dirs[&path].contains_key(&entry.name));
+        entries.extend(self.dirs[&path].iter().map(|(name, is_file)| {
+          FsDirEntry {
+            name: name.clone(),
+            is_file: *is_file,
+            is_directory: !*is_file,
+            is_symlink: false,
+          }
+        }));
+        Ok(entries)
+      }
+      Some(Embedded::File(..)) => {
+        Err(io::Error::new(ErrorKind::Other, "Not a directory"))
+      }
+      None => self.real_fs.read_dir(&self.on_disk(path)),
+    }
+  }
+
+  fn rename(&self, oldpath: &Path, newpath: &Path) -> io::Result<()> {
+    if self.is_embedded(oldpath) || self.is_embedded(newpath) {
+      return Err(read_only_error());
+    }
+    self
+      .real_fs
+      .rename(&self.on_disk(oldpath), &self.on_disk(newpath))
+  }
+
+  fn link(&self, oldpath: &Path, newpath: &Path) -> io::Result<()> {
+    if self.is_embedded(oldpath) || self.is_embedded(newpath) {
+      return Err(read_only_error());
+    }
+    self
+      .real_fs
+      .link(&self.on_disk(oldpath), &self.on_disk(newpath))
+  }
+
+  fn symlink(
+    &self,
+    oldpath: &Path,
+    newpath: &Path,
+    file_type: Option<FsFileType>,
+  ) -> Result<(), AnyError> {
+    if self.is_embedded(newpath) {
+      return Err(read_only_error().into());
+    }
+    self
+      .real_fs
+      .symlink(oldpath, &self.on_disk(newpath), file_type)
+  }
+
+  fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+    if self.is_embedded(path) {
+      return Err(io::Error::new(ErrorKind::InvalidInput, "Invalid argument"));
+    }
+    self.real_fs.read_link(&self.on_disk(path))
+  }
+
+  fn truncate(&self, path: &Path, len: u64) -> io::Result<()> {
+    if self.is_embedded(path) {
+      return Err(read_only_error());
+    }
+    self.real_fs.truncate(&self.on_disk(path), len)
+  }
+
+  fn utime(
+    &self,
+    path: &Path,
+    atime: FileTime,
+    mtime: FileTime,
+  ) -> io::Result<()> {
+    if self.is_embedded(path) {
+      return Err(read_only_error());
+    }
+    self.real_fs.utime(&self.on_disk(path), atime, mtime)
+  }
+
+  fn write_file(
+    &self,
+    path: &Path,
+    options: OpenOptions,
+    data: &[u8],
+  ) -> io::Result<()> {
+    if self.is_embedded(path) {
+      return Err(read_only_error());
+    }
+    self.real_fs.write_file(&self.on_disk(path), options, data)
+  }
+
+  fn read_file(&self, path: &Path) -> io::Result<Vec<u8>> {
+    match self.lookup(path) {
+      Some(Embedded::File(_, data)) => Ok(data.to_vec()),
+      Some(Embedded::Dir(_)) => {
+        Err(io::Error::new(ErrorKind::Other, "Is a directory"))
+      }
+      None => self.real_fs.read_file(&self.on_disk(path)),
+    }
+  }
+}
+
+struct EmbeddedFile {
+  data: Arc<Vec<u8>>,
+  position: u64,
+}
+
+impl File for EmbeddedFile {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    let start = (self.position as usize).min(self.data.len());
+    let n = buf.len().min(self.data.len() - start);
+    buf[..n].copy_from_slice(&self.data[start..start + n]);
+    self.position += n as u64;
+    Ok(n)
+  }
+
+  fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+    Err(read_only_error())
+  }
+
+  fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+    let position = match pos {
+      SeekFrom::Start(offset) => Some(offset),
+      SeekFrom::End(offset) => {
+        (self.data.len() as u64).checked_add_signed(offset)
+      }
+      SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+    };
+    match position {
+      Some(position) => {
+        self.position = position;
+        Ok(position)
+      }
+      None => Err(io::Error::new(
+        ErrorKind::InvalidInput,
+        "invalid seek to a negative or overflowing position",
+      )),
+    }
+  }
+
+  fn sync_data(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+
+  fn sync_all(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+
+  fn stat(&mut self) -> io::Result<FsStat> {
+    Ok(Embedded::File(PathBuf::new(), self.data.clone()).stat())
+  }
+
+  fn lock(&mut self, _exclusive: bool) -> io::Result<()> {
+    Ok(())
+  }
+
+  fn unlock(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+
+  fn truncate(&mut self, _len: u64) -> io::Result<()> {
+    Err(read_only_error())
+  }
+
+  fn utime(&mut self, _atime: FileTime, _mtime: FileTime) -> io::Result<()> {
+    Err(read_only_error())
+  }
+
+  fn try_clone(&self) -> io::Result<Box<dyn File>> {
+    Ok(Box::new(EmbeddedFile {
+      data: self.data.clone(),
+      position: self.position,
+    }))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use test_util::TempDir;
+
+  #[test]
+  fn embedded_files_are_read_only_overlay() {
+    let temp_dir = TempDir::new();
+    let root = temp_dir.path().to_path_buf();
+    temp_dir.write("on_disk.txt", "disk");
+    let fs = EmbeddedFs::new(
+      &root,
+      vec![
+        ("static/index.html".to_string(), b"<html>".to_vec()),
+        ("static/css/main.css".to_string(), b"body {}".to_vec()),
+      ],
+    );
+
+    let index = root.join("static/index.html");
+    assert_eq!(fs.read_file(&index).unwrap(), b"<html>");
+    assert_eq!(
+      fs.read_file(&root.join("static/css/../index.html"))
+        .unwrap(),
+      b"<html>"
+    );
+    let stat = fs.stat(&index).unwrap();
+    assert!(stat.is_file);
+    assert_eq!(stat.size, 6);
+    assert!(fs.stat(&root.join("static/css")).unwrap().is_directory);
+
+    let mut file = fs.open(&index, OpenOptions::read()).unwrap();
+    file.seek(SeekFrom::Start(1)).unwrap();
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf).unwrap();
+    assert_eq!(buf, b"html>");
+
+    let err = fs
+      .write_file(&index, OpenOptions::write(true, false, false, None), b"")
+      .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+    assert!(fs.remove(&root.join("static"), true).is_err());
+
+    let names = fs
+      .read_dir(&root)
+      .unwrap()
+      .into_iter()
+      .map(|entry| entry.name)
+      .collect::<Vec<_>>();
+    assert_eq!(names, vec!["on_disk.txt", "static"]);
+
+    // files that are not embedded come from the real file system
+    assert_eq!(fs.read_file(&root.join("on_disk.txt")).unwrap(), b"disk");
+    assert!(fs.read_file(&root.join("static/missing.txt")).is_err());
+  }
+
+  #[test]
+  fn chdir_to_embedded_dir() {
+    let temp_dir = TempDir::new();
+    let root = temp_dir.path().to_path_buf();
+    temp_dir.write("on_disk.txt", "disk");
+    let fs = EmbeddedFs::new(
+      &root,
+      vec![("static/css/main.css".to_string(), b"body {}".to_vec())],
+    );
+    let cwd = std::env::current_dir().unwrap();
+
+    fs.chdir(&root.join("static")).unwrap();
+    assert_eq!(fs.cwd().unwrap(), root.join("static"));
+    assert_eq!(fs.read_file(Path::new("css/main.css")).unwrap(), b"body {}");
+    assert!(fs.stat(Path::new("css")).unwrap().is_directory);
+    // relative paths which are not embedded resolve against the embedded
+    // current directory
+    assert_eq!(fs.read_file(Path::new("../on_disk.txt")).unwrap(), b"disk");
+    // the current directory of the process is unchanged
+    assert_eq!(std::env::current_dir().unwrap(), cwd);
+
+    let err = fs.chdir(Path::new("css/main.css")).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Other);
+    fs.chdir(&cwd).unwrap();
+    assert_eq!(fs.cwd().unwrap(), cwd);
+  }
+}
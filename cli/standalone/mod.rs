@@ -24,11 +24,14 @@ use deno_core::url::Url;
 use deno_core::v8_set_flags;
 use deno_core::ModuleLoader;
 use deno_core::ModuleSpecifier;
+use deno_core::ModuleType;
 use deno_core::ResolutionKind;
 use deno_graph::source::Resolver;
 use deno_runtime::deno_broadcast_channel::InMemoryBroadcastChannel;
 use deno_runtime::deno_web::BlobStore;
 use deno_runtime::fmt_errors::format_js_error;
+use deno_runtime::fs::FileSystemRc;
+use deno_runtime::fs::RealFs;
 use deno_runtime::permissions::Permissions;
 use deno_runtime::permissions::PermissionsContainer;
 use deno_runtime::permissions::PermissionsOptions;
@@ -37,12 +40,22 @@ use deno_runtime::worker::WorkerOptions;
 use deno_runtime::BootstrapOptions;
 use import_map::parse_from_json;
 use log::Level;
+use std::collections::HashMap;
+use std::env::current_dir;
 use std::env::current_exe;
+use std::io::Read;
+use std::io::Seek;
 use std::io::SeekFrom;
 use std::pin::Pin;
 use std::rc::Rc;
 use std::sync::Arc;
 
+mod embedded_fs;
+
+pub use embedded_fs::EmbeddedFileEntry;
+pub use embedded_fs::EmbeddedFiles;
+use embedded_fs::EmbeddedFs;
+
 #[derive(Deserialize, Serialize)]
 pub struct Metadata {
   pub argv: Vec<String>,
@@ -57,6 +70,21 @@ pub struct Metadata {
   pub unsafely_ignore_certificate_errors: Option<Vec<String>>,
   pub maybe_import_map: Option<(Url, String)>,
   pub entrypoint: ModuleSpecifier,
+  pub embedded_files: Option<EmbeddedFiles>,
+  pub raw_modules: Vec<RawModule>,
+  /// The variables of the `.env` file, read when compiling.
+  pub env_vars: Vec<(String, String)>,
+}
+
+/// A module which the module graph doesn't parse, like a WebAssembly module,
+/// so it is not in the eszip archive. Its contents follow the archive.
+#[derive(Deserialize, Serialize)]
+pub struct RawModule {
+  pub specifier: ModuleSpecifier,
+  pub module_type: ModuleType,
+  /// The position of the contents in the executable.
+  pub pos: u64,
+  pub len: u64,
 }
 
 pub const MAGIC_TRAILER: &[u8; 8] = b"d3n0l4nd";
@@ -118,6 +146,41 @@ pub async fn extract_standalone(
   Ok(Some((metadata, eszip)))
 }
 
+/// Reads the files included with `--include` from the current executable and
+/// mounts them in the current directory.
+fn read_embedded_files(
+  embedded_files: &EmbeddedFiles,
+) -> Result<EmbeddedFs, AnyError> {
+  let mut file = std::fs::File::open(current_exe()?)?;
+  let mut files = Vec::with_capacity(embedded_files.entries.len());
+  for entry in &embedded_files.entries {
+    file.seek(SeekFrom::Start(embedded_files.pos + entry.offset))?;
+    let mut data = vec![0; entry.len as usize];
+    file.read_exact(&mut data).with_context(|| {
+      format!("Failed to read embedded file \"{}\"", entry.path)
+    })?;
+    files.push((entry.path.clone(), data));
+  }
+  Ok(EmbeddedFs::new(&current_dir()?, files))
+}
+
+/// Reads the raw modules from the current executable.
+fn read_raw_modules(
+  raw_modules: &[RawModule],
+) -> Result<HashMap<ModuleSpecifier, (ModuleType, Vec<u8>)>, AnyError> {
+  let mut file = std::fs::File::open(current_exe()?)?;
+  let mut modules = HashMap::with_capacity(raw_modules.len());
+  for module in raw_modules {
+    file.seek(SeekFrom::Start(module.pos))?;
+    let mut code = vec![0; module.len as usize];
+    file.read_exact(&mut code).with_context(|| {
+      format!("Failed to read embedded module \"{}\"", module.specifier)
+    })?;
+    modules.insert(module.specifier.clone(), (module.module_type, code));
+  }
+  Ok(modules)
+}
+
 fn u64_from_bytes(arr: &[u8]) -> Result<u64, AnyError> {
   let fixed_arr: &[u8; 8] = arr
     .try_into()
@@ -127,6 +190,7 @@ fn u64_from_bytes(arr: &[u8]) -> Result<u64, AnyError> {
 
 struct EmbeddedModuleLoader {
   eszip: eszip::EszipV2,
+  raw_modules: HashMap<ModuleSpecifier, (ModuleType, Vec<u8>)>,
   maybe_import_map_resolver: Option<CliResolver>,
 }
 
@@ -160,6 +224,17 @@ impl ModuleLoader for EmbeddedModuleLoader {
     _maybe_referrer: Option<ModuleSpecifier>,
     _is_dynamic: bool,
   ) -> Pin<Box<deno_core::ModuleSourceFuture>> {
+    if let Some((module_type, code)) = self.raw_modules.get(module_specifier) {
+      let module_source = deno_core::ModuleSource {
+        code: code.clone().into_boxed_slice(),
+        module_type: *module_type,
+        module_url_specified: module_specifier.to_string(),
+        module_url_found: module_specifier.to_string(),
+      };
+      return deno_core::futures::future::ready(Ok(module_source))
+        .boxed_local();
+    }
+
     let is_data_uri = get_source_from_data_url(module_specifier).ok();
     let module = self
       .eszip
@@ -233,6 +308,7 @@ pub async fn run(
   let broadcast_channel = InMemoryBroadcastChannel::default();
   let module_loader = Rc::new(EmbeddedModuleLoader {
     eszip,
+    raw_modules: read_raw_modules(&metadata.raw_modules)?,
     maybe_import_map_resolver: metadata.maybe_import_map.map(
       |(base, source)| {
         CliResolver::with_import_map(Arc::new(
@@ -250,6 +326,11 @@ pub async fn run(
 
   v8_set_flags(construct_v8_flags(&metadata.v8_flags, vec![]));
 
+  let fs: FileSystemRc = match &metadata.embedded_files {
+    Some(embedded_files) => Arc::new(read_embedded_files(embedded_files)?),
+    None => Arc::new(RealFs),
+  };
+
   let root_cert_store = ps.root_cert_store.clone();
 
   let options = WorkerOptions {
@@ -295,6 +376,7 @@ pub async fn run(
     shared_array_buffer_store: None,
     compiled_wasm_module_store: None,
     stdio: Default::default(),
+    fs,
   };
   let mut worker = MainWorker::bootstrap_from_options(
     main_module.clone(),
@@ -117,6 +117,19 @@ itest!(group_baseline {
   output: "bench/group_baseline.out",
 });
 
+itest!(json_output {
+  args: "bench --json bench/json/json.ts",
+  exit_code: 0,
+  output: "bench/json/json.out",
+});
+
+itest!(baseline_regression {
+  args:
+    "bench --baseline=bench/json/regressed_baseline.json bench/json/json.ts",
+  exit_code: 1,
+  output: "bench/json/regressed.out",
+});
+
 itest!(unresolved_promise {
   args: "bench bench/unresolved_promise.ts",
   exit_code: 1,
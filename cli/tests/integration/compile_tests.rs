@@ -564,3 +564,166 @@ fn check_local_by_default2() {
     r#"error: TS2322 [ERROR]: Type '12' is not assignable to type '"b"'."#
   ));
 }
+
+#[test]
+fn compile_with_include() {
+  let dir = TempDir::new();
+  let exe = if cfg!(windows) {
+    dir.path().join("include.exe")
+  } else {
+    dir.path().join("include")
+  };
+  dir.write(
+    "main.ts",
+    r#"const cwd = Deno.cwd().replaceAll("\\", "/");
+const dir = new URL(`file://${cwd.startsWith("/") ? "" : "/"}${cwd}/static/`);
+console.log(await Deno.readTextFile("static/hello.txt"));
+console.log(await (await fetch(new URL("data.json", dir))).text());
+const names = [];
+for await (const entry of Deno.readDir("static")) names.push(entry.name);
+console.log(names.sort().join(","));
+try {
+  Deno.writeTextFileSync("static/hello.txt", "overwritten");
+} catch (err) {
+  console.log(err.name);
+}
+Deno.chdir("static");
+console.log(Deno.readTextFileSync("data.json"));
+"#,
+  );
+  dir.create_dir_all("static");
+  dir.write("static/hello.txt", "Hello from an embedded file");
+  dir.write("static/data.json", r#"{"embedded":true}"#);
+  let output = util::deno_cmd()
+    .current_dir(dir.path())
+    .env("NO_COLOR", "1")
+    .arg("compile")
+    .arg("--allow-read")
+    .arg("--allow-write")
+    .arg("--include")
+    .arg("static/*")
+    .arg("--output")
+    .arg(&exe)
+    .arg("main.ts")
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert!(stderr.contains("Include static/data.json"));
+  assert!(stderr.contains("Include static/hello.txt"));
+
+  // the files are read from the executable and mounted in the directory it
+  // runs in, not where it was compiled
+  std::fs::remove_dir_all(dir.path().join("static")).unwrap();
+  let run_dir = TempDir::new();
+  let output = Command::new(&exe)
+    .current_dir(run_dir.path())
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  assert_eq!(
+    String::from_utf8(output.stdout).unwrap(),
+    concat!(
+      "Hello from an embedded file\n",
+      "{\"embedded\":true}\n",
+      "data.json,hello.txt\n",
+      "PermissionDenied\n",
+      "{\"embedded\":true}\n",
+    )
+  );
+}
+
+#[test]
+fn compile_with_include_no_matches() {
+  let dir = TempDir::new();
+  dir.write("main.ts", "console.log(1);");
+  let output = util::deno_cmd()
+    .current_dir(dir.path())
+    .env("NO_COLOR", "1")
+    .arg("compile")
+    .arg("--include")
+    .arg("missing/**")
+    .arg("--output")
+    .arg(dir.path().join("main"))
+    .arg("main.ts")
+    .output()
+    .unwrap();
+  assert!(!output.status.success());
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert!(
+    stderr.contains("No files matched the --include pattern \"missing/**\"")
+  );
+}
+
+#[test]
+fn compile_wasm_module() {
+  let dir = TempDir::new();
+  let exe = if cfg!(windows) {
+    dir.path().join("wasm_module.exe")
+  } else {
+    dir.path().join("wasm_module")
+  };
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("compile")
+    .arg("--output")
+    .arg(&exe)
+    .arg("./run/wasm_module/attributes.js")
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+
+  // the raw modules are read from the executable
+  let output = Command::new(&exe).current_dir(dir.path()).output().unwrap();
+  assert!(output.status.success());
+  assert_eq!(
+    String::from_utf8(output.stdout).unwrap(),
+    "3\nHello world!\ntrue 41\n"
+  );
+}
+
+#[test]
+fn compile_with_env_file() {
+  let dir = TempDir::new();
+  let exe = if cfg!(windows) {
+    dir.path().join("env.exe")
+  } else {
+    dir.path().join("env")
+  };
+  dir.write("main.ts", r#"console.log(Deno.env.get("EMBEDDED_VAR"));"#);
+  dir.write(".env", "EMBEDDED_VAR=hello\n");
+  dir.write("deno.json", r#"{ "envFile": ".env" }"#);
+  let compile = |env_file: Option<&str>| {
+    let mut command = util::deno_cmd();
+    command
+      .current_dir(dir.path())
+      .env("NO_COLOR", "1")
+      .arg("compile")
+      .arg("--allow-env")
+      .arg("--output")
+      .arg(&exe);
+    if let Some(env_file) = env_file {
+      command.arg(env_file);
+    }
+    let output = command.arg("main.ts").output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stderr).unwrap()
+  };
+  let run = || {
+    let output = Command::new(&exe)
+      .env_remove("EMBEDDED_VAR")
+      .output()
+      .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+  };
+
+  // the `envFile` of the config file isn't embedded
+  let stderr = compile(None);
+  assert!(!stderr.contains("unencrypted"));
+  assert_eq!(run(), "undefined\n");
+
+  let stderr = compile(Some("--env-file"));
+  assert!(stderr.contains("are stored unencrypted in the executable"));
+  assert_eq!(run(), "hello\n");
+}
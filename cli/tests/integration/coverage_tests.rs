@@ -85,6 +85,50 @@ fn error_if_invalid_cache() {
   assert!(error.contains("Before generating coverage report, run `deno test --coverage` to ensure consistent state."));
 }
 
+#[test]
+fn html_report() {
+  let deno_dir = TempDir::new();
+  let tempdir = TempDir::new();
+  let tempdir = tempdir.path().join("cov");
+
+  let status = util::deno_cmd_with_deno_dir(&deno_dir)
+    .current_dir(util::testdata_path())
+    .arg("test")
+    .arg("--quiet")
+    .arg(format!("--coverage={}", tempdir.to_str().unwrap()))
+    .arg("coverage/branch_test.ts")
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::inherit())
+    .status()
+    .unwrap();
+
+  assert!(status.success());
+
+  let output = util::deno_cmd_with_deno_dir(&deno_dir)
+    .current_dir(util::testdata_path())
+    .arg("coverage")
+    .arg("--html")
+    .arg(format!("{}/", tempdir.to_str().unwrap()))
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .output()
+    .unwrap();
+
+  assert!(output.status.success());
+  assert!(output.stdout.is_empty());
+
+  let html_dir = tempdir.join("html");
+  let index = fs::read_to_string(html_dir.join("index.html")).unwrap();
+  assert!(index.contains("<a href=\"coverage/branch.ts.html\">branch.ts</a>"));
+  assert!(html_dir.join("style.css").exists());
+
+  let file_page =
+    fs::read_to_string(html_dir.join("coverage/branch.ts.html")).unwrap();
+  assert!(file_page.contains("<a href=\"../index.html\">All files</a>"));
+  assert!(file_page.contains("<tr class=\"hit\""));
+  assert!(file_page.contains("<tr class=\"miss\""));
+}
+
 fn run_coverage_text(test_name: &str, extension: &str) {
   let deno_dir = TempDir::new();
   let tempdir = TempDir::new();
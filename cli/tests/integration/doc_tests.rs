@@ -70,3 +70,71 @@ itest!(deno_doc_invalid_url {
   output: "doc/invalid_url.out",
   exit_code: 1,
 });
+
+#[test]
+fn deno_doc_html() {
+  let dir = TempDir::new();
+  let output_dir = dir.path().join("docs");
+  let output = util::deno_cmd_with_deno_dir(&dir)
+    .current_dir(util::testdata_path())
+    .arg("doc")
+    .arg("--html")
+    .arg(format!("--output={}", output_dir.display()))
+    .arg("doc/html/mod.ts")
+    .arg("doc/html/shapes.ts")
+    .env("NO_COLOR", "1")
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  assert_contains!(
+    std::str::from_utf8(&output.stderr).unwrap(),
+    "Wrote documentation of 2 modules"
+  );
+
+  for file in ["styles.css", "search.js"] {
+    assert!(output_dir.join(file).exists());
+  }
+  let index = std::fs::read_to_string(output_dir.join("index.html")).unwrap();
+  assert_contains!(index, "<a href=\"doc_html_mod.ts/index.html\">");
+  assert_contains!(index, "<p>Shapes that can be drawn.</p>");
+  let search_index =
+    std::fs::read_to_string(output_dir.join("search_index.js")).unwrap();
+  assert_contains!(search_index, "\"name\":\"unitCircle\"");
+
+  let module_dir = output_dir.join("doc_html_mod.ts");
+  // the page of the `index` export doesn't replace the page of the module
+  let module = std::fs::read_to_string(module_dir.join("index.html")).unwrap();
+  assert_contains!(module, "<h1>doc/html/mod.ts</h1>");
+  assert_contains!(
+    module,
+    "<a href=\"../doc_html_mod.ts/~/index.html\">index</a>"
+  );
+  let variable =
+    std::fs::read_to_string(module_dir.join("~/index.html")).unwrap();
+  assert_contains!(variable, "<p>The index of the first shape.</p>");
+
+  let function =
+    std::fs::read_to_string(module_dir.join("~/unitCircle.html")).unwrap();
+  assert_contains!(function, "function unitCircle(");
+  assert_contains!(
+    function,
+    "): <a href=\"../../doc_html_mod.ts/~/Circle.html\">Circle</a>"
+  );
+  assert_contains!(function, "<dt><code>radius</code></dt>");
+  let class =
+    std::fs::read_to_string(module_dir.join("~/Circle.html")).unwrap();
+  assert_contains!(
+    class,
+    "see <a href=\"../../doc_html_mod.ts/~/Point.html\">Point</a> for its center."
+  );
+  assert_contains!(class, "<p>The area of the circle.</p>");
+}
+
+itest!(deno_doc_lint {
+  args: "doc --lint doc/lint/mod.ts",
+  output: "doc/lint/expected.out",
+  exit_code: 1,
+});
@@ -99,6 +99,37 @@ itest!(info_recursive_modules {
   exit_code: 0,
 });
 
+itest!(info_format_dot {
+  args: "info --quiet --format=dot info/info_recursive_imports_test.ts",
+  output: "info/info_recursive_imports_dot.out",
+});
+
+itest!(info_format_mermaid {
+  args: "info --quiet --format=mermaid info/info_recursive_imports_test.ts",
+  output: "info/info_recursive_imports_mermaid.out",
+});
+
+itest!(info_sizes {
+  args: "info --quiet --sizes info/info_recursive_imports_test.ts",
+  output: "info/info_recursive_imports_sizes.out",
+});
+
+itest!(info_why {
+  args: "info --quiet --why=info/recursive_imports/common.ts info/info_recursive_imports_test.ts",
+  output: "info/info_why_recursive_imports.out",
+});
+
+itest!(info_why_import_map {
+  args: "info --quiet --import-map=import_maps/import_map.json --why=import_maps/scoped_moment.ts import_maps/test.ts",
+  output: "info/info_why_import_map.out",
+});
+
+itest!(info_why_not_found {
+  args: "info --quiet --why=npm:chalk info/info_recursive_imports_test.ts",
+  output: "info/info_why_not_found.out",
+  exit_code: 1,
+});
+
 itest!(info_type_import {
   args: "info info/info_type_import.ts",
   output: "info/info_type_import.out",
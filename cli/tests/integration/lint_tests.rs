@@ -1,5 +1,6 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::serde_json;
 use test_util as util;
 
 #[test]
@@ -22,6 +23,96 @@ fn ignore_unexplicit_files() {
   );
 }
 
+#[test]
+fn fix() {
+  let temp_dir = util::TempDir::new();
+  let file_path = temp_dir.path().join("file.ts");
+  std::fs::write(
+    &file_path,
+    "let a = 1;\nconsole.log(a);;\n// deno-lint-ignore no-explicit-any\nif (a) {}\n",
+  )
+  .unwrap();
+  let output = util::deno_cmd()
+    .current_dir(temp_dir.path())
+    .env("NO_COLOR", "1")
+    .arg("lint")
+    .arg("--fix")
+    .arg(&file_path)
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  // the empty block has no fix
+  assert!(!output.status.success());
+  assert!(stderr.contains("Fixed 3 problems in"));
+  assert!(stderr.contains("(no-empty)"));
+  assert!(stderr.contains("Found 1 problem"));
+  assert_eq!(
+    std::fs::read_to_string(&file_path).unwrap(),
+    "const a = 1;\nconsole.log(a);\nif (a) {}\n"
+  );
+}
+
+#[test]
+fn sarif() {
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .env("NO_COLOR", "1")
+    .arg("lint")
+    .arg("--sarif")
+    .arg("lint/without_config/file1.js")
+    .arg("lint/without_config/malformed.js")
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(!output.status.success());
+
+  let sarif: serde_json::Value =
+    serde_json::from_slice(&output.stdout).unwrap();
+  assert_eq!(sarif["version"], "2.1.0");
+  let run = &sarif["runs"][0];
+  assert_eq!(run["tool"]["driver"]["name"], "deno lint");
+  let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+  assert_eq!(rules.len(), 2);
+  assert_eq!(rules[1]["id"], "no-empty");
+  assert_eq!(rules[1]["helpUri"], "https://lint.deno.land/#no-empty");
+  assert!(rules[1]["fullDescription"]["text"].is_string());
+
+  let results = run["results"].as_array().unwrap();
+  assert_eq!(results.len(), 2);
+  assert_eq!(results[1]["ruleId"], "no-empty");
+  assert_eq!(results[1]["ruleIndex"], 1);
+  let location = &results[1]["locations"][0]["physicalLocation"];
+  assert_eq!(
+    location["artifactLocation"],
+    serde_json::json!({
+      "uri": "lint/without_config/file1.js",
+      "uriBaseId": "%SRCROOT%",
+    })
+  );
+  assert_eq!(
+    location["region"],
+    serde_json::json!({
+      "startLine": 2,
+      "startColumn": 15,
+      "endLine": 2,
+      "endColumn": 17,
+    })
+  );
+
+  let invocation = &run["invocations"][0];
+  assert_eq!(invocation["executionSuccessful"], false);
+  let notification = &invocation["toolExecutionNotifications"][0];
+  assert_eq!(
+    notification["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+    "lint/without_config/malformed.js"
+  );
+}
+
 itest!(all {
   args: "lint lint/without_config/file1.js lint/without_config/file2.ts lint/without_config/ignored_file.ts",
   output: "lint/expected.out",
@@ -41,6 +132,12 @@ itest!(json {
     exit_code: 1,
 });
 
+itest!(github {
+  args: "lint --github lint/without_config/file1.js",
+  output: "lint/expected_github.out",
+  exit_code: 1,
+});
+
 itest!(compact {
   args:
     "lint --compact lint/without_config/file1.js lint/without_config/ignored_file.tss",
@@ -94,6 +191,18 @@ itest!(lint_with_config {
   exit_code: 1,
 });
 
+itest!(lint_plugins {
+  args: "lint --compact --config lint/plugins/deno.json lint/plugins/main.ts",
+  output: "lint/expected_plugins.out",
+  exit_code: 1,
+});
+
+itest!(lint_workspace {
+  args: "lint --compact workspaces/",
+  output: "workspaces/lint.out",
+  exit_code: 1,
+});
+
 itest!(lint_with_report_config {
   args: "lint --config lint/Deno.compact.format.jsonc lint/with_config/",
   output: "lint/with_report_config_compact.out",
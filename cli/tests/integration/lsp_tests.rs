@@ -6336,6 +6336,72 @@ fn lsp_code_actions_ignore_lint() {
   shutdown(&mut client);
 }
 
+#[test]
+fn lsp_code_actions_fix_lint() {
+  let mut client = init("initialize_params.json");
+  did_open(
+    &mut client,
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts",
+        "languageId": "typescript",
+        "version": 1,
+        "text": "let message = 'Hello, Deno!';\nconsole.log(message);\n"
+      }
+    }),
+  );
+  let diagnostic = json!({
+    "range": {
+      "start": { "line": 0, "character": 4 },
+      "end": { "line": 0, "character": 11 }
+    },
+    "severity": 2,
+    "code": "prefer-const",
+    "source": "deno-lint",
+    "message": "'message' is never reassigned\nUse 'const' instead"
+  });
+  let (maybe_res, maybe_err) = client
+    .write_request::<_, _, Value>(
+      "textDocument/codeAction",
+      json!({
+        "textDocument": { "uri": "file:///a/file.ts" },
+        "range": diagnostic["range"],
+        "context": {
+          "diagnostics": [diagnostic],
+          "only": ["quickfix"]
+        }
+      }),
+    )
+    .unwrap();
+  assert!(maybe_err.is_none());
+  let res = maybe_res.unwrap();
+  assert_eq!(
+    res[0],
+    json!({
+      "title": "Use `const` instead of `let`",
+      "kind": "quickfix",
+      "diagnostics": [diagnostic],
+      "edit": {
+        "changes": {
+          "file:///a/file.ts": [
+            {
+              "range": {
+                "start": { "line": 0, "character": 0 },
+                "end": { "line": 0, "character": 3 }
+              },
+              "newText": "const"
+            }
+          ]
+        }
+      },
+      "isPreferred": true
+    })
+  );
+  // the ignore actions are still offered
+  assert_eq!(res.as_array().unwrap().len(), 4);
+  shutdown(&mut client);
+}
+
 /// This test exercises updating an existing deno-lint-ignore-file comment.
 #[test]
 fn lsp_code_actions_update_ignore_lint() {
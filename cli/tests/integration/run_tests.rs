@@ -1,9 +1,11 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::serde_json;
 use deno_core::url;
 use deno_runtime::deno_fetch::reqwest;
 use std::io::Read;
 use std::io::Write;
+use std::path::Path;
 use std::process::Command;
 use std::process::Stdio;
 use test_util as util;
@@ -1223,6 +1225,23 @@ itest!(wasm_url {
   http_server: true,
 });
 
+itest!(wasm_module {
+  args: "run --quiet run/wasm_module/main.js",
+  output: "run/wasm_module/main.out",
+});
+
+itest!(wasm_module_import_attributes {
+  args: "run --quiet run/wasm_module/attributes.js",
+  output: "run/wasm_module/attributes.out",
+});
+
+itest!(wasm_module_lock_check_err {
+  args: "run --lock=run/wasm_module/lock_check_err.json http://127.0.0.1:4545/run/wasm_module/main.js",
+  output: "run/wasm_module/lock_check_err.out",
+  exit_code: 10,
+  http_server: true,
+});
+
 itest!(weakref {
   args: "run --quiet --reload run/weakref.ts",
   output: "run/weakref.ts.out",
@@ -2551,6 +2570,138 @@ fn navigator_languages() {
   assert!(!res.is_empty())
 }
 
+#[test]
+fn permission_audit_log() {
+  let temp_dir = TempDir::new();
+  let script_path = temp_dir.path().join("main.js");
+  let audit_path = temp_dir.path().join("audit.jsonl");
+  std::fs::write(
+    &script_path,
+    "Deno.readTextFileSync(Deno.args[0]);\n\
+     try { Deno.env.get('AUDITED_VAR'); } catch {}\n\
+     Deno.cwd();\n",
+  )
+  .unwrap();
+  let status = util::deno_cmd()
+    .current_dir(temp_dir.path())
+    .arg("run")
+    .arg("--allow-read")
+    .arg("--no-prompt")
+    .arg(format!("--permission-audit={}", audit_path.display()))
+    .arg(&script_path)
+    .arg("main.js")
+    .stdin(Stdio::null())
+    .spawn()
+    .unwrap()
+    .wait()
+    .unwrap();
+  assert!(status.success());
+
+  let records = std::fs::read_to_string(&audit_path)
+    .unwrap()
+    .lines()
+    .map(|line| serde_json::from_str(line).unwrap())
+    .collect::<Vec<serde_json::Value>>();
+  // paths are logged resolved against the current directory
+  let is_absolute_path = |descriptor: &serde_json::Value, file_name: &str| {
+    descriptor.as_str().map_or(false, |descriptor| {
+      let path = Path::new(descriptor);
+      path.is_absolute() && path.ends_with(file_name)
+    })
+  };
+  let dir_name = temp_dir.path().file_name().unwrap().to_str().unwrap();
+  assert!(records.iter().any(|record| {
+    record["kind"] == "read"
+      && is_absolute_path(&record["descriptor"], "main.js")
+      && record["api"] == "Deno.readTextFileSync()"
+      && record["result"] == "granted"
+      && record["location"]
+        .as_str()
+        .map_or(false, |location| location.contains("main.js:1:"))
+  }));
+  assert!(records.iter().any(|record| {
+    record["kind"] == "env"
+      && record["descriptor"] == "AUDITED_VAR"
+      && record["result"] == "denied"
+      && record["prompted"] == false
+      && record["location"]
+        .as_str()
+        .map_or(false, |location| location.contains("main.js:2:"))
+  }));
+  assert!(records.iter().any(|record| {
+    record["kind"] == "read"
+      && is_absolute_path(&record["descriptor"], dir_name)
+      && record["api"] == "Deno.cwd()"
+  }));
+}
+
+#[test]
+fn permissions_learn() {
+  let temp_dir = TempDir::new();
+  let data_dir = temp_dir.path().join("data");
+  std::fs::create_dir(&data_dir).unwrap();
+  std::fs::write(data_dir.join("a.txt"), "a").unwrap();
+  std::fs::write(data_dir.join("b.txt"), "b").unwrap();
+  let script_path = temp_dir.path().join("main.js");
+  std::fs::write(
+    &script_path,
+    "Deno.readTextFileSync(Deno.args[0] + '/a.txt');\n\
+     Deno.readTextFileSync(Deno.args[0] + '/b.txt');\n\
+     Deno.env.get('LEARNED_VAR');\n\
+     Deno.exit(0);\n",
+  )
+  .unwrap();
+  let output = util::deno_cmd()
+    .current_dir(temp_dir.path())
+    .arg("run")
+    .arg("--permissions-learn")
+    .arg(&script_path)
+    .arg(&data_dir)
+    .env("NO_COLOR", "1")
+    .stdin(Stdio::null())
+    .stderr(Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+
+  let stderr = std::str::from_utf8(&output.stderr).unwrap();
+  assert!(stderr.contains(&format!(
+    "Learned permissions: --allow-read={} --allow-env=LEARNED_VAR",
+    data_dir.display()
+  )));
+
+  let config_path = temp_dir.path().join("deno.json");
+  std::fs::write(&config_path, "{ \"tasks\": {} }").unwrap();
+  let status = util::deno_cmd()
+    .current_dir(temp_dir.path())
+    .arg("run")
+    .arg("--permissions-learn=deno.json")
+    .arg(&script_path)
+    .arg(&data_dir)
+    .stdin(Stdio::null())
+    .stderr(Stdio::null())
+    .spawn()
+    .unwrap()
+    .wait()
+    .unwrap();
+  assert!(status.success());
+  let config: serde_json::Value =
+    serde_json::from_str(&std::fs::read_to_string(&config_path).unwrap())
+      .unwrap();
+  assert_eq!(
+    config,
+    serde_json::json!({
+      "tasks": {},
+      "permissions": [
+        format!("--allow-read={}", data_dir.display()),
+        "--allow-env=LEARNED_VAR",
+      ],
+    })
+  );
+}
+
 /// Regression test for https://github.com/denoland/deno/issues/12740.
 #[test]
 fn issue12740() {
@@ -3844,3 +3995,16 @@ itest!(node_prefix_missing {
   envs: env_vars_for_npm_tests_no_sync_download(),
   exit_code: 1,
 });
+
+itest!(env_file {
+  args:
+    "run --quiet --allow-env --env-file=run/env_file/.env run/env_file/main.ts",
+  output: "run/env_file/main.out",
+  envs: vec![("EXISTING".to_string(), "from the environment".to_string())],
+});
+
+itest!(env_file_no_permission {
+  args: "run --quiet --env-file=run/env_file/.env run/env_file/main.ts",
+  output: "run/env_file/no_permission.out",
+  exit_code: 1,
+});
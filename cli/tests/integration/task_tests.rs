@@ -3,6 +3,9 @@
 // Most of the tests for this are in deno_task_shell.
 // These tests are intended to only test integration.
 
+use test_util as util;
+use test_util::TempDir;
+
 itest!(task_no_args {
   args: "task -q --config task/deno.json",
   output: "task/task_no_args.out",
@@ -41,6 +44,20 @@ itest!(task_cwd_resolves_config_from_specified_dir {
   exit_code: 1,
 });
 
+itest!(task_workspace_member {
+  args: "task -q --cwd workspaces/a hello",
+  output: "workspaces/task_member.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+  exit_code: 0,
+});
+
+itest!(task_workspace_member_inherited {
+  args: "task -q --cwd workspaces/a root",
+  output: "workspaces/task_member_inherited.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+  exit_code: 0,
+});
+
 itest!(task_non_existent {
   args: "task --config task/deno.json non_existent",
   output: "task/task_non_existent.out",
@@ -128,3 +145,103 @@ itest!(task_piped_stdin {
   output: "task/task_piped_stdin.out",
   envs: vec![("NO_COLOR".to_string(), "1".to_string())],
 });
+
+itest!(task_dependencies_list {
+  args: "task -q --config task/dependencies/deno.json",
+  output: "task/dependencies/list.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+  exit_code: 1,
+});
+
+itest!(task_dependencies {
+  args: "task -q --config task/dependencies/deno.json build",
+  output: "task/dependencies/build.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+});
+
+itest!(task_dependencies_cwd {
+  args: "task -q --config task/dependencies/deno.json echo_cwd",
+  output: "task/dependencies/echo_cwd.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+});
+
+itest!(task_dependencies_cycle {
+  args: "task -q --config task/dependencies/deno.json cycle",
+  output: "task/dependencies/cycle.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+  exit_code: 1,
+});
+
+itest!(task_dependencies_failed {
+  args: "task -q --config task/dependencies/deno.json after_fail",
+  output: "task/dependencies/after_fail.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+  exit_code: 3,
+});
+
+#[test]
+fn task_cached_outputs() {
+  let deno_dir = util::new_deno_dir();
+  let temp_dir = TempDir::new();
+  temp_dir.write(
+    "deno.json",
+    r#"{
+      "tasks": {
+        "generate": {
+          "command": "deno run --allow-read --allow-write generate.ts",
+          "inputs": ["generate.ts", "input.txt"],
+          "outputs": ["out.txt"]
+        }
+      }
+    }"#,
+  );
+  temp_dir.write(
+    "generate.ts",
+    r#"const text = Deno.readTextFileSync("input.txt");
+Deno.writeTextFileSync("out.txt", text.toUpperCase());
+console.log("generated");"#,
+  );
+  temp_dir.write("input.txt", "hello");
+
+  let run_task = || {
+    let output = util::deno_cmd_with_deno_dir(&deno_dir)
+      .current_dir(temp_dir.path())
+      .env("NO_COLOR", "1")
+      .arg("task")
+      .arg("generate")
+      .stdout(std::process::Stdio::piped())
+      .stderr(std::process::Stdio::piped())
+      .spawn()
+      .unwrap()
+      .wait_with_output()
+      .unwrap();
+    assert!(output.status.success());
+    (
+      String::from_utf8(output.stdout).unwrap(),
+      String::from_utf8(output.stderr).unwrap(),
+    )
+  };
+
+  let (stdout, _) = run_task();
+  assert_eq!(stdout, "generated\n");
+  assert_eq!(temp_dir.read_to_string("out.txt"), "HELLO");
+
+  // the task is skipped and its output restored
+  std::fs::remove_file(temp_dir.path().join("out.txt")).unwrap();
+  let (stdout, stderr) = run_task();
+  assert_eq!(stdout, "");
+  assert!(stderr.contains("Task generate (cached)"));
+  assert_eq!(temp_dir.read_to_string("out.txt"), "HELLO");
+
+  // changing an input runs the task again
+  temp_dir.write("input.txt", "bye");
+  let (stdout, _) = run_task();
+  assert_eq!(stdout, "generated\n");
+  assert_eq!(temp_dir.read_to_string("out.txt"), "BYE");
+}
+
+itest!(task_env_file {
+  args: "task -q --config task/env_file/deno.json echo",
+  output: "task/env_file/echo.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+});
@@ -2,6 +2,7 @@
 
 use deno_core::url::Url;
 use test_util as util;
+use test_util::TempDir;
 
 #[test]
 fn no_color() {
@@ -37,6 +38,12 @@ itest!(pass {
   output: "test/pass.out",
 });
 
+itest!(workspace {
+  args: "test workspaces/",
+  exit_code: 0,
+  output: "workspaces/test.out",
+});
+
 itest!(ignore {
   args: "test test/ignore.ts",
   exit_code: 0,
@@ -55,6 +62,24 @@ itest!(fail {
   output: "test/fail.out",
 });
 
+itest!(reporter_dot {
+  args: "test --reporter=dot test/reporters/reporters.ts",
+  exit_code: 1,
+  output: "test/reporters/dot.out",
+});
+
+itest!(reporter_tap {
+  args: "test --reporter=tap test/reporters/reporters.ts",
+  exit_code: 1,
+  output: "test/reporters/tap.out",
+});
+
+itest!(reporter_junit {
+  args: "test --reporter=junit test/reporters/reporters.ts",
+  exit_code: 1,
+  output: "test/reporters/junit.out",
+});
+
 itest!(collect {
   args: "test --ignore=test/collect/ignore test/collect",
   exit_code: 0,
@@ -249,6 +274,12 @@ itest!(resource_sanitizer {
   output: "test/resource_sanitizer.out",
 });
 
+itest!(resource_sanitizer_trace {
+  args: "test --allow-read --trace-ops test/resource_sanitizer_trace.ts",
+  exit_code: 1,
+  output: "test/resource_sanitizer_trace.out",
+});
+
 itest!(exit_sanitizer {
   args: "test test/exit_sanitizer.ts",
   output: "test/exit_sanitizer.out",
@@ -285,6 +316,42 @@ itest!(filter {
   output: "test/filter.out",
 });
 
+itest!(shard_1 {
+  args: "test --shard=1/2 test/shard",
+  exit_code: 0,
+  output: "test/shard_1.out",
+});
+
+itest!(shard_2 {
+  args: "test --shard=2/2 test/shard",
+  exit_code: 0,
+  output: "test/shard_2.out",
+});
+
+itest!(timeout {
+  args: "test test/timeout.ts",
+  exit_code: 1,
+  output: "test/timeout.out",
+});
+
+itest!(timeout_ops_sanitizer {
+  args: "test test/timeout_ops_sanitizer.ts",
+  exit_code: 1,
+  output: "test/timeout_ops_sanitizer.out",
+});
+
+itest!(retry {
+  args: "test --retry=1 test/retry.ts",
+  exit_code: 1,
+  output: "test/retry.out",
+});
+
+itest!(fake_time {
+  args: "test test/fake_time.ts",
+  exit_code: 0,
+  output: "test/fake_time.out",
+});
+
 itest!(shuffle {
   args: "test --shuffle test/shuffle",
   exit_code: 0,
@@ -422,6 +489,78 @@ fn file_protocol() {
   .run();
 }
 
+#[test]
+fn snapshots() {
+  let temp_dir = TempDir::new();
+  let write_test = |value: &str| {
+    temp_dir.write(
+      "snapshot_test.ts",
+      format!(
+        r#"Deno.test("value", async (t) => {{
+  t.assertSnapshot({value});
+  await t.step("step", (t) => t.assertSnapshot("step"));
+}});"#
+      ),
+    );
+  };
+  let run_test = |update: bool| {
+    let mut cmd = util::deno_cmd();
+    cmd
+      .current_dir(temp_dir.path())
+      .env("NO_COLOR", "1")
+      .arg("test")
+      .stdout(std::process::Stdio::piped())
+      .stderr(std::process::Stdio::piped());
+    if update {
+      cmd.arg("--update-snapshots");
+    }
+    let output = cmd.spawn().unwrap().wait_with_output().unwrap();
+    (
+      output.status.success(),
+      String::from_utf8(output.stdout).unwrap(),
+      String::from_utf8(output.stderr).unwrap(),
+    )
+  };
+
+  write_test("{ a: 1 }");
+  let (success, stdout, _) = run_test(false);
+  assert!(!success);
+  assert!(stdout.contains("Missing snapshot \"value 1\""), "{stdout}");
+
+  let (success, _, stderr) = run_test(true);
+  assert!(success);
+  assert!(stderr.contains("Updated 2 snapshots in"), "{stderr}");
+  let snapshots =
+    temp_dir.read_to_string("__snapshots__/snapshot_test.ts.snap");
+  assert!(snapshots.contains("snapshot[`value 1`]"), "{snapshots}");
+  assert!(
+    snapshots.contains("snapshot[`value > step 1`]"),
+    "{snapshots}"
+  );
+  let (success, _, _) = run_test(false);
+  assert!(success);
+
+  write_test("{ a: 2 }");
+  let (success, stdout, _) = run_test(false);
+  assert!(!success);
+  assert!(
+    stdout.contains("Snapshot \"value 1\" does not match"),
+    "{stdout}"
+  );
+
+  temp_dir.write(
+    "snapshot_test.ts",
+    r#"Deno.test("value", (t) => t.assertSnapshot(1));"#,
+  );
+  let (success, _, stderr) = run_test(true);
+  assert!(success);
+  assert!(
+    stderr.contains("Removed 1 obsolete snapshot from"),
+    "{stderr}"
+  );
+  assert!(stderr.contains("  value > step 1"), "{stderr}");
+}
+
 itest!(uncaught_errors {
   args: "test --quiet test/uncaught_errors_1.ts test/uncaught_errors_2.ts test/uncaught_errors_3.ts",
   output: "test/uncaught_errors.out",
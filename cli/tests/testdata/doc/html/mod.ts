@@ -0,0 +1,16 @@
+import { Circle, Point } from "./shapes.ts";
+
+export * from "./shapes.ts";
+
+/**
+ * Creates a circle at the origin.
+ *
+ * @param radius The radius of the circle.
+ */
+export function unitCircle(radius = 1): Circle {
+  const origin: Point = { x: 0, y: 0 };
+  return new Circle(origin, radius);
+}
+
+/** The index of the first shape. */
+export const index = 0;
@@ -0,0 +1,20 @@
+/** Shapes that can be drawn.
+ *
+ * @module
+ */
+
+/** A point on a plane. */
+export interface Point {
+  x: number;
+  y: number;
+}
+
+/** A circle, see {@link Point} for its center. */
+export class Circle {
+  constructor(public center: Point, public radius: number) {}
+
+  /** The area of the circle. */
+  area(): number {
+    return Math.PI * this.radius ** 2;
+  }
+}
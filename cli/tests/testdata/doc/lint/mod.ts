@@ -0,0 +1,28 @@
+interface Options {
+  verbose: boolean;
+}
+
+/** Documented, with an explicit return type. */
+export function documented(): number {
+  return 1;
+}
+
+export function undocumented(): void {}
+
+/** Has an inferred return type. */
+export function inferred() {
+  return 1;
+}
+
+/** References a type which isn't exported. */
+export function configure(options: Options): void {
+  console.log(options.verbose);
+}
+
+/** References a type which isn't exported in a type argument. */
+export type Wrapped = Promise<Options[]>;
+
+/** Only mentions the name of a type which isn't exported in a string. */
+export function describe(name: "Options"): string {
+  return name;
+}
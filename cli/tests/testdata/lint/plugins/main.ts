@@ -0,0 +1,3 @@
+console.log(Deno.env.get("HOME"));
+// deno-lint-ignore test-plugin/no-deno-env
+console.log(Deno.env.get("PATH"));
@@ -0,0 +1,30 @@
+interface Node {
+  type: string;
+  range: [number, number];
+}
+
+interface Context {
+  getText(node: Node): string;
+  report(diagnostic: { node: Node; message: string; hint?: string }): void;
+}
+
+export default {
+  name: "test-plugin",
+  rules: {
+    "no-deno-env": {
+      create(context: Context) {
+        return {
+          MemberExpr(node: Node) {
+            if (context.getText(node) === "Deno.env") {
+              context.report({
+                node,
+                message: "`Deno.env` is not allowed in this project.",
+                hint: "Pass the configuration as arguments instead.",
+              });
+            }
+          },
+        };
+      },
+    },
+  },
+};
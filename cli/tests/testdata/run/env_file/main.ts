@@ -0,0 +1,3 @@
+console.log(Deno.env.get("MESSAGE"));
+console.log(Deno.env.get("MULTILINE"));
+console.log(Deno.env.get("EXISTING"));
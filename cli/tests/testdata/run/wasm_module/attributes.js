@@ -0,0 +1,10 @@
+import text from "./hello.txt" with { type: "text" };
+import { add } from "./add.wasm";
+
+const { default: bytes } = await import("./add.wasm", {
+  with: { type: "bytes" },
+});
+
+console.log(add(1, 2));
+console.log(text.trim());
+console.log(bytes instanceof Uint8Array, bytes.length);
@@ -0,0 +1,7 @@
+import { add } from "./add.wasm";
+import text from "./hello.txt" assert { type: "text" };
+import bytes from "./add.wasm" assert { type: "bytes" };
+
+console.log(add(1, 2));
+console.log(text.trim());
+console.log(bytes instanceof Uint8Array, bytes.length);
@@ -0,0 +1,104 @@
+function assertEquals(actual: unknown, expected: unknown) {
+  if (actual !== expected) {
+    throw new Error(`expected ${expected}, got ${actual}`);
+  }
+}
+
+Deno.test("timers run when the clock is advanced", (t) => {
+  const time = t.useFakeTime(0);
+  const calls: string[] = [];
+  setTimeout(() => calls.push("b"), 20);
+  setTimeout(() => calls.push("a"), 10);
+  const id = setInterval(() => calls.push("interval"), 15);
+  const cancelled = setTimeout(() => calls.push("cancelled"), 5);
+  clearTimeout(cancelled);
+
+  time.tick(9);
+  assertEquals(calls.join(), "");
+  time.tick(21);
+  assertEquals(calls.join(), "a,interval,b,interval");
+  assertEquals(time.now, 30);
+  clearInterval(id);
+  time.tick(100);
+  assertEquals(calls.length, 4);
+});
+
+Deno.test("Date and performance.now() follow the clock", (t) => {
+  const start = new Date("2023-01-01T00:00:00Z");
+  const time = t.useFakeTime(start);
+  const perfStart = performance.now();
+  assertEquals(Date.now(), start.getTime());
+  assertEquals(new Date().toISOString(), "2023-01-01T00:00:00.000Z");
+  assertEquals(new Date(0).getTime(), 0);
+  assertEquals(new Date() instanceof Date, true);
+
+  time.tick(1500);
+  assertEquals(Date.now(), start.getTime() + 1500);
+  assertEquals(performance.now() - perfStart, 1500);
+});
+
+Deno.test("runAll runs the timers scheduled by timers", (t) => {
+  const time = t.useFakeTime(0);
+  let depth = 0;
+  const schedule = () => {
+    if (++depth < 3) {
+      setTimeout(schedule, 1000);
+    }
+  };
+  setTimeout(schedule, 1000);
+  time.runAll();
+  assertEquals(depth, 3);
+  assertEquals(time.now, 3000);
+});
+
+Deno.test("an interval of 0 is reported as an infinite loop", (t) => {
+  const time = t.useFakeTime();
+  setInterval(() => {}, 0);
+  let message = "";
+  try {
+    time.runAll();
+  } catch (error) {
+    message = (error as Error).message;
+  }
+  assertEquals(
+    message,
+    "Aborting after running 10000 timers, assuming an infinite loop.",
+  );
+});
+
+Deno.test("pending fake timers don't leak", (t) => {
+  t.useFakeTime();
+  setTimeout(() => {
+    throw new Error("unreachable");
+  }, 10);
+  setInterval(() => {}, 10);
+});
+
+Deno.test("the real time is restored after the test", async () => {
+  const start = Date.now();
+  assertEquals(start > new Date("2023-01-01T00:00:00Z").getTime(), true);
+  await new Promise((resolve) => setTimeout(resolve, 10));
+});
+
+Deno.test("the time can only be faked once", (t) => {
+  const time = t.useFakeTime();
+  let message = "";
+  try {
+    t.useFakeTime();
+  } catch (error) {
+    message = (error as Error).message;
+  }
+  assertEquals(
+    message,
+    "Time is already faked. Restore the current fake time before faking it again.",
+  );
+  time.restore();
+  t.useFakeTime().restore();
+  message = "";
+  try {
+    time.tick(1);
+  } catch (error) {
+    message = (error as Error).message;
+  }
+  assertEquals(message, "The fake time has already been restored.");
+});
@@ -0,0 +1,18 @@
+Deno.test("passing test", () => {});
+
+Deno.test({
+  name: "ignored test",
+  ignore: true,
+  fn() {},
+});
+
+Deno.test("failing test", () => {
+  throw new Error("Fail");
+});
+
+Deno.test("test with steps", async (t) => {
+  await t.step("step 1", async (t) => {
+    await t.step("inner", () => {});
+  });
+  await t.step("step 2", () => {});
+});
@@ -0,0 +1,4 @@
+Deno.test("leak", async function () {
+  Deno.openSync("run/001_hello.js");
+  await Deno.open("run/001_hello.js");
+});
@@ -0,0 +1,15 @@
+let attempts = 0;
+Deno.test({
+  name: "flaky",
+  retries: 2,
+  fn() {
+    attempts++;
+    if (attempts < 2) {
+      throw new Error("fails on the first attempt");
+    }
+  },
+});
+
+Deno.test("always fails", () => {
+  throw new Error("fails");
+});
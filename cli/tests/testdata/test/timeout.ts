@@ -0,0 +1,9 @@
+Deno.test({
+  name: "hangs",
+  timeout: 100,
+  async fn() {
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+  },
+});
+
+Deno.test("passes", () => {});
@@ -0,0 +1,11 @@
+Deno.test({
+  name: "hangs",
+  timeout: 100,
+  async fn() {
+    await new Promise((resolve) => setTimeout(resolve, 500));
+  },
+});
+
+Deno.test("completes the ops of the timed out test", async () => {
+  await new Promise((resolve) => setTimeout(resolve, 1000));
+});
@@ -0,0 +1,3 @@
+import { name } from "shared/name.ts";
+
+export const greeting = `Hello, ${name}!`;
@@ -0,0 +1,3 @@
+import { greeting } from "greeting";
+
+const unused = greeting;
@@ -0,0 +1,7 @@
+import { greeting } from "greeting";
+
+Deno.test("a", () => {
+  if (greeting !== "Hello, workspace!") {
+    throw new Error(greeting);
+  }
+});
@@ -0,0 +1,3 @@
+Deno.test("excluded by the member's configuration", () => {
+  throw new Error("should not run");
+});
@@ -0,0 +1 @@
+const unused = "only reported in a";
@@ -0,0 +1,350 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+import {
+  assert,
+  assertEquals,
+  assertRejects,
+  assertThrows,
+} from "./test_util.ts";
+
+function dbTest(name: string, fn: (db: Deno.Kv) => Promise<void>) {
+  Deno.test({
+    name,
+    async fn() {
+      const db: Deno.Kv = await Deno.openKv(":memory:");
+      try {
+        await fn(db);
+      } finally {
+        db.close();
+      }
+    },
+  });
+}
+
+dbTest("basic read-write-delete", async (db) => {
+  const result = await db.set(["a"], "b");
+  assert(result.ok);
+  assertEquals(result.versionstamp, "00000000000000010000");
+
+  const entry = await db.get(["a"]);
+  assertEquals(entry, {
+    key: ["a"],
+    value: "b",
+    versionstamp: "00000000000000010000",
+  });
+
+  await db.delete(["a"]);
+  const deleted = await db.get(["a"]);
+  assertEquals(deleted, { key: ["a"], value: null, versionstamp: null });
+});
+
+const VALUE_CASES = [
+  { name: "string", value: "hello" },
+  { name: "number", value: 42 },
+  { name: "bigint", value: 42n },
+  { name: "boolean", value: true },
+  { name: "null", value: null },
+  { name: "undefined", value: undefined },
+  { name: "Date", value: new Date(0) },
+  { name: "Uint8Array", value: new Uint8Array([1, 2, 3]) },
+  { name: "array", value: [1, 2, 3] },
+  { name: "object", value: { a: 1, b: { c: [2n] } } },
+  { name: "Map", value: new Map([["a", 1]]) },
+];
+
+for (const { name, value } of VALUE_CASES) {
+  dbTest(`set and get ${name} value`, async (db) => {
+    await db.set(["a"], value);
+    const entry = await db.get(["a"]);
+    assertEquals(entry.value, value);
+  });
+}
+
+dbTest("set unserializable value", async (db) => {
+  await assertRejects(() => db.set(["a"], () => {}), TypeError);
+});
+
+const KEY_CASES = [
+  { name: "string", key: ["a"] },
+  { name: "number", key: [-1.5] },
+  { name: "bigint", key: [-(2n ** 70n)] },
+  { name: "boolean", key: [false] },
+  { name: "Uint8Array", key: [new Uint8Array([0, 1, 0xff])] },
+  { name: "mixed", key: [new Uint8Array(), "", 0, 0n, true] },
+];
+
+for (const { name, key } of KEY_CASES) {
+  dbTest(`set and get ${name} key`, async (db) => {
+    await db.set(key, "b");
+    const entry = await db.get(key);
+    assertEquals(entry.key, key);
+    assertEquals(entry.value, "b");
+  });
+}
+
+dbTest("invalid key part", async (db) => {
+  // deno-lint-ignore no-explicit-any
+  await assertRejects(() => db.set([{}] as any, "b"), TypeError);
+  // deno-lint-ignore no-explicit-any
+  await assertRejects(() => db.get("a" as any), TypeError);
+});
+
+dbTest("getMany", async (db) => {
+  await db.set(["a"], 1);
+  await db.set(["b"], 2);
+  const entries = await db.getMany([["a"], ["b"], ["c"]]);
+  assertEquals(entries.map((entry) => entry.value), [1, 2, null]);
+});
+
+dbTest("compare and mutate", async (db) => {
+  await db.set(["t"], "1");
+
+  const currentValue = await db.get(["t"]);
+  assertEquals(currentValue.versionstamp, "00000000000000010000");
+
+  let res = await db.atomic()
+    .check({ key: ["t"], versionstamp: currentValue.versionstamp })
+    .set(currentValue.key, "2")
+    .commit();
+  assert(res.ok);
+  assertEquals(res.versionstamp, "00000000000000020000");
+
+  const newValue = await db.get(["t"]);
+  assertEquals(newValue.versionstamp, "00000000000000020000");
+  assertEquals(newValue.value, "2");
+
+  res = await db.atomic()
+    .check({ key: ["t"], versionstamp: currentValue.versionstamp })
+    .set(currentValue.key, "3")
+    .commit();
+  assert(!res.ok);
+
+  const newValue2 = await db.get(["t"]);
+  assertEquals(newValue2.versionstamp, "00000000000000020000");
+  assertEquals(newValue2.value, "2");
+});
+
+dbTest("compare and mutate not exists", async (db) => {
+  let res = await db.atomic()
+    .check({ key: ["t"], versionstamp: null })
+    .set(["t"], "1")
+    .commit();
+  assert(res.ok);
+
+  const newValue = await db.get(["t"]);
+  assertEquals(newValue.value, "1");
+
+  res = await db.atomic()
+    .check({ key: ["t"], versionstamp: null })
+    .set(["t"], "2")
+    .commit();
+  assert(!res.ok);
+});
+
+dbTest("atomic mutations are all or nothing", async (db) => {
+  await db.set(["a"], 1);
+  const res = await db.atomic()
+    .check({ key: ["b"], versionstamp: "00000000000000010000" })
+    .set(["a"], 2)
+    .delete(["a"])
+    .commit();
+  assert(!res.ok);
+  assertEquals((await db.get(["a"])).value, 1);
+
+  const res2 = await db.atomic()
+    .set(["b"], 2)
+    .delete(["a"])
+    .commit();
+  assert(res2.ok);
+  assertEquals((await db.getMany([["a"], ["b"]])).map((e) => e.value), [
+    null,
+    2,
+  ]);
+});
+
+async function collect<T>(iter: Deno.KvListIterator<T>) {
+  const entries = [];
+  for await (const entry of iter) {
+    entries.push(entry);
+  }
+  return entries;
+}
+
+async function setupData(db: Deno.Kv) {
+  await db.atomic()
+    .set(["a"], -1)
+    .set(["a", "a"], 0)
+    .set(["a", "b"], 1)
+    .set(["a", "c"], 2)
+    .set(["a", "d"], 3)
+    .set(["a", "e"], 4)
+    .set(["b"], 99)
+    .set(["b", "a"], 100)
+    .commit();
+}
+
+dbTest("list prefix", async (db) => {
+  await setupData(db);
+  const entries = await collect(db.list({ prefix: ["a"] }));
+  assertEquals(entries.map((e) => e.key), [
+    ["a", "a"],
+    ["a", "b"],
+    ["a", "c"],
+    ["a", "d"],
+    ["a", "e"],
+  ]);
+  assertEquals(entries.map((e) => e.value), [0, 1, 2, 3, 4]);
+});
+
+dbTest("list prefix empty", async (db) => {
+  await setupData(db);
+  const entries = await collect(db.list({ prefix: ["c"] }));
+  assertEquals(entries.length, 0);
+});
+
+dbTest("list prefix with start and end", async (db) => {
+  await setupData(db);
+  let entries = await collect(db.list({ prefix: ["a"], start: ["a", "c"] }));
+  assertEquals(entries.map((e) => e.value), [2, 3, 4]);
+  entries = await collect(db.list({ prefix: ["a"], end: ["a", "c"] }));
+  assertEquals(entries.map((e) => e.value), [0, 1]);
+  assertThrows(
+    () => db.list({ prefix: ["a"], start: ["b"] }),
+    TypeError,
+    "Start key is not in the keyspace defined by prefix",
+  );
+});
+
+dbTest("list range", async (db) => {
+  await setupData(db);
+  const entries = await collect(db.list({ start: ["a", "b"], end: ["b"] }));
+  assertEquals(entries.map((e) => e.value), [1, 2, 3, 4]);
+});
+
+dbTest("list reverse with limit", async (db) => {
+  await setupData(db);
+  const entries = await collect(
+    db.list({ prefix: ["a"] }, { reverse: true, limit: 2 }),
+  );
+  assertEquals(entries.map((e) => e.value), [4, 3]);
+});
+
+dbTest("list in batches", async (db) => {
+  await setupData(db);
+  const entries = await collect(db.list({ prefix: ["a"] }, { batchSize: 2 }));
+  assertEquals(entries.map((e) => e.value), [0, 1, 2, 3, 4]);
+  const reversed = await collect(
+    db.list({ prefix: ["a"] }, { batchSize: 2, reverse: true }),
+  );
+  assertEquals(reversed.map((e) => e.value), [4, 3, 2, 1, 0]);
+});
+
+dbTest("list with cursor", async (db) => {
+  await setupData(db);
+  const iter = db.list({ prefix: ["a"] }, { limit: 2 });
+  assertEquals((await collect(iter)).map((e) => e.value), [0, 1]);
+  const cursor = iter.cursor;
+
+  const iter2 = db.list({ prefix: ["a"] }, { cursor, limit: 2 });
+  assertEquals((await collect(iter2)).map((e) => e.value), [2, 3]);
+
+  const iter3 = db.list({ prefix: ["a"] }, { cursor: iter2.cursor });
+  assertEquals((await collect(iter3)).map((e) => e.value), [4]);
+});
+
+dbTest("list with cursor reverse", async (db) => {
+  await setupData(db);
+  const iter = db.list({ prefix: ["a"] }, { limit: 2, reverse: true });
+  assertEquals((await collect(iter)).map((e) => e.value), [4, 3]);
+
+  const iter2 = db.list({ prefix: ["a"] }, {
+    cursor: iter.cursor,
+    reverse: true,
+  });
+  assertEquals((await collect(iter2)).map((e) => e.value), [2, 1, 0]);
+});
+
+dbTest("list cursor before first iteration", async (db) => {
+  const iter = db.list({ prefix: ["a"] });
+  assertThrows(
+    () => iter.cursor,
+    TypeError,
+    "Cannot get cursor before first iteration",
+  );
+});
+
+dbTest("key ordering", async (db) => {
+  const keys = [
+    [new Uint8Array([0x00])],
+    [new Uint8Array([0x00, 0x01])],
+    [""],
+    ["a"],
+    ["a", 1],
+    ["b"],
+    [-Infinity],
+    [-1.5],
+    [0],
+    [1],
+    [Infinity],
+    [-(2n ** 64n)],
+    [-1n],
+    [0n],
+    [256n],
+    [false],
+    [true],
+  ];
+  const op = db.atomic();
+  for (const key of [...keys].reverse()) {
+    op.set(key, 0);
+  }
+  assert((await op.commit()).ok);
+
+  const entries = await collect(
+    db.list({ start: [new Uint8Array()], end: [true, 0] }),
+  );
+  assertEquals(entries.map((e) => e.key), keys);
+});
+
+dbTest("value size limit", async (db) => {
+  await assertRejects(
+    () => db.set(["a"], new Uint8Array(65537)),
+    TypeError,
+    "value too large",
+  );
+});
+
+Deno.test(
+  { permissions: { read: true, write: true } },
+  async function kvPersistsToPath() {
+    const path = await Deno.makeTempFile({ suffix: ".sqlite3" });
+    try {
+      const db = await Deno.openKv(path);
+      await db.set(["a"], "persisted");
+      db.close();
+
+      const db2 = await Deno.openKv(path);
+      assertEquals((await db2.get(["a"])).value, "persisted");
+      db2.close();
+    } finally {
+      await Deno.remove(path);
+    }
+  },
+);
+
+Deno.test(
+  { permissions: { read: true, write: false } },
+  async function kvOpenPathPerm() {
+    await assertRejects(
+      () => Deno.openKv("kv_test.sqlite3"),
+      Deno.errors.PermissionDenied,
+    );
+  },
+);
+
+Deno.test(function kvIllegalConstructor() {
+  assertThrows(
+    // deno-lint-ignore no-explicit-any
+    () => new (Deno.Kv as any)(),
+    TypeError,
+    "Illegal constructor",
+  );
+});
@@ -15,6 +15,7 @@ use crate::util::fs::collect_specifiers;
 use crate::util::path::is_supported_ext;
 use crate::worker::create_main_worker_for_test_or_bench;
 
+use deno_core::anyhow::Context;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::error::JsError;
@@ -22,6 +23,8 @@ use deno_core::futures::future;
 use deno_core::futures::stream;
 use deno_core::futures::FutureExt;
 use deno_core::futures::StreamExt;
+use deno_core::serde_json;
+use deno_core::url::Url;
 use deno_core::ModuleSpecifier;
 use deno_runtime::permissions::Permissions;
 use deno_runtime::permissions::PermissionsContainer;
@@ -41,6 +44,17 @@ use tokio::sync::mpsc::UnboundedSender;
 #[derive(Debug, Clone)]
 struct BenchSpecifierOptions {
   filter: TestFilter,
+  json: bool,
+  baseline: Option<Arc<BenchBaseline>>,
+}
+
+/// A previous run loaded from a report written with `--json`, which the
+/// current measurements are compared against.
+#[derive(Debug)]
+struct BenchBaseline {
+  report: JsonBenchReport,
+  /// The maximum allowed increase of the average time, in percent.
+  regression_threshold: f64,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
@@ -109,8 +123,46 @@ impl BenchReport {
   }
 }
 
-fn create_reporter(show_output: bool) -> Box<dyn BenchReporter + Send> {
-  Box::new(ConsoleReporter::new(show_output))
+/// The document written by `deno bench --json` and read by `--baseline`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonBenchReport {
+  version: u8,
+  runtime: String,
+  cpu: String,
+  benches: Vec<JsonBench>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonBench {
+  /// The module the bench was declared in, relative to the current directory
+  /// so that reports can be compared between machines.
+  origin: String,
+  group: Option<String>,
+  name: String,
+  baseline: bool,
+  result: JsonBenchResult,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum JsonBenchResult {
+  Ok(BenchStats),
+  Failed(String),
+}
+
+const JSON_REPORT_VERSION: u8 = 1;
+
+fn create_reporter(
+  show_output: bool,
+  json: bool,
+) -> Box<dyn BenchReporter + Send> {
+  if json {
+    Box::new(JsonReporter::new())
+  } else {
+    Box::new(ConsoleReporter::new(show_output))
+  }
 }
 
 pub trait BenchReporter {
@@ -325,6 +377,199 @@ impl BenchReporter for ConsoleReporter {
   }
 }
 
+struct JsonReporter {
+  cwd: Url,
+  benches: Vec<JsonBench>,
+}
+
+impl JsonReporter {
+  fn new() -> Self {
+    Self {
+      cwd: Url::from_directory_path(std::env::current_dir().unwrap()).unwrap(),
+      benches: Vec::new(),
+    }
+  }
+}
+
+impl BenchReporter for JsonReporter {
+  fn report_group_summary(&mut self) {}
+
+  fn report_plan(&mut self, _plan: &BenchPlan) {}
+
+  fn report_register(&mut self, _desc: &BenchDescription) {}
+
+  fn report_wait(&mut self, _desc: &BenchDescription) {}
+
+  fn report_output(&mut self, output: &str) {
+    // keep stdout parseable
+    eprint!("{output}");
+  }
+
+  fn report_result(&mut self, desc: &BenchDescription, result: &BenchResult) {
+    let result = match result {
+      BenchResult::Ok(stats) => JsonBenchResult::Ok(stats.clone()),
+      BenchResult::Failed(js_error) => {
+        JsonBenchResult::Failed(js_error.exception_message.clone())
+      }
+    };
+    self.benches.push(JsonBench {
+      origin: relative_origin(&self.cwd, &desc.origin),
+      group: desc.group.clone(),
+      name: desc.name.clone(),
+      baseline: desc.baseline,
+      result,
+    });
+  }
+
+  fn report_end(&mut self, _report: &BenchReport) {
+    let report = JsonBenchReport {
+      version: JSON_REPORT_VERSION,
+      runtime: format!("deno {} ({})", crate::version::deno(), env!("TARGET")),
+      cpu: mitata::cpu::name(),
+      benches: std::mem::take(&mut self.benches),
+    };
+    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+  }
+}
+
+fn relative_origin(cwd: &Url, origin: &str) -> String {
+  match Url::parse(origin) {
+    Ok(url) if url.scheme() == "file" => match cwd.make_relative(&url) {
+      Some(relative) if !relative.starts_with("../") => {
+        format!("./{relative}")
+      }
+      _ => origin.to_string(),
+    },
+    _ => origin.to_string(),
+  }
+}
+
+fn load_baseline(
+  path: &str,
+  regression_threshold: f64,
+) -> Result<BenchBaseline, AnyError> {
+  let text = std::fs::read_to_string(path)
+    .with_context(|| format!("Failed reading bench baseline '{path}'"))?;
+  let report: JsonBenchReport = serde_json::from_str(&text).with_context(
+    || format!("Failed parsing bench baseline '{path}'. It should be the output of `deno bench --json`."),
+  )?;
+  if report.version != JSON_REPORT_VERSION {
+    return Err(generic_error(format!(
+      "Unsupported bench baseline version {} in '{}', expected {}.",
+      report.version, path, JSON_REPORT_VERSION
+    )));
+  }
+  Ok(BenchBaseline {
+    report,
+    regression_threshold,
+  })
+}
+
+fn resolve_baseline(
+  bench_options: &BenchOptions,
+) -> Result<Option<Arc<BenchBaseline>>, AnyError> {
+  match &bench_options.baseline {
+    Some(path) => Ok(Some(Arc::new(load_baseline(
+      path,
+      bench_options.regression_threshold as f64,
+    )?))),
+    None => Ok(None),
+  }
+}
+
+/// Formats a duration in nanoseconds like the console reporter does.
+fn format_duration(ns: f64) -> String {
+  if ns < 1e3 {
+    format!("{ns:.2} ns")
+  } else if ns < 1e6 {
+    format!("{:.2} µs", ns / 1e3)
+  } else if ns < 1e9 {
+    format!("{:.2} ms", ns / 1e6)
+  } else {
+    format!("{:.2} s", ns / 1e9)
+  }
+}
+
+/// The relative change of the average time of `current` compared to
+/// `baseline`, in percent.
+fn avg_delta_percent(baseline: &BenchStats, current: &BenchStats) -> f64 {
+  if baseline.avg == 0.0 {
+    return 0.0;
+  }
+  (current.avg - baseline.avg) / baseline.avg * 100.0
+}
+
+/// Prints the delta of every measured bench against the baseline run and
+/// returns the names of the benches that regressed by more than the
+/// threshold.
+fn compare_with_baseline(
+  baseline: &BenchBaseline,
+  report: &BenchReport,
+  to_stderr: bool,
+) -> Vec<String> {
+  let cwd = Url::from_directory_path(std::env::current_dir().unwrap()).unwrap();
+  let mut lines = Vec::new();
+  let mut regressions = Vec::new();
+  lines.push(format!(
+    "{}",
+    colors::gray(format!(
+      "comparison with baseline (regression threshold: {}%)",
+      baseline.regression_threshold
+    ))
+  ));
+  for (desc, stats) in &report.measurements {
+    let origin = relative_origin(&cwd, &desc.origin);
+    let display_name = format!("{} > {}", origin, desc.name);
+    let maybe_baseline_stats =
+      baseline
+        .report
+        .benches
+        .iter()
+        .find_map(|b| match &b.result {
+          JsonBenchResult::Ok(stats)
+            if b.origin == origin
+              && b.name == desc.name
+              && b.group == desc.group =>
+          {
+            Some(stats)
+          }
+          _ => None,
+        });
+    let baseline_stats = match maybe_baseline_stats {
+      Some(stats) => stats,
+      None => {
+        lines.push(format!("{} {}", display_name, colors::gray("(new)")));
+        continue;
+      }
+    };
+    let delta = avg_delta_percent(baseline_stats, stats);
+    let delta_text = format!("{delta:+.2}%");
+    let delta_text = if delta > baseline.regression_threshold {
+      regressions.push(display_name.clone());
+      colors::red_bold(delta_text).to_string()
+    } else if delta < 0.0 {
+      colors::green(delta_text).to_string()
+    } else {
+      delta_text
+    };
+    lines.push(format!(
+      "{} {} -> {} {}",
+      display_name,
+      colors::gray(format_duration(baseline_stats.avg)),
+      format_duration(stats.avg),
+      delta_text,
+    ));
+  }
+
+  let output = lines.join("\n");
+  if to_stderr {
+    eprintln!("\n{output}");
+  } else {
+    println!("\n{output}");
+  }
+  regressions
+}
+
 /// Type check a collection of module and document specifiers.
 async fn check_specifiers(
   ps: &ProcState,
@@ -375,6 +620,8 @@ async fn bench_specifiers(
   let log_level = ps.options.log_level();
 
   let (sender, mut receiver) = unbounded_channel::<BenchEvent>();
+  let json = options.json;
+  let maybe_baseline = options.baseline.clone();
 
   let join_handles = specifiers.into_iter().map(move |specifier| {
     let ps = ps.clone();
@@ -398,7 +645,7 @@ async fn bench_specifiers(
     tokio::task::spawn(async move {
       let mut used_only = false;
       let mut report = BenchReport::new();
-      let mut reporter = create_reporter(log_level != Some(Level::Error));
+      let mut reporter = create_reporter(log_level != Some(Level::Error), json);
       let mut benches = IndexMap::new();
 
       while let Some(event) = receiver.recv().await {
@@ -444,6 +691,18 @@ async fn bench_specifiers(
 
       reporter.report_end(&report);
 
+      if let Some(baseline) = &maybe_baseline {
+        let regressions = compare_with_baseline(baseline, &report, json);
+        if !regressions.is_empty() {
+          return Err(generic_error(format!(
+            "Bench failed because {} benchmark(s) regressed by more than {}% compared to the baseline:\n  {}",
+            regressions.len(),
+            baseline.regression_threshold,
+            regressions.join("\n  "),
+          )));
+        }
+      }
+
       if used_only {
         return Err(generic_error(
           "Bench failed because the \"only\" option was used",
@@ -503,12 +762,15 @@ pub async fn run_benchmarks(
 
   check_specifiers(&ps, permissions.clone(), specifiers.clone()).await?;
 
+  let maybe_baseline = resolve_baseline(&bench_options)?;
   bench_specifiers(
     &ps,
     &permissions,
     specifiers,
     BenchSpecifierOptions {
       filter: TestFilter::from_flag(&bench_options.filter),
+      json: bench_options.json,
+      baseline: maybe_baseline,
     },
   )
   .await?;
@@ -516,7 +778,7 @@ pub async fn run_benchmarks(
   Ok(())
 }
 
-// TODO(bartlomieju): heavy duplication of code with `cli/tools/test.rs`
+// TODO(bartlomieju): heavy duplication of code with `cli/tools/test/mod.rs`
 pub async fn run_benchmarks_with_watch(
   cli_options: CliOptions,
   bench_options: BenchOptions,
@@ -528,6 +790,7 @@ pub async fn run_benchmarks_with_watch(
   let permissions =
     Permissions::from_options(&ps.options.permissions_options())?;
   let no_check = ps.options.type_check_mode() == TypeCheckMode::None;
+  let maybe_baseline = resolve_baseline(&bench_options)?;
 
   let ps = RefCell::new(ps);
 
@@ -640,6 +903,7 @@ pub async fn run_benchmarks_with_watch(
   let operation = |modules_to_reload: Vec<ModuleSpecifier>| {
     let permissions = &permissions;
     let bench_options = &bench_options;
+    let maybe_baseline = maybe_baseline.clone();
     ps.borrow_mut().reset_for_file_watcher();
     let ps = ps.borrow().clone();
 
@@ -658,6 +922,8 @@ pub async fn run_benchmarks_with_watch(
         specifiers,
         BenchSpecifierOptions {
           filter: TestFilter::from_flag(&bench_options.filter),
+          json: bench_options.json,
+          baseline: maybe_baseline,
         },
       )
       .await?;
@@ -0,0 +1,399 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use super::CoverageReport;
+use super::CoverageReporter;
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::url::Url;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2em; color: #222; }
+h1 { font-size: 1.4em; }
+a { color: #0550ae; text-decoration: none; }
+a:hover { text-decoration: underline; }
+table { border-collapse: collapse; }
+table.summary td, table.summary th { padding: 4px 12px; border-bottom: 1px solid #ddd; text-align: right; }
+table.summary td:first-child, table.summary th:first-child { text-align: left; }
+table.summary tr.dir td { font-weight: bold; background: #f3f3f3; }
+table.summary tr.file td:first-child { padding-left: 2em; }
+.high { color: #1a7f37; }
+.medium { color: #9a6700; }
+.low { color: #cf222e; }
+table.source { font-family: ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; font-size: 13px; width: 100%; }
+table.source td { padding: 0 8px; white-space: pre; vertical-align: top; }
+table.source td.line-number, table.source td.hits, table.source td.branches { text-align: right; color: #666; user-select: none; }
+table.source tr.hit td.code { background: #e6ffec; }
+table.source tr.miss td.code { background: #ffebe9; }
+table.source tr.miss td.hits { color: #cf222e; }
+table.source td.branches.partial { background: #fff8c5; color: #9a6700; }
+"#;
+
+#[derive(Default, Clone, Copy)]
+struct CoverageStats {
+  lines_found: usize,
+  lines_hit: usize,
+  branches_found: usize,
+  branches_hit: usize,
+  functions_found: usize,
+  functions_hit: usize,
+}
+
+impl CoverageStats {
+  fn from_report(report: &CoverageReport) -> Self {
+    Self {
+      lines_found: report.found_lines.len(),
+      lines_hit: report
+        .found_lines
+        .iter()
+        .filter(|(_, count)| *count > 0)
+        .count(),
+      branches_found: report.branches.len(),
+      branches_hit: report.branches.iter().filter(|b| b.is_hit).count(),
+      functions_found: report.named_functions.len(),
+      functions_hit: report
+        .named_functions
+        .iter()
+        .filter(|f| f.execution_count > 0)
+        .count(),
+    }
+  }
+
+  fn add(&mut self, other: &CoverageStats) {
+    self.lines_found += other.lines_found;
+    self.lines_hit += other.lines_hit;
+    self.branches_found += other.branches_found;
+    self.branches_hit += other.branches_hit;
+    self.functions_found += other.functions_found;
+    self.functions_hit += other.functions_hit;
+  }
+}
+
+struct FileCoverage {
+  /// Path of the file relative to the report root, using `/` separators.
+  path: String,
+  stats: CoverageStats,
+  line_hits: HashMap<usize, i64>,
+  /// Number of hit and found branches per line.
+  line_branches: HashMap<usize, (usize, usize)>,
+  source: String,
+}
+
+/// Writes a static site with an index of all covered files grouped by
+/// directory and one page per file with its source annotated with line hit
+/// counts and branch coverage.
+pub struct HtmlCoverageReporter {
+  output_dir: PathBuf,
+  cwd: Url,
+  files: Vec<FileCoverage>,
+}
+
+impl HtmlCoverageReporter {
+  pub fn new(output_dir: PathBuf) -> HtmlCoverageReporter {
+    HtmlCoverageReporter {
+      output_dir,
+      cwd: Url::from_directory_path(std::env::current_dir().unwrap()).unwrap(),
+      files: Vec::new(),
+    }
+  }
+
+  /// Returns the path of the report page for a module relative to the report
+  /// root. Local files keep their path relative to the current directory,
+  /// other modules are placed under a directory named after their scheme and
+  /// host.
+  fn report_path(&self, url: &Url) -> String {
+    if url.scheme() == "file" {
+      if let Some(relative) = self.cwd.make_relative(url) {
+        if !relative.starts_with("../") {
+          return sanitize_path(&relative);
+        }
+      }
+      return sanitize_path(&format!("__root__{}", url.path()));
+    }
+    sanitize_path(&format!(
+      "{}/{}{}",
+      url.scheme(),
+      url.host_str().unwrap_or_default(),
+      url.path()
+    ))
+  }
+
+  fn write_index(&self) -> Result<(), AnyError> {
+    let mut directories: BTreeMap<&str, Vec<&FileCoverage>> = BTreeMap::new();
+    for file in &self.files {
+      let dir = file
+        .path
+        .rsplit_once('/')
+        .map(|(dir, _)| dir)
+        .unwrap_or(".");
+      directories.entry(dir).or_default().push(file);
+    }
+
+    let mut total = CoverageStats::default();
+    let mut rows = String::new();
+    for (dir, files) in &directories {
+      let mut dir_stats = CoverageStats::default();
+      for file in files {
+        dir_stats.add(&file.stats);
+      }
+      total.add(&dir_stats);
+      write_summary_row(&mut rows, "dir", &escape_html(dir), &dir_stats);
+      for file in files {
+        let name = file.path.rsplit('/').next().unwrap();
+        let link = format!(
+          "<a href=\"{}.html\">{}</a>",
+          escape_html(&file.path),
+          escape_html(name)
+        );
+        write_summary_row(&mut rows, "file", &link, &file.stats);
+      }
+    }
+
+    let mut body = String::new();
+    writeln!(body, "<h1>Coverage report</h1>").unwrap();
+    body.push_str(&summary_table(&total, &rows));
+    write_page(
+      &self.output_dir.join("index.html"),
+      "Coverage report",
+      "",
+      &body,
+    )
+  }
+
+  fn write_file_page(&self, file: &FileCoverage) -> Result<(), AnyError> {
+    let depth = file.path.matches('/').count();
+    let root = "../".repeat(depth);
+
+    let mut body = String::new();
+    writeln!(
+      body,
+      "<h1><a href=\"{}index.html\">All files</a> / {}</h1>",
+      root,
+      escape_html(&file.path)
+    )
+    .unwrap();
+    let mut rows = String::new();
+    write_summary_row(&mut rows, "file", &escape_html(&file.path), &file.stats);
+    body.push_str(&summary_table(&file.stats, &rows));
+
+    body.push_str("<table class=\"source\">\n");
+    for (line_index, line) in file.source.split('\n').enumerate() {
+      let line = line.strip_suffix('\r').unwrap_or(line);
+      let (class, hits) = match file.line_hits.get(&line_index) {
+        Some(count) if *count > 0 => ("hit", format!("{count}x")),
+        Some(_) => ("miss", "0x".to_string()),
+        None => ("", String::new()),
+      };
+      let branches = match file.line_branches.get(&line_index) {
+        Some((hit, found)) if hit < found => {
+          format!("<td class=\"branches partial\">{hit}/{found}</td>")
+        }
+        Some((hit, found)) => {
+          format!("<td class=\"branches\">{hit}/{found}</td>")
+        }
+        None => "<td class=\"branches\"></td>".to_string(),
+      };
+      writeln!(
+        body,
+        "<tr class=\"{}\" id=\"L{}\"><td class=\"line-number\"><a href=\"#L{}\">{}</a></td><td class=\"hits\">{}</td>{}<td class=\"code\">{}</td></tr>",
+        class,
+        line_index + 1,
+        line_index + 1,
+        line_index + 1,
+        hits,
+        branches,
+        escape_html(line),
+      )
+      .unwrap();
+    }
+    body.push_str("</table>\n");
+
+    let path = self.output_dir.join(format!("{}.html", file.path));
+    write_page(&path, &file.path, &root, &body)
+  }
+}
+
+impl CoverageReporter for HtmlCoverageReporter {
+  fn report(
+    &mut self,
+    coverage_report: &CoverageReport,
+    file_text: &str,
+  ) -> Result<(), AnyError> {
+    let mut line_branches: HashMap<usize, (usize, usize)> = HashMap::new();
+    for branch in &coverage_report.branches {
+      let entry = line_branches.entry(branch.line_index).or_default();
+      entry.1 += 1;
+      if branch.is_hit {
+        entry.0 += 1;
+      }
+    }
+
+    self.files.push(FileCoverage {
+      path: self.report_path(&coverage_report.url),
+      stats: CoverageStats::from_report(coverage_report),
+      line_hits: coverage_report.found_lines.iter().copied().collect(),
+      line_branches,
+      source: file_text.to_string(),
+    });
+    Ok(())
+  }
+
+  fn done(&mut self) -> Result<(), AnyError> {
+    fs::create_dir_all(&self.output_dir).with_context(|| {
+      format!(
+        "Failed to create coverage report directory '{}'",
+        self.output_dir.display()
+      )
+    })?;
+    self.files.sort_by(|a, b| a.path.cmp(&b.path));
+    fs::write(self.output_dir.join("style.css"), STYLE.trim_start())?;
+    self.write_index()?;
+    for file in &self.files {
+      self.write_file_page(file)?;
+    }
+    log::info!(
+      "HTML coverage report has been generated at file://{}",
+      fs::canonicalize(&self.output_dir)?
+        .join("index.html")
+        .display()
+    );
+    Ok(())
+  }
+}
+
+fn write_page(
+  path: &Path,
+  title: &str,
+  root: &str,
+  body: &str,
+) -> Result<(), AnyError> {
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent)?;
+  }
+  let html = format!(
+    "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{} - Coverage report</title>
+<link rel=\"stylesheet\" href=\"{}style.css\">
+</head>
+<body>
+{}</body>
+</html>
+",
+    escape_html(title),
+    root,
+    body
+  );
+  fs::write(path, html)
+    .with_context(|| format!("Failed to write '{}'", path.display()))?;
+  Ok(())
+}
+
+fn summary_table(total: &CoverageStats, rows: &str) -> String {
+  let mut table = String::new();
+  writeln!(
+    table,
+    "<table class=\"summary\">
+<tr><th>File</th><th>Lines</th><th>Branches</th><th>Functions</th></tr>"
+  )
+  .unwrap();
+  table.push_str(rows);
+  write_summary_row(&mut table, "dir", "All files", total);
+  table.push_str("</table>\n");
+  table
+}
+
+fn write_summary_row(
+  out: &mut String,
+  class: &str,
+  name: &str,
+  stats: &CoverageStats,
+) {
+  writeln!(
+    out,
+    "<tr class=\"{}\"><td>{}</td>{}{}{}</tr>",
+    class,
+    name,
+    ratio_cell(stats.lines_hit, stats.lines_found),
+    ratio_cell(stats.branches_hit, stats.branches_found),
+    ratio_cell(stats.functions_hit, stats.functions_found),
+  )
+  .unwrap();
+}
+
+fn ratio_cell(hit: usize, found: usize) -> String {
+  if found == 0 {
+    return "<td>-</td>".to_string();
+  }
+  let ratio = hit as f32 / found as f32;
+  // same thresholds as the pretty reporter
+  let class = if ratio >= 0.9 {
+    "high"
+  } else if ratio >= 0.75 {
+    "medium"
+  } else {
+    "low"
+  };
+  format!(
+    "<td class=\"{}\">{:.2}% ({}/{})</td>",
+    class,
+    ratio * 100.0,
+    hit,
+    found
+  )
+}
+
+/// Drops empty, `.` and `..` path segments and characters that are not valid
+/// in file names on Windows so that report pages are always written inside
+/// the output directory.
+fn sanitize_path(path: &str) -> String {
+  path
+    .split('/')
+    .filter(|segment| !matches!(*segment, "" | "." | ".."))
+    .map(|segment| {
+      segment.replace(
+        |c: char| matches!(c, ':' | '*' | '?' | '"' | '<' | '>' | '|' | '\\'),
+        "_",
+      )
+    })
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+fn escape_html(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_sanitize_path() {
+    assert_eq!(sanitize_path("src/foo.ts"), "src/foo.ts");
+    assert_eq!(sanitize_path("/../../etc/passwd"), "etc/passwd");
+    assert_eq!(
+      sanitize_path("https/deno.land/x/a:b.ts"),
+      "https/deno.land/x/a_b.ts"
+    );
+  }
+
+  #[test]
+  fn test_ratio_cell() {
+    assert_eq!(ratio_cell(0, 0), "<td>-</td>");
+    assert_eq!(ratio_cell(1, 2), "<td class=\"low\">50.00% (1/2)</td>");
+    assert_eq!(ratio_cell(9, 10), "<td class=\"high\">90.00% (9/10)</td>");
+  }
+}
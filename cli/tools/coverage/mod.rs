@@ -31,10 +31,12 @@ use std::path::PathBuf;
 use text_lines::TextLines;
 use uuid::Uuid;
 
+mod html;
 mod json_types;
 mod merge;
 mod range_tree;
 
+use html::HtmlCoverageReporter;
 use json_types::*;
 
 pub struct CoverageCollector {
@@ -361,6 +363,7 @@ fn generate_coverage_report(
 enum CoverageReporterKind {
   Pretty,
   Lcov,
+  Html(PathBuf),
 }
 
 fn create_reporter(
@@ -369,6 +372,9 @@ fn create_reporter(
   match kind {
     CoverageReporterKind::Lcov => Box::new(LcovCoverageReporter::new()),
     CoverageReporterKind::Pretty => Box::new(PrettyCoverageReporter::new()),
+    CoverageReporterKind::Html(output_dir) => {
+      Box::new(HtmlCoverageReporter::new(output_dir))
+    }
   }
 }
 
@@ -379,7 +385,7 @@ trait CoverageReporter {
     file_text: &str,
   ) -> Result<(), AnyError>;
 
-  fn done(&mut self);
+  fn done(&mut self) -> Result<(), AnyError>;
 }
 
 struct LcovCoverageReporter {}
@@ -481,7 +487,9 @@ impl CoverageReporter for LcovCoverageReporter {
     Ok(())
   }
 
-  fn done(&mut self) {}
+  fn done(&mut self) -> Result<(), AnyError> {
+    Ok(())
+  }
 }
 
 struct PrettyCoverageReporter {}
@@ -554,7 +562,9 @@ impl CoverageReporter for PrettyCoverageReporter {
     Ok(())
   }
 
-  fn done(&mut self) {}
+  fn done(&mut self) -> Result<(), AnyError> {
+    Ok(())
+  }
 }
 
 fn collect_coverages(
@@ -617,7 +627,7 @@ pub async fn cover_files(
 
   let ps = ProcState::build(flags).await?;
 
-  let script_coverages = collect_coverages(coverage_flags.files)?;
+  let script_coverages = collect_coverages(coverage_flags.files.clone())?;
   let script_coverages = filter_coverages(
     script_coverages,
     coverage_flags.include,
@@ -638,22 +648,29 @@ pub async fn cover_files(
 
   let reporter_kind = if coverage_flags.lcov {
     CoverageReporterKind::Lcov
+  } else if coverage_flags.html {
+    // the report is written next to the coverage profiles by default
+    let output_dir = coverage_flags
+      .output
+      .clone()
+      .unwrap_or_else(|| coverage_flags.files.include[0].join("html"));
+    CoverageReporterKind::Html(output_dir)
   } else {
     CoverageReporterKind::Pretty
   };
 
-  let mut reporter = create_reporter(reporter_kind);
-
   let out_mode = match coverage_flags.output {
-    Some(ref path) => match File::create(path) {
+    Some(ref path) if coverage_flags.lcov => match File::create(path) {
       Ok(_) => Some(PathBuf::from(path)),
       Err(e) => {
         return Err(anyhow!("Failed to create output file: {}", e));
       }
     },
-    None => None,
+    _ => None,
   };
 
+  let mut reporter = create_reporter(reporter_kind);
+
   for script_coverage in script_coverages {
     let module_specifier =
       deno_core::resolve_url_or_path(&script_coverage.url)?;
@@ -717,7 +734,7 @@ pub async fn cover_files(
     }
   }
 
-  reporter.done();
+  reporter.done()?;
 
   Ok(())
 }
@@ -0,0 +1,635 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Writes the documentation of modules as a static HTML site, with a page for
+//! each module and for each of its symbols, and a search index that is used
+//! by the pages to search the symbols client-side.
+
+use super::markdown::render_markdown;
+use super::signature::member_signatures;
+use super::signature::node_signature;
+use super::signature::IDENTIFIER_RE;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_doc::js_doc::JsDoc;
+use deno_doc::js_doc::JsDocTag;
+use deno_doc::DocNode;
+use deno_doc::DocNodeKind;
+use deno_graph::ModuleSpecifier;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+static STYLES_CSS: &str = include_str!("./styles.css");
+static SEARCH_JS: &str = include_str!("./search.js");
+
+/// The order of the sections of symbols in module and namespace pages.
+const SECTIONS: &[(DocNodeKind, &str)] = &[
+  (DocNodeKind::Namespace, "Namespaces"),
+  (DocNodeKind::Class, "Classes"),
+  (DocNodeKind::Enum, "Enums"),
+  (DocNodeKind::Variable, "Variables"),
+  (DocNodeKind::Function, "Functions"),
+  (DocNodeKind::Interface, "Interfaces"),
+  (DocNodeKind::TypeAlias, "Type Aliases"),
+];
+
+/// The files at the root of the site, which the directories of the modules
+/// must not replace.
+const RESERVED_FILE_NAMES: &[&str] =
+  &["index.html", "styles.css", "search.js", "search_index.js"];
+
+/// The directory of the symbol pages in the directory of a module, so that
+/// they don't collide with the `index.html` of the module.
+const SYMBOLS_DIR: &str = "~";
+
+/// Writes the site for the documentation nodes of each module to the output
+/// directory, overwriting the files of a previous site.
+pub fn write_docs_site(
+  output_dir: &Path,
+  doc_nodes_by_module: &[(ModuleSpecifier, Vec<DocNode>)],
+) -> Result<(), AnyError> {
+  let site = Site::new(doc_nodes_by_module);
+  write_file(&output_dir.join("index.html"), &site.render_index())?;
+  write_file(&output_dir.join("styles.css"), STYLES_CSS)?;
+  write_file(&output_dir.join("search.js"), SEARCH_JS)?;
+  write_file(
+    &output_dir.join("search_index.js"),
+    &site.render_search_index(),
+  )?;
+  for module in &site.modules {
+    let module_dir = output_dir.join(&module.slug);
+    write_file(&module_dir.join("index.html"), &site.render_module(module))?;
+    for symbol in &module.symbols {
+      write_file(
+        &module_dir.join(SYMBOLS_DIR).join(symbol.file_name()),
+        &site.render_symbol(module, symbol),
+      )?;
+    }
+  }
+  log::info!(
+    "Wrote documentation of {} {} to {}",
+    site.modules.len(),
+    if site.modules.len() == 1 {
+      "module"
+    } else {
+      "modules"
+    },
+    output_dir.display()
+  );
+  Ok(())
+}
+
+fn write_file(path: &Path, text: &str) -> Result<(), AnyError> {
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).with_context(|| {
+      format!("Failed to create directory \"{}\"", parent.display())
+    })?;
+  }
+  fs::write(path, text)
+    .with_context(|| format!("Failed to write \"{}\"", path.display()))
+}
+
+struct Site<'a> {
+  modules: Vec<ModulePage<'a>>,
+  /// The URL of each symbol, relative to the root of the site, by its name
+  /// qualified with its namespaces. The first module wins for names that
+  /// are exported by more than one module.
+  links: HashMap<String, String>,
+}
+
+struct ModulePage<'a> {
+  name: String,
+  /// The name of the directory of the module's pages.
+  slug: String,
+  doc_nodes: &'a [DocNode],
+  /// The symbols of the module and of its namespaces.
+  symbols: Vec<SymbolPage<'a>>,
+}
+
+impl ModulePage<'_> {
+  fn js_doc(&self) -> Option<&JsDoc> {
+    self
+      .doc_nodes
+      .iter()
+      .find(|node| node.kind == DocNodeKind::ModuleDoc)
+      .map(|node| &node.js_doc)
+  }
+}
+
+/// The declarations of a name, like the overloads of a function or an
+/// interface and a variable with the same name.
+struct SymbolPage<'a> {
+  /// The name qualified with the namespaces containing the symbol.
+  name: String,
+  /// The name of the symbol's page without the extension, unique in the
+  /// module.
+  slug: String,
+  /// The qualified name of the namespace containing the symbol, if any.
+  namespace: Option<String>,
+  nodes: Vec<&'a DocNode>,
+}
+
+impl SymbolPage<'_> {
+  fn kind(&self) -> DocNodeKind {
+    self.nodes[0].kind
+  }
+
+  fn file_name(&self) -> String {
+    format!("{}.html", self.slug)
+  }
+}
+
+impl<'a> Site<'a> {
+  fn new(doc_nodes_by_module: &'a [(ModuleSpecifier, Vec<DocNode>)]) -> Self {
+    let cwd = std::env::current_dir()
+      .ok()
+      .and_then(|cwd| ModuleSpecifier::from_directory_path(cwd).ok());
+    let mut modules: Vec<ModulePage> = Vec::new();
+    for (specifier, doc_nodes) in doc_nodes_by_module {
+      let name = module_name(specifier, cwd.as_ref());
+      let slug = unique_slug(slugify(&name), |slug| {
+        RESERVED_FILE_NAMES.contains(&slug)
+          || modules.iter().any(|module| module.slug == slug)
+      });
+      let mut symbols = Vec::new();
+      collect_symbols(doc_nodes, None, &mut symbols);
+      modules.push(ModulePage {
+        name,
+        slug,
+        doc_nodes,
+        symbols,
+      });
+    }
+
+    let mut links = HashMap::new();
+    for module in &modules {
+      for symbol in &module.symbols {
+        links
+          .entry(symbol.name.clone())
+          .or_insert_with(|| symbol_url(module, symbol));
+      }
+    }
+    Self { modules, links }
+  }
+
+  fn render_index(&self) -> String {
+    let mut body = String::from("<h1>Modules</h1>\n<ul class=\"modules\">\n");
+    for module in &self.modules {
+      write!(
+        body,
+        "<li><a href=\"{}/index.html\">{}</a>{}</li>\n",
+        module.slug,
+        escape_html(&module.name),
+        module
+          .js_doc()
+          .map(|js_doc| self.render_summary(js_doc, ""))
+          .unwrap_or_default()
+      )
+      .unwrap();
+    }
+    body.push_str("</ul>\n");
+    render_page("Documentation", "", &body)
+  }
+
+  fn render_module(&self, module: &ModulePage) -> String {
+    let root = "../";
+    let mut body = format!(
+      "<nav><a href=\"{root}index.html\">Modules</a></nav>\n<h1>{}</h1>\n",
+      escape_html(&module.name)
+    );
+    if let Some(js_doc) = module.js_doc() {
+      body.push_str(&self.render_js_doc(js_doc, root));
+    }
+    body.push_str(&self.render_symbol_list(
+      module,
+      module.symbols.iter().filter(|s| s.namespace.is_none()),
+      root,
+    ));
+    render_page(&module.name, root, &body)
+  }
+
+  fn render_symbol(&self, module: &ModulePage, symbol: &SymbolPage) -> String {
+    let root = "../../";
+    let mut body = format!(
+      "<nav><a href=\"{root}index.html\">Modules</a> / <a href=\"../index.html\">{}</a></nav>\n",
+      escape_html(&module.name)
+    );
+    write!(
+      body,
+      "<h1><span class=\"kind\">{}</span> {}</h1>\n",
+      kind_label(symbol.kind()),
+      escape_html(&symbol.name)
+    )
+    .unwrap();
+    for node in &symbol.nodes {
+      body.push_str("<section class=\"declaration\">\n");
+      write!(
+        body,
+        "<pre class=\"signature\">{}</pre>\n",
+        self.render_code(&node_signature(node), root)
+      )
+      .unwrap();
+      body.push_str(&self.render_js_doc(&node.js_doc, root));
+      body.push_str(&self.render_members(node, root));
+      body.push_str("</section>\n");
+    }
+    if symbol.kind() == DocNodeKind::Namespace {
+      body.push_str(
+        &self.render_symbol_list(
+          module,
+          module
+            .symbols
+            .iter()
+            .filter(|s| s.namespace.as_deref() == Some(&symbol.name)),
+          root,
+        ),
+      );
+    }
+    render_page(&format!("{} - {}", symbol.name, module.name), root, &body)
+  }
+
+  /// Renders links to the symbols of the module, in a section for each kind.
+  fn render_symbol_list<'b>(
+    &self,
+    module: &ModulePage,
+    symbols: impl Iterator<Item = &'b SymbolPage<'a>>,
+    root: &str,
+  ) -> String
+  where
+    'a: 'b,
+  {
+    let symbols = symbols.collect::<Vec<_>>();
+    let mut html = String::new();
+    for (kind, title) in SECTIONS {
+      let section_symbols = symbols
+        .iter()
+        .filter(|symbol| symbol.kind() == *kind)
+        .collect::<Vec<_>>();
+      if section_symbols.is_empty() {
+        continue;
+      }
+      write!(html, "<h2>{title}</h2>\n<ul class=\"symbols\">\n").unwrap();
+      for symbol in section_symbols {
+        write!(
+          html,
+          "<li><a href=\"{root}{}\">{}</a>{}</li>\n",
+          symbol_url(module, symbol),
+          escape_html(&symbol.name),
+          self.render_summary(&symbol.nodes[0].js_doc, root)
+        )
+        .unwrap();
+      }
+      html.push_str("</ul>\n");
+    }
+    html
+  }
+
+  fn render_members(&self, node: &DocNode, root: &str) -> String {
+    let members = member_signatures(node);
+    if members.is_empty() {
+      return String::new();
+    }
+
+    let mut html = String::from("<h2>Members</h2>\n");
+    for (signature, js_doc) in members {
+      write!(
+        html,
+        "<div class=\"member\">\n<pre class=\"signature\">{}</pre>\n{}</div>\n",
+        self.render_code(&signature, root),
+        self.render_js_doc(js_doc, root)
+      )
+      .unwrap();
+    }
+    html
+  }
+
+  fn render_js_doc(&self, js_doc: &JsDoc, root: &str) -> String {
+    let mut html = String::new();
+    let mut params = String::new();
+    let mut returns = String::new();
+    let mut examples = String::new();
+    for tag in &js_doc.tags {
+      match tag {
+        JsDocTag::Deprecated { doc } => {
+          html
+            .push_str("<div class=\"deprecated\"><strong>Deprecated</strong>");
+          if let Some(doc) = doc {
+            html.push_str(&self.render_markdown(doc, root));
+          }
+          html.push_str("</div>\n");
+        }
+        JsDocTag::Param { name, doc, .. } => {
+          write!(
+            params,
+            "<dt><code>{}</code></dt><dd>{}</dd>\n",
+            escape_html(name),
+            doc
+              .as_ref()
+              .map(|doc| self.render_markdown(doc, root))
+              .unwrap_or_default()
+          )
+          .unwrap();
+        }
+        JsDocTag::Return { doc: Some(doc), .. } => {
+          returns.push_str(&self.render_markdown(doc, root));
+        }
+        JsDocTag::Example { doc } => {
+          examples.push_str(&self.render_markdown(doc, root));
+        }
+        _ => {}
+      }
+    }
+    if let Some(doc) = &js_doc.doc {
+      write!(
+        html,
+        "<div class=\"doc\">{}</div>\n",
+        self.render_markdown(doc, root)
+      )
+      .unwrap();
+    }
+    if !params.is_empty() {
+      write!(html, "<h3>Parameters</h3>\n<dl>\n{params}</dl>\n").unwrap();
+    }
+    if !returns.is_empty() {
+      write!(html, "<h3>Returns</h3>\n{returns}").unwrap();
+    }
+    if !examples.is_empty() {
+      write!(html, "<h3>Examples</h3>\n{examples}").unwrap();
+    }
+    html
+  }
+
+  /// Renders the first paragraph of the documentation.
+  fn render_summary(&self, js_doc: &JsDoc, root: &str) -> String {
+    match js_doc
+      .doc
+      .as_deref()
+      .and_then(|doc| doc.split("\n\n").next())
+    {
+      Some(summary) if !summary.trim().is_empty() => format!(
+        "<div class=\"summary\">{}</div>",
+        self.render_markdown(summary, root)
+      ),
+      _ => String::new(),
+    }
+  }
+
+  fn render_markdown(&self, markdown: &str, root: &str) -> String {
+    render_markdown(markdown, |name| {
+      self.links.get(name).map(|url| format!("{root}{url}"))
+    })
+  }
+
+  /// Escapes the code, linking the names of documented symbols to their
+  /// pages.
+  fn render_code(&self, code: &str, root: &str) -> String {
+    let mut html = String::with_capacity(code.len());
+    let mut last_end = 0;
+    for m in IDENTIFIER_RE.find_iter(code) {
+      html.push_str(&escape_html(&code[last_end..m.start()]));
+      match self.links.get(m.as_str()) {
+        Some(url) => write!(
+          html,
+          "<a href=\"{root}{url}\">{}</a>",
+          escape_html(m.as_str())
+        )
+        .unwrap(),
+        None => html.push_str(&escape_html(m.as_str())),
+      }
+      last_end = m.end();
+    }
+    html.push_str(&escape_html(&code[last_end..]));
+    html
+  }
+
+  fn render_search_index(&self) -> String {
+    let entries = self
+      .modules
+      .iter()
+      .flat_map(|module| {
+        module.symbols.iter().map(|symbol| {
+          json!({
+            "name": symbol.name,
+            "kind": kind_label(symbol.kind()),
+            "module": module.name,
+            "url": symbol_url(module, symbol),
+          })
+        })
+      })
+      .collect::<Vec<_>>();
+    format!(
+      "window.DOC_SEARCH_INDEX = {};\n",
+      serde_json::to_string(&entries).unwrap()
+    )
+  }
+}
+
+/// Groups the documentation nodes by name, recursing into namespaces.
+fn collect_symbols<'a>(
+  doc_nodes: &'a [DocNode],
+  namespace: Option<&str>,
+  symbols: &mut Vec<SymbolPage<'a>>,
+) {
+  let start = symbols.len();
+  for node in doc_nodes {
+    if matches!(node.kind, DocNodeKind::Import | DocNodeKind::ModuleDoc) {
+      continue;
+    }
+    let name = match namespace {
+      Some(namespace) => format!("{}.{}", namespace, node.name),
+      None => node.name.clone(),
+    };
+    match symbols[start..].iter_mut().find(|s| s.name == name) {
+      Some(symbol) => symbol.nodes.push(node),
+      None => {
+        let slug = unique_slug(slugify(&name), |slug| {
+          symbols.iter().any(|symbol| symbol.slug == slug)
+        });
+        symbols.push(SymbolPage {
+          name,
+          slug,
+          namespace: namespace.map(ToString::to_string),
+          nodes: vec![node],
+        });
+      }
+    }
+  }
+  for i in start..symbols.len() {
+    let name = symbols[i].name.clone();
+    let namespace_nodes = symbols[i]
+      .nodes
+      .iter()
+      .filter_map(|node| node.namespace_def.as_ref())
+      .collect::<Vec<_>>();
+    for namespace_def in namespace_nodes {
+      collect_symbols(&namespace_def.elements, Some(&name), symbols);
+    }
+  }
+}
+
+/// The URL of the symbol's page, relative to the root of the site.
+fn symbol_url(module: &ModulePage, symbol: &SymbolPage) -> String {
+  format!("{}/{}/{}", module.slug, SYMBOLS_DIR, symbol.file_name())
+}
+
+fn kind_label(kind: DocNodeKind) -> &'static str {
+  match kind {
+    DocNodeKind::Function => "function",
+    DocNodeKind::Variable => "variable",
+    DocNodeKind::Class => "class",
+    DocNodeKind::Enum => "enum",
+    DocNodeKind::Interface => "interface",
+    DocNodeKind::TypeAlias => "type",
+    DocNodeKind::Namespace => "namespace",
+    DocNodeKind::ModuleDoc | DocNodeKind::Import => "",
+  }
+}
+
+/// The path of local modules relative to the current directory, or the URL
+/// of remote modules without the scheme.
+pub fn module_name(
+  specifier: &ModuleSpecifier,
+  maybe_cwd: Option<&ModuleSpecifier>,
+) -> String {
+  if specifier.scheme() == "file" {
+    if let Some(relative) =
+      maybe_cwd.and_then(|cwd| cwd.make_relative(specifier))
+    {
+      if !relative.starts_with("../") {
+        return relative;
+      }
+    }
+    if let Ok(path) = specifier.to_file_path() {
+      return path.display().to_string();
+    }
+  }
+  match specifier.as_str().split_once("://") {
+    Some((_, rest)) => rest.to_string(),
+    None => specifier.to_string(),
+  }
+}
+
+/// Replaces the characters of the name that aren't safe in file names and
+/// URLs.
+fn slugify(name: &str) -> String {
+  name
+    .chars()
+    .map(|c| {
+      if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+        c
+      } else {
+        '_'
+      }
+    })
+    .collect()
+}
+
+/// Appends a number to the slug while it is already taken.
+fn unique_slug(slug: String, is_taken: impl Fn(&str) -> bool) -> String {
+  if !is_taken(&slug) {
+    return slug;
+  }
+  (1..)
+    .map(|i| format!("{slug}_{i}"))
+    .find(|slug| !is_taken(slug))
+    .unwrap()
+}
+
+fn escape_html(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&#39;"),
+      c => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+fn render_page(title: &str, root: &str, body: &str) -> String {
+  format!(
+    r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{}</title>
+<link rel="stylesheet" href="{root}styles.css">
+</head>
+<body data-root="{root}">
+<header>
+<a class="home" href="{root}index.html">Documentation</a>
+<input id="search" type="search" placeholder="Search symbols" autocomplete="off">
+<ul id="search-results" hidden></ul>
+</header>
+<main>
+{body}</main>
+<script src="{root}search_index.js"></script>
+<script src="{root}search.js"></script>
+</body>
+</html>
+"#,
+    escape_html(title)
+  )
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_module_name() {
+    let cwd = ModuleSpecifier::parse("file:///project/").unwrap();
+    let name = |specifier: &str| {
+      module_name(&ModuleSpecifier::parse(specifier).unwrap(), Some(&cwd))
+    };
+    assert_eq!(name("file:///project/src/mod.ts"), "src/mod.ts");
+    assert_eq!(
+      name("https://deno.land/std/path/mod.ts"),
+      "deno.land/std/path/mod.ts"
+    );
+  }
+
+  #[test]
+  fn test_slugify() {
+    assert_eq!(slugify("src/mod.ts"), "src_mod.ts");
+    assert_eq!(slugify("Deno.Listener"), "Deno.Listener");
+  }
+
+  #[test]
+  fn test_unique_slug() {
+    let is_taken =
+      |slug: &str| RESERVED_FILE_NAMES.contains(&slug) || slug == "search.js_1";
+    assert_eq!(unique_slug("mod.ts".to_string(), is_taken), "mod.ts");
+    assert_eq!(
+      unique_slug("index.html".to_string(), is_taken),
+      "index.html_1"
+    );
+    assert_eq!(
+      unique_slug("search.js".to_string(), is_taken),
+      "search.js_2"
+    );
+  }
+
+  #[test]
+  fn test_render_code() {
+    let site = Site {
+      modules: Vec::new(),
+      links: HashMap::from([(
+        "Foo".to_string(),
+        "mod.ts/~/Foo.html".to_string(),
+      )]),
+    };
+    assert_eq!(
+      site.render_code("function f(a: Foo): Array<Bar>", "../"),
+      "function f(a: <a href=\"../mod.ts/~/Foo.html\">Foo</a>): Array&lt;Bar&gt;"
+    );
+  }
+}
@@ -0,0 +1,508 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Checks that the exported symbols of modules are documented, that exported
+//! functions have explicit return types, and that exported signatures don't
+//! reference types which aren't exported.
+
+use super::html::module_name;
+use crate::colors;
+use deno_ast::swc::ast::Accessibility;
+use deno_ast::swc::ast::MethodKind;
+use deno_core::error::AnyError;
+use deno_core::error::JsStackFrame;
+use deno_doc::function::FunctionDef;
+use deno_doc::js_doc::JsDoc;
+use deno_doc::params::ParamDef;
+use deno_doc::ts_type::TsTypeDef;
+use deno_doc::ts_type_param::TsTypeParamDef;
+use deno_doc::DocNode;
+use deno_doc::DocNodeKind;
+use deno_doc::DocParser;
+use deno_graph::ModuleSpecifier;
+use deno_runtime::fmt_errors::format_location;
+use indexmap::IndexMap;
+use log::info;
+use std::collections::HashSet;
+
+struct DocDiagnostic {
+  code: &'static str,
+  message: String,
+  filename: String,
+  line: usize,
+  col: usize,
+}
+
+impl DocDiagnostic {
+  fn new(code: &'static str, message: String, node: &DocNode) -> Self {
+    Self {
+      code,
+      message,
+      filename: node.location.filename.clone(),
+      line: node.location.line,
+      col: node.location.col,
+    }
+  }
+}
+
+/// The number of exported symbols of a module and how many are documented.
+#[derive(Default)]
+struct Coverage {
+  symbols: usize,
+  documented: usize,
+}
+
+/// Checks the exported symbols of the modules and of the modules they
+/// re-export from, and prints the problems and the documentation coverage of
+/// each module. `doc_parser` must only parse exported symbols, while
+/// `private_doc_parser` parses every declaration of a module.
+pub fn lint_docs(
+  module_specifiers: &[ModuleSpecifier],
+  doc_parser: &DocParser,
+  private_doc_parser: &DocParser,
+) -> Result<(), AnyError> {
+  // the exported symbols by the module declaring them
+  let mut doc_nodes_by_module: IndexMap<String, Vec<DocNode>> =
+    module_specifiers
+      .iter()
+      .map(|specifier| (specifier.to_string(), Vec::new()))
+      .collect();
+  for specifier in module_specifiers {
+    for node in doc_parser.parse_with_reexports(specifier)? {
+      if matches!(node.kind, DocNodeKind::Import | DocNodeKind::ModuleDoc) {
+        continue;
+      }
+      let nodes = doc_nodes_by_module
+        .entry(node.location.filename.clone())
+        .or_default();
+      if !nodes
+        .iter()
+        .any(|n| n.name == node.name && is_same_location(n, &node))
+      {
+        nodes.push(node);
+      }
+    }
+  }
+
+  let mut diagnostics = Vec::new();
+  let mut coverage_by_module = Vec::new();
+  for (filename, doc_nodes) in &doc_nodes_by_module {
+    let specifier = ModuleSpecifier::parse(filename)?;
+    let private_types = private_type_names(
+      &specifier,
+      doc_nodes,
+      doc_parser,
+      private_doc_parser,
+    )?;
+    let mut coverage = Coverage::default();
+    lint_doc_nodes(
+      doc_nodes,
+      None,
+      &private_types,
+      &mut coverage,
+      &mut diagnostics,
+    );
+    coverage_by_module.push((specifier, coverage));
+  }
+
+  for diagnostic in &diagnostics {
+    eprintln!(
+      "({}) {}\n    at {}\n",
+      colors::red(diagnostic.code),
+      diagnostic.message,
+      format_location(&JsStackFrame::from_location(
+        Some(diagnostic.filename.clone()),
+        Some(diagnostic.line as i64),
+        Some(diagnostic.col as i64 + 1),
+      ))
+    );
+  }
+
+  let cwd = std::env::current_dir()
+    .ok()
+    .and_then(|cwd| ModuleSpecifier::from_directory_path(cwd).ok());
+  info!("Documentation coverage:");
+  for (specifier, coverage) in &coverage_by_module {
+    let percent = if coverage.symbols == 0 {
+      100
+    } else {
+      coverage.documented * 100 / coverage.symbols
+    };
+    info!(
+      "  {} {}/{} ({}%)",
+      module_name(specifier, cwd.as_ref()),
+      coverage.documented,
+      coverage.symbols,
+      percent
+    );
+  }
+
+  match diagnostics.len() {
+    0 => {}
+    1 => info!("Found 1 problem"),
+    n => info!("Found {} problems", n),
+  }
+  if !diagnostics.is_empty() {
+    std::process::exit(1);
+  }
+  Ok(())
+}
+
+/// The names of the types declared by the module which aren't exported.
+fn private_type_names(
+  specifier: &ModuleSpecifier,
+  exported_nodes: &[DocNode],
+  doc_parser: &DocParser,
+  private_doc_parser: &DocParser,
+) -> Result<HashSet<String>, AnyError> {
+  let module_exports = doc_parser.parse_module(specifier)?.definitions;
+  let is_exported = |node: &DocNode| {
+    exported_nodes
+      .iter()
+      .chain(module_exports.iter())
+      .any(|n| is_same_location(n, node))
+  };
+  Ok(
+    private_doc_parser
+      .parse_module(specifier)?
+      .definitions
+      .into_iter()
+      .filter(|node| {
+        matches!(
+          node.kind,
+          DocNodeKind::Class
+            | DocNodeKind::Enum
+            | DocNodeKind::Interface
+            | DocNodeKind::TypeAlias
+            | DocNodeKind::Namespace
+        ) && !is_exported(node)
+      })
+      .map(|node| node.name)
+      .collect(),
+  )
+}
+
+fn is_same_location(a: &DocNode, b: &DocNode) -> bool {
+  a.location.filename == b.location.filename
+    && a.location.line == b.location.line
+    && a.location.col == b.location.col
+}
+
+fn lint_doc_nodes(
+  doc_nodes: &[DocNode],
+  namespace: Option<&str>,
+  private_types: &HashSet<String>,
+  coverage: &mut Coverage,
+  diagnostics: &mut Vec<DocDiagnostic>,
+) {
+  // the declarations of a name, like the overloads of a function, are
+  // documented if any of them is
+  let mut names: Vec<&str> = Vec::new();
+  for node in doc_nodes {
+    if !matches!(node.kind, DocNodeKind::Import | DocNodeKind::ModuleDoc)
+      && !names.contains(&node.name.as_str())
+    {
+      names.push(&node.name);
+    }
+  }
+
+  for name in names {
+    let nodes = doc_nodes
+      .iter()
+      .filter(|node| node.name == name)
+      .collect::<Vec<_>>();
+    let qualified_name = match namespace {
+      Some(namespace) => format!("{namespace}.{name}"),
+      None => name.to_string(),
+    };
+
+    coverage.symbols += 1;
+    if nodes.iter().any(|node| has_js_doc(&node.js_doc)) {
+      coverage.documented += 1;
+    } else {
+      diagnostics.push(DocDiagnostic::new(
+        "missing-jsdoc",
+        format!("Exported symbol \"{qualified_name}\" has no JSDoc comment."),
+        nodes[0],
+      ));
+    }
+
+    for node in &nodes {
+      lint_return_types(node, &qualified_name, diagnostics);
+      lint_type_references(node, &qualified_name, private_types, diagnostics);
+      if let Some(namespace_def) = &node.namespace_def {
+        lint_doc_nodes(
+          &namespace_def.elements,
+          Some(&qualified_name),
+          private_types,
+          coverage,
+          diagnostics,
+        );
+      }
+    }
+  }
+}
+
+fn has_js_doc(js_doc: &JsDoc) -> bool {
+  js_doc.doc.is_some() || !js_doc.tags.is_empty()
+}
+
+fn lint_return_types(
+  node: &DocNode,
+  qualified_name: &str,
+  diagnostics: &mut Vec<DocDiagnostic>,
+) {
+  if let Some(function_def) = &node.function_def {
+    if function_def.return_type.is_none() {
+      diagnostics.push(DocDiagnostic::new(
+        "missing-return-type",
+        format!(
+          "Exported function \"{qualified_name}\" has no explicit return type."
+        ),
+        node,
+      ));
+    }
+  }
+  if let Some(class_def) = &node.class_def {
+    for method in &class_def.methods {
+      if method.accessibility == Some(Accessibility::Private)
+        || method.name.starts_with('#')
+        || method.kind == MethodKind::Setter
+      {
+        continue;
+      }
+      if method.function_def.return_type.is_none() {
+        diagnostics.push(DocDiagnostic {
+          code: "missing-return-type",
+          message: format!(
+            "Method \"{}.{}\" has no explicit return type.",
+            qualified_name, method.name
+          ),
+          filename: method.location.filename.clone(),
+          line: method.location.line,
+          col: method.location.col,
+        });
+      }
+    }
+  }
+}
+
+fn lint_type_references(
+  node: &DocNode,
+  qualified_name: &str,
+  private_types: &HashSet<String>,
+  diagnostics: &mut Vec<DocDiagnostic>,
+) {
+  let mut type_names = TypeNames::default();
+  type_names.node(node);
+  let mut referenced = Vec::new();
+  for type_name in type_names.0 {
+    let name = type_name.split('.').next().unwrap();
+    if name != node.name
+      && private_types.contains(name)
+      && !referenced.contains(&name)
+    {
+      referenced.push(name);
+    }
+  }
+  for name in referenced {
+    diagnostics.push(DocDiagnostic::new(
+      "private-type-ref",
+      format!(
+        "Exported symbol \"{qualified_name}\" references the non-exported type \"{name}\"."
+      ),
+      node,
+    ));
+  }
+}
+
+/// Collects the names of the types referenced by a declaration and its public
+/// members, like `A` and `b.C` for `(a: A): Promise<b.C>`.
+#[derive(Default)]
+struct TypeNames<'a>(Vec<&'a str>);
+
+impl<'a> TypeNames<'a> {
+  fn node(&mut self, node: &'a DocNode) {
+    let is_public = |accessibility: &Option<Accessibility>, name: &str| {
+      *accessibility != Some(Accessibility::Private) && !name.starts_with('#')
+    };
+    if let Some(def) = &node.function_def {
+      self.function(def);
+    }
+    if let Some(def) = &node.variable_def {
+      self.maybe_ts_type(def.ts_type.as_ref());
+    }
+    if let Some(def) = &node.class_def {
+      self.type_params(&def.type_params);
+      // the super class is an expression, which is usually its name
+      if let Some(extends) = &def.extends {
+        self.0.push(extends);
+      }
+      self.ts_types(&def.super_type_params);
+      self.ts_types(&def.implements);
+      for constructor in &def.constructors {
+        if is_public(&constructor.accessibility, &constructor.name) {
+          for param in &constructor.params {
+            self.param(&param.param);
+          }
+        }
+      }
+      for property in &def.properties {
+        if is_public(&property.accessibility, &property.name) {
+          self.maybe_ts_type(property.ts_type.as_ref());
+        }
+      }
+      for method in &def.methods {
+        if is_public(&method.accessibility, &method.name) {
+          self.function(&method.function_def);
+        }
+      }
+    }
+    if let Some(def) = &node.interface_def {
+      self.type_params(&def.type_params);
+      self.ts_types(&def.extends);
+      for property in &def.properties {
+        self.params(&property.params);
+        self.maybe_ts_type(property.ts_type.as_ref());
+        self.type_params(&property.type_params);
+      }
+      for method in &def.methods {
+        self.params(&method.params);
+        self.maybe_ts_type(method.return_type.as_ref());
+        self.type_params(&method.type_params);
+      }
+    }
+    if let Some(def) = &node.type_alias_def {
+      self.type_params(&def.type_params);
+      self.ts_type(&def.ts_type);
+    }
+  }
+
+  fn function(&mut self, def: &'a FunctionDef) {
+    self.params(&def.params);
+    self.maybe_ts_type(def.return_type.as_ref());
+    self.type_params(&def.type_params);
+  }
+
+  fn params(&mut self, params: &'a [ParamDef]) {
+    for param in params {
+      self.param(param);
+    }
+  }
+
+  fn param(&mut self, param: &'a ParamDef) {
+    match param {
+      ParamDef::Array { ts_type, .. }
+      | ParamDef::Identifier { ts_type, .. }
+      | ParamDef::Object { ts_type, .. } => {
+        self.maybe_ts_type(ts_type.as_ref())
+      }
+      ParamDef::Assign { left, ts_type, .. } => {
+        self.param(left);
+        self.maybe_ts_type(ts_type.as_ref());
+      }
+      ParamDef::Rest { arg, ts_type, .. } => {
+        self.param(arg);
+        self.maybe_ts_type(ts_type.as_ref());
+      }
+    }
+  }
+
+  fn type_params(&mut self, type_params: &'a [TsTypeParamDef]) {
+    for type_param in type_params {
+      self.type_param(type_param);
+    }
+  }
+
+  fn type_param(&mut self, type_param: &'a TsTypeParamDef) {
+    self.maybe_ts_type(type_param.constraint.as_ref());
+    self.maybe_ts_type(type_param.default.as_ref());
+  }
+
+  fn ts_types(&mut self, ts_types: &'a [TsTypeDef]) {
+    for ts_type in ts_types {
+      self.ts_type(ts_type);
+    }
+  }
+
+  fn maybe_ts_type(&mut self, maybe_ts_type: Option<&'a TsTypeDef>) {
+    if let Some(ts_type) = maybe_ts_type {
+      self.ts_type(ts_type);
+    }
+  }
+
+  fn ts_type(&mut self, ts_type: &'a TsTypeDef) {
+    if let Some(type_ref) = &ts_type.type_ref {
+      self.0.push(&type_ref.type_name);
+      if let Some(type_params) = &type_ref.type_params {
+        self.ts_types(type_params);
+      }
+    }
+    for ts_types in [&ts_type.union, &ts_type.intersection, &ts_type.tuple]
+      .into_iter()
+      .flatten()
+    {
+      self.ts_types(ts_types);
+    }
+    for inner in [
+      &ts_type.array,
+      &ts_type.parenthesized,
+      &ts_type.rest,
+      &ts_type.optional,
+    ]
+    .into_iter()
+    .flatten()
+    {
+      self.ts_type(inner);
+    }
+    if let Some(type_operator) = &ts_type.type_operator {
+      self.ts_type(&type_operator.ts_type);
+    }
+    if let Some(def) = &ts_type.fn_or_constructor {
+      self.params(&def.params);
+      self.ts_type(&def.ts_type);
+      self.type_params(&def.type_params);
+    }
+    if let Some(def) = &ts_type.conditional_type {
+      self.ts_type(&def.check_type);
+      self.ts_type(&def.extends_type);
+      self.ts_type(&def.true_type);
+      self.ts_type(&def.false_type);
+    }
+    if let Some(def) = &ts_type.infer {
+      self.type_param(&def.type_param);
+    }
+    if let Some(def) = &ts_type.indexed_access {
+      self.ts_type(&def.obj_type);
+      self.ts_type(&def.index_type);
+    }
+    if let Some(def) = &ts_type.mapped_type {
+      self.type_param(&def.type_param);
+      self.maybe_ts_type(def.name_type.as_deref());
+      self.maybe_ts_type(def.ts_type.as_deref());
+    }
+    if let Some(def) = &ts_type.type_literal {
+      for method in &def.methods {
+        self.params(&method.params);
+        self.maybe_ts_type(method.return_type.as_ref());
+        self.type_params(&method.type_params);
+      }
+      for property in &def.properties {
+        self.params(&property.params);
+        self.maybe_ts_type(property.ts_type.as_ref());
+        self.type_params(&property.type_params);
+      }
+      for call_signature in &def.call_signatures {
+        self.params(&call_signature.params);
+        self.maybe_ts_type(call_signature.ts_type.as_ref());
+        self.type_params(&call_signature.type_params);
+      }
+      for index_signature in &def.index_signatures {
+        self.params(&index_signature.params);
+        self.maybe_ts_type(index_signature.ts_type.as_ref());
+      }
+    }
+    if let Some(def) = &ts_type.type_predicate {
+      self.maybe_ts_type(def.r#type.as_deref());
+    }
+  }
+}
@@ -0,0 +1,134 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Renders JSDoc comments, which are markdown with `{@link}` tags, to HTML.
+
+use deno_core::url;
+use deno_core::url::Url;
+use once_cell::sync::Lazy;
+use pulldown_cmark::html::push_html;
+use pulldown_cmark::Event;
+use pulldown_cmark::Options;
+use pulldown_cmark::Parser;
+use pulldown_cmark::Tag;
+use regex::Captures;
+use regex::Regex;
+
+static JSDOC_LINK_RE: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(r"\{@link(?:code|plain)?\s+([^\s|}]+)(?:\s*\|\s*|\s+)?([^}]*)\}")
+    .unwrap()
+});
+
+/// Renders the markdown to HTML. `{@link Name}` and `{@link Name label}`
+/// tags link to the URL returned by `resolve_link` for the name, or are
+/// rendered as code if there is none. Raw HTML is escaped and links or images
+/// with URLs of other schemes than `http`, `https` and `mailto` are rendered
+/// as their text.
+pub fn render_markdown(
+  markdown: &str,
+  resolve_link: impl Fn(&str) -> Option<String>,
+) -> String {
+  let markdown = JSDOC_LINK_RE.replace_all(markdown, |captures: &Captures| {
+    let name = &captures[1];
+    let label = captures[2].trim();
+    let label = if label.is_empty() { name } else { label };
+    match resolve_link(name) {
+      Some(url) => format!("[{label}]({url})"),
+      None => format!("`{label}`"),
+    }
+  });
+  let parser = Parser::new_ext(
+    &markdown,
+    Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH,
+  )
+  .filter(|event| match event {
+    Event::Start(Tag::Link(_, url, _) | Tag::Image(_, url, _))
+    | Event::End(Tag::Link(_, url, _) | Tag::Image(_, url, _)) => {
+      is_safe_url(url)
+    }
+    _ => true,
+  })
+  .map(|event| match event {
+    Event::Html(html) => Event::Text(html),
+    event => event,
+  });
+  let mut html = String::new();
+  push_html(&mut html, parser);
+  html
+}
+
+/// Whether the URL is relative or has a scheme that can't run scripts.
+fn is_safe_url(url: &str) -> bool {
+  match Url::parse(url) {
+    Ok(url) => matches!(url.scheme(), "http" | "https" | "mailto"),
+    Err(url::ParseError::RelativeUrlWithoutBase) => true,
+    Err(_) => false,
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn resolve_link(name: &str) -> Option<String> {
+    (name == "Foo").then(|| "Foo.html".to_string())
+  }
+
+  #[test]
+  fn renders_markdown() {
+    assert_eq!(
+      render_markdown("Some *text* with `code`.", resolve_link),
+      "<p>Some <em>text</em> with <code>code</code>.</p>\n"
+    );
+    assert_eq!(
+      render_markdown("```ts\nconst a = 1;\n```", resolve_link),
+      "<pre><code class=\"language-ts\">const a = 1;\n</code></pre>\n"
+    );
+  }
+
+  #[test]
+  fn renders_links() {
+    assert_eq!(
+      render_markdown("See {@link Foo} and {@link Bar}.", resolve_link),
+      "<p>See <a href=\"Foo.html\">Foo</a> and <code>Bar</code>.</p>\n"
+    );
+    assert_eq!(
+      render_markdown("See {@link Foo | the foo}.", resolve_link),
+      "<p>See <a href=\"Foo.html\">the foo</a>.</p>\n"
+    );
+  }
+
+  #[test]
+  fn escapes_html() {
+    let html = render_markdown("<script>alert(1)</script>", resolve_link);
+    assert!(!html.contains("<script>"));
+    assert!(html.contains("&lt;script&gt;"));
+    let html = render_markdown("Some <b>bold</b> text", resolve_link);
+    assert_eq!(html, "<p>Some &lt;b&gt;bold&lt;/b&gt; text</p>\n");
+  }
+
+  #[test]
+  fn renders_unsafe_links_as_text() {
+    assert_eq!(
+      render_markdown(
+        "[a](https://deno.land) [b](./b.html) [c](#c) [d](mailto:d@deno.land)",
+        resolve_link
+      ),
+      "<p><a href=\"https://deno.land\">a</a> <a href=\"./b.html\">b</a> \
+       <a href=\"#c\">c</a> <a href=\"mailto:d@deno.land\">d</a></p>\n"
+    );
+    assert_eq!(
+      render_markdown(
+        "[a](javascript:alert(1)) [b]( JavaScript:alert(1)) [c](data:text/html,x)",
+        resolve_link
+      ),
+      "<p>a b c</p>\n"
+    );
+    assert_eq!(
+      render_markdown(
+        "![a](javascript:alert(1)) <javascript:alert(1)>",
+        resolve_link
+      ),
+      "<p>a javascript:alert(1)</p>\n"
+    );
+  }
+}
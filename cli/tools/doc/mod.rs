@@ -17,13 +17,21 @@ use deno_doc as doc;
 use deno_graph::ModuleSpecifier;
 use std::path::PathBuf;
 
+mod html;
+mod lint;
+mod markdown;
+mod signature;
+
 pub async fn print_docs(
   flags: Flags,
   doc_flags: DocFlags,
 ) -> Result<(), AnyError> {
   let ps = ProcState::build(flags).await?;
 
-  let mut doc_nodes = match doc_flags.source_file {
+  let doc_nodes_by_module = match doc_flags.source_file {
+    DocSourceFileFlag::Builtin if doc_flags.lint => {
+      bail!("--lint requires the modules to check");
+    }
     DocSourceFileFlag::Builtin => {
       let source_file_specifier =
         ModuleSpecifier::parse("internal://lib.deno.d.ts").unwrap();
@@ -57,10 +65,15 @@ pub async fn print_docs(
         doc_flags.private,
         analyzer.as_capturing_parser(),
       );
-      doc_parser.parse_module(&source_file_specifier)?.definitions
+      let doc_nodes =
+        doc_parser.parse_module(&source_file_specifier)?.definitions;
+      vec![(source_file_specifier, doc_nodes)]
     }
-    DocSourceFileFlag::Path(source_file) => {
-      let module_specifier = resolve_url_or_path(&source_file)?;
+    DocSourceFileFlag::Paths(source_files) => {
+      let module_specifiers = source_files
+        .iter()
+        .map(|source_file| resolve_url_or_path(source_file))
+        .collect::<Result<Vec<_>, _>>()?;
 
       // If the root module has external types, the module graph won't redirect it,
       // so instead create a dummy file which exports everything from the actual file being documented.
@@ -69,7 +82,11 @@ pub async fn print_docs(
         local: PathBuf::from("./$deno$doc.ts"),
         maybe_types: None,
         media_type: MediaType::TypeScript,
-        source: format!("export * from \"{module_specifier}\";").into(),
+        source: module_specifiers
+          .iter()
+          .map(|specifier| format!("export * from \"{specifier}\";\n"))
+          .collect::<String>()
+          .into(),
         specifier: root_specifier.clone(),
         maybe_headers: None,
       };
@@ -83,10 +100,44 @@ pub async fn print_docs(
         doc_flags.private,
         ps.parsed_source_cache.as_capturing_parser(),
       );
-      doc_parser.parse_with_reexports(&root_specifier)?
+      if doc_flags.lint {
+        // lints also need the declarations which aren't exported
+        let graph = ps.create_graph(vec![root_specifier]).await?;
+        let private_doc_parser = doc::DocParser::new(
+          graph,
+          true,
+          ps.parsed_source_cache.as_capturing_parser(),
+        );
+        return lint::lint_docs(
+          &module_specifiers,
+          &doc_parser,
+          &private_doc_parser,
+        );
+      }
+      if doc_flags.html.is_some() {
+        // the site has a page for each module
+        module_specifiers
+          .into_iter()
+          .map(|specifier| {
+            let doc_nodes = doc_parser.parse_with_reexports(&specifier)?;
+            Ok((specifier, doc_nodes))
+          })
+          .collect::<Result<Vec<_>, AnyError>>()?
+      } else {
+        let doc_nodes = doc_parser.parse_with_reexports(&root_specifier)?;
+        vec![(root_specifier, doc_nodes)]
+      }
     }
   };
 
+  if let Some(html_flag) = doc_flags.html {
+    return html::write_docs_site(&html_flag.output, &doc_nodes_by_module);
+  }
+
+  let mut doc_nodes = doc_nodes_by_module
+    .into_iter()
+    .flat_map(|(_, doc_nodes)| doc_nodes)
+    .collect::<Vec<_>>();
   if doc_flags.json {
     write_json_to_stdout(&doc_nodes)
   } else {
@@ -0,0 +1,34 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+"use strict";
+
+// Searches the symbols of `window.DOC_SEARCH_INDEX`, which is written by
+// `html.rs` along with the pages.
+(() => {
+  const root = document.body.dataset.root;
+  const input = document.getElementById("search");
+  const results = document.getElementById("search-results");
+
+  input.addEventListener("input", () => {
+    const query = input.value.trim().toLowerCase();
+    results.replaceChildren();
+    if (query === "") {
+      results.hidden = true;
+      return;
+    }
+    const matches = window.DOC_SEARCH_INDEX
+      .filter((entry) => entry.name.toLowerCase().includes(query))
+      .slice(0, 50);
+    for (const entry of matches) {
+      const item = document.createElement("li");
+      const link = document.createElement("a");
+      link.href = root + entry.url;
+      link.textContent = entry.name;
+      const detail = document.createElement("span");
+      detail.className = "kind";
+      detail.textContent = ` ${entry.kind} in ${entry.module}`;
+      item.append(link, detail);
+      results.append(item);
+    }
+    results.hidden = matches.length === 0;
+  });
+})();
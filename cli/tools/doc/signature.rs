@@ -0,0 +1,166 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Formats the declarations of documentation nodes as TypeScript-like
+//! signatures.
+
+use deno_ast::swc::ast::Accessibility;
+use deno_ast::swc::ast::VarDeclKind;
+use deno_doc::function::FunctionDef;
+use deno_doc::js_doc::JsDoc;
+use deno_doc::ts_type_param::TsTypeParamDef;
+use deno_doc::DocNode;
+use deno_doc::DocNodeKind;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::fmt::Write;
+
+/// Matches the identifiers and qualified names in a signature.
+pub static IDENTIFIER_RE: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"[A-Za-z_$][\w$]*(?:\.[A-Za-z_$][\w$]*)*").unwrap());
+
+pub fn node_signature(node: &DocNode) -> String {
+  let name = &node.name;
+  if let Some(def) = &node.function_def {
+    return format!(
+      "{}function{} {}",
+      if def.is_async { "async " } else { "" },
+      if def.is_generator { "*" } else { "" },
+      function_signature(name, def)
+    );
+  }
+  if let Some(def) = &node.variable_def {
+    let keyword = match def.kind {
+      VarDeclKind::Const => "const",
+      VarDeclKind::Let => "let",
+      VarDeclKind::Var => "var",
+    };
+    return match &def.ts_type {
+      Some(ts_type) => format!("{keyword} {name}: {ts_type}"),
+      None => format!("{keyword} {name}"),
+    };
+  }
+  if let Some(def) = &node.class_def {
+    let mut signature = format!(
+      "{}class {}{}",
+      if def.is_abstract { "abstract " } else { "" },
+      name,
+      type_params(&def.type_params)
+    );
+    if let Some(extends) = &def.extends {
+      write!(signature, " extends {extends}").unwrap();
+      if !def.super_type_params.is_empty() {
+        write!(signature, "<{}>", join(&def.super_type_params)).unwrap();
+      }
+    }
+    if !def.implements.is_empty() {
+      write!(signature, " implements {}", join(&def.implements)).unwrap();
+    }
+    return signature;
+  }
+  if let Some(def) = &node.interface_def {
+    let mut signature =
+      format!("interface {}{}", name, type_params(&def.type_params));
+    if !def.extends.is_empty() {
+      write!(signature, " extends {}", join(&def.extends)).unwrap();
+    }
+    return signature;
+  }
+  if let Some(def) = &node.type_alias_def {
+    return format!(
+      "type {}{} = {}",
+      name,
+      type_params(&def.type_params),
+      def.ts_type
+    );
+  }
+  match node.kind {
+    DocNodeKind::Enum => format!("enum {name}"),
+    DocNodeKind::Namespace => format!("namespace {name}"),
+    _ => name.clone(),
+  }
+}
+
+fn function_signature(name: &str, def: &FunctionDef) -> String {
+  let mut signature = format!(
+    "{}{}({})",
+    name,
+    type_params(&def.type_params),
+    join(&def.params)
+  );
+  if let Some(return_type) = &def.return_type {
+    write!(signature, ": {return_type}").unwrap();
+  }
+  signature
+}
+
+fn type_params(type_params: &[TsTypeParamDef]) -> String {
+  if type_params.is_empty() {
+    String::new()
+  } else {
+    format!("<{}>", join(type_params))
+  }
+}
+
+fn join<T: ToString>(items: &[T]) -> String {
+  items
+    .iter()
+    .map(ToString::to_string)
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// The signatures of the members of classes, interfaces and enums, except for
+/// private class members.
+pub fn member_signatures(node: &DocNode) -> Vec<(String, &JsDoc)> {
+  let is_public = |accessibility: &Option<Accessibility>, name: &str| {
+    *accessibility != Some(Accessibility::Private) && !name.starts_with('#')
+  };
+  let mut members: Vec<(String, &JsDoc)> = Vec::new();
+  if let Some(class_def) = &node.class_def {
+    members.extend(
+      class_def
+        .constructors
+        .iter()
+        .filter(|c| is_public(&c.accessibility, &c.name))
+        .map(|c| (c.to_string(), &c.js_doc)),
+    );
+    members.extend(
+      class_def
+        .properties
+        .iter()
+        .filter(|p| is_public(&p.accessibility, &p.name))
+        .map(|p| (p.to_string(), &p.js_doc)),
+    );
+    members.extend(
+      class_def
+        .methods
+        .iter()
+        .filter(|m| is_public(&m.accessibility, &m.name))
+        .map(|m| (m.to_string(), &m.js_doc)),
+    );
+  }
+  if let Some(interface_def) = &node.interface_def {
+    members.extend(
+      interface_def
+        .properties
+        .iter()
+        .map(|p| (p.to_string(), &p.js_doc)),
+    );
+    members.extend(
+      interface_def
+        .methods
+        .iter()
+        .map(|m| (m.to_string(), &m.js_doc)),
+    );
+  }
+  if let Some(enum_def) = &node.enum_def {
+    members.extend(enum_def.members.iter().map(|m| {
+      let signature = match &m.init {
+        Some(init) => format!("{} = {}", m.name, init),
+        None => m.name.clone(),
+      };
+      (signature, &m.js_doc)
+    }));
+  }
+  members
+}
@@ -6,6 +6,7 @@ use std::fmt;
 use std::fmt::Write;
 
 use deno_ast::ModuleSpecifier;
+use deno_core::anyhow::bail;
 use deno_core::error::AnyError;
 use deno_core::resolve_url_or_path;
 use deno_core::serde_json;
@@ -16,14 +17,21 @@ use deno_graph::ModuleGraph;
 use deno_graph::ModuleGraphError;
 use deno_graph::Resolved;
 use deno_runtime::colors;
+use import_map::ImportMap;
+use import_map::SpecifierMap;
+use indexmap::IndexMap;
+use once_cell::sync::Lazy;
+use regex::Regex;
 
 use crate::args::Flags;
 use crate::args::InfoFlags;
+use crate::args::InfoGraphFormat;
 use crate::display;
 use crate::npm::NpmPackageId;
 use crate::npm::NpmPackageReference;
 use crate::npm::NpmPackageReq;
 use crate::npm::NpmPackageResolver;
+use crate::npm::NpmRegistryApi;
 use crate::npm::NpmResolutionPackage;
 use crate::npm::NpmResolutionSnapshot;
 use crate::proc_state::ProcState;
@@ -39,6 +47,22 @@ pub async fn info(flags: Flags, info_flags: InfoFlags) -> Result<(), AnyError> {
       let mut json_graph = json!(graph);
       add_npm_packages_to_json(&mut json_graph, &ps.npm_resolver);
       display::write_json_to_stdout(&json_graph)?;
+    } else if info_flags.format.is_some() || info_flags.sizes {
+      let dependency_graph = DependencyGraph::build(&graph, &ps.npm_resolver);
+      let mut output = String::new();
+      match info_flags.format {
+        Some(InfoGraphFormat::Dot) => {
+          dependency_graph.write_dot(&mut output)?
+        }
+        Some(InfoGraphFormat::Mermaid) => {
+          dependency_graph.write_mermaid(&mut output)?
+        }
+        None => dependency_graph.write_sizes(&mut output)?,
+      }
+      display::write_to_stdout_ignore_sigpipe(output.as_bytes())?;
+    } else if let Some(query) = &info_flags.why {
+      let output = why(&ps, &graph, query).await?;
+      display::write_to_stdout_ignore_sigpipe(output.as_bytes())?;
     } else {
       let mut output = String::new();
       GraphDisplayContext::write(&graph, &ps.npm_resolver, &mut output)?;
@@ -667,3 +691,742 @@ fn maybe_size_to_text(maybe_size: Option<u64>) -> String {
   ))
   .to_string()
 }
+
+/// The most import chains that `--why` shows.
+const MAX_WHY_CHAINS: usize = 100;
+
+static VERSION_IN_URL_RE: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"@v?\d+\.\d+\.\d+[\w.+-]*").unwrap());
+
+/// The modules and npm packages of a module graph with the edges to their
+/// dependencies, which is used to export the graph and to report the sizes
+/// of the dependencies.
+struct DependencyGraph {
+  /// The nodes by their name, which is the specifier of a module or the
+  /// `npm:` prefixed id of a package.
+  nodes: IndexMap<String, DependencyNode>,
+  roots: Vec<String>,
+}
+
+struct DependencyNode {
+  size: Option<u64>,
+  dependencies: Vec<DependencyEdge>,
+}
+
+struct DependencyEdge {
+  /// The name of the node of the dependency.
+  name: String,
+  /// How the dependency is referred to, which is the specifier of an import
+  /// or the key in the dependencies of an npm package.
+  specifier: String,
+}
+
+impl DependencyGraph {
+  pub fn build(graph: &ModuleGraph, npm_resolver: &NpmPackageResolver) -> Self {
+    let npm_snapshot = npm_resolver.snapshot();
+    let npm_info = NpmInfo::build(graph, npm_resolver, &npm_snapshot);
+    let node_name = |specifier: &ModuleSpecifier| {
+      let specifier = graph.resolve(specifier);
+      match npm_info.package_from_specifier(&specifier) {
+        Some(package) => npm_package_name(&package.id),
+        None => specifier.to_string(),
+      }
+    };
+
+    let mut nodes = IndexMap::new();
+    for module in graph.modules() {
+      if npm_info.package_from_specifier(&module.specifier).is_some() {
+        continue;
+      }
+      let resolved_deps = module
+        .maybe_types_dependency
+        .iter()
+        .map(|(text, resolved)| (text, resolved))
+        .chain(module.dependencies.iter().flat_map(|(text, dep)| {
+          [(text, &dep.maybe_code), (text, &dep.maybe_type)]
+        }));
+      let mut dependencies: Vec<DependencyEdge> = Vec::new();
+      for (text, resolved) in resolved_deps {
+        if let Resolved::Ok { specifier, .. } = resolved {
+          let name = node_name(specifier);
+          if !dependencies.iter().any(|dep| dep.name == name) {
+            dependencies.push(DependencyEdge {
+              name,
+              specifier: text.clone(),
+            });
+          }
+        }
+      }
+      nodes.insert(
+        module.specifier.to_string(),
+        DependencyNode {
+          size: module
+            .maybe_source
+            .as_ref()
+            .map(|s| s.as_bytes().len() as u64),
+          dependencies,
+        },
+      );
+    }
+
+    let mut packages = npm_info.packages.values().collect::<Vec<_>>();
+    packages.sort_by(|a, b| a.id.cmp(&b.id));
+    for package in packages {
+      let mut dependencies = package.dependencies.iter().collect::<Vec<_>>();
+      dependencies.sort_by(|a, b| a.1.cmp(b.1));
+      nodes.insert(
+        npm_package_name(&package.id),
+        DependencyNode {
+          size: npm_info.package_sizes.get(&package.id).copied(),
+          dependencies: dependencies
+            .into_iter()
+            .map(|(key, id)| DependencyEdge {
+              name: npm_package_name(id),
+              specifier: key.clone(),
+            })
+            .collect(),
+        },
+      );
+    }
+
+    // modules that failed to load aren't nodes
+    let names = nodes.keys().cloned().collect::<HashSet<_>>();
+    for node in nodes.values_mut() {
+      node.dependencies.retain(|dep| names.contains(&dep.name));
+    }
+    let roots = graph
+      .roots
+      .iter()
+      .map(node_name)
+      .filter(|name| names.contains(name))
+      .collect();
+    Self { nodes, roots }
+  }
+
+  pub fn write_dot<TWrite: Write>(&self, writer: &mut TWrite) -> fmt::Result {
+    writeln!(writer, "digraph {{")?;
+    writeln!(writer, "  node [shape=box];")?;
+    for (index, (name, node)) in self.nodes.iter().enumerate() {
+      let label = format!("{}\n({})", name, size_text(node.size));
+      write!(writer, "  n{} [label={}", index, dot_string(&label))?;
+      if self.roots.contains(name) {
+        write!(writer, ", style=bold")?;
+      }
+      writeln!(writer, "];")?;
+    }
+    for (index, node) in self.nodes.values().enumerate() {
+      for dep in &node.dependencies {
+        let dep_index = self.nodes.get_index_of(&dep.name).unwrap();
+        writeln!(writer, "  n{index} -> n{dep_index};")?;
+      }
+    }
+    writeln!(writer, "}}")
+  }
+
+  pub fn write_mermaid<TWrite: Write>(
+    &self,
+    writer: &mut TWrite,
+  ) -> fmt::Result {
+    writeln!(writer, "graph LR")?;
+    for (index, (name, node)) in self.nodes.iter().enumerate() {
+      let label = format!("{} ({})", name, size_text(node.size));
+      let label = label.replace('"', "#quot;");
+      if self.roots.contains(name) {
+        writeln!(writer, "  n{index}[[\"{label}\"]]")?;
+      } else {
+        writeln!(writer, "  n{index}[\"{label}\"]")?;
+      }
+    }
+    for (index, node) in self.nodes.values().enumerate() {
+      for dep in &node.dependencies {
+        let dep_index = self.nodes.get_index_of(&dep.name).unwrap();
+        writeln!(writer, "  n{index} --> n{dep_index}")?;
+      }
+    }
+    Ok(())
+  }
+
+  /// Writes the size of each node with all of its transitive dependencies,
+  /// largest first, and the modules and packages that are in the graph with
+  /// more than one version.
+  pub fn write_sizes<TWrite: Write>(&self, writer: &mut TWrite) -> fmt::Result {
+    let mut sizes = self
+      .nodes
+      .iter()
+      .map(|(name, node)| (name, node.size, self.transitive_size(name)))
+      .collect::<Vec<_>>();
+    sizes.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(b.0)));
+    writeln!(
+      writer,
+      "{}",
+      colors::bold(format!("{:>10} {:>10}  {}", "total", "own", "dependency"))
+    )?;
+    for (name, size, transitive_size) in sizes {
+      writeln!(
+        writer,
+        "{:>10} {:>10}  {}",
+        display::human_size(transitive_size as f64),
+        size_text(size),
+        name
+      )?;
+    }
+
+    writeln!(writer)?;
+    let duplicates = self.duplicates();
+    if duplicates.is_empty() {
+      return writeln!(writer, "No duplicated dependencies.");
+    }
+    writeln!(writer, "{}", colors::bold("Duplicated dependencies:"))?;
+    for (package, versions) in duplicates {
+      writeln!(writer, "{package}")?;
+      for (version, names) in versions {
+        let size = names
+          .iter()
+          .filter_map(|name| self.nodes[*name].size)
+          .sum::<u64>();
+        writeln!(
+          writer,
+          "  {} {}",
+          version,
+          colors::gray(format!(
+            "({} {}, {})",
+            names.len(),
+            match (package.starts_with("npm:"), names.len()) {
+              (true, 1) => "copy",
+              (true, _) => "copies",
+              (false, 1) => "module",
+              (false, _) => "modules",
+            },
+            display::human_size(size as f64)
+          ))
+        )?;
+      }
+    }
+    Ok(())
+  }
+
+  fn transitive_size(&self, name: &str) -> u64 {
+    let mut seen = HashSet::new();
+    let mut pending = vec![name];
+    let mut size = 0;
+    while let Some(name) = pending.pop() {
+      if !seen.insert(name) {
+        continue;
+      }
+      let node = &self.nodes[name];
+      size += node.size.unwrap_or(0);
+      pending.extend(node.dependencies.iter().map(|dep| dep.name.as_str()));
+    }
+    size
+  }
+
+  /// The names of the nodes that match the query, which is the name of an npm
+  /// package, optionally with its version, or the specifier of a module.
+  fn find_nodes(&self, query: &str, graph: &ModuleGraph) -> Vec<&str> {
+    let package_query = query.strip_prefix("npm:").unwrap_or(query);
+    let packages = self
+      .nodes
+      .keys()
+      .filter(|name| {
+        match name.strip_prefix("npm:").and_then(split_npm_package_id) {
+          Some((package, version)) => {
+            package == package_query
+              || format!("{package}@{version}") == package_query
+          }
+          None => false,
+        }
+      })
+      .map(|name| name.as_str())
+      .collect::<Vec<_>>();
+    if !packages.is_empty() {
+      return packages;
+    }
+    match resolve_url_or_path(query) {
+      Ok(specifier) => self
+        .nodes
+        .get_key_value(graph.resolve(&specifier).as_str())
+        .map(|(name, _)| vec![name.as_str()])
+        .unwrap_or_default(),
+      Err(_) => Vec::new(),
+    }
+  }
+
+  /// Every chain of dependencies from a root to one of the targets, which
+  /// doesn't go through a node twice, and whether all of the chains are
+  /// returned or only the first `MAX_WHY_CHAINS`.
+  fn find_chains<'a>(
+    &'a self,
+    targets: &[&'a str],
+  ) -> (Vec<Vec<&'a str>>, bool) {
+    // only walk the dependencies that lead to a target
+    let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (name, node) in &self.nodes {
+      for dep in &node.dependencies {
+        dependents
+          .entry(dep.name.as_str())
+          .or_default()
+          .push(name.as_str());
+      }
+    }
+    let mut leads_to_target = HashSet::new();
+    let mut pending = targets.to_vec();
+    while let Some(name) = pending.pop() {
+      if leads_to_target.insert(name) {
+        if let Some(names) = dependents.get(name) {
+          pending.extend(names.iter().copied());
+        }
+      }
+    }
+
+    fn walk<'a>(
+      graph: &'a DependencyGraph,
+      chain: &mut Vec<&'a str>,
+      targets: &[&'a str],
+      leads_to_target: &HashSet<&'a str>,
+      chains: &mut Vec<Vec<&'a str>>,
+    ) -> bool {
+      let name = *chain.last().unwrap();
+      if targets.contains(&name) {
+        chains.push(chain.clone());
+        return chains.len() < MAX_WHY_CHAINS;
+      }
+      for dep in &graph.nodes[name].dependencies {
+        let dep_name = dep.name.as_str();
+        if leads_to_target.contains(dep_name) && !chain.contains(&dep_name) {
+          chain.push(dep_name);
+          let is_complete =
+            walk(graph, chain, targets, leads_to_target, chains);
+          chain.pop();
+          if !is_complete {
+            return false;
+          }
+        }
+      }
+      true
+    }
+
+    let mut chains = Vec::new();
+    for root in &self.roots {
+      if leads_to_target.contains(root.as_str()) {
+        let mut chain = vec![root.as_str()];
+        if !walk(self, &mut chain, targets, &leads_to_target, &mut chains) {
+          return (chains, false);
+        }
+      }
+    }
+    (chains, true)
+  }
+
+  /// The nodes by version for each remote package or npm package that is in
+  /// the graph with more than one version. The package of a remote module is
+  /// the part of the URL before its version, like `https://deno.land/std`.
+  fn duplicates(&self) -> IndexMap<String, IndexMap<String, Vec<&str>>> {
+    let mut packages: IndexMap<String, IndexMap<String, Vec<&str>>> =
+      IndexMap::new();
+    for name in self.nodes.keys() {
+      let maybe_package_version = if let Some(id) = name.strip_prefix("npm:") {
+        split_npm_package_id(id).map(|(package, version)| {
+          (format!("npm:{package}"), version.to_string())
+        })
+      } else if name.starts_with("http:") || name.starts_with("https:") {
+        VERSION_IN_URL_RE.find(name).map(|m| {
+          (
+            name[..m.start()].to_string(),
+            m.as_str()[1..].trim_start_matches('v').to_string(),
+          )
+        })
+      } else {
+        None
+      };
+      if let Some((package, version)) = maybe_package_version {
+        packages
+          .entry(package)
+          .or_default()
+          .entry(version)
+          .or_default()
+          .push(name);
+      }
+    }
+    packages.retain(|_, versions| versions.len() > 1);
+    packages.sort_keys();
+    packages
+  }
+}
+
+/// Writes every chain of imports from the roots of the graph to the modules
+/// or npm packages that match the query, with how each import was resolved.
+async fn why(
+  ps: &ProcState,
+  graph: &ModuleGraph,
+  query: &str,
+) -> Result<String, AnyError> {
+  let dependency_graph = DependencyGraph::build(graph, &ps.npm_resolver);
+  let targets = dependency_graph.find_nodes(query, graph);
+  if targets.is_empty() {
+    bail!(
+      "No module or npm package in the module graph matches \"{}\".",
+      query
+    );
+  }
+  let (chains, is_complete) = dependency_graph.find_chains(&targets);
+
+  let mut output = String::new();
+  writeln!(
+    output,
+    "Found {} import {} to {}:",
+    chains.len(),
+    if chains.len() == 1 { "chain" } else { "chains" },
+    targets.join(", ")
+  )?;
+  for chain in &chains {
+    writeln!(output)?;
+    writeln!(output, "{}", chain[0])?;
+    for pair in chain.windows(2) {
+      let edge = dependency_graph.nodes[pair[0]]
+        .dependencies
+        .iter()
+        .find(|dep| dep.name == pair[1])
+        .unwrap();
+      let reason = dependency_reason(ps, pair[0], edge).await;
+      writeln!(output, "  {}", colors::gray(reason))?;
+      writeln!(output, "{}", pair[1])?;
+    }
+  }
+  if !is_complete {
+    writeln!(output)?;
+    writeln!(
+      output,
+      "Only the first {MAX_WHY_CHAINS} import chains are shown."
+    )?;
+  }
+  Ok(output)
+}
+
+/// Describes how the referrer depends on the dependency, with the range of
+/// npm package dependencies and the import map entry that imports are
+/// resolved with.
+async fn dependency_reason(
+  ps: &ProcState,
+  referrer: &str,
+  edge: &DependencyEdge,
+) -> String {
+  if let Some(id) = referrer.strip_prefix("npm:") {
+    // the range is in the package.json of the package
+    let maybe_range = match split_npm_package_id(id) {
+      Some((name, version)) => ps
+        .npm_resolver
+        .api()
+        .package_info(name)
+        .await
+        .ok()
+        .and_then(|info| {
+          let version_info = info.versions.get(version)?;
+          version_info
+            .dependencies
+            .get(&edge.specifier)
+            .or_else(|| version_info.peer_dependencies.get(&edge.specifier))
+            .cloned()
+        }),
+      None => None,
+    };
+    return match maybe_range {
+      Some(range) => {
+        format!("depends on \"{}\": \"{}\"", edge.specifier, range)
+      }
+      None => format!("depends on \"{}\"", edge.specifier),
+    };
+  }
+
+  let mut reason = format!("imports \"{}\"", edge.specifier);
+  if let (Some(import_map), Ok(referrer)) =
+    (&ps.maybe_import_map, ModuleSpecifier::parse(referrer))
+  {
+    if let Some(entry) =
+      import_map_entry(import_map, &edge.specifier, &referrer)
+    {
+      write!(reason, " ({entry})").unwrap();
+    }
+  }
+  reason
+}
+
+/// Describes the entry of the import map that the specifier of an import in
+/// the referrer is resolved with, if any.
+fn import_map_entry(
+  import_map: &ImportMap,
+  specifier: &str,
+  referrer: &ModuleSpecifier,
+) -> Option<String> {
+  fn find_entry(imports: &SpecifierMap, specifier: &str) -> Option<String> {
+    imports
+      .entries()
+      .filter(|entry| {
+        let key: &str = &entry.key;
+        key == specifier || (key.ends_with('/') && specifier.starts_with(key))
+      })
+      .max_by_key(|entry| entry.key.len())
+      .map(|entry| {
+        format!(
+          "import map \"{}\": \"{}\"",
+          entry.raw_key,
+          entry.raw_value.unwrap_or_default()
+        )
+      })
+  }
+
+  // the keys of import maps are URLs for relative specifiers and URLs
+  let specifier = if specifier.starts_with("./")
+    || specifier.starts_with("../")
+    || specifier.starts_with('/')
+  {
+    referrer.join(specifier).ok()?.to_string()
+  } else {
+    match ModuleSpecifier::parse(specifier) {
+      Ok(url) => url.to_string(),
+      Err(_) => specifier.to_string(),
+    }
+  };
+  let mut scopes = import_map
+    .scopes()
+    .filter(|scope| {
+      let key: &str = &scope.key;
+      key == referrer.as_str()
+        || (key.ends_with('/') && referrer.as_str().starts_with(key))
+    })
+    .collect::<Vec<_>>();
+  // the most specific scope first
+  scopes.sort_by_key(|scope| std::cmp::Reverse(scope.key.len()));
+  for scope in scopes {
+    if let Some(entry) = find_entry(scope.imports, &specifier) {
+      return Some(format!("{} in scope \"{}\"", entry, scope.raw_key));
+    }
+  }
+  find_entry(import_map.imports(), &specifier)
+}
+
+fn npm_package_name(id: &NpmPackageId) -> String {
+  format!("npm:{}", id.as_serialized())
+}
+
+/// Splits the serialized id of an npm package into the name, which may have a
+/// scope, and the version, without the peer dependencies.
+fn split_npm_package_id(id: &str) -> Option<(&str, &str)> {
+  let index = id.get(1..)?.find('@')? + 1;
+  let version = id[index + 1..].split('_').next().unwrap();
+  Some((&id[..index], version))
+}
+
+fn size_text(maybe_size: Option<u64>) -> String {
+  match maybe_size {
+    Some(size) => display::human_size(size as f64),
+    None => "unknown".to_string(),
+  }
+}
+
+/// Quotes the text as a DOT string.
+fn dot_string(text: &str) -> String {
+  let mut quoted = String::with_capacity(text.len() + 2);
+  quoted.push('"');
+  for c in text.chars() {
+    match c {
+      '"' => quoted.push_str("\\\""),
+      '\\' => quoted.push_str("\\\\"),
+      '\n' => quoted.push_str("\\n"),
+      c => quoted.push(c),
+    }
+  }
+  quoted.push('"');
+  quoted
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn dependency_graph_duplicates() {
+    let node = |size| DependencyNode {
+      size: Some(size),
+      dependencies: Vec::new(),
+    };
+    let graph = DependencyGraph {
+      nodes: IndexMap::from([
+        ("file:///main.ts".to_string(), node(1)),
+        (
+          "https://deno.land/std@0.170.0/path/mod.ts".to_string(),
+          node(2),
+        ),
+        (
+          "https://deno.land/std@0.170.0/fs/mod.ts".to_string(),
+          node(3),
+        ),
+        (
+          "https://deno.land/std@0.171.0/path/mod.ts".to_string(),
+          node(4),
+        ),
+        (
+          "https://deno.land/x/oak@v11.1.0/mod.ts".to_string(),
+          node(5),
+        ),
+        ("npm:chalk@4.1.2".to_string(), node(6)),
+        ("npm:chalk@5.0.0".to_string(), node(7)),
+        ("npm:@types/node@18.0.0".to_string(), node(8)),
+        (
+          "npm:@types/node@18.0.0_typescript@4.9.4".to_string(),
+          node(9),
+        ),
+      ]),
+      roots: vec!["file:///main.ts".to_string()],
+    };
+    let duplicates = graph.duplicates();
+    assert_eq!(
+      duplicates.keys().collect::<Vec<_>>(),
+      vec!["https://deno.land/std", "npm:chalk"]
+    );
+    assert_eq!(
+      duplicates["https://deno.land/std"],
+      IndexMap::from([
+        (
+          "0.170.0".to_string(),
+          vec![
+            "https://deno.land/std@0.170.0/path/mod.ts",
+            "https://deno.land/std@0.170.0/fs/mod.ts"
+          ]
+        ),
+        (
+          "0.171.0".to_string(),
+          vec!["https://deno.land/std@0.171.0/path/mod.ts"]
+        ),
+      ])
+    );
+  }
+
+  fn edge(name: &str) -> DependencyEdge {
+    DependencyEdge {
+      name: name.to_string(),
+      specifier: format!("./{name}.ts"),
+    }
+  }
+
+  #[test]
+  fn dependency_graph_transitive_size() {
+    let graph = DependencyGraph {
+      nodes: IndexMap::from([
+        (
+          "a".to_string(),
+          DependencyNode {
+            size: Some(1),
+            dependencies: vec![edge("b"), edge("c")],
+          },
+        ),
+        (
+          "b".to_string(),
+          DependencyNode {
+            size: Some(2),
+            dependencies: vec![edge("a"), edge("c")],
+          },
+        ),
+        (
+          "c".to_string(),
+          DependencyNode {
+            size: None,
+            dependencies: Vec::new(),
+          },
+        ),
+      ]),
+      roots: vec!["a".to_string()],
+    };
+    assert_eq!(graph.transitive_size("a"), 3);
+    assert_eq!(graph.transitive_size("b"), 3);
+    assert_eq!(graph.transitive_size("c"), 0);
+  }
+
+  #[test]
+  fn dependency_graph_find_chains() {
+    let node = |dependencies: &[&str]| DependencyNode {
+      size: None,
+      dependencies: dependencies.iter().map(|name| edge(name)).collect(),
+    };
+    let graph = DependencyGraph {
+      nodes: IndexMap::from([
+        ("main".to_string(), node(&["a", "b"])),
+        ("a".to_string(), node(&["b", "c"])),
+        ("b".to_string(), node(&["a", "c"])),
+        ("c".to_string(), node(&[])),
+        ("d".to_string(), node(&["c"])),
+      ]),
+      roots: vec!["main".to_string()],
+    };
+    let (chains, is_complete) = graph.find_chains(&["c"]);
+    assert!(is_complete);
+    assert_eq!(
+      chains,
+      vec![
+        vec!["main", "a", "b", "c"],
+        vec!["main", "a", "c"],
+        vec!["main", "b", "a", "c"],
+        vec!["main", "b", "c"],
+      ]
+    );
+    let (chains, _) = graph.find_chains(&["d"]);
+    assert!(chains.is_empty());
+  }
+
+  #[test]
+  fn test_import_map_entry() {
+    let base_url = ModuleSpecifier::parse("file:///project/").unwrap();
+    let import_map = import_map::parse_from_json(
+      &base_url,
+      r#"{
+        "imports": {
+          "std/": "https://deno.land/std@0.170.0/",
+          "std/path/": "https://deno.land/std@0.171.0/path/",
+          "./vendor/": "./local/"
+        },
+        "scopes": {
+          "./scoped/": { "std/": "https://deno.land/std@0.160.0/" }
+        }
+      }"#,
+    )
+    .unwrap()
+    .import_map;
+    let referrer = ModuleSpecifier::parse("file:///project/main.ts").unwrap();
+    assert_eq!(
+      import_map_entry(&import_map, "std/fs/mod.ts", &referrer).unwrap(),
+      r#"import map "std/": "https://deno.land/std@0.170.0/""#
+    );
+    assert_eq!(
+      import_map_entry(&import_map, "std/path/mod.ts", &referrer).unwrap(),
+      r#"import map "std/path/": "https://deno.land/std@0.171.0/path/""#
+    );
+    assert_eq!(
+      import_map_entry(&import_map, "./vendor/a.ts", &referrer).unwrap(),
+      r#"import map "./vendor/": "./local/""#
+    );
+    assert_eq!(import_map_entry(&import_map, "./a.ts", &referrer), None);
+    let referrer =
+      ModuleSpecifier::parse("file:///project/scoped/mod.ts").unwrap();
+    assert_eq!(
+      import_map_entry(&import_map, "std/fs/mod.ts", &referrer).unwrap(),
+      r#"import map "std/": "https://deno.land/std@0.160.0/" in scope "./scoped/""#
+    );
+  }
+
+  #[test]
+  fn test_split_npm_package_id() {
+    assert_eq!(
+      split_npm_package_id("chalk@5.0.0"),
+      Some(("chalk", "5.0.0"))
+    );
+    assert_eq!(
+      split_npm_package_id("@types/node@18.0.0_typescript@4.9.4"),
+      Some(("@types/node", "18.0.0"))
+    );
+    assert_eq!(split_npm_package_id("chalk"), None);
+  }
+
+  #[test]
+  fn dot_string_escapes() {
+    assert_eq!(dot_string("a \"b\"\\\nc"), "\"a \\\"b\\\"\\\\\\nc\"");
+  }
+}
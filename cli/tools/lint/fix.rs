@@ -0,0 +1,361 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Safe automatic fixes for lint diagnostics, applied by `deno lint --fix`
+//! and offered as code actions by the language server.
+
+use deno_ast::swc::ast::Pat;
+use deno_ast::swc::ast::Program;
+use deno_ast::swc::ast::VarDecl;
+use deno_ast::swc::ast::VarDeclKind;
+use deno_ast::swc::visit::noop_visit_type;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
+use deno_ast::ParsedSource;
+use deno_ast::SourceRangedForSpanned;
+use deno_ast::StartSourcePos;
+use std::ops::Range;
+
+/// A replacement of the source text that fixes a lint diagnostic.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LintFix {
+  pub description: String,
+  /// The byte range of the source text to replace.
+  pub range: Range<usize>,
+  pub new_text: String,
+}
+
+/// Returns the fix for the diagnostic with `code` and `message` at the byte
+/// `range` of the parsed source, if the rule has a fix that can't change the
+/// behaviour of the code.
+pub fn get_lint_fix(
+  parsed_source: &ParsedSource,
+  code: &str,
+  message: &str,
+  range: Range<usize>,
+) -> Option<LintFix> {
+  let text = parsed_source.text_info().text_str();
+  if range.start > range.end || range.end > text.len() {
+    return None;
+  }
+  match code {
+    "prefer-const" => prefer_const_fix(parsed_source, range),
+    "no-extra-semi" => {
+      if &text[range.clone()] != ";" {
+        return None;
+      }
+      Some(LintFix {
+        description: "Remove extra semicolon".to_string(),
+        range,
+        new_text: "".to_string(),
+      })
+    }
+    "ban-untagged-ignore" => {
+      let directive = parse_ignore_directive(&text[range.clone()])?;
+      if !directive.codes.is_empty() {
+        return None;
+      }
+      Some(LintFix {
+        description: "Remove ignore directive".to_string(),
+        range: removal_range(text, range),
+        new_text: "".to_string(),
+      })
+    }
+    "ban-unused-ignore" => {
+      let unused_code = message
+        .split('"')
+        .nth(1)
+        .filter(|_| message.starts_with("Ignore for code \""))?;
+      let mut directive = parse_ignore_directive(&text[range.clone()])?;
+      let len = directive.codes.len();
+      directive.codes.retain(|code| code != unused_code);
+      if directive.codes.len() == len {
+        return None;
+      }
+      let description = format!("Remove unused ignore of {unused_code}");
+      if directive.codes.is_empty() {
+        Some(LintFix {
+          description,
+          range: removal_range(text, range),
+          new_text: "".to_string(),
+        })
+      } else {
+        Some(LintFix {
+          description,
+          range,
+          new_text: format!(
+            "// {} {}",
+            directive.kind,
+            directive.codes.join(" ")
+          ),
+        })
+      }
+    }
+    _ => None,
+  }
+}
+
+/// Applies the fixes to the text, skipping those that overlap with a fix
+/// that was already applied. Returns the fixed text and the number of
+/// applied fixes.
+pub fn apply_lint_fixes(
+  text: &str,
+  mut fixes: Vec<LintFix>,
+) -> (String, usize) {
+  fixes.sort_by_key(|fix| (fix.range.start, fix.range.end));
+  let mut fixed_text = String::with_capacity(text.len());
+  let mut last_end = 0;
+  let mut applied_count = 0;
+  for fix in fixes {
+    // the end of an empty range at the last end is fine, but overlapping
+    // edits would mangle the text
+    if fix.range.start < last_end {
+      continue;
+    }
+    fixed_text.push_str(&text[last_end..fix.range.start]);
+    fixed_text.push_str(&fix.new_text);
+    last_end = fix.range.end;
+    applied_count += 1;
+  }
+  fixed_text.push_str(&text[last_end..]);
+  (fixed_text, applied_count)
+}
+
+/// Replaces `let` with `const` for declarations of a single identifier,
+/// which are the only ones where every declared binding is known to be
+/// flagged.
+fn prefer_const_fix(
+  parsed_source: &ParsedSource,
+  range: Range<usize>,
+) -> Option<LintFix> {
+  let text_info = parsed_source.text_info();
+  let mut finder = LetDeclFinder {
+    start_pos: text_info.range().start,
+    text: text_info.text_str(),
+    ident_range: range,
+    maybe_let_start: None,
+  };
+  let program = parsed_source.program();
+  let program: &Program = &program;
+  program.visit_with(&mut finder);
+  let let_start = finder.maybe_let_start?;
+  Some(LintFix {
+    description: "Use `const` instead of `let`".to_string(),
+    range: let_start..let_start + "let".len(),
+    new_text: "const".to_string(),
+  })
+}
+
+struct LetDeclFinder<'a> {
+  start_pos: StartSourcePos,
+  text: &'a str,
+  ident_range: Range<usize>,
+  maybe_let_start: Option<usize>,
+}
+
+impl LetDeclFinder<'_> {
+  fn byte_range(&self, range: deno_ast::SourceRange) -> Range<usize> {
+    range.start.as_byte_index(self.start_pos)
+      ..range.end.as_byte_index(self.start_pos)
+  }
+
+  /// Whether the declaration is the head of a `for...in` or `for...of` loop,
+  /// which is the only place `const` can have no initializer.
+  fn is_for_in_or_of_head(&self, decl_end: usize) -> bool {
+    let rest = self.text[decl_end..].trim_start();
+    let is_ident_char = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    ["in", "of"].iter().any(|keyword| {
+      rest.starts_with(keyword)
+        && !rest[keyword.len()..].starts_with(is_ident_char)
+    })
+  }
+}
+
+impl Visit for LetDeclFinder<'_> {
+  noop_visit_type!();
+
+  fn visit_var_decl(&mut self, var_decl: &VarDecl) {
+    if var_decl.kind == VarDeclKind::Let && var_decl.decls.len() == 1 {
+      let decl = &var_decl.decls[0];
+      if let Pat::Ident(binding_ident) = &decl.name {
+        let decl_range = self.byte_range(var_decl.range());
+        let can_be_const =
+          decl.init.is_some() || self.is_for_in_or_of_head(decl_range.end);
+        if self.byte_range(binding_ident.id.range()) == self.ident_range
+          && self.text[decl_range.start..].starts_with("let")
+          && can_be_const
+        {
+          self.maybe_let_start = Some(decl_range.start);
+          return;
+        }
+      }
+    }
+    var_decl.visit_children_with(self);
+  }
+}
+
+struct IgnoreDirective<'a> {
+  kind: &'a str,
+  codes: Vec<&'a str>,
+}
+
+/// Parses a `// deno-lint-ignore` or `// deno-lint-ignore-file` comment.
+fn parse_ignore_directive(comment: &str) -> Option<IgnoreDirective> {
+  let mut words = comment.strip_prefix("//")?.split_whitespace();
+  let kind = words.next().filter(|kind| {
+    *kind == "deno-lint-ignore" || *kind == "deno-lint-ignore-file"
+  })?;
+  Some(IgnoreDirective {
+    kind,
+    codes: words.collect(),
+  })
+}
+
+/// Extends the range of text to remove to its whole line when nothing else
+/// is on it, or to the whitespace before it when it ends the line.
+fn removal_range(text: &str, range: Range<usize>) -> Range<usize> {
+  let line_start = text[..range.start].rfind('\n').map_or(0, |i| i + 1);
+  let line_end = text[range.end..]
+    .find('\n')
+    .map_or(text.len(), |i| range.end + i);
+  let before = &text[line_start..range.start];
+  let after = &text[range.end..line_end];
+  if !after.trim().is_empty() {
+    range
+  } else if before.trim().is_empty() {
+    line_start..(line_end + 1).min(text.len())
+  } else {
+    line_start + before.trim_end().len()..range.end
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use deno_ast::MediaType;
+  use deno_ast::ParseParams;
+  use deno_ast::SourceTextInfo;
+
+  fn parse_module(text: &str) -> ParsedSource {
+    deno_ast::parse_module(ParseParams {
+      specifier: "file:///mod.ts".to_string(),
+      capture_tokens: false,
+      maybe_syntax: None,
+      media_type: MediaType::TypeScript,
+      scope_analysis: false,
+      text_info: SourceTextInfo::from_string(text.to_string()),
+    })
+    .unwrap()
+  }
+
+  fn fix(text: &str, code: &str, message: &str, target: &str) -> String {
+    let parsed_source = parse_module(text);
+    let start = text.find(target).unwrap();
+    let fix =
+      get_lint_fix(&parsed_source, code, message, start..start + target.len())
+        .unwrap();
+    apply_lint_fixes(text, vec![fix]).0
+  }
+
+  #[test]
+  fn prefer_const() {
+    assert_eq!(
+      fix("let a = 1;\nconsole.log(a);\n", "prefer-const", "", "a"),
+      "const a = 1;\nconsole.log(a);\n"
+    );
+    assert_eq!(
+      fix("for (let item of items) {}\n", "prefer-const", "", "item"),
+      "for (const item of items) {}\n"
+    );
+    assert_eq!(
+      fix("export let a: number = 1;\n", "prefer-const", "", "a"),
+      "export const a: number = 1;\n"
+    );
+
+    // not every binding is known to be flagged
+    let text = "let a = 1, b = 2;\n";
+    let parsed_source = parse_module(text);
+    assert_eq!(get_lint_fix(&parsed_source, "prefer-const", "", 4..5), None);
+    let text = "let { a } = b;\n";
+    let parsed_source = parse_module(text);
+    assert_eq!(get_lint_fix(&parsed_source, "prefer-const", "", 6..7), None);
+  }
+
+  #[test]
+  fn no_extra_semi() {
+    assert_eq!(
+      fix("function f() {};\n", "no-extra-semi", "", ";"),
+      "function f() {}\n"
+    );
+  }
+
+  #[test]
+  fn ban_untagged_ignore() {
+    assert_eq!(
+      fix(
+        "  // deno-lint-ignore\n  f();\n",
+        "ban-untagged-ignore",
+        "",
+        "// deno-lint-ignore"
+      ),
+      "  f();\n"
+    );
+    assert_eq!(
+      fix(
+        "f(); // deno-lint-ignore\n",
+        "ban-untagged-ignore",
+        "",
+        "// deno-lint-ignore"
+      ),
+      "f();\n"
+    );
+  }
+
+  #[test]
+  fn ban_unused_ignore() {
+    let text = "// deno-lint-ignore no-explicit-any no-empty\nf();\n";
+    assert_eq!(
+      fix(
+        text,
+        "ban-unused-ignore",
+        "Ignore for code \"no-empty\" was not used.",
+        "// deno-lint-ignore no-explicit-any no-empty"
+      ),
+      "// deno-lint-ignore no-explicit-any\nf();\n"
+    );
+    let text = "// deno-lint-ignore no-empty\nf();\n";
+    assert_eq!(
+      fix(
+        text,
+        "ban-unused-ignore",
+        "Ignore for code \"no-empty\" was not used.",
+        "// deno-lint-ignore no-empty"
+      ),
+      "f();\n"
+    );
+  }
+
+  #[test]
+  fn apply_skips_overlapping_fixes() {
+    let fixes = vec![
+      LintFix {
+        description: "".to_string(),
+        range: 4..7,
+        new_text: "c".to_string(),
+      },
+      LintFix {
+        description: "".to_string(),
+        range: 0..5,
+        new_text: "a".to_string(),
+      },
+      LintFix {
+        description: "".to_string(),
+        range: 8..9,
+        new_text: "".to_string(),
+      },
+    ];
+    assert_eq!(
+      apply_lint_fixes("0123456789", fixes),
+      ("a5679".to_string(), 2)
+    );
+  }
+}
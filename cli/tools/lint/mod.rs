@@ -0,0 +1,1173 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! This module provides file linting utilities using
+//! [`deno_lint`](https://github.com/denoland/deno_lint).
+//!
+//! At the moment it is only consumed using CLI but in
+//! the future it can be easily extended to provide
+//! the same functions as ops available in JS runtime.
+use crate::args::CliOptions;
+use crate::args::FilesConfig;
+use crate::args::LintOptions;
+use crate::args::LintReporterKind;
+use crate::args::LintRulesConfig;
+use crate::colors;
+use crate::tools::fmt::run_parallelized;
+use crate::util::file_watcher;
+use crate::util::file_watcher::ResolutionResult;
+use crate::util::fs::atomic_write_file;
+use crate::util::fs::FileCollector;
+use crate::util::path::is_supported_ext;
+use deno_ast::MediaType;
+use deno_ast::ParsedSource;
+use deno_ast::SourceTextInfo;
+use deno_core::anyhow::bail;
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::error::JsStackFrame;
+use deno_core::serde_json;
+use deno_core::ModuleSpecifier;
+use deno_lint::diagnostic::LintDiagnostic;
+use deno_lint::linter::Linter;
+use deno_lint::linter::LinterBuilder;
+use deno_lint::rules;
+use deno_lint::rules::LintRule;
+use deno_runtime::fmt_errors::format_location;
+use log::debug;
+use log::info;
+use serde::Serialize;
+use std::fs;
+use std::io::stdin;
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use crate::cache::IncrementalCache;
+
+mod fix;
+mod plugins;
+
+pub use fix::apply_lint_fixes;
+pub use fix::get_lint_fix;
+pub use fix::LintFix;
+pub use plugins::lint_with_plugins;
+
+static STDIN_FILE_NAME: &str = "_stdin.ts";
+
+/// Fixes can overlap or uncover new diagnostics, so files are re-linted and
+/// fixed until there are no more fixes, up to this many times.
+const MAX_FIX_PASSES: usize = 10;
+
+fn create_reporter(kind: LintReporterKind) -> Box<dyn LintReporter + Send> {
+  match kind {
+    LintReporterKind::Pretty => Box::new(PrettyLintReporter::new()),
+    LintReporterKind::Json => Box::new(JsonLintReporter::new()),
+    LintReporterKind::Compact => Box::new(CompactLintReporter::new()),
+    LintReporterKind::Sarif => Box::new(SarifLintReporter::new()),
+    LintReporterKind::Github => Box::new(GithubLintReporter::new()),
+  }
+}
+
+/// The built-in rules and the plugins to lint a file with.
+#[derive(Clone)]
+struct FileLintRules {
+  rules: Vec<Arc<dyn LintRule>>,
+  plugins: Vec<ModuleSpecifier>,
+}
+
+impl FileLintRules {
+  fn new(lint_options: &LintOptions) -> Result<Self, AnyError> {
+    plugins::check_lint_plugins(&lint_options.plugins)?;
+    Ok(Self {
+      rules: get_configured_rules(lint_options.rules.clone()),
+      plugins: lint_options.plugins.clone(),
+    })
+  }
+}
+
+/// The lint rules of the root configuration and of each workspace member.
+#[derive(Clone)]
+struct WorkspaceLintRules {
+  root: FileLintRules,
+  members: Vec<(PathBuf, FilesConfig, FileLintRules)>,
+}
+
+impl WorkspaceLintRules {
+  fn new(lint_options: &LintOptions) -> Result<Self, AnyError> {
+    // Try to get lint rules. If none were set use recommended rules.
+    let root = FileLintRules::new(lint_options)?;
+    if root.rules.is_empty() {
+      bail!("No rules have been configured")
+    }
+    let mut members = Vec::with_capacity(lint_options.workspace_members.len());
+    for (dir, member_options) in &lint_options.workspace_members {
+      let member_rules = FileLintRules::new(member_options)?;
+      if member_rules.rules.is_empty() {
+        bail!(
+          "No rules have been configured for workspace member {}",
+          dir.display()
+        )
+      }
+      members.push((dir.clone(), member_options.files.clone(), member_rules));
+    }
+    Ok(Self { root, members })
+  }
+
+  /// Returns the rules to lint the file with, or `None` if the workspace
+  /// member containing it excludes it.
+  fn for_path(&self, file_path: &Path) -> Option<FileLintRules> {
+    let maybe_member = self
+      .members
+      .iter()
+      .filter(|(dir, _, _)| file_path.starts_with(dir))
+      .max_by_key(|(dir, _, _)| dir.components().count());
+    match maybe_member {
+      Some((_, files, rules)) => {
+        let specifier = ModuleSpecifier::from_file_path(file_path).ok()?;
+        files.matches_specifier(&specifier).then(|| rules.clone())
+      }
+      None => Some(self.root.clone()),
+    }
+  }
+
+  fn all(&self) -> impl Iterator<Item = &FileLintRules> {
+    std::iter::once(&self.root)
+      .chain(self.members.iter().map(|(_, _, rules)| rules))
+  }
+
+  /// Names of all configured rules, used to bust the incremental cache.
+  fn codes(&self) -> Vec<&'static str> {
+    let mut codes = self
+      .all()
+      .flat_map(|rules| rules.rules.iter())
+      .map(|r| r.code())
+      .collect::<Vec<_>>();
+    // ensure this is stable by sorting it
+    codes.sort_unstable();
+    codes.dedup();
+    codes
+  }
+
+  /// The specifier and source of each plugin, used to bust the incremental
+  /// cache when a plugin changes. Changes to the modules imported by a
+  /// plugin are not seen.
+  fn plugin_sources(&self) -> Vec<(String, String)> {
+    let mut sources = self
+      .all()
+      .flat_map(|rules| rules.plugins.iter())
+      .map(|specifier| {
+        let source = specifier
+          .to_file_path()
+          .ok()
+          .and_then(|path| fs::read_to_string(path).ok())
+          .unwrap_or_default();
+        (specifier.to_string(), source)
+      })
+      .collect::<Vec<_>>();
+    sources.sort_unstable();
+    sources.dedup();
+    sources
+  }
+}
+
+pub async fn lint(
+  cli_options: CliOptions,
+  lint_options: LintOptions,
+) -> Result<(), AnyError> {
+  let workspace_rules = WorkspaceLintRules::new(&lint_options)?;
+
+  let files = lint_options.files;
+  let reporter_kind = lint_options.reporter_kind;
+  let fix = lint_options.fix;
+
+  let resolver = |changed: Option<Vec<PathBuf>>| {
+    let files_changed = changed.is_some();
+    let result = collect_lint_files(&files).map(|files| {
+      if let Some(paths) = changed {
+        files
+          .iter()
+          .any(|path| paths.contains(path))
+          .then_some(files)
+          .unwrap_or_else(|| [].to_vec())
+      } else {
+        files
+      }
+    });
+
+    let paths_to_watch = files.include.clone();
+
+    async move {
+      if files_changed && matches!(result, Ok(ref files) if files.is_empty()) {
+        ResolutionResult::Ignore
+      } else {
+        ResolutionResult::Restart {
+          paths_to_watch,
+          result,
+        }
+      }
+    }
+  };
+
+  let has_error = Arc::new(AtomicBool::new(false));
+  let deno_dir = cli_options.resolve_deno_dir()?;
+  let operation = |paths: Vec<PathBuf>| async {
+    let incremental_cache = Arc::new(IncrementalCache::new(
+      &deno_dir.lint_incremental_cache_db_file_path(),
+      // use a hash of the rule names and plugins in order to bust the cache
+      &(workspace_rules.codes(), workspace_rules.plugin_sources()),
+      &paths,
+    ));
+    let target_files_len = paths.len();
+    let reporter_lock =
+      Arc::new(Mutex::new(create_reporter(reporter_kind.clone())));
+
+    run_parallelized(paths, {
+      let has_error = has_error.clone();
+      let workspace_rules = workspace_rules.clone();
+      let reporter_lock = reporter_lock.clone();
+      let incremental_cache = incremental_cache.clone();
+      move |file_path| {
+        let lint_rules = match workspace_rules.for_path(&file_path) {
+          Some(lint_rules) => lint_rules,
+          None => return Ok(()),
+        };
+        let file_text = fs::read_to_string(&file_path)?;
+
+        // don't bother rechecking this file if it didn't have any diagnostics before
+        if incremental_cache.is_file_same(&file_path, &file_text) {
+          return Ok(());
+        }
+
+        let r = if fix {
+          lint_and_fix_file(&file_path, file_text, lint_rules)
+        } else {
+          lint_file(&file_path, file_text, lint_rules)
+        };
+        if let Ok((file_diagnostics, file_text)) = &r {
+          if file_diagnostics.is_empty() {
+            // update the incremental cache if there were no diagnostics
+            incremental_cache.update_file(&file_path, file_text)
+          }
+        }
+
+        handle_lint_result(
+          &file_path.to_string_lossy(),
+          r,
+          reporter_lock.clone(),
+          has_error,
+        );
+
+        Ok(())
+      }
+    })
+    .await?;
+    incremental_cache.wait_completion().await;
+    reporter_lock.lock().unwrap().close(target_files_len);
+
+    Ok(())
+  };
+  if cli_options.watch_paths().is_some() {
+    if lint_options.is_stdin {
+      return Err(generic_error(
+        "Lint watch on standard input is not supported.",
+      ));
+    }
+    file_watcher::watch_func(
+      resolver,
+      operation,
+      file_watcher::PrintConfig {
+        job_name: "Lint".to_string(),
+        clear_screen: !cli_options.no_clear_screen(),
+      },
+    )
+    .await?;
+  } else {
+    if lint_options.is_stdin {
+      if fix {
+        return Err(generic_error(
+          "Lint fix on standard input is not supported.",
+        ));
+      }
+      let reporter_lock = Arc::new(Mutex::new(create_reporter(reporter_kind)));
+      let r = lint_stdin(workspace_rules.root);
+      handle_lint_result(
+        STDIN_FILE_NAME,
+        r,
+        reporter_lock.clone(),
+        has_error.clone(),
+      );
+      reporter_lock.lock().unwrap().close(1);
+    } else {
+      let target_files = collect_lint_files(&files).and_then(|files| {
+        if files.is_empty() {
+          Err(generic_error("No target files found."))
+        } else {
+          Ok(files)
+        }
+      })?;
+      debug!("Found {} files", target_files.len());
+      operation(target_files).await?;
+    };
+    let has_error = has_error.load(Ordering::Relaxed);
+    if has_error {
+      std::process::exit(1);
+    }
+  }
+
+  Ok(())
+}
+
+fn collect_lint_files(files: &FilesConfig) -> Result<Vec<PathBuf>, AnyError> {
+  FileCollector::new(is_supported_ext)
+    .ignore_git_folder()
+    .ignore_node_modules()
+    .add_ignore_paths(&files.exclude)
+    .collect_files(&files.include)
+}
+
+pub fn print_rules_list(json: bool) {
+  let lint_rules = rules::get_recommended_rules();
+
+  if json {
+    let json_rules: Vec<serde_json::Value> = lint_rules
+      .iter()
+      .map(|rule| {
+        serde_json::json!({
+          "code": rule.code(),
+          "tags": rule.tags(),
+          "docs": rule.docs(),
+        })
+      })
+      .collect();
+    let json_str = serde_json::to_string_pretty(&json_rules).unwrap();
+    println!("{json_str}");
+  } else {
+    // The rules should still be printed even if `--quiet` option is enabled,
+    // so use `println!` here instead of `info!`.
+    println!("Available rules:");
+    for rule in lint_rules.iter() {
+      println!(" - {}", rule.code());
+      println!("   help: https://lint.deno.land/#{}", rule.code());
+      println!();
+    }
+  }
+}
+
+pub fn create_linter(
+  media_type: MediaType,
+  rules: Vec<Arc<dyn LintRule>>,
+) -> Linter {
+  LinterBuilder::default()
+    .ignore_file_directive("deno-lint-ignore-file")
+    .ignore_diagnostic_directive("deno-lint-ignore")
+    .media_type(media_type)
+    .rules(rules)
+    .build()
+}
+
+fn lint_file(
+  file_path: &PathBuf,
+  source_code: String,
+  lint_rules: FileLintRules,
+) -> Result<(Vec<LintDiagnostic>, String), AnyError> {
+  let file_name = file_path.to_string_lossy().to_string();
+  let media_type = MediaType::from(file_path);
+
+  let linter = create_linter(media_type, lint_rules.rules);
+
+  let (parsed_source, mut file_diagnostics) =
+    linter.lint(file_name.clone(), source_code.clone())?;
+  lint_with_plugins(
+    &lint_rules.plugins,
+    &parsed_source,
+    &file_name,
+    &mut file_diagnostics,
+  )?;
+
+  Ok((file_diagnostics, source_code))
+}
+
+/// Lints the file and applies the fixes of its diagnostics until none are
+/// left, then writes the file if it changed. Returns the remaining
+/// diagnostics and the fixed source.
+fn lint_and_fix_file(
+  file_path: &PathBuf,
+  source_code: String,
+  lint_rules: FileLintRules,
+) -> Result<(Vec<LintDiagnostic>, String), AnyError> {
+  let file_name = file_path.to_string_lossy().to_string();
+  let media_type = MediaType::from(file_path);
+  let mut source_code = source_code;
+  let mut fixed_count = 0;
+  for _ in 0..MAX_FIX_PASSES {
+    let linter = create_linter(media_type, lint_rules.rules.clone());
+    let (parsed_source, mut file_diagnostics) =
+      linter.lint(file_name.clone(), source_code.clone())?;
+    // plugin rules have no fixes, but they use ignore directives that would
+    // otherwise be removed as unused
+    lint_with_plugins(
+      &lint_rules.plugins,
+      &parsed_source,
+      &file_name,
+      &mut file_diagnostics,
+    )?;
+    let fixes = file_diagnostics
+      .iter()
+      .filter_map(|d| {
+        get_lint_fix(
+          &parsed_source,
+          &d.code,
+          &d.message,
+          d.range.start.byte_pos..d.range.end.byte_pos,
+        )
+      })
+      .collect::<Vec<_>>();
+    if fixes.is_empty() {
+      break;
+    }
+    let (fixed_source_code, count) = apply_lint_fixes(&source_code, fixes);
+    source_code = fixed_source_code;
+    fixed_count += count;
+  }
+
+  // lint the final source, which also makes sure the fixes didn't break it
+  let (file_diagnostics, source_code) =
+    lint_file(file_path, source_code, lint_rules)?;
+  if fixed_count > 0 {
+    atomic_write_file(file_path, &source_code, file_mode(file_path))?;
+    info!(
+      "Fixed {} {} in {}",
+      fixed_count,
+      if fixed_count == 1 {
+        "problem"
+      } else {
+        "problems"
+      },
+      file_path.display()
+    );
+  }
+  Ok((file_diagnostics, source_code))
+}
+
+#[cfg(unix)]
+fn file_mode(file_path: &Path) -> u32 {
+  use std::os::unix::fs::PermissionsExt;
+  fs::metadata(file_path)
+    .map(|metadata| metadata.permissions().mode())
+    .unwrap_or(0o644)
+}
+
+#[cfg(not(unix))]
+fn file_mode(_file_path: &Path) -> u32 {
+  0o644
+}
+
+/// Lint stdin and write result to stdout.
+/// Treats input as TypeScript.
+/// Compatible with `--json` flag.
+fn lint_stdin(
+  lint_rules: FileLintRules,
+) -> Result<(Vec<LintDiagnostic>, String), AnyError> {
+  let mut source_code = String::new();
+  if stdin().read_to_string(&mut source_code).is_err() {
+    return Err(generic_error("Failed to read from stdin"));
+  }
+
+  let linter = create_linter(MediaType::TypeScript, lint_rules.rules);
+
+  let (parsed_source, mut file_diagnostics) =
+    linter.lint(STDIN_FILE_NAME.to_string(), source_code.clone())?;
+  lint_with_plugins(
+    &lint_rules.plugins,
+    &parsed_source,
+    STDIN_FILE_NAME,
+    &mut file_diagnostics,
+  )?;
+
+  Ok((file_diagnostics, source_code))
+}
+
+fn handle_lint_result(
+  file_path: &str,
+  result: Result<(Vec<LintDiagnostic>, String), AnyError>,
+  reporter_lock: Arc<Mutex<Box<dyn LintReporter + Send>>>,
+  has_error: Arc<AtomicBool>,
+) {
+  let mut reporter = reporter_lock.lock().unwrap();
+
+  match result {
+    Ok((mut file_diagnostics, source)) => {
+      sort_diagnostics(&mut file_diagnostics);
+      for d in file_diagnostics.iter() {
+        has_error.store(true, Ordering::Relaxed);
+        reporter.visit_diagnostic(d, source.split('\n').collect());
+      }
+    }
+    Err(err) => {
+      has_error.store(true, Ordering::Relaxed);
+      reporter.visit_error(file_path, &err);
+    }
+  }
+}
+
+trait LintReporter {
+  fn visit_diagnostic(&mut self, d: &LintDiagnostic, source_lines: Vec<&str>);
+  fn visit_error(&mut self, file_path: &str, err: &AnyError);
+  fn close(&mut self, check_count: usize);
+}
+
+#[derive(Serialize)]
+struct LintError {
+  file_path: String,
+  message: String,
+}
+
+struct PrettyLintReporter {
+  lint_count: u32,
+}
+
+impl PrettyLintReporter {
+  fn new() -> PrettyLintReporter {
+    PrettyLintReporter { lint_count: 0 }
+  }
+}
+
+impl LintReporter for PrettyLintReporter {
+  fn visit_diagnostic(&mut self, d: &LintDiagnostic, source_lines: Vec<&str>) {
+    self.lint_count += 1;
+
+    let pretty_message = format!("({}) {}", colors::red(&d.code), &d.message);
+
+    let message = format_diagnostic(
+      &d.code,
+      &pretty_message,
+      &source_lines,
+      &d.range,
+      d.hint.as_ref(),
+      &format_location(&JsStackFrame::from_location(
+        Some(d.filename.clone()),
+        // todo(dsherret): these should use "display positions"
+        // which take into account the added column index of tab
+        // indentation
+        Some(d.range.start.line_index as i64 + 1),
+        Some(d.range.start.column_index as i64 + 1),
+      )),
+    );
+
+    eprintln!("{message}\n");
+  }
+
+  fn visit_error(&mut self, file_path: &str, err: &AnyError) {
+    eprintln!("Error linting: {file_path}");
+    eprintln!("   {err}");
+  }
+
+  fn close(&mut self, check_count: usize) {
+    match self.lint_count {
+      1 => info!("Found 1 problem"),
+      n if n > 1 => info!("Found {} problems", self.lint_count),
+      _ => (),
+    }
+
+    match check_count {
+      n if n <= 1 => info!("Checked {} file", n),
+      n if n > 1 => info!("Checked {} files", n),
+      _ => unreachable!(),
+    }
+  }
+}
+
+struct CompactLintReporter {
+  lint_count: u32,
+}
+
+impl CompactLintReporter {
+  fn new() -> CompactLintReporter {
+    CompactLintReporter { lint_count: 0 }
+  }
+}
+
+impl LintReporter for CompactLintReporter {
+  fn visit_diagnostic(&mut self, d: &LintDiagnostic, _source_lines: Vec<&str>) {
+    self.lint_count += 1;
+
+    eprintln!(
+      "{}: line {}, col {} - {} ({})",
+      d.filename,
+      d.range.start.line_index + 1,
+      d.range.start.column_index + 1,
+      d.message,
+      d.code
+    )
+  }
+
+  fn visit_error(&mut self, file_path: &str, err: &AnyError) {
+    eprintln!("Error linting: {file_path}");
+    eprintln!("   {err}");
+  }
+
+  fn close(&mut self, check_count: usize) {
+    match self.lint_count {
+      1 => info!("Found 1 problem"),
+      n if n > 1 => info!("Found {} problems", self.lint_count),
+      _ => (),
+    }
+
+    match check_count {
+      n if n <= 1 => info!("Checked {} file", n),
+      n if n > 1 => info!("Checked {} files", n),
+      _ => unreachable!(),
+    }
+  }
+}
+
+pub fn format_diagnostic(
+  diagnostic_code: &str,
+  message_line: &str,
+  source_lines: &[&str],
+  range: &deno_lint::diagnostic::Range,
+  maybe_hint: Option<&String>,
+  formatted_location: &str,
+) -> String {
+  let mut lines = vec![];
+
+  for (i, line) in source_lines
+    .iter()
+    .enumerate()
+    .take(range.end.line_index + 1)
+    .skip(range.start.line_index)
+  {
+    lines.push(line.to_string());
+    if range.start.line_index == range.end.line_index {
+      lines.push(format!(
+        "{}{}",
+        " ".repeat(range.start.column_index),
+        colors::red(
+          &"^".repeat(range.end.column_index - range.start.column_index)
+        )
+      ));
+    } else {
+      let line_len = line.len();
+      if range.start.line_index == i {
+        lines.push(format!(
+          "{}{}",
+          " ".repeat(range.start.column_index),
+          colors::red(&"^".repeat(line_len - range.start.column_index))
+        ));
+      } else if range.end.line_index == i {
+        lines
+          .push(colors::red(&"^".repeat(range.end.column_index)).to_string());
+      } else if line_len != 0 {
+        lines.push(colors::red(&"^".repeat(line_len)).to_string());
+      }
+    }
+  }
+
+  let hint = if let Some(hint) = maybe_hint {
+    format!("    {} {}\n", colors::cyan("hint:"), hint)
+  } else {
+    "".to_string()
+  };
+  let help = format!(
+    "    {} for further information visit https://lint.deno.land/#{}",
+    colors::cyan("help:"),
+    diagnostic_code
+  );
+
+  format!(
+    "{message_line}\n{snippets}\n    at {formatted_location}\n\n{hint}{help}",
+    message_line = message_line,
+    snippets = lines.join("\n"),
+    formatted_location = formatted_location,
+    hint = hint,
+    help = help
+  )
+}
+
+#[derive(Serialize)]
+struct JsonLintReporter {
+  diagnostics: Vec<LintDiagnostic>,
+  errors: Vec<LintError>,
+}
+
+impl JsonLintReporter {
+  fn new() -> JsonLintReporter {
+    JsonLintReporter {
+      diagnostics: Vec::new(),
+      errors: Vec::new(),
+    }
+  }
+}
+
+impl LintReporter for JsonLintReporter {
+  fn visit_diagnostic(&mut self, d: &LintDiagnostic, _source_lines: Vec<&str>) {
+    self.diagnostics.push(d.clone());
+  }
+
+  fn visit_error(&mut self, file_path: &str, err: &AnyError) {
+    self.errors.push(LintError {
+      file_path: file_path.to_string(),
+      message: err.to_string(),
+    });
+  }
+
+  fn close(&mut self, _check_count: usize) {
+    sort_diagnostics(&mut self.diagnostics);
+    let json = serde_json::to_string_pretty(&self);
+    println!("{}", json.unwrap());
+  }
+}
+
+/// Reports in the SARIF 2.1 format, which code scanning tools understand.
+/// See https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+struct SarifLintReporter {
+  diagnostics: Vec<(LintDiagnostic, Option<LintFix>)>,
+  errors: Vec<LintError>,
+  /// The last parsed file, which is reused to get the fixes of its other
+  /// diagnostics.
+  maybe_parsed_source: Option<ParsedSource>,
+}
+
+impl SarifLintReporter {
+  fn new() -> SarifLintReporter {
+    SarifLintReporter {
+      diagnostics: Vec::new(),
+      errors: Vec::new(),
+      maybe_parsed_source: None,
+    }
+  }
+
+  fn get_fix(
+    &mut self,
+    d: &LintDiagnostic,
+    source_lines: Vec<&str>,
+  ) -> Option<LintFix> {
+    let is_parsed = matches!(
+      &self.maybe_parsed_source,
+      Some(parsed_source) if parsed_source.specifier() == d.filename
+    );
+    if !is_parsed {
+      self.maybe_parsed_source =
+        deno_ast::parse_program(deno_ast::ParseParams {
+          specifier: d.filename.clone(),
+          text_info: SourceTextInfo::from_string(source_lines.join("\n")),
+          media_type: MediaType::from(Path::new(&d.filename)),
+          capture_tokens: true,
+          scope_analysis: false,
+          maybe_syntax: None,
+        })
+        .ok();
+    }
+    get_lint_fix(
+      self.maybe_parsed_source.as_ref()?,
+      &d.code,
+      &d.message,
+      d.range.start.byte_pos..d.range.end.byte_pos,
+    )
+  }
+}
+
+impl LintReporter for SarifLintReporter {
+  fn visit_diagnostic(&mut self, d: &LintDiagnostic, source_lines: Vec<&str>) {
+    let maybe_fix = self.get_fix(d, source_lines);
+    self.diagnostics.push((d.clone(), maybe_fix));
+  }
+
+  fn visit_error(&mut self, file_path: &str, err: &AnyError) {
+    self.errors.push(LintError {
+      file_path: file_path.to_string(),
+      message: err.to_string(),
+    });
+  }
+
+  fn close(&mut self, _check_count: usize) {
+    self
+      .diagnostics
+      .sort_by(|(a, _), (b, _)| compare_diagnostics(a, b));
+    // Locations are relative to the current directory where possible, so
+    // they match the paths of the checked out repository.
+    let maybe_root = std::env::current_dir()
+      .ok()
+      .and_then(|cwd| ModuleSpecifier::from_directory_path(cwd).ok());
+
+    let mut codes = self
+      .diagnostics
+      .iter()
+      .map(|(d, _)| d.code.as_str())
+      .collect::<Vec<_>>();
+    codes.sort_unstable();
+    codes.dedup();
+    let all_rules = rules::get_all_rules();
+    let sarif_rules = codes
+      .iter()
+      .map(|code| {
+        let mut sarif_rule = serde_json::json!({
+          "id": code,
+          "helpUri": format!("https://lint.deno.land/#{code}"),
+          "defaultConfiguration": { "level": "error" },
+        });
+        if let Some(rule) = all_rules.iter().find(|r| r.code() == *code) {
+          sarif_rule["fullDescription"] = serde_json::json!({
+            "text": rule.docs(),
+          });
+          sarif_rule["properties"] = serde_json::json!({
+            "tags": rule.tags(),
+          });
+        }
+        sarif_rule
+      })
+      .collect::<Vec<_>>();
+
+    let results = self
+      .diagnostics
+      .iter()
+      .map(|(d, maybe_fix)| {
+        let message = match &d.hint {
+          Some(hint) => format!("{}\nhint: {}", d.message, hint),
+          None => d.message.clone(),
+        };
+        let artifact_location =
+          sarif_artifact_location(&d.filename, maybe_root.as_ref());
+        let mut result = serde_json::json!({
+          "ruleId": d.code,
+          "ruleIndex": codes.binary_search(&d.code.as_str()).unwrap(),
+          "level": "error",
+          "message": { "text": message },
+          "locations": [{
+            "physicalLocation": {
+              "artifactLocation": artifact_location,
+              "region": {
+                "startLine": d.range.start.line_index + 1,
+                "startColumn": d.range.start.column_index + 1,
+                "endLine": d.range.end.line_index + 1,
+                "endColumn": d.range.end.column_index + 1,
+              },
+            },
+          }],
+        });
+        if let Some(fix) = maybe_fix {
+          result["fixes"] =
+            serde_json::json!([sarif_fix(fix, artifact_location)]);
+        }
+        result
+      })
+      .collect::<Vec<_>>();
+
+    let notifications = self
+      .errors
+      .iter()
+      .map(|err| {
+        serde_json::json!({
+          "level": "error",
+          "message": { "text": err.message },
+          "locations": [{
+            "physicalLocation": {
+              "artifactLocation":
+                sarif_artifact_location(&err.file_path, maybe_root.as_ref()),
+            },
+          }],
+        })
+      })
+      .collect::<Vec<_>>();
+
+    let mut run = serde_json::json!({
+      "tool": {
+        "driver": {
+          "name": "deno lint",
+          "informationUri": "https://lint.deno.land/",
+          "version": crate::version::deno(),
+          "rules": sarif_rules,
+        },
+      },
+      "results": results,
+      "invocations": [{
+        "executionSuccessful": self.errors.is_empty(),
+        "toolExecutionNotifications": notifications,
+      }],
+    });
+    if let Some(root) = maybe_root {
+      run["originalUriBaseIds"] = serde_json::json!({
+        "%SRCROOT%": { "uri": root.as_str() },
+      });
+    }
+    let sarif = serde_json::json!({
+      "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+      "version": "2.1.0",
+      "runs": [run],
+    });
+    println!("{}", serde_json::to_string_pretty(&sarif).unwrap());
+  }
+}
+
+/// A SARIF fix replacing the byte range of the fix in the artifact.
+fn sarif_fix(
+  fix: &LintFix,
+  artifact_location: serde_json::Value,
+) -> serde_json::Value {
+  serde_json::json!({
+    "description": { "text": fix.description },
+    "artifactChanges": [{
+      "artifactLocation": artifact_location,
+      "replacements": [{
+        "deletedRegion": {
+          "byteOffset": fix.range.start,
+          "byteLength": fix.range.end - fix.range.start,
+        },
+        "insertedContent": { "text": fix.new_text },
+      }],
+    }],
+  })
+}
+
+fn sarif_artifact_location(
+  file_path: &str,
+  maybe_root: Option<&ModuleSpecifier>,
+) -> serde_json::Value {
+  let url = match ModuleSpecifier::from_file_path(file_path) {
+    Ok(url) => url,
+    // eg. stdin
+    Err(_) => return serde_json::json!({ "uri": file_path }),
+  };
+  match maybe_root.and_then(|root| url.as_str().strip_prefix(root.as_str())) {
+    Some(relative) => {
+      serde_json::json!({ "uri": relative, "uriBaseId": "%SRCROOT%" })
+    }
+    None => serde_json::json!({ "uri": url.as_str() }),
+  }
+}
+
+/// Reports as GitHub Actions workflow commands, which show up as annotations
+/// on the pull request diff.
+/// See https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions
+struct GithubLintReporter {
+  lint_count: u32,
+  maybe_cwd: Option<PathBuf>,
+}
+
+impl GithubLintReporter {
+  fn new() -> GithubLintReporter {
+    GithubLintReporter {
+      lint_count: 0,
+      maybe_cwd: std::env::current_dir().ok(),
+    }
+  }
+
+  /// Annotations need paths relative to the repository root.
+  fn relative_path(&self, file_path: &str) -> String {
+    let path = Path::new(file_path);
+    match self
+      .maybe_cwd
+      .as_ref()
+      .and_then(|cwd| path.strip_prefix(cwd).ok())
+    {
+      // GitHub expects forward slashes, also on Windows
+      Some(relative) => relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/"),
+      None => file_path.to_string(),
+    }
+  }
+}
+
+impl LintReporter for GithubLintReporter {
+  fn visit_diagnostic(&mut self, d: &LintDiagnostic, _source_lines: Vec<&str>) {
+    self.lint_count += 1;
+
+    let message = match &d.hint {
+      Some(hint) => format!("{}\nhint: {}", d.message, hint),
+      None => d.message.clone(),
+    };
+    println!(
+      "::error file={},line={},endLine={},col={},endColumn={},title={}::{}",
+      escape_github_property(&self.relative_path(&d.filename)),
+      d.range.start.line_index + 1,
+      d.range.end.line_index + 1,
+      d.range.start.column_index + 1,
+      d.range.end.column_index + 1,
+      escape_github_property(&d.code),
+      escape_github_data(&message),
+    );
+  }
+
+  fn visit_error(&mut self, file_path: &str, err: &AnyError) {
+    println!(
+      "::error file={},title=Error linting::{}",
+      escape_github_property(&self.relative_path(file_path)),
+      escape_github_data(&err.to_string()),
+    );
+  }
+
+  fn close(&mut self, check_count: usize) {
+    match self.lint_count {
+      1 => info!("Found 1 problem"),
+      n if n > 1 => info!("Found {} problems", self.lint_count),
+      _ => (),
+    }
+
+    match check_count {
+      n if n <= 1 => info!("Checked {} file", n),
+      n if n > 1 => info!("Checked {} files", n),
+      _ => unreachable!(),
+    }
+  }
+}
+
+fn escape_github_data(data: &str) -> String {
+  data
+    .replace('%', "%25")
+    .replace('\r', "%0D")
+    .replace('\n', "%0A")
+}
+
+fn escape_github_property(property: &str) -> String {
+  escape_github_data(property)
+    .replace(':', "%3A")
+    .replace(',', "%2C")
+}
+
+fn sort_diagnostics(diagnostics: &mut [LintDiagnostic]) {
+  // Sort so that we guarantee a deterministic output which is useful for tests
+  diagnostics.sort_by(compare_diagnostics);
+}
+
+fn compare_diagnostics(
+  a: &LintDiagnostic,
+  b: &LintDiagnostic,
+) -> std::cmp::Ordering {
+  use std::cmp::Ordering;
+  let file_order = a.filename.cmp(&b.filename);
+  match file_order {
+    Ordering::Equal => {
+      let line_order = a.range.start.line_index.cmp(&b.range.start.line_index);
+      match line_order {
+        Ordering::Equal => {
+          a.range.start.column_index.cmp(&b.range.start.column_index)
+        }
+        _ => line_order,
+      }
+    }
+    _ => file_order,
+  }
+}
+
+pub fn get_configured_rules(rules: LintRulesConfig) -> Vec<Arc<dyn LintRule>> {
+  if rules.tags.is_none() && rules.include.is_none() && rules.exclude.is_none()
+  {
+    rules::get_recommended_rules()
+  } else {
+    rules::get_filtered_rules(
+      rules.tags.or_else(|| Some(vec!["recommended".to_string()])),
+      rules.exclude,
+      rules.include,
+    )
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use deno_lint::rules::get_recommended_rules;
+
+  use super::*;
+  use crate::args::LintRulesConfig;
+
+  #[test]
+  fn github_escaping() {
+    assert_eq!(escape_github_data("100%\nok: a,b"), "100%25%0Aok: a,b");
+    assert_eq!(escape_github_property("C:\\a,b.ts"), "C%3A\\a%2Cb.ts");
+  }
+
+  #[test]
+  fn github_relative_path() {
+    let cwd = std::env::current_dir().unwrap();
+    let reporter = GithubLintReporter {
+      lint_count: 0,
+      maybe_cwd: Some(cwd.clone()),
+    };
+    let file_path = cwd.join("src").join("a.ts");
+    assert_eq!(
+      reporter.relative_path(&file_path.to_string_lossy()),
+      "src/a.ts"
+    );
+  }
+
+  #[test]
+  fn sarif_fixes() {
+    let mut reporter = SarifLintReporter::new();
+    let source = "let a = 1;;\nconsole.log(a);\n";
+    let linter = create_linter(MediaType::TypeScript, get_recommended_rules());
+    let (_, diagnostics) =
+      linter.lint("a.ts".to_string(), source.to_string()).unwrap();
+    let d = diagnostics
+      .iter()
+      .find(|d| d.code == "no-extra-semi")
+      .unwrap();
+    let fix = reporter.get_fix(d, source.split('\n').collect()).unwrap();
+    assert_eq!(fix.range, 10..11);
+    assert_eq!(
+      sarif_fix(&fix, serde_json::json!({ "uri": "a.ts" })),
+      serde_json::json!({
+        "description": { "text": "Remove extra semicolon" },
+        "artifactChanges": [{
+          "artifactLocation": { "uri": "a.ts" },
+          "replacements": [{
+            "deletedRegion": { "byteOffset": 10, "byteLength": 1 },
+            "insertedContent": { "text": "" },
+          }],
+        }],
+      })
+    );
+  }
+
+  #[test]
+  fn sarif_artifact_locations() {
+    let (root_dir, other_path) = if cfg!(windows) {
+      ("C:\\project", "C:\\other\\b.ts")
+    } else {
+      ("/project", "/other/b.ts")
+    };
+    let root = ModuleSpecifier::from_directory_path(root_dir).unwrap();
+    let file_path = Path::new(root_dir).join("src").join("a b.ts");
+    assert_eq!(
+      sarif_artifact_location(&file_path.to_string_lossy(), Some(&root)),
+      serde_json::json!({ "uri": "src/a%20b.ts", "uriBaseId": "%SRCROOT%" })
+    );
+    assert_eq!(
+      sarif_artifact_location(other_path, Some(&root)),
+      serde_json::json!({
+        "uri": ModuleSpecifier::from_file_path(other_path).unwrap().as_str(),
+      })
+    );
+    assert_eq!(
+      sarif_artifact_location("_stdin.ts", Some(&root)),
+      serde_json::json!({ "uri": "_stdin.ts" })
+    );
+  }
+
+  #[test]
+  fn recommended_rules_when_no_tags_in_config() {
+    let rules_config = LintRulesConfig {
+      exclude: Some(vec!["no-debugger".to_string()]),
+      include: None,
+      tags: None,
+    };
+    let rules = get_configured_rules(rules_config);
+    let mut rule_names = rules
+      .into_iter()
+      .map(|r| r.code().to_string())
+      .collect::<Vec<_>>();
+    rule_names.sort();
+    let mut recommended_rule_names = get_recommended_rules()
+      .into_iter()
+      .map(|r| r.code().to_string())
+      .filter(|n| n != "no-debugger")
+      .collect::<Vec<_>>();
+    recommended_rule_names.sort();
+    assert_eq!(rule_names, recommended_rule_names);
+  }
+}
@@ -0,0 +1,109 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+"use strict";
+
+// Runs the rules of lint plugins against the syntax tree of a file, which is
+// serialized by `plugins.rs`. This script is evaluated in an isolate without
+// any of the Deno APIs.
+//
+// A plugin module default exports an object like:
+//
+//   export default {
+//     name: "my-plugin",
+//     rules: {
+//       "no-foo": {
+//         create(context) {
+//           return {
+//             Ident(node) {
+//               if (node.name === "foo") {
+//                 context.report({ node, message: "Don't use foo." });
+//               }
+//             },
+//           };
+//         },
+//       },
+//     },
+//   };
+//
+// Each node has a `type`, which is the name of the swc AST node, a `range`
+// of UTF-16 indexes into the source text, `children` and a `parent`.
+// Identifiers also have a `name`. A visitor is called with the nodes of its
+// type on the way down, and with those of its `<type>:exit` on the way up.
+((window) => {
+  const plugins = [];
+
+  function register(specifier, plugin) {
+    if (
+      plugin === null || typeof plugin !== "object" ||
+      typeof plugin.name !== "string" || plugin.rules === null ||
+      typeof plugin.rules !== "object"
+    ) {
+      throw new TypeError(
+        `Lint plugin "${specifier}" must default export an object with a "name" and "rules".`,
+      );
+    }
+    for (const [ruleName, rule] of Object.entries(plugin.rules)) {
+      if (rule === null || typeof rule?.create !== "function") {
+        throw new TypeError(
+          `Lint rule "${plugin.name}/${ruleName}" must have a "create" function.`,
+        );
+      }
+    }
+    plugins.push(plugin);
+  }
+
+  function codes() {
+    return plugins.flatMap((plugin) =>
+      Object.keys(plugin.rules).map((ruleName) => `${plugin.name}/${ruleName}`)
+    );
+  }
+
+  function run(fileName, sourceText, program) {
+    const diagnostics = [];
+    const visitors = [];
+    for (const plugin of plugins) {
+      for (const [ruleName, rule] of Object.entries(plugin.rules)) {
+        const code = `${plugin.name}/${ruleName}`;
+        const context = {
+          fileName,
+          sourceText,
+          getText(node) {
+            return sourceText.slice(node.range[0], node.range[1]);
+          },
+          report({ node, range, message, hint }) {
+            range ??= node?.range;
+            if (!Array.isArray(range) || typeof message !== "string") {
+              throw new TypeError(
+                `Lint rule "${code}" must report a "message" with a "node" or "range".`,
+              );
+            }
+            diagnostics.push({
+              code,
+              message,
+              hint: typeof hint === "string" ? hint : null,
+              range: [range[0], range[1]],
+            });
+          },
+        };
+        visitors.push(rule.create(context) ?? {});
+      }
+    }
+    visit(program, null, visitors);
+    return diagnostics;
+  }
+
+  function visit(node, parent, visitors) {
+    node.parent = parent;
+    for (const visitor of visitors) {
+      visitor[node.type]?.(node);
+    }
+    for (const child of node.children) {
+      visit(child, node, visitors);
+    }
+    const exitType = `${node.type}:exit`;
+    for (const visitor of visitors) {
+      visitor[exitType]?.(node);
+    }
+  }
+
+  window.__lintPluginRunner = { register, codes, run };
+})(globalThis);
@@ -0,0 +1,595 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Lint rules defined in JavaScript or TypeScript plugins, configured by
+//! `lint.plugins` in the config file. Plugins run in an isolate without any
+//! Deno APIs and may only import local modules. Loading the plugins or
+//! linting a file with them is terminated after `PLUGIN_TIMEOUT`.
+
+use deno_ast::view::Node;
+use deno_ast::view::NodeTrait;
+use deno_ast::MediaType;
+use deno_ast::ModuleSpecifier;
+use deno_ast::ParseParams;
+use deno_ast::ParsedSource;
+use deno_ast::SourceRanged;
+use deno_ast::SourceRangedForSpanned;
+use deno_ast::SourceTextInfo;
+use deno_core::anyhow::Context;
+use deno_core::error::custom_error;
+use deno_core::error::generic_error;
+use deno_core::error::get_custom_error_class;
+use deno_core::error::AnyError;
+use deno_core::futures::executor::block_on;
+use deno_core::futures::FutureExt;
+use deno_core::resolve_import;
+use deno_core::serde_json;
+use deno_core::serde_v8;
+use deno_core::v8;
+use deno_core::JsRuntime;
+use deno_core::ModuleLoader;
+use deno_core::ModuleSource;
+use deno_core::ModuleSourceFuture;
+use deno_core::ModuleType;
+use deno_core::ResolutionKind;
+use deno_core::RuntimeOptions;
+use deno_lint::diagnostic::LintDiagnostic;
+use deno_lint::diagnostic::Position;
+use deno_lint::diagnostic::Range;
+use serde::Deserialize;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::time::Duration;
+
+static PLUGIN_RUNNER: &str = include_str!("./plugin_runner.js");
+
+/// How long plugins may run when loading or linting one file, so that a
+/// plugin stuck in a loop doesn't hang `deno lint` or the language server.
+const PLUGIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+thread_local! {
+  // Loading plugins creates an isolate, so the host of the last plugins is
+  // kept for the next file linted on the same thread.
+  static PLUGIN_HOST: RefCell<Option<LintPluginHost>> = RefCell::new(None);
+}
+
+/// Loads the plugins, to report errors in them before any file is linted.
+pub fn check_lint_plugins(plugins: &[ModuleSpecifier]) -> Result<(), AnyError> {
+  if plugins.is_empty() {
+    return Ok(());
+  }
+  with_plugin_host(plugins, |_| Ok(()))
+}
+
+/// Adds the diagnostics of the rules of the plugins to the diagnostics of the
+/// built-in rules. Diagnostics of the built-in rules about ignore directives
+/// for the codes of plugin rules are removed, since the built-in rules don't
+/// know about them.
+pub fn lint_with_plugins(
+  plugins: &[ModuleSpecifier],
+  parsed_source: &ParsedSource,
+  file_name: &str,
+  diagnostics: &mut Vec<LintDiagnostic>,
+) -> Result<(), AnyError> {
+  if plugins.is_empty() {
+    return Ok(());
+  }
+  with_plugin_host(plugins, |host| {
+    let plugin_diagnostics = host.lint(parsed_source, file_name)?;
+    let directives = IgnoreDirectives::new(parsed_source);
+    let mut used_directives = HashSet::new();
+    let plugin_diagnostics = plugin_diagnostics
+      .into_iter()
+      .filter(|d| match directives.find(d) {
+        Some(directive) => {
+          used_directives.insert((directive.line_index, d.code.clone()));
+          false
+        }
+        None => true,
+      })
+      .collect::<Vec<_>>();
+    diagnostics.retain(|d| {
+      let code = match quoted_code(&d.message) {
+        Some(code) => code,
+        None => return true,
+      };
+      match d.code.as_str() {
+        "ban-unknown-rule-code" => !host.rule_codes.contains(code),
+        "ban-unused-ignore" => !used_directives
+          .contains(&(d.range.start.line_index, code.to_string())),
+        _ => true,
+      }
+    });
+    diagnostics.extend(plugin_diagnostics);
+    Ok(())
+  })
+}
+
+fn with_plugin_host<R>(
+  plugins: &[ModuleSpecifier],
+  f: impl FnOnce(&mut LintPluginHost) -> Result<R, AnyError>,
+) -> Result<R, AnyError> {
+  PLUGIN_HOST.with(|cell| {
+    let mut maybe_host = cell.borrow_mut();
+    if !matches!(&*maybe_host, Some(host) if host.plugins == plugins) {
+      // drop the previous isolate before creating a new one
+      *maybe_host = None;
+      *maybe_host = Some(LintPluginHost::load(plugins)?);
+    }
+    let host = maybe_host.as_mut().unwrap();
+    let result = f(host);
+    if host.is_terminated {
+      // the isolate was stopped in an unknown state, so start over
+      *maybe_host = None;
+    }
+    result
+  })
+}
+
+/// Runs `f`, terminating the JavaScript execution of the runtime if it takes
+/// longer than `timeout`. Fails with a "TimedOut" error if it was terminated.
+fn run_with_timeout<R>(
+  runtime: &mut JsRuntime,
+  timeout: Duration,
+  f: impl FnOnce(&mut JsRuntime) -> Result<R, AnyError>,
+) -> Result<R, AnyError> {
+  let isolate_handle = runtime.v8_isolate().thread_safe_handle();
+  let (done_tx, done_rx) = mpsc::channel::<()>();
+  let watchdog = std::thread::spawn(move || {
+    let timed_out = matches!(
+      done_rx.recv_timeout(timeout),
+      Err(mpsc::RecvTimeoutError::Timeout)
+    );
+    if timed_out {
+      isolate_handle.terminate_execution();
+    }
+    timed_out
+  });
+  let result = f(runtime);
+  drop(done_tx);
+  if watchdog.join().unwrap() {
+    return Err(custom_error(
+      "TimedOut",
+      format!(
+        "Lint plugins were terminated after running for {} seconds.",
+        timeout.as_secs_f64()
+      ),
+    ));
+  }
+  result
+}
+
+struct LintPluginHost {
+  plugins: Vec<ModuleSpecifier>,
+  rule_codes: HashSet<String>,
+  runtime: JsRuntime,
+  /// Whether a plugin timed out and its execution was terminated.
+  is_terminated: bool,
+}
+
+impl LintPluginHost {
+  fn load(plugins: &[ModuleSpecifier]) -> Result<Self, AnyError> {
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      module_loader: Some(Rc::new(PluginModuleLoader)),
+      ..Default::default()
+    });
+    runtime
+      .execute_script("internal:cli/lint_plugin_runner.js", PLUGIN_RUNNER)?;
+
+    let mut main_code = String::new();
+    for (i, plugin) in plugins.iter().enumerate() {
+      let specifier = serde_json::to_string(plugin.as_str()).unwrap();
+      writeln!(main_code, "import plugin{i} from {specifier};").unwrap();
+      writeln!(
+        main_code,
+        "globalThis.__lintPluginRunner.register({specifier}, plugin{i});"
+      )
+      .unwrap();
+    }
+    let main_specifier =
+      ModuleSpecifier::parse("internal:///lint_plugins.js").unwrap();
+    run_with_timeout(&mut runtime, PLUGIN_TIMEOUT, |runtime| {
+      block_on(async {
+        let module_id = runtime
+          .load_main_module(&main_specifier, Some(main_code))
+          .await?;
+        let receiver = runtime.mod_evaluate(module_id);
+        runtime.run_event_loop(false).await?;
+        receiver.await?
+      })
+    })
+    .context("Failed to load lint plugins")?;
+
+    let rule_codes: Vec<String> = execute_and_deserialize(
+      &mut runtime,
+      "globalThis.__lintPluginRunner.codes()",
+    )?;
+    Ok(Self {
+      plugins: plugins.to_vec(),
+      rule_codes: rule_codes.into_iter().collect(),
+      runtime,
+      is_terminated: false,
+    })
+  }
+
+  fn lint(
+    &mut self,
+    parsed_source: &ParsedSource,
+    file_name: &str,
+  ) -> Result<Vec<LintDiagnostic>, AnyError> {
+    let text_info = parsed_source.text_info();
+    let text = text_info.text_str();
+    let indexes = Utf16Indexes::new(text);
+    let start_pos = text_info.range().start;
+    let program = parsed_source.with_view(|program| {
+      serialize_node(program.into(), &|range| {
+        (
+          indexes.to_utf16(range.start.as_byte_index(start_pos)),
+          indexes.to_utf16(range.end.as_byte_index(start_pos)),
+        )
+      })
+    });
+    let script = format!(
+      "globalThis.__lintPluginRunner.run({}, {}, {})",
+      serde_json::to_string(file_name)?,
+      serde_json::to_string(text)?,
+      serde_json::to_string(&program)?,
+    );
+    let plugin_diagnostics: Vec<PluginDiagnostic> =
+      run_with_timeout(&mut self.runtime, PLUGIN_TIMEOUT, |runtime| {
+        execute_and_deserialize(runtime, &script)
+      })
+      .map_err(|err| {
+        if get_custom_error_class(&err) == Some("TimedOut") {
+          self.is_terminated = true;
+        }
+        err
+      })?;
+
+    Ok(
+      plugin_diagnostics
+        .into_iter()
+        .map(|d| {
+          let end = indexes.to_byte(d.range.1);
+          let start = indexes.to_byte(d.range.0).min(end);
+          let position = |byte_index: usize| {
+            let loc = text_info.line_and_column_index(start_pos + byte_index);
+            Position {
+              line_index: loc.line_index,
+              column_index: loc.column_index,
+              byte_pos: byte_index,
+            }
+          };
+          LintDiagnostic {
+            range: Range {
+              start: position(start),
+              end: position(end),
+            },
+            filename: file_name.to_string(),
+            message: d.message,
+            code: d.code,
+            hint: d.hint,
+          }
+        })
+        .collect(),
+    )
+  }
+}
+
+fn execute_and_deserialize<T: serde::de::DeserializeOwned>(
+  runtime: &mut JsRuntime,
+  script: &str,
+) -> Result<T, AnyError> {
+  let value = runtime.execute_script("internal:cli/lint_plugins.js", script)?;
+  let scope = &mut runtime.handle_scope();
+  let local = v8::Local::new(scope, value);
+  Ok(serde_v8::from_v8(scope, local)?)
+}
+
+#[derive(Deserialize)]
+struct PluginDiagnostic {
+  code: String,
+  message: String,
+  hint: Option<String>,
+  /// UTF-16 indexes into the source text.
+  range: (usize, usize),
+}
+
+/// A syntax tree node as it is passed to plugins.
+#[derive(Serialize)]
+struct PluginNode {
+  #[serde(rename = "type")]
+  kind: String,
+  range: (usize, usize),
+  #[serde(skip_serializing_if = "Option::is_none")]
+  name: Option<String>,
+  children: Vec<PluginNode>,
+}
+
+fn serialize_node(
+  node: Node,
+  to_range: &impl Fn(deno_ast::SourceRange) -> (usize, usize),
+) -> PluginNode {
+  let name = match node {
+    Node::Ident(ident) => Some(ident.sym().to_string()),
+    _ => None,
+  };
+  PluginNode {
+    kind: format!("{:?}", node.kind()),
+    range: to_range(node.range()),
+    name,
+    children: node
+      .children()
+      .into_iter()
+      .map(|child| serialize_node(child, to_range))
+      .collect(),
+  }
+}
+
+/// Converts between byte indexes into the source text and UTF-16 indexes
+/// into the same text as a JavaScript string.
+struct Utf16Indexes {
+  byte_to_utf16: Vec<usize>,
+  utf16_to_byte: Vec<usize>,
+}
+
+impl Utf16Indexes {
+  fn new(text: &str) -> Self {
+    let mut byte_to_utf16 = vec![0; text.len() + 1];
+    let mut utf16_to_byte = Vec::with_capacity(text.len() + 1);
+    let mut utf16_index = 0;
+    for (byte_index, c) in text.char_indices() {
+      for i in 0..c.len_utf8() {
+        byte_to_utf16[byte_index + i] = utf16_index;
+      }
+      for _ in 0..c.len_utf16() {
+        utf16_to_byte.push(byte_index);
+      }
+      utf16_index += c.len_utf16();
+    }
+    byte_to_utf16[text.len()] = utf16_index;
+    utf16_to_byte.push(text.len());
+    Self {
+      byte_to_utf16,
+      utf16_to_byte,
+    }
+  }
+
+  fn to_utf16(&self, byte_index: usize) -> usize {
+    self.byte_to_utf16[byte_index.min(self.byte_to_utf16.len() - 1)]
+  }
+
+  fn to_byte(&self, utf16_index: usize) -> usize {
+    self.utf16_to_byte[utf16_index.min(self.utf16_to_byte.len() - 1)]
+  }
+}
+
+struct IgnoreDirective {
+  line_index: usize,
+  /// The ignored codes, or all codes if empty.
+  codes: Vec<String>,
+}
+
+impl IgnoreDirective {
+  fn ignores(&self, code: &str) -> bool {
+    self.codes.is_empty() || self.codes.iter().any(|c| c == code)
+  }
+}
+
+/// The `deno-lint-ignore` and `deno-lint-ignore-file` comments of a file,
+/// which the linter only applies to the built-in rules.
+struct IgnoreDirectives {
+  file: Option<IgnoreDirective>,
+  lines: Vec<IgnoreDirective>,
+}
+
+impl IgnoreDirectives {
+  fn new(parsed_source: &ParsedSource) -> Self {
+    let text_info = parsed_source.text_info();
+    let mut directives = Self {
+      file: None,
+      lines: Vec::new(),
+    };
+    for comment in parsed_source.comments().get_vec() {
+      let mut words = comment.text.split_whitespace();
+      let kind = words.next();
+      let directive = IgnoreDirective {
+        line_index: text_info.line_and_column_index(comment.start()).line_index,
+        codes: words.map(ToString::to_string).collect(),
+      };
+      match kind {
+        Some("deno-lint-ignore-file") => directives.file = Some(directive),
+        Some("deno-lint-ignore") => directives.lines.push(directive),
+        _ => {}
+      }
+    }
+    directives
+  }
+
+  /// Returns the directive that ignores the diagnostic, which is either for
+  /// the file or on the line before the diagnostic.
+  fn find(&self, diagnostic: &LintDiagnostic) -> Option<&IgnoreDirective> {
+    let line_index = diagnostic.range.start.line_index;
+    self
+      .lines
+      .iter()
+      .find(|directive| {
+        directive.line_index + 1 == line_index
+          && directive.ignores(&diagnostic.code)
+      })
+      .or_else(|| {
+        self
+          .file
+          .as_ref()
+          .filter(|directive| directive.ignores(&diagnostic.code))
+      })
+  }
+}
+
+/// Gets the code quoted in the messages of the built-in rules about ignore
+/// directives, like `Ignore for code "x" was not used.`.
+fn quoted_code(message: &str) -> Option<&str> {
+  message.split('"').nth(1)
+}
+
+/// Loads local modules, transpiling TypeScript.
+struct PluginModuleLoader;
+
+impl ModuleLoader for PluginModuleLoader {
+  fn resolve(
+    &self,
+    specifier: &str,
+    referrer: &str,
+    _kind: ResolutionKind,
+  ) -> Result<ModuleSpecifier, AnyError> {
+    let specifier = resolve_import(specifier, referrer)?;
+    if specifier.scheme() != "file" {
+      return Err(generic_error(format!(
+        "Lint plugins can only import local modules, but \"{specifier}\" was imported."
+      )));
+    }
+    Ok(specifier)
+  }
+
+  fn load(
+    &self,
+    module_specifier: &ModuleSpecifier,
+    _maybe_referrer: Option<ModuleSpecifier>,
+    _is_dyn_import: bool,
+  ) -> Pin<Box<ModuleSourceFuture>> {
+    let module_specifier = module_specifier.clone();
+    async move {
+      let path = module_specifier.to_file_path().map_err(|_| {
+        generic_error(format!(
+          "Invalid lint plugin path \"{module_specifier}\""
+        ))
+      })?;
+      let code = std::fs::read_to_string(&path).with_context(|| {
+        format!("Failed to read lint plugin module \"{}\"", path.display())
+      })?;
+      let media_type = MediaType::from(&module_specifier);
+      let (module_type, code) = match media_type {
+        MediaType::JavaScript | MediaType::Mjs => {
+          (ModuleType::JavaScript, code)
+        }
+        MediaType::Json => (ModuleType::Json, code),
+        MediaType::TypeScript
+        | MediaType::Mts
+        | MediaType::Jsx
+        | MediaType::Tsx => {
+          let parsed_source = deno_ast::parse_module(ParseParams {
+            specifier: module_specifier.to_string(),
+            text_info: SourceTextInfo::from_string(code),
+            media_type,
+            capture_tokens: false,
+            scope_analysis: false,
+            maybe_syntax: None,
+          })?;
+          let code = parsed_source
+            .transpile(&deno_ast::EmitOptions::default())?
+            .text;
+          (ModuleType::JavaScript, code)
+        }
+        _ => {
+          return Err(generic_error(format!(
+            "Unsupported lint plugin module type \"{module_specifier}\""
+          )))
+        }
+      };
+      Ok(ModuleSource {
+        code: code.into_bytes().into_boxed_slice(),
+        module_type,
+        module_url_specified: module_specifier.to_string(),
+        module_url_found: module_specifier.to_string(),
+      })
+    }
+    .boxed_local()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn utf16_indexes() {
+    let indexes = Utf16Indexes::new("a€😀b");
+    assert_eq!(indexes.to_utf16(0), 0);
+    assert_eq!(indexes.to_utf16(1), 1);
+    assert_eq!(indexes.to_utf16(4), 2);
+    assert_eq!(indexes.to_utf16(8), 4);
+    assert_eq!(indexes.to_utf16(9), 5);
+    assert_eq!(indexes.to_byte(2), 4);
+    assert_eq!(indexes.to_byte(4), 8);
+    assert_eq!(indexes.to_byte(5), 9);
+    assert_eq!(indexes.to_byte(100), 9);
+  }
+
+  #[test]
+  fn run_with_timeout_terminates() {
+    let mut runtime = JsRuntime::new(Default::default());
+    let err =
+      run_with_timeout(&mut runtime, Duration::from_millis(100), |runtime| {
+        runtime.execute_script("loop.js", "for (;;) {}")?;
+        Ok(())
+      })
+      .unwrap_err();
+    assert_eq!(get_custom_error_class(&err), Some("TimedOut"));
+    assert_eq!(
+      err.to_string(),
+      "Lint plugins were terminated after running for 0.1 seconds."
+    );
+
+    let value = run_with_timeout(&mut runtime, PLUGIN_TIMEOUT, |runtime| {
+      runtime.v8_isolate().cancel_terminate_execution();
+      execute_and_deserialize::<u32>(runtime, "1 + 1")
+    })
+    .unwrap();
+    assert_eq!(value, 2);
+  }
+
+  #[test]
+  fn ignore_directives() {
+    let parsed_source = deno_ast::parse_module(ParseParams {
+      specifier: "file:///mod.ts".to_string(),
+      capture_tokens: false,
+      maybe_syntax: None,
+      media_type: MediaType::TypeScript,
+      scope_analysis: false,
+      text_info: SourceTextInfo::from_string(
+        "// deno-lint-ignore-file p/a\n// deno-lint-ignore p/b\nf();\n"
+          .to_string(),
+      ),
+    })
+    .unwrap();
+    let directives = IgnoreDirectives::new(&parsed_source);
+    let position = |line_index| Position {
+      line_index,
+      column_index: 0,
+      byte_pos: 0,
+    };
+    let diagnostic = |code: &str, line_index| LintDiagnostic {
+      range: Range {
+        start: position(line_index),
+        end: position(line_index),
+      },
+      filename: "file:///mod.ts".to_string(),
+      message: "".to_string(),
+      code: code.to_string(),
+      hint: None,
+    };
+    let find_line = |code, line_index| {
+      directives
+        .find(&diagnostic(code, line_index))
+        .map(|directive| directive.line_index)
+    };
+    assert_eq!(find_line("p/a", 2), Some(0));
+    assert_eq!(find_line("p/b", 2), Some(1));
+    assert_eq!(find_line("p/b", 1), None);
+    assert_eq!(find_line("p/c", 2), None);
+  }
+}
@@ -10,6 +10,7 @@ use deno_core::resolve_url_or_path;
 use deno_runtime::permissions::Permissions;
 use deno_runtime::permissions::PermissionsContainer;
 
+use crate::args::CliOptions;
 use crate::args::EvalFlags;
 use crate::args::Flags;
 use crate::args::RunFlags;
@@ -20,10 +21,10 @@ use crate::util;
 use crate::worker::create_main_worker;
 
 pub async fn run_script(
-  flags: Flags,
+  cli_options: CliOptions,
   run_flags: RunFlags,
 ) -> Result<i32, AnyError> {
-  if !flags.has_permission() && flags.has_permission_in_argv() {
+  if !cli_options.has_permission() && cli_options.has_permission_in_argv() {
     log::warn!(
       "{}",
       crate::colors::yellow(
@@ -34,14 +35,14 @@ To grant permissions, set them before the script argument. For example:
     );
   }
 
-  if flags.watch.is_some() {
-    return run_with_watch(flags, run_flags.script).await;
+  if cli_options.watch_paths().is_some() {
+    return run_with_watch(cli_options, run_flags.script).await;
   }
 
   // TODO(bartlomieju): actually I think it will also fail if there's an import
   // map specified and bare specifier is used on the command line - this should
   // probably call `ProcState::resolve` instead
-  let ps = ProcState::build(flags).await?;
+  let ps = ProcState::from_options(Arc::new(cli_options)).await?;
 
   // Run a background task that checks for available upgrades. If an earlier
   // run of this background task found a new version of Deno.
@@ -65,8 +66,8 @@ To grant permissions, set them before the script argument. For example:
   Ok(exit_code)
 }
 
-pub async fn run_from_stdin(flags: Flags) -> Result<i32, AnyError> {
-  let ps = ProcState::build(flags).await?;
+pub async fn run_from_stdin(cli_options: CliOptions) -> Result<i32, AnyError> {
+  let ps = ProcState::from_options(Arc::new(cli_options)).await?;
   let main_module = resolve_url_or_path("./$deno$stdin.ts").unwrap();
   let mut worker = create_main_worker(
     &ps,
@@ -98,12 +99,16 @@ pub async fn run_from_stdin(flags: Flags) -> Result<i32, AnyError> {
 
 // TODO(bartlomieju): this function is not handling `exit_code` set by the runtime
 // code properly.
-async fn run_with_watch(flags: Flags, script: String) -> Result<i32, AnyError> {
-  let flags = Arc::new(flags);
+async fn run_with_watch(
+  cli_options: CliOptions,
+  script: String,
+) -> Result<i32, AnyError> {
   let main_module = resolve_url_or_path(&script)?;
+  let clear_screen = !cli_options.no_clear_screen();
   let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
   let mut ps =
-    ProcState::build_for_file_watcher((*flags).clone(), sender.clone()).await?;
+    ProcState::build_for_file_watcher(Arc::new(cli_options), sender.clone())
+      .await?;
 
   let operation = |main_module: ModuleSpecifier| {
     ps.reset_for_file_watcher();
@@ -125,7 +130,7 @@ async fn run_with_watch(flags: Flags, script: String) -> Result<i32, AnyError> {
     main_module,
     util::file_watcher::PrintConfig {
       job_name: "Process".to_string(),
-      clear_screen: !flags.no_clear_screen,
+      clear_screen,
     },
   )
   .await?;
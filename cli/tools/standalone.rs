@@ -1,13 +1,19 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use crate::args::load_env_file;
 use crate::args::CaData;
 use crate::args::CompileFlags;
 use crate::args::Flags;
 use crate::cache::DenoDir;
 use crate::graph_util::create_graph_and_maybe_check;
 use crate::graph_util::error_for_any_npm_specifier;
+use crate::graph_util::graph_raw_modules;
+use crate::graph_util::graph_valid;
 use crate::http_util::HttpClient;
+use crate::standalone::EmbeddedFileEntry;
+use crate::standalone::EmbeddedFiles;
 use crate::standalone::Metadata;
+use crate::standalone::RawModule;
 use crate::standalone::MAGIC_TRAILER;
 use crate::util::path::path_has_trailing_slash;
 use crate::util::progress_bar::ProgressBar;
@@ -19,6 +25,8 @@ use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::resolve_url_or_path;
 use deno_core::serde_json;
+use deno_core::ModuleType;
+use deno_graph::MediaType;
 use deno_graph::ModuleSpecifier;
 use deno_runtime::colors;
 use std::env;
@@ -52,7 +60,14 @@ pub async fn compile(
   // at the moment, we don't support npm specifiers in deno_compile, so show an error
   error_for_any_npm_specifier(&graph)?;
 
-  graph.valid()?;
+  graph_valid(&graph, true, false)?;
+
+  // the eszip archive only contains the modules parsed by the graph
+  let mut raw_modules = Vec::new();
+  for (specifier, media_type) in graph_raw_modules(&graph) {
+    let code = ps.file_fetcher.get_source_bytes(specifier)?;
+    raw_modules.push((specifier.clone(), media_type, code));
+  }
 
   let parser = ps.parsed_source_cache.as_capturing_parser();
   let eszip = eszip::EszipV2::from_graph(graph, &parser, Default::default())?;
@@ -71,6 +86,7 @@ pub async fn compile(
   let final_bin = create_standalone_binary(
     original_binary,
     eszip,
+    raw_modules,
     module_specifier,
     &compile_flags,
     ps,
@@ -156,11 +172,50 @@ async fn download_base_binary(
 async fn create_standalone_binary(
   mut original_bin: Vec<u8>,
   eszip: eszip::EszipV2,
+  raw_modules: Vec<(ModuleSpecifier, MediaType, Vec<u8>)>,
   entrypoint: ModuleSpecifier,
   compile_flags: &CompileFlags,
   ps: ProcState,
 ) -> Result<Vec<u8>, AnyError> {
   let mut eszip_archive = eszip.into_bytes();
+  let eszip_pos = original_bin.len();
+
+  let mut embedded_data = Vec::new();
+  let embedded_files = if compile_flags.include.is_empty() {
+    None
+  } else {
+    let root = env::current_dir()?;
+    let mut entries = Vec::new();
+    for (path, data) in collect_included_files(&root, &compile_flags.include)? {
+      entries.push(EmbeddedFileEntry {
+        path,
+        offset: embedded_data.len() as u64,
+        len: data.len() as u64,
+      });
+      embedded_data.extend(data);
+    }
+    Some(EmbeddedFiles {
+      pos: (eszip_pos + eszip_archive.len()) as u64,
+      entries,
+    })
+  };
+
+  let raw_modules = raw_modules
+    .into_iter()
+    .map(|(specifier, media_type, code)| {
+      let module = RawModule {
+        specifier,
+        module_type: match media_type {
+          MediaType::Wasm => ModuleType::Wasm,
+          _ => ModuleType::JavaScript,
+        },
+        pos: (eszip_pos + eszip_archive.len() + embedded_data.len()) as u64,
+        len: code.len() as u64,
+      };
+      embedded_data.extend(code);
+      module
+    })
+    .collect();
 
   let ca_data = match ps.options.ca_data() {
     Some(CaData::File(ca_file)) => {
@@ -174,6 +229,20 @@ async fn create_standalone_binary(
     .resolve_import_map(&ps.file_fetcher)
     .await?
     .map(|import_map| (import_map.base_url().clone(), import_map.to_json()));
+  // The values are stored unencrypted in the executable, so they are only
+  // embedded for an explicit `--env-file` and not for the config's `envFile`.
+  let env_vars = match ps.options.resolve_env_file_flag()? {
+    Some(path) => {
+      let env_vars = load_env_file(&path)?;
+      log::warn!(
+        "{} The variables of {} are stored unencrypted in the executable.",
+        colors::yellow("Warning"),
+        path.display()
+      );
+      env_vars
+    }
+    None => Vec::new(),
+  };
   let metadata = Metadata {
     argv: compile_flags.args.clone(),
     unstable: ps.options.unstable(),
@@ -190,26 +259,85 @@ async fn create_standalone_binary(
     ca_data,
     entrypoint,
     maybe_import_map,
+    embedded_files,
+    raw_modules,
+    env_vars,
   };
   let mut metadata = serde_json::to_string(&metadata)?.as_bytes().to_vec();
 
-  let eszip_pos = original_bin.len();
-  let metadata_pos = eszip_pos + eszip_archive.len();
+  let metadata_pos = eszip_pos + eszip_archive.len() + embedded_data.len();
   let mut trailer = MAGIC_TRAILER.to_vec();
   trailer.write_all(&eszip_pos.to_be_bytes())?;
   trailer.write_all(&metadata_pos.to_be_bytes())?;
 
   let mut final_bin = Vec::with_capacity(
-    original_bin.len() + eszip_archive.len() + trailer.len(),
+    original_bin.len()
+      + eszip_archive.len()
+      + embedded_data.len()
+      + metadata.len()
+      + trailer.len(),
   );
   final_bin.append(&mut original_bin);
   final_bin.append(&mut eszip_archive);
+  final_bin.append(&mut embedded_data);
   final_bin.append(&mut metadata);
   final_bin.append(&mut trailer);
 
   Ok(final_bin)
 }
 
+/// Expands the `--include` globs relative to `root` and reads the matched
+/// files. Returns the paths relative to `root`, separated by `/`.
+fn collect_included_files(
+  root: &Path,
+  patterns: &[String],
+) -> Result<Vec<(String, Vec<u8>)>, AnyError> {
+  let mut paths = Vec::new();
+  for pattern in patterns {
+    let full_pattern = format!(
+      "{}/{}",
+      glob::Pattern::escape(&root.to_string_lossy()),
+      pattern
+    );
+    let matches = glob::glob(&full_pattern)
+      .with_context(|| format!("Invalid --include pattern \"{pattern}\""))?;
+    let mut matched = false;
+    for entry in matches {
+      let path = entry?;
+      if path.is_file() {
+        matched = true;
+        paths.push(path);
+      }
+    }
+    if !matched {
+      bail!("No files matched the --include pattern \"{}\"", pattern);
+    }
+  }
+  paths.sort();
+  paths.dedup();
+
+  let mut files = Vec::with_capacity(paths.len());
+  for path in paths {
+    let relative_path = match path.strip_prefix(root) {
+      Ok(relative_path) => relative_path,
+      Err(_) => bail!(
+        "Included file \"{}\" is outside of the current directory",
+        path.display()
+      ),
+    };
+    let relative_path = relative_path
+      .components()
+      .map(|c| c.as_os_str().to_string_lossy())
+      .collect::<Vec<_>>()
+      .join("/");
+    let data = fs::read(&path)
+      .with_context(|| format!("Reading: {}", path.display()))?;
+    log::info!("{} {}", colors::green("Include"), relative_path);
+    files.push((relative_path, data));
+  }
+  Ok(files)
+}
+
 /// This function writes out a final binary to specified path. If output path
 /// is not already standalone binary it will return error instead.
 async fn write_standalone_binary(
@@ -332,6 +460,7 @@ mod test {
       output: Some(PathBuf::from("./file")),
       args: Vec::new(),
       target: Some("x86_64-unknown-linux-gnu".to_string()),
+      include: vec![],
     })
     .unwrap();
 
@@ -348,6 +477,7 @@ mod test {
       output: Some(PathBuf::from("./file")),
       args: Vec::new(),
       target: Some("x86_64-pc-windows-msvc".to_string()),
+      include: vec![],
     })
     .unwrap();
     assert_eq!(path.file_name().unwrap(), "file.exe");
@@ -1,31 +1,58 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
-use crate::args::Flags;
+use crate::args::CliOptions;
+use crate::args::TaskConfig;
 use crate::args::TaskFlags;
+use crate::cache::DenoDir;
+use crate::cache::IncrementalCache;
 use crate::colors;
 use crate::proc_state::ProcState;
+use crate::util::checksum;
 use crate::util::fs::canonicalize_path;
+use crate::util::fs::copy_dir_recursive;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
+use deno_core::futures::stream::FuturesUnordered;
+use deno_core::futures::FutureExt;
+use deno_core::futures::StreamExt;
+use deno_task_shell::parser::SequentialList;
+use deno_task_shell::ShellPipeReader;
+use deno_task_shell::ShellState;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
 
-fn print_available_tasks(tasks_config: BTreeMap<String, String>) {
+fn print_available_tasks(tasks_config: BTreeMap<String, TaskConfig>) {
   eprintln!("{}", colors::green("Available tasks:"));
 
-  for name in tasks_config.keys() {
+  for (name, task) in &tasks_config {
     eprintln!("- {}", colors::cyan(name));
-    eprintln!("    {}", tasks_config[name])
+    if let Some(description) = &task.description {
+      eprintln!("    {}", colors::italic_gray(format!("// {description}")));
+    }
+    if let Some(command) = &task.command {
+      eprintln!("    {command}");
+    }
+    if !task.dependencies.is_empty() {
+      eprintln!(
+        "    {} {}",
+        colors::gray("depends on:"),
+        task.dependencies.join(", ")
+      );
+    }
   }
 }
 
 pub async fn execute_script(
-  flags: Flags,
+  cli_options: CliOptions,
   task_flags: TaskFlags,
 ) -> Result<i32, AnyError> {
-  let ps = ProcState::build(flags).await?;
+  let ps = ProcState::from_options(Arc::new(cli_options)).await?;
   let tasks_config = ps.options.resolve_tasks_config()?;
   let config_file_url = ps.options.maybe_config_file_specifier().unwrap();
   let config_file_path = if config_file_url.scheme() == "file" {
@@ -39,50 +66,603 @@ pub async fn execute_script(
     return Ok(1);
   }
 
+  let config_dir = config_file_path.parent().unwrap();
   let cwd = match task_flags.cwd {
     Some(path) => canonicalize_path(&PathBuf::from(path))?,
-    None => config_file_path.parent().unwrap().to_owned(),
+    None => config_dir.to_owned(),
   };
   let task_name = task_flags.task;
-  let maybe_script = tasks_config.get(&task_name);
+  if !tasks_config.contains_key(&task_name) {
+    eprintln!("Task not found: {task_name}");
+    print_available_tasks(tasks_config);
+    return Ok(1);
+  }
+  let task_names = resolve_task_order(&tasks_config, &task_name)?;
 
-  if let Some(script) = maybe_script {
-    let additional_args = ps
-      .options
-      .argv()
-      .iter()
-      // surround all the additional arguments in double quotes
-      // and santize any command substition
-      .map(|a| format!("\"{}\"", a.replace('"', "\\\"").replace('$', "\\$")))
-      .collect::<Vec<_>>()
-      .join(" ");
-    let script = format!("{script} {additional_args}");
-    let script = script.trim();
-    log::info!(
-      "{} {} {}",
-      colors::green("Task"),
-      colors::cyan(&task_name),
-      script,
+  let additional_args = ps
+    .options
+    .argv()
+    .iter()
+    // surround all the additional arguments in double quotes
+    // and santize any command substition
+    .map(|a| format!("\"{}\"", a.replace('"', "\\\"").replace('$', "\\$")))
+    .collect::<Vec<_>>()
+    .join(" ");
+
+  // get the starting env vars (the PWD env var will be set by deno_task_shell)
+  let mut env_vars = std::env::vars().collect::<HashMap<String, String>>();
+  const INIT_CWD_NAME: &str = "INIT_CWD";
+  if !env_vars.contains_key(INIT_CWD_NAME) {
+    if let Ok(cwd) = std::env::current_dir() {
+      // if not set, set an INIT_CWD env var that has the cwd
+      env_vars
+        .insert(INIT_CWD_NAME.to_string(), cwd.to_string_lossy().to_string());
+    }
+  }
+
+  let cache_keys = task_names
+    .iter()
+    .filter(|name| !tasks_config[**name].inputs.is_empty())
+    .map(|name| task_cache_key(&config_file_path, name))
+    .collect::<Vec<_>>();
+  let context = TaskContext {
+    env_vars,
+    cwd,
+    config_file_path: &config_file_path,
+    config_dir,
+    // the output of the tasks is only prefixed when more than one may run
+    // at the same time
+    prefix_output: task_names.len() > 1,
+    cache: (!cache_keys.is_empty())
+      .then(|| TaskCache::new(&ps.dir, &cache_keys)),
+  };
+
+  // start each task once the tasks it depends on have completed, and stop
+  // starting tasks once one of them fails
+  let mut pending = task_names;
+  let mut completed = HashSet::new();
+  let mut running = FuturesUnordered::new();
+  let mut exit_code = 0;
+  loop {
+    if exit_code == 0 {
+      let (ready, not_ready): (Vec<&str>, Vec<&str>) =
+        std::mem::take(&mut pending).into_iter().partition(|name| {
+          tasks_config[*name]
+            .dependencies
+            .iter()
+            .all(|dependency| completed.contains(dependency.as_str()))
+        });
+      pending = not_ready;
+      for name in ready {
+        let args = if name == task_name {
+          additional_args.as_str()
+        } else {
+          ""
+        };
+        let context = &context;
+        let task = &tasks_config[name];
+        running.push(
+          async move { (name, run_task(context, name, task, args).await) }
+            .boxed_local(),
+        );
+      }
+    }
+
+    match running.next().await {
+      Some((name, result)) => {
+        let task_exit_code = result?;
+        if task_exit_code != 0 && exit_code == 0 {
+          exit_code = task_exit_code;
+        }
+        completed.insert(name);
+      }
+      None => break,
+    }
+  }
+  if let Some(cache) = &context.cache {
+    cache.incremental_cache.wait_completion().await;
+  }
+  Ok(exit_code)
+}
+
+/// The state shared by the tasks run by `deno task`.
+struct TaskContext<'a> {
+  env_vars: HashMap<String, String>,
+  /// The directory tasks without a `cwd` run in.
+  cwd: PathBuf,
+  config_file_path: &'a Path,
+  config_dir: &'a Path,
+  prefix_output: bool,
+  /// The cache of the tasks with inputs, if any will run.
+  cache: Option<TaskCache>,
+}
+
+/// Returns the name of the task and of all the tasks it transitively depends
+/// on, ordered so that each task comes after its dependencies.
+fn resolve_task_order<'a>(
+  tasks_config: &'a BTreeMap<String, TaskConfig>,
+  task_name: &'a str,
+) -> Result<Vec<&'a str>, AnyError> {
+  fn visit<'a>(
+    tasks_config: &'a BTreeMap<String, TaskConfig>,
+    name: &'a str,
+    path: &mut Vec<&'a str>,
+    order: &mut Vec<&'a str>,
+  ) -> Result<(), AnyError> {
+    if order.contains(&name) {
+      return Ok(());
+    }
+    if let Some(index) = path.iter().position(|n| *n == name) {
+      let mut cycle = path[index..].to_vec();
+      cycle.push(name);
+      bail!("Task cycle detected: {}", cycle.join(" -> "));
+    }
+    let task = match tasks_config.get(name) {
+      Some(task) => task,
+      None => bail!(
+        "Task \"{}\" depends on unknown task \"{}\"",
+        path.last().unwrap_or(&name),
+        name
+      ),
+    };
+    path.push(name);
+    for dependency in &task.dependencies {
+      visit(tasks_config, dependency, path, order)?;
+    }
+    path.pop();
+    order.push(name);
+    Ok(())
+  }
+
+  let mut order = Vec::new();
+  visit(tasks_config, task_name, &mut Vec::new(), &mut order)?;
+  Ok(order)
+}
+
+async fn run_task(
+  context: &TaskContext<'_>,
+  name: &str,
+  task: &TaskConfig,
+  additional_args: &str,
+) -> Result<i32, AnyError> {
+  let command = match &task.command {
+    Some(command) => command,
+    // the task only groups its dependencies
+    None => return Ok(0),
+  };
+  let script = format!("{command} {additional_args}");
+  let script = script.trim();
+  let cwd = match &task.cwd {
+    Some(cwd) => canonicalize_path(&context.config_dir.join(cwd))
+      .with_context(|| format!("Invalid cwd of task '{name}'."))?,
+    None => context.cwd.clone(),
+  };
+
+  let maybe_cache_entry = match &context.cache {
+    Some(cache) if !task.inputs.is_empty() => {
+      let key = task_cache_key(context.config_file_path, name);
+      let fingerprint =
+        task_fingerprint(context.config_dir, task, script, &cwd)?;
+      if cache.restore(&key, &fingerprint, context.config_dir, &task.outputs)? {
+        log::info!(
+          "{} {} {}",
+          colors::green("Task"),
+          colors::cyan(name),
+          colors::gray("(cached)")
+        );
+        return Ok(0);
+      }
+      Some((cache, key, fingerprint))
+    }
+    _ => None,
+  };
+
+  log::info!(
+    "{} {} {}",
+    colors::green("Task"),
+    colors::cyan(name),
+    script
+  );
+  let seq_list = deno_task_shell::parser::parse(script)
+    .with_context(|| format!("Error parsing script '{name}'."))?;
+  let mut env_vars = context.env_vars.clone();
+  env_vars.extend(task.env.clone());
+
+  let exit_code = if context.prefix_output {
+    execute_prefixed(name, seq_list, env_vars, &cwd).await?
+  } else {
+    deno_task_shell::execute(seq_list, env_vars, &cwd).await
+  };
+
+  if exit_code == 0 {
+    if let Some((cache, key, fingerprint)) = maybe_cache_entry {
+      if let Err(err) =
+        cache.save(&key, &fingerprint, context.config_dir, &task.outputs)
+      {
+        log::warn!(
+          "{} Failed caching task '{}': {:#}",
+          colors::yellow("Warning"),
+          name,
+          err
+        );
+      }
+    }
+  }
+  Ok(exit_code)
+}
+
+/// Executes the script with its output prefixed with the name of the task.
+async fn execute_prefixed(
+  name: &str,
+  seq_list: SequentialList,
+  env_vars: HashMap<String, String>,
+  cwd: &Path,
+) -> Result<i32, AnyError> {
+  let prefix = format!("{} ", colors::cyan(format!("[{name}]")));
+  let (stdout_reader, stdout_writer) = deno_task_shell::pipe();
+  let (stderr_reader, stderr_writer) = deno_task_shell::pipe();
+  let stdout_handle = pipe_prefixed(stdout_reader, &prefix, std::io::stdout());
+  let stderr_handle = pipe_prefixed(stderr_reader, &prefix, std::io::stderr());
+  let exit_code = deno_task_shell::execute_with_pipes(
+    seq_list,
+    ShellState::new(env_vars, cwd),
+    ShellPipeReader::stdin(),
+    stdout_writer,
+    stderr_writer,
+  )
+  .await;
+  stdout_handle.await??;
+  stderr_handle.await??;
+  Ok(exit_code)
+}
+
+/// Skips tasks whose inputs haven't changed since they last succeeded and
+/// restores their outputs from the copies made when they ran.
+struct TaskCache {
+  incremental_cache: IncrementalCache,
+  outputs_path: PathBuf,
+}
+
+impl TaskCache {
+  fn new(deno_dir: &DenoDir, keys: &[PathBuf]) -> Self {
+    Self {
+      incremental_cache: IncrementalCache::new(
+        &deno_dir.task_incremental_cache_db_file_path(),
+        &(),
+        keys,
+      ),
+      outputs_path: deno_dir.task_outputs_path(),
+    }
+  }
+
+  /// Restores the outputs of the task if it last succeeded with the same
+  /// fingerprint. Returns whether the task can be skipped.
+  fn restore(
+    &self,
+    key: &Path,
+    fingerprint: &str,
+    config_dir: &Path,
+    outputs: &[String],
+  ) -> Result<bool, AnyError> {
+    if !self.incremental_cache.is_file_same(key, fingerprint) {
+      return Ok(false);
+    }
+    let copies_dir = self.outputs_dir(key).join(fingerprint);
+    if !copies_dir.is_dir() {
+      return Ok(false);
+    }
+    for (index, output) in outputs.iter().enumerate() {
+      copy_path(
+        &copies_dir.join(index.to_string()),
+        &config_dir.join(output),
+      )?;
+    }
+    Ok(true)
+  }
+
+  /// Copies the outputs of the task, replacing the copies of its previous
+  /// run, and records the fingerprint of its inputs.
+  fn save(
+    &self,
+    key: &Path,
+    fingerprint: &str,
+    config_dir: &Path,
+    outputs: &[String],
+  ) -> Result<(), AnyError> {
+    let outputs_dir = self.outputs_dir(key);
+    if outputs_dir.exists() {
+      std::fs::remove_dir_all(&outputs_dir)?;
+    }
+    let copies_dir = outputs_dir.join(fingerprint);
+    std::fs::create_dir_all(&copies_dir)?;
+    for (index, output) in outputs.iter().enumerate() {
+      let path = config_dir.join(output);
+      if !path.exists() {
+        bail!("the output \"{}\" was not created", output);
+      }
+      copy_path(&path, &copies_dir.join(index.to_string()))?;
+    }
+    self.incremental_cache.update_file(key, fingerprint);
+    Ok(())
+  }
+
+  fn outputs_dir(&self, key: &Path) -> PathBuf {
+    self
+      .outputs_path
+      .join(checksum::gen(&[key.to_string_lossy().as_bytes()]))
+  }
+}
+
+/// The key of a task in the incremental cache, which is the path of the
+/// configuration file joined with the name of the task.
+fn task_cache_key(config_file_path: &Path, name: &str) -> PathBuf {
+  config_file_path.join(name)
+}
+
+/// Hashes the script, working directory and environment variables of the task
+/// along with the paths and contents of its input files.
+fn task_fingerprint(
+  config_dir: &Path,
+  task: &TaskConfig,
+  script: &str,
+  cwd: &Path,
+) -> Result<String, AnyError> {
+  let mut parts = Vec::new();
+  // prefix each part with its length so that moving bytes from one part to
+  // the next changes the fingerprint
+  let mut push_part = |part: &[u8]| {
+    parts.push(part.len().to_le_bytes().to_vec());
+    parts.push(part.to_vec());
+  };
+  push_part(script.as_bytes());
+  push_part(cwd.to_string_lossy().as_bytes());
+  for (key, value) in &task.env {
+    push_part(key.as_bytes());
+    push_part(value.as_bytes());
+  }
+  for path in collect_input_files(config_dir, &task.inputs)? {
+    let relative_path = path.strip_prefix(config_dir).unwrap_or(&path);
+    push_part(relative_path.to_string_lossy().as_bytes());
+    push_part(
+      &std::fs::read(&path)
+        .with_context(|| format!("Reading {}", path.display()))?,
+    );
+  }
+  Ok(checksum::gen(&parts))
+}
+
+/// Expands the input globs of a task relative to `config_dir`.
+fn collect_input_files(
+  config_dir: &Path,
+  patterns: &[String],
+) -> Result<Vec<PathBuf>, AnyError> {
+  let mut paths = Vec::new();
+  for pattern in patterns {
+    let full_pattern = format!(
+      "{}/{}",
+      glob::Pattern::escape(&config_dir.to_string_lossy()),
+      pattern
     );
-    let seq_list = deno_task_shell::parser::parse(script)
-      .with_context(|| format!("Error parsing script '{task_name}'."))?;
-
-    // get the starting env vars (the PWD env var will be set by deno_task_shell)
-    let mut env_vars = std::env::vars().collect::<HashMap<String, String>>();
-    const INIT_CWD_NAME: &str = "INIT_CWD";
-    if !env_vars.contains_key(INIT_CWD_NAME) {
-      if let Ok(cwd) = std::env::current_dir() {
-        // if not set, set an INIT_CWD env var that has the cwd
-        env_vars
-          .insert(INIT_CWD_NAME.to_string(), cwd.to_string_lossy().to_string());
+    let matches = glob::glob(&full_pattern)
+      .with_context(|| format!("Invalid task input pattern \"{pattern}\""))?;
+    for entry in matches {
+      let path = entry?;
+      if path.is_file() {
+        paths.push(path);
       }
     }
+  }
+  paths.sort();
+  paths.dedup();
+  Ok(paths)
+}
 
-    let exit_code = deno_task_shell::execute(seq_list, env_vars, &cwd).await;
-    Ok(exit_code)
+/// Copies a file or a directory, replacing what is at `to`.
+fn copy_path(from: &Path, to: &Path) -> Result<(), AnyError> {
+  if to.is_dir() {
+    std::fs::remove_dir_all(to)?;
+  } else if to.exists() {
+    std::fs::remove_file(to)?;
+  }
+  if from.is_dir() {
+    copy_dir_recursive(from, to)
   } else {
-    eprintln!("Task not found: {task_name}");
-    print_available_tasks(tasks_config);
-    Ok(1)
+    if let Some(parent) = to.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+    std::fs::copy(from, to).with_context(|| {
+      format!("Copying {} to {}", from.display(), to.display())
+    })?;
+    Ok(())
+  }
+}
+
+/// Copies the output of a task to `output` on a blocking thread, prefixing
+/// each line.
+fn pipe_prefixed<W: Write + Send + 'static>(
+  reader: ShellPipeReader,
+  prefix: &str,
+  output: W,
+) -> tokio::task::JoinHandle<Result<(), AnyError>> {
+  let mut writer = PrefixedWriter::new(prefix.to_string(), output);
+  tokio::task::spawn_blocking(move || {
+    reader.pipe_to(&mut writer)?;
+    writer.finish()?;
+    Ok(())
+  })
+}
+
+/// Writes whole lines prefixed with the name of a task, so that the output of
+/// tasks running in parallel is interleaved line by line.
+struct PrefixedWriter<W: Write> {
+  prefix: String,
+  inner: W,
+  line: Vec<u8>,
+}
+
+impl<W: Write> PrefixedWriter<W> {
+  fn new(prefix: String, inner: W) -> Self {
+    Self {
+      prefix,
+      inner,
+      line: Vec::new(),
+    }
+  }
+
+  fn write_line(&mut self) -> std::io::Result<()> {
+    // write the prefix and the line at once so that they aren't separated by
+    // the output of another task
+    let mut output = self.prefix.as_bytes().to_vec();
+    output.append(&mut self.line);
+    self.inner.write_all(&output)?;
+    self.inner.flush()
+  }
+
+  /// Writes the last line when the output doesn't end with a newline.
+  fn finish(mut self) -> std::io::Result<()> {
+    if !self.line.is_empty() {
+      self.line.push(b'\n');
+      self.write_line()?;
+    }
+    Ok(())
+  }
+}
+
+impl<W: Write> Write for PrefixedWriter<W> {
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    for byte in buf {
+      self.line.push(*byte);
+      if *byte == b'\n' {
+        self.write_line()?;
+      }
+    }
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    self.inner.flush()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use test_util::TempDir;
+
+  fn tasks(tasks: &[(&str, &[&str])]) -> BTreeMap<String, TaskConfig> {
+    tasks
+      .iter()
+      .map(|(name, dependencies)| {
+        (
+          name.to_string(),
+          TaskConfig {
+            command: Some(format!("echo {name}")),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+            ..Default::default()
+          },
+        )
+      })
+      .collect()
+  }
+
+  #[test]
+  fn resolves_task_order() {
+    let tasks_config = tasks(&[
+      ("build", &["clean", "generate"]),
+      ("clean", &[]),
+      ("generate", &["clean"]),
+      ("test", &["build"]),
+      ("lint", &[]),
+    ]);
+    assert_eq!(
+      resolve_task_order(&tasks_config, "test").unwrap(),
+      vec!["clean", "generate", "build", "test"]
+    );
+    assert_eq!(
+      resolve_task_order(&tasks_config, "lint").unwrap(),
+      vec!["lint"]
+    );
+  }
+
+  #[test]
+  fn detects_task_cycles() {
+    let tasks_config =
+      tasks(&[("a", &["b"]), ("b", &["c"]), ("c", &["a"]), ("d", &["d"])]);
+    assert_eq!(
+      resolve_task_order(&tasks_config, "a")
+        .unwrap_err()
+        .to_string(),
+      "Task cycle detected: a -> b -> c -> a"
+    );
+    assert_eq!(
+      resolve_task_order(&tasks_config, "d")
+        .unwrap_err()
+        .to_string(),
+      "Task cycle detected: d -> d"
+    );
+  }
+
+  #[test]
+  fn prefixes_lines() {
+    let mut writer = PrefixedWriter::new("[a] ".to_string(), Vec::new());
+    writer.write_all(b"one\ntw").unwrap();
+    writer.write_all(b"o\nthree").unwrap();
+    assert_eq!(
+      String::from_utf8(writer.inner.clone()).unwrap(),
+      "[a] one\n[a] two\n"
+    );
+    let mut output = Vec::new();
+    let mut writer = PrefixedWriter::new("[a] ".to_string(), &mut output);
+    writer.write_all(b"one\ntwo").unwrap();
+    writer.finish().unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), "[a] one\n[a] two\n");
+  }
+
+  #[tokio::test]
+  async fn caches_task_outputs() {
+    let temp_dir = TempDir::new();
+    temp_dir.create_dir_all("project/dist");
+    temp_dir.write("project/input.txt", "a");
+    let config_dir = temp_dir.path().join("project");
+    let deno_dir =
+      DenoDir::new(Some(temp_dir.path().join("deno_dir"))).unwrap();
+    let task = TaskConfig {
+      command: Some("build".to_string()),
+      inputs: vec!["*.txt".to_string()],
+      outputs: vec!["dist".to_string()],
+      ..Default::default()
+    };
+    let key = task_cache_key(&config_dir.join("deno.json"), "build");
+    let fingerprint =
+      task_fingerprint(&config_dir, &task, "build", &config_dir).unwrap();
+    let cache = TaskCache::new(&deno_dir, &[key.clone()]);
+    assert!(!cache
+      .restore(&key, &fingerprint, &config_dir, &task.outputs)
+      .unwrap());
+    temp_dir.write("project/dist/output.txt", "output");
+    cache
+      .save(&key, &fingerprint, &config_dir, &task.outputs)
+      .unwrap();
+    cache.incremental_cache.wait_completion().await;
+
+    // the outputs are restored when the inputs haven't changed
+    std::fs::remove_dir_all(config_dir.join("dist")).unwrap();
+    let cache = TaskCache::new(&deno_dir, &[key.clone()]);
+    assert!(cache
+      .restore(&key, &fingerprint, &config_dir, &task.outputs)
+      .unwrap());
+    assert_eq!(temp_dir.read_to_string("project/dist/output.txt"), "output");
+
+    temp_dir.write("project/input.txt", "b");
+    let changed_fingerprint =
+      task_fingerprint(&config_dir, &task, "build", &config_dir).unwrap();
+    assert_ne!(changed_fingerprint, fingerprint);
+    assert!(!cache
+      .restore(&key, &changed_fingerprint, &config_dir, &task.outputs)
+      .unwrap());
+    let other_script_fingerprint =
+      task_fingerprint(&config_dir, &task, "build --minify", &config_dir)
+        .unwrap();
+    assert_ne!(other_script_fingerprint, changed_fingerprint);
   }
 }
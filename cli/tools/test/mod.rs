@@ -3,9 +3,8 @@
 use crate::args::CliOptions;
 use crate::args::FilesConfig;
 use crate::args::TestOptions;
+use crate::args::TestReporterConfig;
 use crate::args::TypeCheckMode;
-use crate::colors;
-use crate::display;
 use crate::file_fetcher::File;
 use crate::graph_util::graph_valid;
 use crate::ops;
@@ -29,7 +28,6 @@ use deno_core::futures::stream;
 use deno_core::futures::FutureExt;
 use deno_core::futures::StreamExt;
 use deno_core::parking_lot::Mutex;
-use deno_core::url::Url;
 use deno_core::ModuleSpecifier;
 use deno_runtime::fmt_errors::format_js_error;
 use deno_runtime::ops::io::Stdio;
@@ -45,9 +43,8 @@ use rand::SeedableRng;
 use regex::Regex;
 use serde::Deserialize;
 use std::cell::RefCell;
-use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::collections::HashSet;
-use std::fmt::Write as _;
 use std::io::Read;
 use std::io::Write;
 use std::num::NonZeroUsize;
@@ -55,11 +52,23 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
-use std::time::Duration;
 use std::time::Instant;
 use tokio::sync::mpsc::unbounded_channel;
 use tokio::sync::mpsc::UnboundedSender;
 
+mod reporters;
+mod shard;
+mod snapshot;
+
+use reporters::CompoundTestReporter;
+use reporters::DotTestReporter;
+use reporters::JunitTestReporter;
+use reporters::PrettyTestReporter;
+use reporters::TapTestReporter;
+use reporters::TestReporter;
+
+pub use snapshot::SnapshotFile;
+
 /// The test mode is used to determine how a specifier is to be tested.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum TestMode {
@@ -227,6 +236,11 @@ pub enum TestEvent {
   StepRegister(TestStepDescription),
   StepWait(usize),
   StepResult(usize, TestStepResult, u64),
+  /// A test failed and runs again. Holds the id of the test and the number of
+  /// the failed attempt.
+  Retry(usize, usize),
+  /// The changes to the snapshot file of a module with `--update-snapshots`.
+  SnapshotsUpdated(String),
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -243,6 +257,9 @@ pub struct TestSummary {
   pub measured: usize,
   pub failures: Vec<(TestDescription, Box<JsError>)>,
   pub uncaught_errors: Vec<(String, Box<JsError>)>,
+  /// The tests which passed after being retried, with the number of the
+  /// attempt which passed.
+  pub flaky: Vec<(TestDescription, usize)>,
 }
 
 #[derive(Debug, Clone)]
@@ -250,6 +267,9 @@ struct TestSpecifierOptions {
   concurrent_jobs: NonZeroUsize,
   fail_fast: Option<NonZeroUsize>,
   filter: TestFilter,
+  reporter: TestReporterConfig,
+  junit_path: Option<String>,
+  update_snapshots: bool,
 }
 
 impl TestSummary {
@@ -267,6 +287,7 @@ impl TestSummary {
       measured: 0,
       failures: Vec::new(),
       uncaught_errors: Vec::new(),
+      flaky: Vec::new(),
     }
   }
 
@@ -275,379 +296,6 @@ impl TestSummary {
   }
 }
 
-struct PrettyTestReporter {
-  parallel: bool,
-  echo_output: bool,
-  in_new_line: bool,
-  last_wait_id: Option<usize>,
-  cwd: Url,
-  did_have_user_output: bool,
-  started_tests: bool,
-}
-
-impl PrettyTestReporter {
-  fn new(parallel: bool, echo_output: bool) -> PrettyTestReporter {
-    PrettyTestReporter {
-      parallel,
-      echo_output,
-      in_new_line: true,
-      last_wait_id: None,
-      cwd: Url::from_directory_path(std::env::current_dir().unwrap()).unwrap(),
-      did_have_user_output: false,
-      started_tests: false,
-    }
-  }
-
-  fn force_report_wait(&mut self, description: &TestDescription) {
-    if !self.in_new_line {
-      println!();
-    }
-    if self.parallel {
-      print!(
-        "{}",
-        colors::gray(format!(
-          "{} => ",
-          self.to_relative_path_or_remote_url(&description.origin)
-        ))
-      );
-    }
-    print!("{} ...", description.name);
-    self.in_new_line = false;
-    // flush for faster feedback when line buffered
-    std::io::stdout().flush().unwrap();
-    self.last_wait_id = Some(description.id);
-  }
-
-  fn to_relative_path_or_remote_url(&self, path_or_url: &str) -> String {
-    let url = Url::parse(path_or_url).unwrap();
-    if url.scheme() == "file" {
-      if let Some(mut r) = self.cwd.make_relative(&url) {
-        if !r.starts_with("../") {
-          r = format!("./{r}");
-        }
-        return r;
-      }
-    }
-    path_or_url.to_string()
-  }
-
-  fn force_report_step_wait(&mut self, description: &TestStepDescription) {
-    self.write_output_end();
-    if !self.in_new_line {
-      println!();
-    }
-    print!("{}{} ...", "  ".repeat(description.level), description.name);
-    self.in_new_line = false;
-    // flush for faster feedback when line buffered
-    std::io::stdout().flush().unwrap();
-    self.last_wait_id = Some(description.id);
-  }
-
-  fn force_report_step_result(
-    &mut self,
-    description: &TestStepDescription,
-    result: &TestStepResult,
-    elapsed: u64,
-  ) {
-    let status = match result {
-      TestStepResult::Ok => colors::green("ok").to_string(),
-      TestStepResult::Ignored => colors::yellow("ignored").to_string(),
-      TestStepResult::Pending(_) => colors::gray("pending").to_string(),
-      TestStepResult::Failed(_) => colors::red("FAILED").to_string(),
-    };
-
-    self.write_output_end();
-    if self.in_new_line || self.last_wait_id != Some(description.id) {
-      self.force_report_step_wait(description);
-    }
-
-    println!(
-      " {} {}",
-      status,
-      colors::gray(format!("({})", display::human_elapsed(elapsed.into())))
-    );
-
-    if let Some(js_error) = result.error() {
-      let err_string = format_test_error(js_error);
-      let err_string = format!("{}: {}", colors::red_bold("error"), err_string);
-      for line in err_string.lines() {
-        println!("{}{}", "  ".repeat(description.level + 1), line);
-      }
-    }
-    self.in_new_line = true;
-  }
-
-  fn write_output_end(&mut self) {
-    if self.did_have_user_output {
-      println!("{}", colors::gray("----- output end -----"));
-      self.in_new_line = true;
-      self.did_have_user_output = false;
-    }
-  }
-
-  fn report_register(&mut self, _description: &TestDescription) {}
-
-  fn report_plan(&mut self, plan: &TestPlan) {
-    if self.parallel {
-      return;
-    }
-    let inflection = if plan.total == 1 { "test" } else { "tests" };
-    println!(
-      "{}",
-      colors::gray(format!(
-        "running {} {} from {}",
-        plan.total,
-        inflection,
-        self.to_relative_path_or_remote_url(&plan.origin)
-      ))
-    );
-    self.in_new_line = true;
-  }
-
-  fn report_wait(&mut self, description: &TestDescription) {
-    if !self.parallel {
-      self.force_report_wait(description);
-    }
-    self.started_tests = true;
-  }
-
-  fn report_output(&mut self, output: &[u8]) {
-    if !self.echo_output {
-      return;
-    }
-
-    if !self.did_have_user_output && self.started_tests {
-      self.did_have_user_output = true;
-      if !self.in_new_line {
-        println!();
-      }
-      println!("{}", colors::gray("------- output -------"));
-      self.in_new_line = true;
-    }
-
-    // output everything to stdout in order to prevent
-    // stdout and stderr racing
-    std::io::stdout().write_all(output).unwrap();
-  }
-
-  fn report_result(
-    &mut self,
-    description: &TestDescription,
-    result: &TestResult,
-    elapsed: u64,
-  ) {
-    if self.parallel {
-      self.force_report_wait(description);
-    }
-
-    self.write_output_end();
-    if self.in_new_line || self.last_wait_id != Some(description.id) {
-      self.force_report_wait(description);
-    }
-
-    let status = match result {
-      TestResult::Ok => colors::green("ok").to_string(),
-      TestResult::Ignored => colors::yellow("ignored").to_string(),
-      TestResult::Failed(_) => colors::red("FAILED").to_string(),
-      TestResult::Cancelled => colors::gray("cancelled").to_string(),
-    };
-
-    println!(
-      " {} {}",
-      status,
-      colors::gray(format!("({})", display::human_elapsed(elapsed.into())))
-    );
-    self.in_new_line = true;
-  }
-
-  fn report_uncaught_error(&mut self, origin: &str, _error: &JsError) {
-    if !self.in_new_line {
-      println!();
-    }
-    println!(
-      "Uncaught error from {} {}",
-      self.to_relative_path_or_remote_url(origin),
-      colors::red("FAILED")
-    );
-    self.in_new_line = true;
-    self.did_have_user_output = false;
-  }
-
-  fn report_step_register(&mut self, _description: &TestStepDescription) {}
-
-  fn report_step_wait(&mut self, description: &TestStepDescription) {
-    if !self.parallel {
-      self.force_report_step_wait(description);
-    }
-  }
-
-  fn report_step_result(
-    &mut self,
-    description: &TestStepDescription,
-    result: &TestStepResult,
-    elapsed: u64,
-    tests: &IndexMap<usize, TestDescription>,
-    test_steps: &IndexMap<usize, TestStepDescription>,
-  ) {
-    if self.parallel {
-      self.write_output_end();
-      let root;
-      let mut ancestor_names = vec![];
-      let mut current_desc = description;
-      loop {
-        if let Some(step_desc) = test_steps.get(&current_desc.parent_id) {
-          ancestor_names.push(&step_desc.name);
-          current_desc = step_desc;
-        } else {
-          root = tests.get(&current_desc.parent_id).unwrap();
-          break;
-        }
-      }
-      ancestor_names.reverse();
-      print!(
-        "{}",
-        colors::gray(format!(
-          "{} =>",
-          self.to_relative_path_or_remote_url(&description.origin)
-        ))
-      );
-      print!(" {} ...", root.name);
-      for name in ancestor_names {
-        print!(" {name} ...");
-      }
-      print!(" {} ...", description.name);
-      self.in_new_line = false;
-      self.last_wait_id = Some(description.id);
-    }
-    self.force_report_step_result(description, result, elapsed);
-  }
-
-  fn report_summary(&mut self, summary: &TestSummary, elapsed: &Duration) {
-    if !summary.failures.is_empty() || !summary.uncaught_errors.is_empty() {
-      #[allow(clippy::type_complexity)] // Type alias doesn't look better here
-      let mut failures_by_origin: BTreeMap<
-        String,
-        (Vec<(&TestDescription, &JsError)>, Option<&JsError>),
-      > = BTreeMap::default();
-      let mut failure_titles = vec![];
-      for (description, js_error) in &summary.failures {
-        let (failures, _) = failures_by_origin
-          .entry(description.origin.clone())
-          .or_default();
-        failures.push((description, js_error.as_ref()));
-      }
-      for (origin, js_error) in &summary.uncaught_errors {
-        let (_, uncaught_error) =
-          failures_by_origin.entry(origin.clone()).or_default();
-        let _ = uncaught_error.insert(js_error.as_ref());
-      }
-      println!("\n{}\n", colors::white_bold_on_red(" ERRORS "));
-      for (origin, (failures, uncaught_error)) in failures_by_origin {
-        for (description, js_error) in failures {
-          let failure_title = format!(
-            "{} {}",
-            &description.name,
-            colors::gray(format!(
-              "=> {}:{}:{}",
-              self.to_relative_path_or_remote_url(
-                &description.location.file_name
-              ),
-              description.location.line_number,
-              description.location.column_number
-            ))
-          );
-          println!("{}", &failure_title);
-          println!(
-            "{}: {}",
-            colors::red_bold("error"),
-            format_test_error(js_error)
-          );
-          println!();
-          failure_titles.push(failure_title);
-        }
-        if let Some(js_error) = uncaught_error {
-          let failure_title = format!(
-            "{} (uncaught error)",
-            self.to_relative_path_or_remote_url(&origin)
-          );
-          println!("{}", &failure_title);
-          println!(
-            "{}: {}",
-            colors::red_bold("error"),
-            format_test_error(js_error)
-          );
-          println!("This error was not caught from a test and caused the test runner to fail on the referenced module.");
-          println!("It most likely originated from a dangling promise, event/timeout handler or top-level code.");
-          println!();
-          failure_titles.push(failure_title);
-        }
-      }
-      println!("{}\n", colors::white_bold_on_red(" FAILURES "));
-      for failure_title in failure_titles {
-        println!("{failure_title}");
-      }
-    }
-
-    let status = if summary.has_failed() {
-      colors::red("FAILED").to_string()
-    } else {
-      colors::green("ok").to_string()
-    };
-
-    let get_steps_text = |count: usize| -> String {
-      if count == 0 {
-        String::new()
-      } else if count == 1 {
-        " (1 step)".to_string()
-      } else {
-        format!(" ({count} steps)")
-      }
-    };
-
-    let mut summary_result = String::new();
-
-    write!(
-      summary_result,
-      "{} passed{} | {} failed{}",
-      summary.passed,
-      get_steps_text(summary.passed_steps),
-      summary.failed,
-      get_steps_text(summary.failed_steps + summary.pending_steps),
-    )
-    .unwrap();
-
-    let ignored_steps = get_steps_text(summary.ignored_steps);
-    if summary.ignored > 0 || !ignored_steps.is_empty() {
-      write!(
-        summary_result,
-        " | {} ignored{}",
-        summary.ignored, ignored_steps
-      )
-      .unwrap()
-    }
-
-    if summary.measured > 0 {
-      write!(summary_result, " | {} measured", summary.measured,).unwrap();
-    }
-
-    if summary.filtered_out > 0 {
-      write!(summary_result, " | {} filtered out", summary.filtered_out)
-        .unwrap()
-    };
-
-    println!(
-      "\n{} | {} {}\n",
-      status,
-      summary_result,
-      colors::gray(format!(
-        "({})",
-        display::human_elapsed(elapsed.as_millis())
-      )),
-    );
-    self.in_new_line = true;
-  }
-}
-
 fn abbreviate_test_error(js_error: &JsError) -> JsError {
   let mut js_error = js_error.clone();
   let frames = std::mem::take(&mut js_error.frames);
@@ -730,6 +378,7 @@ async fn test_specifier(
       sender,
       fail_fast_tracker,
       options.filter,
+      options.update_snapshots,
     )],
     Stdio {
       stdin: StdioPipe::Inherit,
@@ -985,6 +634,32 @@ pub async fn check_specifiers(
   Ok(())
 }
 
+fn get_test_reporter(
+  options: &TestSpecifierOptions,
+  log_level: Option<Level>,
+) -> Box<dyn TestReporter + Send> {
+  let parallel = options.concurrent_jobs.get() > 1;
+  let reporter: Box<dyn TestReporter + Send> = match &options.reporter {
+    TestReporterConfig::Pretty => Box::new(PrettyTestReporter::new(
+      parallel,
+      log_level != Some(Level::Error),
+    )),
+    TestReporterConfig::Dot => Box::new(DotTestReporter::new()),
+    TestReporterConfig::Junit => {
+      Box::new(JunitTestReporter::new("-".to_string()))
+    }
+    TestReporterConfig::Tap => Box::new(TapTestReporter::new()),
+  };
+
+  if let Some(junit_path) = &options.junit_path {
+    let junit: Box<dyn TestReporter + Send> =
+      Box::new(JunitTestReporter::new(junit_path.clone()));
+    return Box::new(CompoundTestReporter::new(vec![reporter, junit]));
+  }
+
+  reporter
+}
+
 /// Test a collection of specifiers with test modes concurrently.
 async fn test_specifiers(
   ps: &ProcState,
@@ -1006,6 +681,7 @@ async fn test_specifiers(
   let (sender, mut receiver) = unbounded_channel::<TestEvent>();
   let sender = TestEventSender::new(sender);
   let concurrent_jobs = options.concurrent_jobs;
+  let mut reporter = get_test_reporter(&options, log_level);
 
   let join_handles =
     specifiers_with_mode
@@ -1050,11 +726,6 @@ async fn test_specifiers(
     .buffer_unordered(concurrent_jobs.get())
     .collect::<Vec<Result<Result<(), AnyError>, tokio::task::JoinError>>>();
 
-  let mut reporter = Box::new(PrettyTestReporter::new(
-    concurrent_jobs.get() > 1,
-    log_level != Some(Level::Error),
-  ));
-
   let handler = {
     tokio::task::spawn(async move {
       let earlier = Instant::now();
@@ -1063,6 +734,8 @@ async fn test_specifiers(
       let mut tests_with_result = HashSet::new();
       let mut summary = TestSummary::new();
       let mut used_only = false;
+      let mut snapshot_changes = Vec::new();
+      let mut failed_attempts = HashMap::new();
 
       while let Some(event) = receiver.recv().await {
         match event {
@@ -1096,6 +769,9 @@ async fn test_specifiers(
               match &result {
                 TestResult::Ok => {
                   summary.passed += 1;
+                  if let Some(attempts) = failed_attempts.remove(&id) {
+                    summary.flaky.push((description.clone(), attempts + 1));
+                  }
                 }
                 TestResult::Ignored => {
                   summary.ignored += 1;
@@ -1157,11 +833,23 @@ async fn test_specifiers(
               &test_steps,
             );
           }
+
+          TestEvent::Retry(id, attempt) => {
+            failed_attempts.insert(id, attempt);
+          }
+
+          TestEvent::SnapshotsUpdated(changes) => {
+            snapshot_changes.push(changes);
+          }
         }
       }
 
       let elapsed = Instant::now().duration_since(earlier);
       reporter.report_summary(&summary, &elapsed);
+      for changes in snapshot_changes {
+        log::info!("{}", changes.trim_end());
+      }
+      reporter.flush_report(&elapsed, &tests, &test_steps)?;
 
       if used_only {
         return Err(generic_error(
@@ -1314,12 +1002,36 @@ pub async fn run_tests(
     &test_options.files,
     &test_options.doc,
   )
-  .await?;
+  .await?
+  .into_iter()
+  .filter(|(specifier, _)| test_options.matches_workspace_member(specifier))
+  .collect::<Vec<_>>();
 
   if !test_options.allow_none && specifiers_with_mode.is_empty() {
     return Err(generic_error("No test modules found"));
   }
 
+  let specifiers_with_mode = match test_options.shard {
+    Some(test_shard) => {
+      let maybe_timings = match &test_options.shard_timings {
+        Some(path) => {
+          let cwd = std::env::current_dir()?;
+          Some(shard::read_timings(
+            &cwd.join(path),
+            &ModuleSpecifier::from_directory_path(&cwd).unwrap(),
+          )?)
+        }
+        None => None,
+      };
+      shard::select_shard(
+        specifiers_with_mode,
+        test_shard,
+        maybe_timings.as_ref(),
+      )
+    }
+    None => specifiers_with_mode,
+  };
+
   check_specifiers(&ps, permissions.clone(), specifiers_with_mode.clone())
     .await?;
 
@@ -1335,6 +1047,9 @@ pub async fn run_tests(
       concurrent_jobs: test_options.concurrent_jobs,
       fail_fast: test_options.fail_fast,
       filter: TestFilter::from_flag(&test_options.filter),
+      reporter: test_options.reporter,
+      junit_path: test_options.junit_path,
+      update_snapshots: test_options.update_snapshots,
     },
   )
   .await?;
@@ -1480,7 +1195,10 @@ pub async fn run_tests_with_watch(
       )
       .await?
       .into_iter()
-      .filter(|(specifier, _)| modules_to_reload.contains(specifier))
+      .filter(|(specifier, _)| {
+        modules_to_reload.contains(specifier)
+          && test_options.matches_workspace_member(specifier)
+      })
       .collect::<Vec<(ModuleSpecifier, TestMode)>>();
 
       check_specifiers(&ps, permissions.clone(), specifiers_with_mode.clone())
@@ -1498,6 +1216,9 @@ pub async fn run_tests_with_watch(
           concurrent_jobs: test_options.concurrent_jobs,
           fail_fast: test_options.fail_fast,
           filter: TestFilter::from_flag(&test_options.filter),
+          reporter: test_options.reporter,
+          junit_path: test_options.junit_path.clone(),
+          update_snapshots: test_options.update_snapshots,
         },
       )
       .await?;
@@ -0,0 +1,204 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use crate::colors;
+use crate::display;
+use crate::tools::test::format_test_error;
+use crate::tools::test::TestDescription;
+use crate::tools::test::TestStepDescription;
+use crate::tools::test::TestSummary;
+
+use deno_core::error::JsError;
+use deno_core::url::Url;
+use indexmap::IndexMap;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::time::Duration;
+
+static ANSI_ESCAPE_RE: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"\x1b\[[0-9;]*m").unwrap());
+
+/// Removes the color codes added by `format_test_error` so that errors can be
+/// embedded in machine readable reports.
+pub fn strip_ansi_codes(text: &str) -> std::borrow::Cow<str> {
+  ANSI_ESCAPE_RE.replace_all(text, "")
+}
+
+pub fn to_relative_path_or_remote_url(cwd: &Url, path_or_url: &str) -> String {
+  let url = Url::parse(path_or_url).unwrap();
+  if url.scheme() == "file" {
+    if let Some(mut r) = cwd.make_relative(&url) {
+      if !r.starts_with("../") {
+        r = format!("./{r}");
+      }
+      return r;
+    }
+  }
+  path_or_url.to_string()
+}
+
+/// Returns the root test and the names of all the ancestor steps of the
+/// given step, ordered from the outermost to the innermost.
+pub fn test_step_ancestry<'a>(
+  description: &'a TestStepDescription,
+  tests: &'a IndexMap<usize, TestDescription>,
+  test_steps: &'a IndexMap<usize, TestStepDescription>,
+) -> (&'a TestDescription, Vec<&'a str>) {
+  let root;
+  let mut ancestor_names = vec![];
+  let mut current_desc = description;
+  loop {
+    if let Some(step_desc) = test_steps.get(&current_desc.parent_id) {
+      ancestor_names.push(step_desc.name.as_str());
+      current_desc = step_desc;
+    } else {
+      root = tests.get(&current_desc.parent_id).unwrap();
+      break;
+    }
+  }
+  ancestor_names.reverse();
+  (root, ancestor_names)
+}
+
+pub fn report_summary(cwd: &Url, summary: &TestSummary, elapsed: &Duration) {
+  if !summary.failures.is_empty() || !summary.uncaught_errors.is_empty() {
+    #[allow(clippy::type_complexity)] // Type alias doesn't look better here
+    let mut failures_by_origin: BTreeMap<
+      String,
+      (Vec<(&TestDescription, &JsError)>, Option<&JsError>),
+    > = BTreeMap::default();
+    let mut failure_titles = vec![];
+    for (description, js_error) in &summary.failures {
+      let (failures, _) = failures_by_origin
+        .entry(description.origin.clone())
+        .or_default();
+      failures.push((description, js_error.as_ref()));
+    }
+    for (origin, js_error) in &summary.uncaught_errors {
+      let (_, uncaught_error) =
+        failures_by_origin.entry(origin.clone()).or_default();
+      let _ = uncaught_error.insert(js_error.as_ref());
+    }
+    println!("\n{}\n", colors::white_bold_on_red(" ERRORS "));
+    for (origin, (failures, uncaught_error)) in failures_by_origin {
+      for (description, js_error) in failures {
+        let failure_title = format!(
+          "{} {}",
+          &description.name,
+          colors::gray(format!(
+            "=> {}:{}:{}",
+            to_relative_path_or_remote_url(
+              cwd,
+              &description.location.file_name
+            ),
+            description.location.line_number,
+            description.location.column_number
+          ))
+        );
+        println!("{}", &failure_title);
+        println!(
+          "{}: {}",
+          colors::red_bold("error"),
+          format_test_error(js_error)
+        );
+        println!();
+        failure_titles.push(failure_title);
+      }
+      if let Some(js_error) = uncaught_error {
+        let failure_title = format!(
+          "{} (uncaught error)",
+          to_relative_path_or_remote_url(cwd, &origin)
+        );
+        println!("{}", &failure_title);
+        println!(
+          "{}: {}",
+          colors::red_bold("error"),
+          format_test_error(js_error)
+        );
+        println!("This error was not caught from a test and caused the test runner to fail on the referenced module.");
+        println!("It most likely originated from a dangling promise, event/timeout handler or top-level code.");
+        println!();
+        failure_titles.push(failure_title);
+      }
+    }
+    println!("{}\n", colors::white_bold_on_red(" FAILURES "));
+    for failure_title in failure_titles {
+      println!("{failure_title}");
+    }
+  }
+
+  if !summary.flaky.is_empty() {
+    println!("\n{}\n", colors::yellow("FLAKY"));
+    for (description, attempt) in &summary.flaky {
+      println!(
+        "{} {} {}",
+        &description.name,
+        colors::gray(format!(
+          "=> {}:{}:{}",
+          to_relative_path_or_remote_url(cwd, &description.location.file_name),
+          description.location.line_number,
+          description.location.column_number
+        )),
+        colors::yellow(format!("(passed on attempt {attempt})"))
+      );
+    }
+  }
+
+  let status = if summary.has_failed() {
+    colors::red("FAILED").to_string()
+  } else {
+    colors::green("ok").to_string()
+  };
+
+  let get_steps_text = |count: usize| -> String {
+    if count == 0 {
+      String::new()
+    } else if count == 1 {
+      " (1 step)".to_string()
+    } else {
+      format!(" ({count} steps)")
+    }
+  };
+
+  let mut summary_result = String::new();
+
+  write!(
+    summary_result,
+    "{} passed{} | {} failed{}",
+    summary.passed,
+    get_steps_text(summary.passed_steps),
+    summary.failed,
+    get_steps_text(summary.failed_steps + summary.pending_steps),
+  )
+  .unwrap();
+
+  let ignored_steps = get_steps_text(summary.ignored_steps);
+  if summary.ignored > 0 || !ignored_steps.is_empty() {
+    write!(
+      summary_result,
+      " | {} ignored{}",
+      summary.ignored, ignored_steps
+    )
+    .unwrap()
+  }
+
+  if !summary.flaky.is_empty() {
+    write!(summary_result, " | {} flaky", summary.flaky.len()).unwrap();
+  }
+
+  if summary.measured > 0 {
+    write!(summary_result, " | {} measured", summary.measured,).unwrap();
+  }
+
+  if summary.filtered_out > 0 {
+    write!(summary_result, " | {} filtered out", summary.filtered_out).unwrap()
+  };
+
+  println!(
+    "\n{} | {} {}\n",
+    status,
+    summary_result,
+    colors::gray(format!("({})", display::human_elapsed(elapsed.as_millis()))),
+  );
+}
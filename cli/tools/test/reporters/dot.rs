@@ -0,0 +1,116 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use super::common;
+use super::TestReporter;
+use crate::colors;
+use crate::tools::test::TestDescription;
+use crate::tools::test::TestPlan;
+use crate::tools::test::TestResult;
+use crate::tools::test::TestStepDescription;
+use crate::tools::test::TestStepResult;
+use crate::tools::test::TestSummary;
+
+use deno_core::error::AnyError;
+use deno_core::error::JsError;
+use deno_core::url::Url;
+use indexmap::IndexMap;
+use std::io::Write;
+use std::time::Duration;
+
+/// The number of result characters printed before wrapping to a new line.
+const LINE_WIDTH: usize = 80;
+
+/// Reports every test and step result as a single character. User output is
+/// not echoed; failures are listed in full in the summary.
+pub struct DotTestReporter {
+  cwd: Url,
+  column: usize,
+}
+
+impl DotTestReporter {
+  pub fn new() -> DotTestReporter {
+    DotTestReporter {
+      cwd: Url::from_directory_path(std::env::current_dir().unwrap()).unwrap(),
+      column: 0,
+    }
+  }
+
+  fn print_status(&mut self, status: String) {
+    if self.column == LINE_WIDTH {
+      println!();
+      self.column = 0;
+    }
+    print!("{status}");
+    self.column += 1;
+    // flush for faster feedback when line buffered
+    std::io::stdout().flush().unwrap();
+  }
+}
+
+impl TestReporter for DotTestReporter {
+  fn report_register(&mut self, _description: &TestDescription) {}
+
+  fn report_plan(&mut self, _plan: &TestPlan) {}
+
+  fn report_wait(&mut self, _description: &TestDescription) {}
+
+  fn report_output(&mut self, _output: &[u8]) {}
+
+  fn report_result(
+    &mut self,
+    _description: &TestDescription,
+    result: &TestResult,
+    _elapsed: u64,
+  ) {
+    let status = match result {
+      TestResult::Ok => colors::gray(".").to_string(),
+      TestResult::Ignored => colors::cyan(",").to_string(),
+      TestResult::Failed(_) => colors::red_bold("!").to_string(),
+      TestResult::Cancelled => colors::gray("!").to_string(),
+    };
+    self.print_status(status);
+  }
+
+  fn report_uncaught_error(&mut self, _origin: &str, _error: &JsError) {
+    self.print_status(colors::red_bold("E").to_string());
+  }
+
+  fn report_step_register(&mut self, _description: &TestStepDescription) {}
+
+  fn report_step_wait(&mut self, _description: &TestStepDescription) {}
+
+  fn report_step_result(
+    &mut self,
+    _description: &TestStepDescription,
+    result: &TestStepResult,
+    _elapsed: u64,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    let status = match result {
+      TestStepResult::Ok => colors::gray(".").to_string(),
+      TestStepResult::Ignored => colors::cyan(",").to_string(),
+      TestStepResult::Failed(_) | TestStepResult::Pending(_) => {
+        colors::red_bold("!").to_string()
+      }
+    };
+    self.print_status(status);
+  }
+
+  fn report_summary(&mut self, summary: &TestSummary, elapsed: &Duration) {
+    if self.column > 0 {
+      println!();
+      self.column = 0;
+    }
+    common::report_summary(&self.cwd, summary, elapsed);
+  }
+
+  fn flush_report(
+    &mut self,
+    _elapsed: &Duration,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) -> Result<(), AnyError> {
+    Ok(())
+  }
+}
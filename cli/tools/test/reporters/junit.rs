@@ -0,0 +1,338 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use super::common;
+use super::TestReporter;
+use crate::tools::test::format_test_error;
+use crate::tools::test::TestDescription;
+use crate::tools::test::TestLocation;
+use crate::tools::test::TestPlan;
+use crate::tools::test::TestResult;
+use crate::tools::test::TestStepDescription;
+use crate::tools::test::TestStepResult;
+use crate::tools::test::TestSummary;
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::error::JsError;
+use deno_core::url::Url;
+use indexmap::IndexMap;
+use std::fmt::Write as _;
+use std::io::Write;
+use std::time::Duration;
+
+enum JunitOutcome {
+  Passed,
+  Skipped,
+  Failure { message: String, details: String },
+  Error { message: String, details: String },
+}
+
+struct JunitTestCase {
+  name: String,
+  location: Option<TestLocation>,
+  elapsed: u64,
+  outcome: JunitOutcome,
+}
+
+/// Collects test and step results and writes them as a single JUnit XML
+/// document once the run finished. Each test module becomes a `<testsuite>`
+/// and nested steps are flattened into test cases named after their
+/// ancestry (`test > step > sub step`).
+pub struct JunitTestReporter {
+  /// Path of the report, or `-` for stdout.
+  path: String,
+  cwd: Url,
+  suites: IndexMap<String, Vec<JunitTestCase>>,
+}
+
+impl JunitTestReporter {
+  pub fn new(path: String) -> JunitTestReporter {
+    JunitTestReporter {
+      path,
+      cwd: Url::from_directory_path(std::env::current_dir().unwrap()).unwrap(),
+      suites: IndexMap::new(),
+    }
+  }
+
+  fn add_case(&mut self, origin: &str, case: JunitTestCase) {
+    self
+      .suites
+      .entry(origin.to_string())
+      .or_default()
+      .push(case);
+  }
+
+  fn render(&self, elapsed: &Duration) -> String {
+    let mut xml = String::new();
+    let all_cases = self.suites.values().flatten();
+    let (tests, failures, errors) =
+      all_cases.fold((0, 0, 0), |(tests, failures, errors), case| {
+        match case.outcome {
+          JunitOutcome::Failure { .. } => (tests + 1, failures + 1, errors),
+          JunitOutcome::Error { .. } => (tests + 1, failures, errors + 1),
+          _ => (tests + 1, failures, errors),
+        }
+      });
+    writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    writeln!(
+      xml,
+      r#"<testsuites name="deno test" tests="{}" failures="{}" errors="{}" time="{}">"#,
+      tests,
+      failures,
+      errors,
+      format_seconds(elapsed.as_millis() as u64),
+    )
+    .unwrap();
+    for (origin, cases) in &self.suites {
+      let suite_name =
+        common::to_relative_path_or_remote_url(&self.cwd, origin);
+      let mut failures = 0;
+      let mut errors = 0;
+      let mut skipped = 0;
+      let mut time = 0;
+      for case in cases {
+        time += case.elapsed;
+        match case.outcome {
+          JunitOutcome::Failure { .. } => failures += 1,
+          JunitOutcome::Error { .. } => errors += 1,
+          JunitOutcome::Skipped => skipped += 1,
+          JunitOutcome::Passed => {}
+        }
+      }
+      writeln!(
+        xml,
+        r#"  <testsuite name="{}" tests="{}" failures="{}" errors="{}" skipped="{}" time="{}">"#,
+        escape_xml(&suite_name),
+        cases.len(),
+        failures,
+        errors,
+        skipped,
+        format_seconds(time),
+      )
+      .unwrap();
+      for case in cases {
+        write!(
+          xml,
+          r#"    <testcase name="{}" classname="{}" time="{}""#,
+          escape_xml(&case.name),
+          escape_xml(&suite_name),
+          format_seconds(case.elapsed),
+        )
+        .unwrap();
+        if let Some(location) = &case.location {
+          write!(
+            xml,
+            r#" file="{}" line="{}" col="{}""#,
+            escape_xml(&common::to_relative_path_or_remote_url(
+              &self.cwd,
+              &location.file_name
+            )),
+            location.line_number,
+            location.column_number,
+          )
+          .unwrap();
+        }
+        match &case.outcome {
+          JunitOutcome::Passed => {
+            xml.push_str("/>\n");
+          }
+          JunitOutcome::Skipped => {
+            xml.push_str(">\n      <skipped/>\n    </testcase>\n");
+          }
+          JunitOutcome::Failure { message, details }
+          | JunitOutcome::Error { message, details } => {
+            let tag = if matches!(case.outcome, JunitOutcome::Failure { .. }) {
+              "failure"
+            } else {
+              "error"
+            };
+            writeln!(
+              xml,
+              ">\n      <{} message=\"{}\">{}</{}>\n    </testcase>",
+              tag,
+              escape_xml(message),
+              escape_xml(details),
+              tag,
+            )
+            .unwrap();
+          }
+        }
+      }
+      xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+  }
+}
+
+fn error_outcome_parts(js_error: &JsError) -> (String, String) {
+  let formatted = format_test_error(js_error);
+  let details = common::strip_ansi_codes(&formatted).to_string();
+  let message = details.lines().next().unwrap_or_default().to_string();
+  (message, details)
+}
+
+fn format_seconds(elapsed_ms: u64) -> String {
+  format!("{:.3}", elapsed_ms as f64 / 1000.0)
+}
+
+fn escape_xml(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&apos;"),
+      // control characters other than tab and new lines are not allowed in
+      // XML 1.0 documents, not even as character references
+      c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+      c => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+impl TestReporter for JunitTestReporter {
+  fn report_register(&mut self, _description: &TestDescription) {}
+
+  fn report_plan(&mut self, _plan: &TestPlan) {}
+
+  fn report_wait(&mut self, _description: &TestDescription) {}
+
+  fn report_output(&mut self, _output: &[u8]) {}
+
+  fn report_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    elapsed: u64,
+  ) {
+    let outcome = match result {
+      TestResult::Ok => JunitOutcome::Passed,
+      TestResult::Ignored => JunitOutcome::Skipped,
+      TestResult::Failed(js_error) => {
+        let (message, details) = error_outcome_parts(js_error);
+        JunitOutcome::Failure { message, details }
+      }
+      TestResult::Cancelled => JunitOutcome::Error {
+        message: "Test was cancelled".to_string(),
+        details:
+          "Test was cancelled because of an uncaught error in its module."
+            .to_string(),
+      },
+    };
+    self.add_case(
+      &description.origin,
+      JunitTestCase {
+        name: description.name.clone(),
+        location: Some(description.location.clone()),
+        elapsed,
+        outcome,
+      },
+    );
+  }
+
+  fn report_uncaught_error(&mut self, origin: &str, error: &JsError) {
+    let (message, details) = error_outcome_parts(error);
+    self.add_case(
+      origin,
+      JunitTestCase {
+        name: "(uncaught error)".to_string(),
+        location: None,
+        elapsed: 0,
+        outcome: JunitOutcome::Error { message, details },
+      },
+    );
+  }
+
+  fn report_step_register(&mut self, _description: &TestStepDescription) {}
+
+  fn report_step_wait(&mut self, _description: &TestStepDescription) {}
+
+  fn report_step_result(
+    &mut self,
+    description: &TestStepDescription,
+    result: &TestStepResult,
+    elapsed: u64,
+    tests: &IndexMap<usize, TestDescription>,
+    test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    let (root, ancestor_names) =
+      common::test_step_ancestry(description, tests, test_steps);
+    let mut name = root.name.clone();
+    for ancestor_name in ancestor_names {
+      write!(name, " > {ancestor_name}").unwrap();
+    }
+    write!(name, " > {}", description.name).unwrap();
+
+    let outcome = match result {
+      TestStepResult::Ok => JunitOutcome::Passed,
+      TestStepResult::Ignored => JunitOutcome::Skipped,
+      TestStepResult::Failed(maybe_error)
+      | TestStepResult::Pending(maybe_error) => {
+        let (message, details) = match maybe_error {
+          Some(js_error) => error_outcome_parts(js_error),
+          None => {
+            let message = if matches!(result, TestStepResult::Pending(_)) {
+              "Step did not complete"
+            } else {
+              "Step failed"
+            };
+            (message.to_string(), message.to_string())
+          }
+        };
+        JunitOutcome::Failure { message, details }
+      }
+    };
+    self.add_case(
+      &description.origin,
+      JunitTestCase {
+        name,
+        location: Some(description.location.clone()),
+        elapsed,
+        outcome,
+      },
+    );
+  }
+
+  fn report_summary(&mut self, _summary: &TestSummary, _elapsed: &Duration) {}
+
+  fn flush_report(
+    &mut self,
+    elapsed: &Duration,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) -> Result<(), AnyError> {
+    let xml = self.render(elapsed);
+    if self.path == "-" {
+      std::io::stdout().write_all(xml.as_bytes())?;
+    } else {
+      std::fs::write(&self.path, xml).with_context(|| {
+        format!("Failed writing JUnit report to '{}'", self.path)
+      })?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_escape_xml() {
+    assert_eq!(
+      escape_xml(r#"<a href="x">Tom & 'Jerry'</a>"#),
+      "&lt;a href=&quot;x&quot;&gt;Tom &amp; &apos;Jerry&apos;&lt;/a&gt;"
+    );
+    assert_eq!(escape_xml("tab\tline\nbell\u{7}"), "tab\tline\nbell");
+  }
+
+  #[test]
+  fn test_format_seconds() {
+    assert_eq!(format_seconds(0), "0.000");
+    assert_eq!(format_seconds(1234), "1.234");
+  }
+}
@@ -0,0 +1,59 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use crate::tools::test::TestDescription;
+use crate::tools::test::TestPlan;
+use crate::tools::test::TestResult;
+use crate::tools::test::TestStepDescription;
+use crate::tools::test::TestStepResult;
+use crate::tools::test::TestSummary;
+
+use deno_core::error::AnyError;
+use deno_core::error::JsError;
+use indexmap::IndexMap;
+use std::time::Duration;
+
+mod common;
+mod compound;
+mod dot;
+mod junit;
+mod pretty;
+mod tap;
+
+pub use compound::CompoundTestReporter;
+pub use dot::DotTestReporter;
+pub use junit::JunitTestReporter;
+pub use pretty::PrettyTestReporter;
+pub use tap::TapTestReporter;
+
+pub trait TestReporter {
+  fn report_register(&mut self, description: &TestDescription);
+  fn report_plan(&mut self, plan: &TestPlan);
+  fn report_wait(&mut self, description: &TestDescription);
+  fn report_output(&mut self, output: &[u8]);
+  fn report_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    elapsed: u64,
+  );
+  fn report_uncaught_error(&mut self, origin: &str, error: &JsError);
+  fn report_step_register(&mut self, description: &TestStepDescription);
+  fn report_step_wait(&mut self, description: &TestStepDescription);
+  fn report_step_result(
+    &mut self,
+    description: &TestStepDescription,
+    result: &TestStepResult,
+    elapsed: u64,
+    tests: &IndexMap<usize, TestDescription>,
+    test_steps: &IndexMap<usize, TestStepDescription>,
+  );
+  fn report_summary(&mut self, summary: &TestSummary, elapsed: &Duration);
+  /// Called once after all events were received. Reporters that buffer their
+  /// output (e.g. to write a single document) should write it out here.
+  fn flush_report(
+    &mut self,
+    elapsed: &Duration,
+    tests: &IndexMap<usize, TestDescription>,
+    test_steps: &IndexMap<usize, TestStepDescription>,
+  ) -> Result<(), AnyError>;
+}
@@ -0,0 +1,266 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use super::common;
+use super::TestReporter;
+use crate::colors;
+use crate::display;
+use crate::tools::test::format_test_error;
+use crate::tools::test::TestDescription;
+use crate::tools::test::TestPlan;
+use crate::tools::test::TestResult;
+use crate::tools::test::TestStepDescription;
+use crate::tools::test::TestStepResult;
+use crate::tools::test::TestSummary;
+
+use deno_core::error::AnyError;
+use deno_core::error::JsError;
+use deno_core::url::Url;
+use indexmap::IndexMap;
+use std::io::Write;
+use std::time::Duration;
+
+pub struct PrettyTestReporter {
+  parallel: bool,
+  echo_output: bool,
+  in_new_line: bool,
+  last_wait_id: Option<usize>,
+  cwd: Url,
+  did_have_user_output: bool,
+  started_tests: bool,
+}
+
+impl PrettyTestReporter {
+  pub fn new(parallel: bool, echo_output: bool) -> PrettyTestReporter {
+    PrettyTestReporter {
+      parallel,
+      echo_output,
+      in_new_line: true,
+      last_wait_id: None,
+      cwd: Url::from_directory_path(std::env::current_dir().unwrap()).unwrap(),
+      did_have_user_output: false,
+      started_tests: false,
+    }
+  }
+
+  fn force_report_wait(&mut self, description: &TestDescription) {
+    if !self.in_new_line {
+      println!();
+    }
+    if self.parallel {
+      print!(
+        "{}",
+        colors::gray(format!(
+          "{} => ",
+          common::to_relative_path_or_remote_url(
+            &self.cwd,
+            &description.origin
+          )
+        ))
+      );
+    }
+    print!("{} ...", description.name);
+    self.in_new_line = false;
+    // flush for faster feedback when line buffered
+    std::io::stdout().flush().unwrap();
+    self.last_wait_id = Some(description.id);
+  }
+
+  fn force_report_step_wait(&mut self, description: &TestStepDescription) {
+    self.write_output_end();
+    if !self.in_new_line {
+      println!();
+    }
+    print!("{}{} ...", "  ".repeat(description.level), description.name);
+    self.in_new_line = false;
+    // flush for faster feedback when line buffered
+    std::io::stdout().flush().unwrap();
+    self.last_wait_id = Some(description.id);
+  }
+
+  fn force_report_step_result(
+    &mut self,
+    description: &TestStepDescription,
+    result: &TestStepResult,
+    elapsed: u64,
+  ) {
+    let status = match result {
+      TestStepResult::Ok => colors::green("ok").to_string(),
+      TestStepResult::Ignored => colors::yellow("ignored").to_string(),
+      TestStepResult::Pending(_) => colors::gray("pending").to_string(),
+      TestStepResult::Failed(_) => colors::red("FAILED").to_string(),
+    };
+
+    self.write_output_end();
+    if self.in_new_line || self.last_wait_id != Some(description.id) {
+      self.force_report_step_wait(description);
+    }
+
+    println!(
+      " {} {}",
+      status,
+      colors::gray(format!("({})", display::human_elapsed(elapsed.into())))
+    );
+
+    if let Some(js_error) = result.error() {
+      let err_string = format_test_error(js_error);
+      let err_string = format!("{}: {}", colors::red_bold("error"), err_string);
+      for line in err_string.lines() {
+        println!("{}{}", "  ".repeat(description.level + 1), line);
+      }
+    }
+    self.in_new_line = true;
+  }
+
+  fn write_output_end(&mut self) {
+    if self.did_have_user_output {
+      println!("{}", colors::gray("----- output end -----"));
+      self.in_new_line = true;
+      self.did_have_user_output = false;
+    }
+  }
+}
+
+impl TestReporter for PrettyTestReporter {
+  fn report_register(&mut self, _description: &TestDescription) {}
+
+  fn report_plan(&mut self, plan: &TestPlan) {
+    if self.parallel {
+      return;
+    }
+    let inflection = if plan.total == 1 { "test" } else { "tests" };
+    println!(
+      "{}",
+      colors::gray(format!(
+        "running {} {} from {}",
+        plan.total,
+        inflection,
+        common::to_relative_path_or_remote_url(&self.cwd, &plan.origin)
+      ))
+    );
+    self.in_new_line = true;
+  }
+
+  fn report_wait(&mut self, description: &TestDescription) {
+    if !self.parallel {
+      self.force_report_wait(description);
+    }
+    self.started_tests = true;
+  }
+
+  fn report_output(&mut self, output: &[u8]) {
+    if !self.echo_output {
+      return;
+    }
+
+    if !self.did_have_user_output && self.started_tests {
+      self.did_have_user_output = true;
+      if !self.in_new_line {
+        println!();
+      }
+      println!("{}", colors::gray("------- output -------"));
+      self.in_new_line = true;
+    }
+
+    // output everything to stdout in order to prevent
+    // stdout and stderr racing
+    std::io::stdout().write_all(output).unwrap();
+  }
+
+  fn report_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    elapsed: u64,
+  ) {
+    if self.parallel {
+      self.force_report_wait(description);
+    }
+
+    self.write_output_end();
+    if self.in_new_line || self.last_wait_id != Some(description.id) {
+      self.force_report_wait(description);
+    }
+
+    let status = match result {
+      TestResult::Ok => colors::green("ok").to_string(),
+      TestResult::Ignored => colors::yellow("ignored").to_string(),
+      TestResult::Failed(_) => colors::red("FAILED").to_string(),
+      TestResult::Cancelled => colors::gray("cancelled").to_string(),
+    };
+
+    println!(
+      " {} {}",
+      status,
+      colors::gray(format!("({})", display::human_elapsed(elapsed.into())))
+    );
+    self.in_new_line = true;
+  }
+
+  fn report_uncaught_error(&mut self, origin: &str, _error: &JsError) {
+    if !self.in_new_line {
+      println!();
+    }
+    println!(
+      "Uncaught error from {} {}",
+      common::to_relative_path_or_remote_url(&self.cwd, origin),
+      colors::red("FAILED")
+    );
+    self.in_new_line = true;
+    self.did_have_user_output = false;
+  }
+
+  fn report_step_register(&mut self, _description: &TestStepDescription) {}
+
+  fn report_step_wait(&mut self, description: &TestStepDescription) {
+    if !self.parallel {
+      self.force_report_step_wait(description);
+    }
+  }
+
+  fn report_step_result(
+    &mut self,
+    description: &TestStepDescription,
+    result: &TestStepResult,
+    elapsed: u64,
+    tests: &IndexMap<usize, TestDescription>,
+    test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    if self.parallel {
+      self.write_output_end();
+      let (root, ancestor_names) =
+        common::test_step_ancestry(description, tests, test_steps);
+      print!(
+        "{}",
+        colors::gray(format!(
+          "{} =>",
+          common::to_relative_path_or_remote_url(
+            &self.cwd,
+            &description.origin
+          )
+        ))
+      );
+      print!(" {} ...", root.name);
+      for name in ancestor_names {
+        print!(" {name} ...");
+      }
+      print!(" {} ...", description.name);
+      self.in_new_line = false;
+      self.last_wait_id = Some(description.id);
+    }
+    self.force_report_step_result(description, result, elapsed);
+  }
+
+  fn report_summary(&mut self, summary: &TestSummary, elapsed: &Duration) {
+    common::report_summary(&self.cwd, summary, elapsed);
+    self.in_new_line = true;
+  }
+
+  fn flush_report(
+    &mut self,
+    _elapsed: &Duration,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) -> Result<(), AnyError> {
+    Ok(())
+  }
+}
@@ -0,0 +1,245 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use super::common;
+use super::TestReporter;
+use crate::tools::test::format_test_error;
+use crate::tools::test::TestDescription;
+use crate::tools::test::TestLocation;
+use crate::tools::test::TestPlan;
+use crate::tools::test::TestResult;
+use crate::tools::test::TestStepDescription;
+use crate::tools::test::TestStepResult;
+use crate::tools::test::TestSummary;
+
+use deno_core::error::AnyError;
+use deno_core::error::JsError;
+use deno_core::url::Url;
+use indexmap::IndexMap;
+use std::collections::HashMap;
+use std::time::Duration;
+
+type StepResult = (TestStepDescription, TestStepResult, u64);
+
+/// Writes results in the Test Anything Protocol (version 14) format.
+///
+/// Tests are numbered in the order they finish and the plan is written at the
+/// end of the run. Steps are written as subtests of their test; since steps
+/// finish before the test that declares them, they are buffered until the
+/// test result arrives.
+pub struct TapTestReporter {
+  cwd: Url,
+  n: usize,
+  /// Finished steps, keyed by the id of their root test.
+  step_results: HashMap<usize, Vec<StepResult>>,
+}
+
+impl TapTestReporter {
+  pub fn new() -> TapTestReporter {
+    println!("TAP version 14");
+    TapTestReporter {
+      cwd: Url::from_directory_path(std::env::current_dir().unwrap()).unwrap(),
+      n: 0,
+      step_results: HashMap::new(),
+    }
+  }
+
+  fn print_line(
+    indent: usize,
+    status: &str,
+    n: usize,
+    name: &str,
+    directive: Option<&str>,
+  ) {
+    let directive = directive.map(|d| format!(" # {d}")).unwrap_or_default();
+    println!(
+      "{}{} {} - {}{}",
+      "    ".repeat(indent),
+      status,
+      n,
+      escape_description(name),
+      directive,
+    );
+  }
+
+  fn print_diagnostic(
+    &self,
+    indent: usize,
+    location: &TestLocation,
+    severity: &str,
+    error: Option<&JsError>,
+  ) {
+    let indent = "    ".repeat(indent);
+    println!("{indent}  ---");
+    println!("{indent}  severity: {severity}");
+    if let Some(error) = error {
+      let formatted = format_test_error(error);
+      let message = common::strip_ansi_codes(&formatted);
+      println!("{indent}  message: |-");
+      for line in message.lines() {
+        println!("{indent}    {line}");
+      }
+    }
+    println!("{indent}  at:");
+    println!(
+      "{indent}    file: {:?}",
+      common::to_relative_path_or_remote_url(&self.cwd, &location.file_name)
+    );
+    println!("{indent}    line: {}", location.line_number);
+    println!("{indent}    column: {}", location.column_number);
+    println!("{indent}  ...");
+  }
+
+  /// Prints the steps whose parent has the given id, recursing into their own
+  /// steps first, and closes the subtest with its plan.
+  fn print_steps(&self, steps: &[StepResult], parent_id: usize, indent: usize) {
+    let children = steps
+      .iter()
+      .filter(|(desc, _, _)| desc.parent_id == parent_id)
+      .collect::<Vec<_>>();
+    for (i, (desc, result, _)) in children.iter().enumerate() {
+      if steps.iter().any(|(step, _, _)| step.parent_id == desc.id) {
+        println!(
+          "{}# Subtest: {}",
+          "    ".repeat(indent),
+          escape_description(&desc.name)
+        );
+        self.print_steps(steps, desc.id, indent + 1);
+      }
+      match result {
+        TestStepResult::Ok => {
+          Self::print_line(indent, "ok", i + 1, &desc.name, None)
+        }
+        TestStepResult::Ignored => {
+          Self::print_line(indent, "ok", i + 1, &desc.name, Some("SKIP"))
+        }
+        TestStepResult::Failed(_) | TestStepResult::Pending(_) => {
+          Self::print_line(indent, "not ok", i + 1, &desc.name, None);
+          self.print_diagnostic(indent, &desc.location, "fail", result.error());
+        }
+      }
+    }
+    println!("{}1..{}", "    ".repeat(indent), children.len());
+  }
+}
+
+/// `#` starts a directive in a TAP test line, so it needs to be escaped in
+/// test names.
+fn escape_description(name: &str) -> String {
+  name.replace('\\', "\\\\").replace('#', "\\#")
+}
+
+impl TestReporter for TapTestReporter {
+  fn report_register(&mut self, _description: &TestDescription) {}
+
+  fn report_plan(&mut self, plan: &TestPlan) {
+    println!(
+      "# {}",
+      common::to_relative_path_or_remote_url(&self.cwd, &plan.origin)
+    );
+  }
+
+  fn report_wait(&mut self, _description: &TestDescription) {}
+
+  fn report_output(&mut self, output: &[u8]) {
+    // user output is written as comments so that it can't be mistaken for
+    // test results
+    let output = String::from_utf8_lossy(output);
+    for line in output.lines() {
+      println!("# {line}");
+    }
+  }
+
+  fn report_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    _elapsed: u64,
+  ) {
+    if let Some(steps) = self.step_results.remove(&description.id) {
+      println!("# Subtest: {}", escape_description(&description.name));
+      self.print_steps(&steps, description.id, 1);
+    }
+
+    self.n += 1;
+    match result {
+      TestResult::Ok => {
+        Self::print_line(0, "ok", self.n, &description.name, None);
+      }
+      TestResult::Ignored => {
+        Self::print_line(0, "ok", self.n, &description.name, Some("SKIP"));
+      }
+      TestResult::Failed(error) => {
+        Self::print_line(0, "not ok", self.n, &description.name, None);
+        self.print_diagnostic(
+          0,
+          &description.location,
+          "fail",
+          Some(error.as_ref()),
+        );
+      }
+      TestResult::Cancelled => {
+        Self::print_line(0, "not ok", self.n, &description.name, None);
+        self.print_diagnostic(0, &description.location, "cancelled", None);
+      }
+    }
+  }
+
+  fn report_uncaught_error(&mut self, origin: &str, error: &JsError) {
+    self.n += 1;
+    let origin = common::to_relative_path_or_remote_url(&self.cwd, origin);
+    let name = format!("{origin} (uncaught error)");
+    Self::print_line(0, "not ok", self.n, &name, None);
+    let formatted = format_test_error(error);
+    let message = common::strip_ansi_codes(&formatted);
+    println!("  ---");
+    println!("  severity: fail");
+    println!("  message: |-");
+    for line in message.lines() {
+      println!("    {line}");
+    }
+    println!("  ...");
+  }
+
+  fn report_step_register(&mut self, _description: &TestStepDescription) {}
+
+  fn report_step_wait(&mut self, _description: &TestStepDescription) {}
+
+  fn report_step_result(
+    &mut self,
+    description: &TestStepDescription,
+    result: &TestStepResult,
+    elapsed: u64,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    self
+      .step_results
+      .entry(description.root_id)
+      .or_default()
+      .push((description.clone(), result.clone(), elapsed));
+  }
+
+  fn report_summary(&mut self, _summary: &TestSummary, _elapsed: &Duration) {}
+
+  fn flush_report(
+    &mut self,
+    _elapsed: &Duration,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) -> Result<(), AnyError> {
+    println!("1..{}", self.n);
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_escape_description() {
+    assert_eq!(escape_description("plain"), "plain");
+    assert_eq!(escape_description("issue #123"), "issue \\#123");
+    assert_eq!(escape_description("a\\b"), "a\\\\b");
+  }
+}
@@ -0,0 +1,246 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Splits the test modules between the shards of `deno test --shard`, so that
+//! each shard runs a disjoint subset and together they run every module.
+
+use crate::args::TestShard;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::ModuleSpecifier;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+
+static TESTSUITE_RE: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"<testsuite\s([^>]*)>").unwrap());
+static ATTRIBUTE_RE: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r#"([\w:-]+)\s*=\s*"([^"]*)""#).unwrap());
+/// The suffix of the specifiers of the documentation tests of a module.
+static DOC_TEST_SUFFIX_RE: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"\$\d+-\d+\.\w+$").unwrap());
+
+/// Returns the test modules belonging to the shard.
+///
+/// The modules are ordered by specifier first, so every shard computes the
+/// same split no matter in which order the modules were collected. Without
+/// timings the modules are dealt out to the shards in turns. With the
+/// durations of a previous run, which must be the same for all shards, the
+/// slowest modules are assigned first, each to the shard with the lowest
+/// total duration so far. Modules missing from the timings are assumed to
+/// take the average duration.
+pub fn select_shard<T>(
+  mut specifiers: Vec<(ModuleSpecifier, T)>,
+  shard: TestShard,
+  maybe_timings: Option<&HashMap<ModuleSpecifier, u64>>,
+) -> Vec<(ModuleSpecifier, T)> {
+  specifiers.sort_by(|(a, _), (b, _)| a.cmp(b));
+  let shard_index = shard.index - 1;
+
+  let timings = match maybe_timings {
+    Some(timings) if !timings.is_empty() => timings,
+    _ => {
+      return specifiers
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| i % shard.total == shard_index)
+        .map(|(_, entry)| entry)
+        .collect();
+    }
+  };
+
+  let average = timings.values().sum::<u64>() / timings.len() as u64;
+  let durations = specifiers
+    .iter()
+    .map(|(specifier, _)| timings.get(specifier).copied().unwrap_or(average))
+    .collect::<Vec<_>>();
+  // the sort is stable, so modules of equal duration stay ordered by
+  // specifier
+  let mut order = (0..specifiers.len()).collect::<Vec<_>>();
+  order.sort_by(|a, b| durations[*b].cmp(&durations[*a]));
+
+  // the total duration and the number of modules of each shard
+  let mut loads = vec![(0u64, 0usize); shard.total];
+  let mut selected = vec![false; specifiers.len()];
+  for i in order {
+    let (target, _) = loads
+      .iter()
+      .enumerate()
+      .min_by_key(|(index, load)| (**load, *index))
+      .unwrap();
+    loads[target].0 += durations[i];
+    loads[target].1 += 1;
+    selected[i] = target == shard_index;
+  }
+
+  specifiers
+    .into_iter()
+    .zip(selected)
+    .filter(|(_, selected)| *selected)
+    .map(|(entry, _)| entry)
+    .collect()
+}
+
+/// Reads the duration in milliseconds of each test module from a JUnit XML
+/// report, like the one written by `--junit-path`. The `<testsuite>` names
+/// are resolved against `cwd`, and the durations of the documentation tests
+/// of a module are added to the module.
+pub fn read_timings(
+  path: &Path,
+  cwd: &ModuleSpecifier,
+) -> Result<HashMap<ModuleSpecifier, u64>, AnyError> {
+  let text = std::fs::read_to_string(path).with_context(|| {
+    format!("Failed to read test timings from \"{}\"", path.display())
+  })?;
+  Ok(parse_timings(&text, cwd))
+}
+
+fn parse_timings(
+  text: &str,
+  cwd: &ModuleSpecifier,
+) -> HashMap<ModuleSpecifier, u64> {
+  let mut timings = HashMap::new();
+  for captures in TESTSUITE_RE.captures_iter(text) {
+    let mut name = None;
+    let mut time = None;
+    for attribute in ATTRIBUTE_RE.captures_iter(&captures[1]) {
+      match &attribute[1] {
+        "name" => name = Some(unescape_xml(&attribute[2])),
+        "time" => time = attribute[2].parse::<f64>().ok(),
+        _ => {}
+      }
+    }
+    let (name, time) = match (name, time) {
+      (Some(name), Some(time)) if time >= 0.0 => (name, time),
+      _ => continue,
+    };
+    let name = DOC_TEST_SUFFIX_RE.replace(&name, "");
+    if let Ok(specifier) = cwd.join(&name) {
+      *timings.entry(specifier).or_default() += (time * 1000.0).round() as u64;
+    }
+  }
+  timings
+}
+
+fn unescape_xml(text: &str) -> String {
+  text
+    .replace("&lt;", "<")
+    .replace("&gt;", ">")
+    .replace("&quot;", "\"")
+    .replace("&apos;", "'")
+    .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn specifiers(names: &[&str]) -> Vec<(ModuleSpecifier, ())> {
+    names
+      .iter()
+      .map(|name| (ModuleSpecifier::parse(name).unwrap(), ()))
+      .collect()
+  }
+
+  fn shards(
+    names: &[&str],
+    total: usize,
+    timings: Option<&HashMap<ModuleSpecifier, u64>>,
+  ) -> Vec<Vec<String>> {
+    (1..=total)
+      .map(|index| {
+        select_shard(specifiers(names), TestShard { index, total }, timings)
+          .into_iter()
+          .map(|(specifier, _)| specifier.to_string())
+          .collect()
+      })
+      .collect()
+  }
+
+  fn names_sorted(names: &[&str]) -> Vec<String> {
+    let mut names = names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+    names.sort();
+    names
+  }
+
+  #[test]
+  fn splits_modules_in_turns() {
+    let names = [
+      "file:///d.ts",
+      "file:///a.ts",
+      "file:///c.ts",
+      "file:///b.ts",
+    ];
+    assert_eq!(
+      shards(&names, 3, None),
+      vec![
+        vec!["file:///a.ts", "file:///d.ts"],
+        vec!["file:///b.ts"],
+        vec!["file:///c.ts"],
+      ]
+    );
+    let mut reversed = names;
+    reversed.reverse();
+    assert_eq!(shards(&reversed, 3, None), shards(&names, 3, None));
+    assert_eq!(shards(&names, 1, None), vec![names_sorted(&names)]);
+    assert_eq!(shards(&[], 2, None), vec![Vec::<String>::new(); 2]);
+  }
+
+  #[test]
+  fn balances_modules_by_timings() {
+    let names = [
+      "file:///a.ts",
+      "file:///b.ts",
+      "file:///c.ts",
+      "file:///d.ts",
+      "file:///e.ts",
+    ];
+    let timings = HashMap::from([
+      (ModuleSpecifier::parse("file:///a.ts").unwrap(), 1000),
+      (ModuleSpecifier::parse("file:///b.ts").unwrap(), 100),
+      (ModuleSpecifier::parse("file:///c.ts").unwrap(), 400),
+      (ModuleSpecifier::parse("file:///d.ts").unwrap(), 500),
+    ]);
+    // e.ts isn't in the timings and takes the average of 500ms
+    assert_eq!(
+      shards(&names, 2, Some(&timings)),
+      vec![
+        vec!["file:///a.ts", "file:///c.ts"],
+        vec!["file:///b.ts", "file:///d.ts", "file:///e.ts"],
+      ]
+    );
+
+    let mut all = shards(&names, 3, Some(&timings))
+      .into_iter()
+      .flatten()
+      .collect::<Vec<_>>();
+    all.sort();
+    assert_eq!(all, names_sorted(&names));
+  }
+
+  #[test]
+  fn parses_junit_timings() {
+    let cwd = ModuleSpecifier::parse("file:///project/").unwrap();
+    let report = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="deno test" tests="4" failures="0" errors="0" time="2.500">
+  <testsuite name="./a_test.ts" tests="2" failures="0" errors="0" skipped="0" time="1.250">
+    <testcase name="a" classname="./a_test.ts" time="1.000">
+    </testcase>
+  </testsuite>
+  <testsuite name="./a_test.ts$3-8.ts" tests="1" failures="0" errors="0" skipped="0" time="0.050">
+  </testsuite>
+  <testsuite name="./b &amp; c_test.ts" tests="1" failures="0" errors="0" skipped="0" time="1.200">
+  </testsuite>
+  <testsuite name="https://deno.land/x/mod_test.ts" time="0.001" tests="1">
+  </testsuite>
+</testsuites>
+"#;
+    let timings = parse_timings(report, &cwd);
+    let timing =
+      |name: &str| timings.get(&ModuleSpecifier::parse(name).unwrap()).copied();
+    assert_eq!(timings.len(), 3);
+    assert_eq!(timing("file:///project/a_test.ts"), Some(1300));
+    assert_eq!(timing("file:///project/b%20&%20c_test.ts"), Some(1200));
+    assert_eq!(timing("https://deno.land/x/mod_test.ts"), Some(1));
+  }
+}
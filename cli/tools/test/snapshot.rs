@@ -0,0 +1,326 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! The snapshots of `t.assertSnapshot()`, which are stored beside each test
+//! module in `__snapshots__/<file name>.snap`. The files use the format of
+//! the snapshot module of the standard library: a JavaScript module exporting
+//! the serialized values as template literals, keyed by the name of the test
+//! and the number of the assertion within the test.
+
+use crate::util::diff::diff;
+use crate::util::path::specifier_to_file_path;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::ModuleSpecifier;
+use indexmap::IndexMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+const SNAPSHOT_FILE_HEADER: &str = "export const snapshot = {};\n";
+
+pub struct SnapshotFile {
+  path: PathBuf,
+  update: bool,
+  /// The snapshots stored in the file.
+  snapshots: IndexMap<String, String>,
+  /// The values asserted during this run.
+  asserted: IndexMap<String, String>,
+}
+
+impl SnapshotFile {
+  /// Reads the snapshot file of a local test module, if it exists.
+  pub fn read(
+    test_module: &ModuleSpecifier,
+    update: bool,
+  ) -> Result<Self, AnyError> {
+    let test_path = match specifier_to_file_path(test_module) {
+      Ok(path) if test_module.scheme() == "file" => path,
+      _ => bail!(
+        "Snapshots are only supported in local test modules, not \"{}\"",
+        test_module
+      ),
+    };
+    let file_name = test_path.file_name().unwrap().to_string_lossy();
+    let path = test_path
+      .with_file_name("__snapshots__")
+      .join(format!("{file_name}.snap"));
+    let snapshots = match std::fs::read_to_string(&path) {
+      Ok(text) => parse_snapshot_file(&text).with_context(|| {
+        format!("Failed to parse snapshot file \"{}\"", path.display())
+      })?,
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => IndexMap::new(),
+      Err(err) => {
+        return Err(err).with_context(|| {
+          format!("Failed to read snapshot file \"{}\"", path.display())
+        })
+      }
+    };
+    Ok(Self {
+      path,
+      update,
+      snapshots,
+      asserted: IndexMap::new(),
+    })
+  }
+
+  /// Compares the serialized value with its snapshot and returns the error
+  /// message of a mismatch. When updating, the value replaces the snapshot
+  /// once the file is saved.
+  pub fn assert(&mut self, name: String, actual: String) -> Option<String> {
+    let maybe_message = if self.update {
+      None
+    } else {
+      match self.snapshots.get(&name) {
+        Some(expected) if *expected == actual => None,
+        Some(expected) => Some(format!(
+          "Snapshot \"{}\" does not match:\n\n{}\nRun with --update-snapshots to update it.",
+          name,
+          diff(expected, &actual)
+        )),
+        None => Some(format!(
+          "Missing snapshot \"{}\" in \"{}\". Run with --update-snapshots to create it.",
+          name,
+          display_path(&self.path)
+        )),
+      }
+    };
+    self.asserted.insert(name, actual);
+    maybe_message
+  }
+
+  /// Writes the asserted values when updating and returns a summary of the
+  /// changes. The snapshots which weren't asserted are removed as obsolete if
+  /// every test of the module ran (`complete`), and kept otherwise.
+  pub fn save(&mut self, complete: bool) -> Result<Option<String>, AnyError> {
+    if !self.update {
+      return Ok(None);
+    }
+
+    let mut snapshots = IndexMap::new();
+    let mut obsolete = Vec::new();
+    for (name, value) in &self.snapshots {
+      if !self.asserted.contains_key(name) {
+        if complete {
+          obsolete.push(name.as_str());
+        } else {
+          snapshots.insert(name.clone(), value.clone());
+        }
+      }
+    }
+    let mut updated = 0;
+    for (name, value) in &self.asserted {
+      if self.snapshots.get(name) != Some(value) {
+        updated += 1;
+      }
+      snapshots.insert(name.clone(), value.clone());
+    }
+    if updated == 0 && obsolete.is_empty() {
+      return Ok(None);
+    }
+    snapshots.sort_keys();
+
+    let result = if snapshots.is_empty() {
+      std::fs::remove_file(&self.path)
+    } else {
+      std::fs::create_dir_all(self.path.parent().unwrap()).and_then(|_| {
+        std::fs::write(&self.path, print_snapshot_file(&snapshots))
+      })
+    };
+    result.with_context(|| {
+      format!(
+        "Failed to write snapshot file \"{}\"",
+        display_path(&self.path)
+      )
+    })?;
+
+    let mut summary = String::new();
+    let path = display_path(&self.path);
+    if updated > 0 {
+      writeln!(
+        summary,
+        "Updated {} snapshot{} in \"{}\".",
+        updated,
+        if updated == 1 { "" } else { "s" },
+        path
+      )
+      .unwrap();
+    }
+    if !obsolete.is_empty() {
+      writeln!(
+        summary,
+        "Removed {} obsolete snapshot{} from \"{}\":",
+        obsolete.len(),
+        if obsolete.len() == 1 { "" } else { "s" },
+        path
+      )
+      .unwrap();
+      for name in obsolete {
+        writeln!(summary, "  {name}").unwrap();
+      }
+    }
+    self.snapshots = snapshots;
+    Ok(Some(summary))
+  }
+}
+
+fn display_path(path: &Path) -> String {
+  std::env::current_dir()
+    .ok()
+    .and_then(|cwd| path.strip_prefix(cwd).ok())
+    .unwrap_or(path)
+    .display()
+    .to_string()
+}
+
+fn print_snapshot_file(snapshots: &IndexMap<String, String>) -> String {
+  let mut text = SNAPSHOT_FILE_HEADER.to_string();
+  for (name, value) in snapshots {
+    write!(
+      text,
+      "\nsnapshot[`{}`] = `\n{}\n`;\n",
+      escape_template(name),
+      escape_template(value)
+    )
+    .unwrap();
+  }
+  text
+}
+
+fn parse_snapshot_file(
+  text: &str,
+) -> Result<IndexMap<String, String>, AnyError> {
+  let mut snapshots = IndexMap::new();
+  let text = text.replace("\r\n", "\n");
+  let mut rest = strip_expected(&text, SNAPSHOT_FILE_HEADER)?;
+  loop {
+    rest = rest.trim_start();
+    if rest.is_empty() {
+      return Ok(snapshots);
+    }
+    rest = strip_expected(rest, "snapshot[`")?;
+    let (name, after_name) = read_template(rest)?;
+    rest = strip_expected(after_name, "] = `")?;
+    let (value, after_value) = read_template(rest)?;
+    rest = strip_expected(after_value, ";")?;
+    let value = value
+      .strip_prefix('\n')
+      .and_then(|value| value.strip_suffix('\n'))
+      .unwrap_or(value.as_str())
+      .to_string();
+    snapshots.insert(name, value);
+  }
+}
+
+fn strip_expected<'a>(
+  text: &'a str,
+  expected: &str,
+) -> Result<&'a str, AnyError> {
+  match text.strip_prefix(expected) {
+    Some(rest) => Ok(rest),
+    None => bail!("Expected \"{}\"", expected),
+  }
+}
+
+/// Reads the contents of a template literal up to its closing backtick and
+/// returns them with the text after the backtick.
+fn read_template(text: &str) -> Result<(String, &str), AnyError> {
+  let mut value = String::new();
+  let mut chars = text.chars();
+  while let Some(c) = chars.next() {
+    match c {
+      '`' => return Ok((value, chars.as_str())),
+      '\\' => match chars.next() {
+        Some(c @ ('\\' | '`' | '$')) => value.push(c),
+        Some(c) => {
+          value.push('\\');
+          value.push(c);
+        }
+        None => break,
+      },
+      c => value.push(c),
+    }
+  }
+  bail!("Unterminated template literal")
+}
+
+fn escape_template(text: &str) -> String {
+  text
+    .replace('\\', "\\\\")
+    .replace('`', "\\`")
+    .replace("${", "\\${")
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use test_util::TempDir;
+
+  #[test]
+  fn prints_and_parses_snapshot_files() {
+    let snapshots = IndexMap::from([
+      ("a 1".to_string(), "{ a: 1 }".to_string()),
+      ("`quoted` 1".to_string(), "`${a}` \\n\nline".to_string()),
+    ]);
+    let text = print_snapshot_file(&snapshots);
+    assert_eq!(
+      text,
+      r#"export const snapshot = {};
+
+snapshot[`a 1`] = `
+{ a: 1 }
+`;
+
+snapshot[`\`quoted\` 1`] = `
+\`\${a}\` \\n
+line
+`;
+"#
+    );
+    assert_eq!(parse_snapshot_file(&text).unwrap(), snapshots);
+    assert_eq!(
+      parse_snapshot_file("export const snapshot = {};\nsnapshot[`a`] = 1;")
+        .unwrap_err()
+        .to_string(),
+      "Expected \"] = `\"",
+    );
+  }
+
+  #[test]
+  fn asserts_and_updates_snapshots() {
+    let temp_dir = TempDir::new();
+    let test_module =
+      ModuleSpecifier::from_file_path(temp_dir.path().join("a_test.ts"))
+        .unwrap();
+
+    let mut file = SnapshotFile::read(&test_module, true).unwrap();
+    assert_eq!(file.assert("a 1".to_string(), "1".to_string()), None);
+    assert_eq!(file.assert("b 1".to_string(), "2".to_string()), None);
+    let summary = file.save(true).unwrap().unwrap();
+    assert!(summary.starts_with("Updated 2 snapshots in"), "{summary}");
+    assert_eq!(
+      temp_dir.read_to_string("__snapshots__/a_test.ts.snap"),
+      "export const snapshot = {};\n\nsnapshot[`a 1`] = `\n1\n`;\n\nsnapshot[`b 1`] = `\n2\n`;\n",
+    );
+
+    let mut file = SnapshotFile::read(&test_module, false).unwrap();
+    assert_eq!(file.assert("a 1".to_string(), "1".to_string()), None);
+    let message = file.assert("b 1".to_string(), "3".to_string()).unwrap();
+    assert!(message.starts_with("Snapshot \"b 1\" does not match"));
+    let message = file.assert("c 1".to_string(), "3".to_string()).unwrap();
+    assert!(message.starts_with("Missing snapshot \"c 1\""));
+    assert_eq!(file.save(true).unwrap(), None);
+
+    // snapshots of tests which didn't run are only removed if all ran
+    let mut file = SnapshotFile::read(&test_module, true).unwrap();
+    assert_eq!(file.assert("a 1".to_string(), "1".to_string()), None);
+    assert_eq!(file.save(false).unwrap(), None);
+    let summary = file.save(true).unwrap().unwrap();
+    assert!(summary.starts_with("Removed 1 obsolete snapshot from"));
+    assert!(summary.ends_with(":\n  b 1\n"));
+    assert_eq!(
+      temp_dir.read_to_string("__snapshots__/a_test.ts.snap"),
+      "export const snapshot = {};\n\nsnapshot[`a 1`] = `\n1\n`;\n",
+    );
+  }
+}
@@ -43,6 +43,12 @@ const UNSTABLE_DENO_PROPS: &[&str] = &[
   "ServeTlsInit",
   "Handler",
   "osUptime",
+  "openKv",
+  "Kv",
+  "KvKey",
+  "KvKeyPart",
+  "KvListIterator",
+  "AtomicOperation",
 ];
 
 static MSG_MISSING_PROPERTY_DENO: Lazy<Regex> = Lazy::new(|| {
@@ -688,6 +688,66 @@ declare namespace Deno {
      * ```
      */
     step(fn: (t: TestContext) => void | Promise<void>): Promise<boolean>;
+
+    /** Assert that the value matches its snapshot. The value is serialized
+     * like `Deno.inspect()` does, and the snapshots are stored in
+     * `__snapshots__/<file name>.snap` beside the test module, keyed by the
+     * name of the test and the number of the assertion within the test.
+     *
+     * Run `deno test --update-snapshots` to create or update the snapshots
+     * and to remove the ones which are no longer asserted.
+     *
+     * ```ts
+     * Deno.test("a snapshot", (t) => {
+     *   t.assertSnapshot({ name: "deno", versions: [1, 2] });
+     * });
+     * ```
+     */
+    assertSnapshot(actual: unknown): void;
+
+    /** Fake the time until the test or step ends, or until the returned
+     * {@linkcode Deno.FakeTime} is restored. The clock is frozen at `start`,
+     * which defaults to the current time, and only moves forward when it is
+     * advanced. Meanwhile `Date`, `performance.now()` and the timers created
+     * with `setTimeout()` and `setInterval()` follow the fake clock. Timers
+     * created before keep running in real time.
+     *
+     * ```ts
+     * Deno.test("a debounced function", (t) => {
+     *   const time = t.useFakeTime(new Date("2023-01-01T00:00:00Z"));
+     *   let calls = 0;
+     *   setTimeout(() => calls++, 1000);
+     *   time.tick(999);
+     *   console.assert(calls === 0);
+     *   time.tick(1);
+     *   console.assert(calls === 1);
+     *   console.assert(Date.now() === Date.UTC(2023, 0, 1, 0, 0, 1));
+     * });
+     * ```
+     */
+    useFakeTime(start?: number | Date): FakeTime;
+  }
+
+  /** The fake clock returned by {@linkcode Deno.TestContext.useFakeTime}.
+   *
+   * The timers run synchronously while the clock is advanced, so the promise
+   * callbacks they trigger only run after `tick()` or `runAll()` returns.
+   *
+   * @category Testing
+   */
+  export interface FakeTime {
+    /** The fake time in milliseconds since the epoch, as returned by
+     * `Date.now()`. */
+    readonly now: number;
+    /** Advance the clock by `ms` milliseconds, running the timers which are
+     * due on the way in order. */
+    tick(ms?: number): void;
+    /** Advance the clock until all timers, including the ones scheduled by
+     * the timers which run, have run. */
+    runAll(): void;
+    /** Cancel the pending fake timers and restore the real time. This is done
+     * automatically when the test or step which faked the time ends. */
+    restore(): void;
   }
 
   /** @category Testing */
@@ -766,6 +826,17 @@ declare namespace Deno {
      *
      * @default {"inherit"} */
     permissions?: PermissionOptions;
+    /** Fail the test if it doesn't finish within this number of
+     * milliseconds. The error lists the async operations started by the
+     * test which are still pending.
+     *
+     * Defaults to the value of the `--timeout` flag, or no timeout. */
+    timeout?: number;
+    /** Run the test again up to this number of times if it fails. A test
+     * which passes on a retry is reported as flaky.
+     *
+     * Defaults to the value of the `--retry` flag, or `0`. */
+    retries?: number;
   }
 
   /** Register a test which will be run when `deno test` is used on the command
@@ -1640,6 +1640,385 @@ declare namespace Deno {
    * @category Runtime Environment
    */
   export function osUptime(): number;
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Open a new {@linkcode Deno.Kv} connection to persist data.
+   *
+   * When a path is provided, the database will be persisted to disk at that
+   * path. Read and write access to the file is required. The path `":memory:"`
+   * opens a database that only lives in memory.
+   *
+   * When no path is provided, the database will be persisted in the `DENO_DIR`
+   * in a location specific to the origin of the main module, like
+   * `localStorage`.
+   *
+   * ```ts
+   * const db = await Deno.openKv();
+   * await db.set(["users", "alice"], { name: "Alice" });
+   * ```
+   *
+   * @tags allow-read, allow-write
+   * @category KV
+   */
+  export function openKv(path?: string): Promise<Deno.Kv>;
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * A key to be persisted in a {@linkcode Deno.Kv}. A key is a sequence of
+   * {@linkcode Deno.KvKeyPart}s.
+   *
+   * Keys are ordered lexicographically by their parts. The first part is the
+   * most significant, and the last part is the least significant. The order
+   * of the parts is determined by both the type and the value of the part.
+   * The relative significance of the types can be found in documentation for
+   * the {@linkcode Deno.KvKeyPart} type.
+   *
+   * @category KV
+   */
+  export type KvKey = readonly KvKeyPart[];
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * A single part of a {@linkcode Deno.KvKey}. Parts are ordered
+   * lexicographically, first by their type, and within a given type by their
+   * value.
+   *
+   * The ordering of types is as follows:
+   *
+   * 1. `Uint8Array`
+   * 2. `string`
+   * 3. `number`
+   * 4. `bigint`
+   * 5. `boolean`
+   *
+   * Within a given type, the ordering is as follows:
+   *
+   * - `Uint8Array` is ordered by the byte ordering of the array
+   * - `string` is ordered by the byte ordering of the UTF-8 encoding of the
+   *   string
+   * - `number` is ordered following this pattern: `-NaN`
+   *   < `-Infinity` < `-100.0` < `-1.0` < -`0.5` < `-0.0` < `0.0` < `0.5`
+   *   < `1.0` < `100.0` < `Infinity` < `NaN`
+   * - `bigint` is ordered by mathematical ordering, with the largest negative
+   *   number being the least first value, and the largest positive number
+   *   being the last value
+   * - `boolean` is ordered by `false` < `true`
+   *
+   * @category KV
+   */
+  export type KvKeyPart = Uint8Array | string | number | bigint | boolean;
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Consistency level of a KV operation. Databases are stored locally, so
+   * all operations are strongly consistent.
+   *
+   * @category KV
+   */
+  export type KvConsistencyLevel = "strong" | "eventual";
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * A selector that selects the range of data returned by a list operation on
+   * a {@linkcode Deno.Kv}.
+   *
+   * The selector can either be a prefix selector or a range selector. A
+   * prefix selector selects all keys that start with the given prefix (not
+   * including the prefix itself). A range selector selects all keys that are
+   * lexicographically between the given start and end keys (including the
+   * start, and excluding the end). A prefix selector with a `start` or `end`
+   * key selects the keys that start with the prefix, starting at `start` or
+   * ending before `end`.
+   *
+   * @category KV
+   */
+  export type KvListSelector =
+    | { prefix: KvKey }
+    | { prefix: KvKey; start: KvKey }
+    | { prefix: KvKey; end: KvKey }
+    | { start: KvKey; end: KvKey };
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Options for listing key-value pairs in a {@linkcode Deno.Kv}.
+   *
+   * @category KV
+   */
+  export interface KvListOptions {
+    /** The maximum number of key-value pairs to return. If not specified, all
+     * matching key-value pairs will be returned. */
+    limit?: number;
+    /** The cursor to resume the iteration from. If not specified, the
+     * iteration will start from the beginning. */
+    cursor?: string;
+    /** Whether to reverse the order of the returned key-value pairs. If not
+     * specified, the order will be ascending from the start of the range as
+     * per the lexicographical ordering of the keys. */
+    reverse?: boolean;
+    /** The consistency level of the list operation. */
+    consistency?: KvConsistencyLevel;
+    /** The size of the batches in which the list operation is performed on
+     * the database. Defaults to the `limit`, or 100 if no limit is given, and
+     * is capped at 500. */
+    batchSize?: number;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * A versioned pair of key and value in a {@linkcode Deno.Kv}.
+   *
+   * The `versionstamp` is a string that represents the current version of the
+   * key-value pair. It can be used to perform atomic operations on the KV
+   * store by passing it to the `check` method of a
+   * {@linkcode Deno.AtomicOperation}. A `null` versionstamp indicates that no
+   * value exists for the given key in the KV store.
+   *
+   * @category KV
+   */
+  export interface KvEntry<T> {
+    key: KvKey;
+    value: T;
+    versionstamp: string;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * An optional versioned pair of key and value in a {@linkcode Deno.Kv}.
+   *
+   * @category KV
+   */
+  export type KvEntryMaybe<T> = KvEntry<T> | {
+    key: KvKey;
+    value: null;
+    versionstamp: null;
+  };
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * An iterator over a range of data entries in a {@linkcode Deno.Kv}.
+   *
+   * The cursor getter returns the cursor that can be used to resume the
+   * iteration from the current position in the future.
+   *
+   * @category KV
+   */
+  export class KvListIterator<T> implements AsyncIterableIterator<KvEntry<T>> {
+    /**
+     * Returns the cursor of the current position in the iteration. This
+     * cursor can be used to resume the iteration from the current position in
+     * the future by passing it to the `cursor` option of the `list` method.
+     */
+    get cursor(): string;
+
+    next(): Promise<IteratorResult<KvEntry<T>, undefined>>;
+    [Symbol.asyncIterator](): AsyncIterableIterator<KvEntry<T>>;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * A check to perform as part of a {@linkcode Deno.AtomicOperation}. The
+   * check will fail if the versionstamp for the key-value pair in the KV
+   * store does not match the given versionstamp. A check with a `null`
+   * versionstamp checks that the key-value pair does not currently exist in
+   * the KV store.
+   *
+   * @category KV
+   */
+  export interface AtomicCheck {
+    key: KvKey;
+    versionstamp: string | null;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * The result of a successful {@linkcode Deno.AtomicOperation.commit}.
+   *
+   * @category KV
+   */
+  export interface KvCommitResult {
+    ok: true;
+    /** The versionstamp of the value committed to KV. */
+    versionstamp: string;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * The result of a {@linkcode Deno.AtomicOperation.commit} where one of the
+   * checks failed.
+   *
+   * @category KV
+   */
+  export interface KvCommitError {
+    ok: false;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * An operation on a {@linkcode Deno.Kv} that can be performed atomically.
+   * Atomic operations do not auto-commit, and must be committed explicitly by
+   * calling the `commit` method.
+   *
+   * Atomic operations can be used to perform multiple mutations on the KV
+   * store in a single atomic transaction. They can also be used to perform
+   * conditional mutations by specifying one or more
+   * {@linkcode Deno.AtomicCheck}s that ensure that a mutation is only
+   * performed if the key-value pair in the KV has a specific versionstamp. If
+   * any of the checks fail, the entire operation will fail and no mutations
+   * will be made.
+   *
+   * ```ts
+   * const db = await Deno.openKv();
+   * const entry = await db.get<number>(["counter"]);
+   * const result = await db.atomic()
+   *   .check(entry)
+   *   .set(["counter"], (entry.value ?? 0) + 1)
+   *   .commit();
+   * if (!result.ok) console.log("counter was changed concurrently");
+   * ```
+   *
+   * @category KV
+   */
+  export class AtomicOperation {
+    /**
+     * Add to the operation a check that ensures that the versionstamp of the
+     * key-value pair in the KV store matches the given versionstamp. If the
+     * check fails, the entire operation will fail and no mutations will be
+     * performed during the commit.
+     */
+    check(...checks: AtomicCheck[]): this;
+    /**
+     * Add to the operation a mutation that sets the value of the specified
+     * key to the specified value if all checks pass during the commit.
+     */
+    set(key: KvKey, value: unknown): this;
+    /**
+     * Add to the operation a mutation that deletes the specified key if all
+     * checks pass during the commit.
+     */
+    delete(key: KvKey): this;
+    /**
+     * Commit the operation to the KV store. Returns a value indicating whether
+     * checks passed and mutations were performed. If the operation failed
+     * because of a failed check, the return value will be a
+     * {@linkcode Deno.KvCommitError} with an `ok: false` property. If the
+     * operation failed for any other reason (storage error, invalid value,
+     * etc.), an exception will be thrown. If the operation succeeded, the
+     * return value will be a {@linkcode Deno.KvCommitResult} object with a
+     * `ok: true` property and the versionstamp of the value committed to KV.
+     */
+    commit(): Promise<KvCommitResult | KvCommitError>;
+  }
+
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * A key-value database that can be used to store and retrieve data.
+   *
+   * Data is stored as key-value pairs, where the key is a
+   * {@linkcode Deno.KvKey} and the value is an arbitrary structured-serializable
+   * JavaScript value. Keys are ordered lexicographically as described in the
+   * documentation for {@linkcode Deno.KvKey}. Keys are unique within a
+   * database, and the last value set for a given key is the one that is
+   * returned when reading the key. Keys can be deleted from the database, in
+   * which case they will no longer be returned when reading keys.
+   *
+   * Values can be any structured-serializable JavaScript value (objects,
+   * arrays, strings, numbers, etc.). Values are serialized with the V8
+   * structured serialization format.
+   *
+   * Keys can be up to 2 kibibytes in size after serialization. Values can be
+   * up to 64 kibibytes in size after serialization.
+   *
+   * @category KV
+   */
+  export class Kv {
+    /**
+     * Retrieve the value and versionstamp for the given key from the
+     * database in the form of a {@linkcode Deno.KvEntryMaybe}. If no value
+     * exists for the key, the returned entry will have a `null` value and
+     * versionstamp.
+     *
+     * ```ts
+     * const db = await Deno.openKv();
+     * const result = await db.get(["foo"]);
+     * result.key; // ["foo"]
+     * result.value; // "bar"
+     * result.versionstamp; // "00000000000000010000"
+     * ```
+     */
+    get<T = unknown>(
+      key: KvKey,
+      options?: { consistency?: KvConsistencyLevel },
+    ): Promise<KvEntryMaybe<T>>;
+
+    /**
+     * Retrieve multiple values and versionstamps from the database in the
+     * form of an array of {@linkcode Deno.KvEntryMaybe} objects. The returned
+     * array will have the same length as the `keys` array, and the entries
+     * will be in the same order as the keys. At most 10 keys can be
+     * retrieved at once.
+     */
+    getMany<T extends readonly unknown[]>(
+      keys: readonly [...{ [K in keyof T]: KvKey }],
+      options?: { consistency?: KvConsistencyLevel },
+    ): Promise<{ [K in keyof T]: KvEntryMaybe<T[K]> }>;
+
+    /**
+     * Set the value for the given key in the database. If a value already
+     * exists for the key, it will be overwritten.
+     *
+     * ```ts
+     * const db = await Deno.openKv();
+     * await db.set(["foo"], "bar");
+     * ```
+     */
+    set(key: KvKey, value: unknown): Promise<KvCommitResult>;
+
+    /**
+     * Delete the value for the given key from the database. If no value
+     * exists for the key, this operation is a no-op.
+     *
+     * ```ts
+     * const db = await Deno.openKv();
+     * await db.delete(["foo"]);
+     * ```
+     */
+    delete(key: KvKey): Promise<void>;
+
+    /**
+     * Retrieve a list of keys in the database. The returned list is an
+     * {@linkcode Deno.KvListIterator} which can be used to iterate over the
+     * entries in the database.
+     *
+     * The `cursor` of the iterator can be passed to a later call to `list`
+     * with the same selector to continue after the last returned entry.
+     *
+     * ```ts
+     * const db = await Deno.openKv();
+     * const entries = db.list({ prefix: ["users"] });
+     * for await (const entry of entries) {
+     *   entry.key; // ["users", "alice"]
+     *   entry.value; // { name: "Alice" }
+     *   entry.versionstamp; // "00000000000000010000"
+     * }
+     * ```
+     */
+    list<T = unknown>(
+      selector: KvListSelector,
+      options?: KvListOptions,
+    ): KvListIterator<T>;
+
+    /**
+     * Create a new {@linkcode Deno.AtomicOperation} object which can be used
+     * to perform an atomic transaction on the database.
+     */
+    atomic(): AtomicOperation;
+
+    /**
+     * Close the database connection.
+     */
+    close(): void;
+  }
 }
 
 /** **UNSTABLE**: New API, yet to be vetted.
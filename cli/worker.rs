@@ -1,5 +1,6 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use std::num::NonZeroU64;
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::Arc;
@@ -16,6 +17,7 @@ use deno_core::Extension;
 use deno_core::ModuleId;
 use deno_runtime::colors;
 use deno_runtime::fmt_errors::format_js_error;
+use deno_runtime::fs::RealFs;
 use deno_runtime::ops::worker_host::CreateWebWorkerCb;
 use deno_runtime::ops::worker_host::WorkerEventCb;
 use deno_runtime::permissions::PermissionsContainer;
@@ -181,13 +183,21 @@ impl CliMainWorker {
   ) -> Result<(), AnyError> {
     self.enable_test();
 
-    // Enable op call tracing in core to enable better debugging of op sanitizer
-    // failures.
+    // Enable op call and resource tracing in core to enable better debugging
+    // of op and resource sanitizer failures.
     if self.ps.options.trace_ops() {
       self.worker.js_runtime.execute_script(
         &located_script_name!(),
         "Deno[Deno.internal].core.enableOpCallTracing();",
       )?;
+      self.worker.js_runtime.enable_op_tracing();
+      self
+        .worker
+        .js_runtime
+        .op_state()
+        .borrow_mut()
+        .resource_table
+        .enable_tracing();
     }
 
     let mut maybe_coverage_collector =
@@ -201,7 +211,13 @@ impl CliMainWorker {
     }
 
     self.worker.dispatch_load_event(&located_script_name!())?;
-    self.run_tests(&self.ps.options.shuffle_tests()).await?;
+    self
+      .run_tests(
+        &self.ps.options.shuffle_tests(),
+        self.ps.options.test_timeout(),
+        self.ps.options.test_retries(),
+      )
+      .await?;
     loop {
       if !self
         .worker
@@ -233,6 +249,14 @@ impl CliMainWorker {
       &located_script_name!(),
       "Deno[Deno.internal].core.enableOpCallTracing();",
     )?;
+    self.worker.js_runtime.enable_op_tracing();
+    self
+      .worker
+      .js_runtime
+      .op_state()
+      .borrow_mut()
+      .resource_table
+      .enable_tracing();
 
     if mode != TestMode::Documentation {
       // We execute the module module as a side module so that import.meta.main is not set.
@@ -240,7 +264,7 @@ impl CliMainWorker {
     }
 
     self.worker.dispatch_load_event(&located_script_name!())?;
-    self.run_tests(&None).await?;
+    self.run_tests(&None, None, 0).await?;
     loop {
       if !self
         .worker
@@ -348,16 +372,22 @@ impl CliMainWorker {
 
   /// Run tests declared with `Deno.test()`. Test events will be dispatched
   /// by calling ops which are currently only implemented in the CLI crate.
+  /// `timeout` and `retries` apply to the tests which don't set their own.
   pub async fn run_tests(
     &mut self,
     shuffle: &Option<u64>,
+    timeout: Option<NonZeroU64>,
+    retries: usize,
   ) -> Result<(), AnyError> {
     let promise = {
       let scope = &mut self.worker.js_runtime.handle_scope();
       let cb = self.js_run_tests_callback.as_ref().unwrap().open(scope);
       let this = v8::undefined(scope).into();
-      let options =
-        serde_v8::to_v8(scope, json!({ "shuffle": shuffle })).unwrap();
+      let options = serde_v8::to_v8(
+        scope,
+        json!({ "shuffle": shuffle, "timeout": timeout, "retries": retries }),
+      )
+      .unwrap();
       let promise = cb.call(scope, this, &[options]).unwrap();
       v8::Global::new(scope, promise)
     };
@@ -549,6 +579,7 @@ async fn create_main_worker_internal(
     shared_array_buffer_store: Some(ps.shared_array_buffer_store.clone()),
     compiled_wasm_module_store: Some(ps.compiled_wasm_module_store.clone()),
     stdio,
+    fs: Arc::new(RealFs),
   };
 
   let mut worker = MainWorker::bootstrap_from_options(
@@ -713,6 +744,7 @@ fn create_web_worker_callback(
       shared_array_buffer_store: Some(ps.shared_array_buffer_store.clone()),
       compiled_wasm_module_store: Some(ps.compiled_wasm_module_store.clone()),
       stdio: stdio.clone(),
+      fs: Arc::new(RealFs),
       cache_storage_dir,
     };
 
@@ -779,6 +811,7 @@ mod tests {
       shared_array_buffer_store: None,
       compiled_wasm_module_store: None,
       stdio: Default::default(),
+      fs: Arc::new(RealFs),
     };
 
     MainWorker::bootstrap_from_options(main_module, permissions, options)
@@ -308,6 +308,15 @@ export function resources(): Record<string, string> {
   // return ObjectFromEntries(ops.op_resources());
 }
 
+/**
+ * Retrieve the JS stack traces of where the open resources were created, in
+ * the form of a map that maps resource id to the trace. Only the resources
+ * created by ops while resource tracing was enabled have a trace.
+ */
+export function resourceTraces(): Record<string, string> {
+  return ObjectFromEntries(ops.op_resource_traces());
+}
+
 export function metrics() {
   const { 0: aggregate, 1: perOps } = ops.op_metrics();
   //@ts-ignore
@@ -33,6 +33,14 @@ declare namespace Deno {
      */
     function resources(): Record<string, string>;
 
+    /**
+     * Retrieve the JS stack traces of where the open resources were created,
+     * in the form of a map that maps resource id to the trace. Only the
+     * resources created by ops while resource tracing was enabled have a
+     * trace.
+     */
+    function resourceTraces(): Record<string, string>;
+
     /**
      * Close the resource with the specified op id. Throws `BadResource` error
      * if resource doesn't exist in resource table.
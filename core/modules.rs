@@ -40,7 +40,12 @@ fn strip_bom(source_code: &[u8]) -> &[u8] {
   }
 }
 
-const SUPPORTED_TYPE_ASSERTIONS: &[&str] = &["json"];
+const SUPPORTED_TYPE_ASSERTIONS: &[&str] = &["json", "text", "bytes"];
+
+/// The import assertion type that the wrapper module of a WebAssembly module
+/// uses to import the compiled `WebAssembly.Module`. It is never valid in user
+/// code, as it isn't in `SUPPORTED_TYPE_ASSERTIONS`.
+const WASM_MODULE_TYPE_ASSERTION: &str = "$$deno-core-internal-wasm-module";
 
 /// Throws V8 exception if assertions are invalid
 pub(crate) fn validate_import_assertions(
@@ -108,12 +113,11 @@ pub(crate) fn get_asserted_module_type_from_assertions(
 ) -> AssertedModuleType {
   assertions
     .get("type")
-    .map(|ty| {
-      if ty == "json" {
-        AssertedModuleType::Json
-      } else {
-        AssertedModuleType::JavaScriptOrWasm
-      }
+    .map(|ty| match ty.as_str() {
+      "json" => AssertedModuleType::Json,
+      "text" => AssertedModuleType::Text,
+      "bytes" => AssertedModuleType::Bytes,
+      _ => AssertedModuleType::JavaScriptOrWasm,
     })
     .unwrap_or(AssertedModuleType::JavaScriptOrWasm)
 }
@@ -121,7 +125,7 @@ pub(crate) fn get_asserted_module_type_from_assertions(
 // Clippy thinks the return value doesn't need to be an Option, it's unaware
 // of the mapping that MapFnFrom<F> does for ResolveModuleCallback.
 #[allow(clippy::unnecessary_wraps)]
-fn json_module_evaluation_steps<'a>(
+fn synthetic_module_evaluation_steps<'a>(
   context: v8::Local<'a, v8::Context>,
   module: v8::Local<v8::Module>,
 ) -> Option<v8::Local<'a, v8::Value>> {
@@ -133,7 +137,7 @@ fn json_module_evaluation_steps<'a>(
   let handle = v8::Global::<v8::Module>::new(tc_scope, module);
   let value_handle = module_map
     .borrow_mut()
-    .json_value_store
+    .synthetic_module_value_store
     .remove(&handle)
     .unwrap();
   let value_local = v8::Local::new(tc_scope, value_handle);
@@ -153,16 +157,106 @@ fn json_module_evaluation_steps<'a>(
   Some(resolver.get_promise(tc_scope).into())
 }
 
+/// Returns the names of the modules a compiled WebAssembly module imports
+/// from, in order and without duplicates, and the names of its exports.
+fn wasm_module_imports_and_exports(
+  scope: &mut v8::HandleScope,
+  module: v8::Local<v8::WasmModuleObject>,
+) -> Option<(Vec<String>, Vec<String>)> {
+  let global = scope.get_current_context().global(scope);
+  let web_assembly_str = v8::String::new(scope, "WebAssembly").unwrap();
+  let web_assembly: v8::Local<v8::Object> = global
+    .get(scope, web_assembly_str.into())?
+    .try_into()
+    .ok()?;
+  let module_str = v8::String::new(scope, "Module").unwrap();
+  let module_ctor: v8::Local<v8::Object> = web_assembly
+    .get(scope, module_str.into())?
+    .try_into()
+    .ok()?;
+
+  // Calls `WebAssembly.Module[fn_name](module)` and collects `property` of
+  // each of the returned descriptors.
+  let mut collect = |fn_name: &str, property: &str| -> Option<Vec<String>> {
+    let fn_name = v8::String::new(scope, fn_name).unwrap();
+    let func: v8::Local<v8::Function> =
+      module_ctor.get(scope, fn_name.into())?.try_into().ok()?;
+    let receiver = v8::undefined(scope);
+    let descriptors: v8::Local<v8::Array> = func
+      .call(scope, receiver.into(), &[module.into()])?
+      .try_into()
+      .ok()?;
+    let property = v8::String::new(scope, property).unwrap();
+    let mut values = Vec::new();
+    for i in 0..descriptors.length() {
+      let descriptor: v8::Local<v8::Object> =
+        descriptors.get_index(scope, i)?.try_into().ok()?;
+      let value = descriptor.get(scope, property.into())?;
+      let value = value.to_rust_string_lossy(scope);
+      if !values.contains(&value) {
+        values.push(value);
+      }
+    }
+    Some(values)
+  };
+
+  let imports = collect("imports", "module")?;
+  let exports = collect("exports", "name")?;
+  Some((imports, exports))
+}
+
+/// Renders the source of the ES module that instantiates the WebAssembly
+/// module `name` with the modules it imports from, and re-exports the exports
+/// of the instance.
+fn render_wasm_module_wrapper(
+  name: &str,
+  imports: &[String],
+  exports: &[String],
+) -> String {
+  let quote = |s: &str| serde_json::to_string(s).unwrap();
+  let mut source = format!(
+    "import wasmModule from {} assert {{ type: {} }};\n",
+    quote(name),
+    quote(WASM_MODULE_TYPE_ASSERTION),
+  );
+  for (i, import) in imports.iter().enumerate() {
+    source
+      .push_str(&format!("import * as import{i} from {};\n", quote(import)));
+  }
+  source.push_str("const instance = new WebAssembly.Instance(wasmModule, {\n");
+  for (i, import) in imports.iter().enumerate() {
+    source.push_str(&format!("  {}: import{i},\n", quote(import)));
+  }
+  source.push_str("});\n");
+  for (i, export) in exports.iter().enumerate() {
+    let export = quote(export);
+    source
+      .push_str(&format!("const export{i} = instance.exports[{export}];\n"));
+    source.push_str(&format!("export {{ export{i} as {export} }};\n"));
+  }
+  source
+}
+
 /// A type of module to be executed.
 ///
-/// For non-`JavaScript` modules, this value doesn't tell
-/// how to interpret the module; it is only used to validate
-/// the module against an import assertion (if one is present
-/// in the import statement).
+/// The module type tells how the source code of a module is interpreted and
+/// is used to validate the module against an import assertion (if one is
+/// present in the import statement).
+///
+/// Loaders don't need to return `Text` or `Bytes` modules: any module imported
+/// with a `"text"` or `"bytes"` type assertion is interpreted as such,
+/// regardless of the type the loader reported.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub enum ModuleType {
   JavaScript,
   Json,
+  /// A WebAssembly module, which is instantiated with its imports resolved
+  /// from the module graph. Its exports are the exports of the instance.
+  Wasm,
+  /// A module whose default export is its source decoded as UTF-8.
+  Text,
+  /// A module whose default export is its source as a `Uint8Array`.
+  Bytes,
 }
 
 impl std::fmt::Display for ModuleType {
@@ -170,6 +264,9 @@ impl std::fmt::Display for ModuleType {
     match self {
       Self::JavaScript => write!(f, "JavaScript"),
       Self::Json => write!(f, "JSON"),
+      Self::Wasm => write!(f, "Wasm"),
+      Self::Text => write!(f, "Text"),
+      Self::Bytes => write!(f, "Bytes"),
     }
   }
 }
@@ -324,10 +421,10 @@ impl ModuleLoader for FsModuleLoader {
       })?;
       let module_type = if let Some(extension) = path.extension() {
         let ext = extension.to_string_lossy().to_lowercase();
-        if ext == "json" {
-          ModuleType::Json
-        } else {
-          ModuleType::JavaScript
+        match ext.as_str() {
+          "json" => ModuleType::Json,
+          "wasm" => ModuleType::Wasm,
+          _ => ModuleType::JavaScript,
         }
       } else {
         ModuleType::JavaScript
@@ -532,7 +629,13 @@ impl RecursiveModuleLoad {
     module_request: &ModuleRequest,
     module_source: &ModuleSource,
   ) -> Result<(), ModuleError> {
-    let expected_asserted_module_type = module_source.module_type.into();
+    // Text and bytes imports reinterpret whatever source was loaded.
+    let module_type = match module_request.asserted_module_type {
+      AssertedModuleType::Text => ModuleType::Text,
+      AssertedModuleType::Bytes => ModuleType::Bytes,
+      _ => module_source.module_type,
+    };
+    let expected_asserted_module_type = module_type.into();
     if module_request.asserted_module_type != expected_asserted_module_type {
       return Err(ModuleError::Other(generic_error(format!(
         "Expected a \"{}\" module but loaded a \"{}\" module.",
@@ -561,7 +664,7 @@ impl RecursiveModuleLoad {
         );
         id
       }
-      None => match module_source.module_type {
+      None => match module_type {
         ModuleType::JavaScript => {
           self.module_map_rc.borrow_mut().new_es_module(
             scope,
@@ -576,6 +679,21 @@ impl RecursiveModuleLoad {
           &module_source.module_url_found,
           &module_source.code,
         )?,
+        ModuleType::Wasm => self.module_map_rc.borrow_mut().new_wasm_module(
+          scope,
+          self.is_currently_loading_main_module(),
+          &module_source.module_url_found,
+          &module_source.code,
+          self.is_dynamic_import(),
+        )?,
+        ModuleType::Text | ModuleType::Bytes => {
+          self.module_map_rc.borrow_mut().new_raw_module(
+            scope,
+            &module_source.module_url_found,
+            module_type,
+            &module_source.code,
+          )?
+        }
       },
     };
 
@@ -631,7 +749,7 @@ impl RecursiveModuleLoad {
     // Update `self.state` however applicable.
     if self.state == LoadState::LoadingRoot {
       self.root_module_id = Some(module_id);
-      self.root_asserted_module_type = Some(module_source.module_type.into());
+      self.root_asserted_module_type = Some(module_type.into());
       self.state = LoadState::LoadingImports;
     }
     if self.pending.is_empty() {
@@ -726,13 +844,19 @@ impl Stream for RecursiveModuleLoad {
 pub(crate) enum AssertedModuleType {
   JavaScriptOrWasm,
   Json,
+  Text,
+  Bytes,
 }
 
 impl From<ModuleType> for AssertedModuleType {
   fn from(module_type: ModuleType) -> AssertedModuleType {
     match module_type {
-      ModuleType::JavaScript => AssertedModuleType::JavaScriptOrWasm,
+      ModuleType::JavaScript | ModuleType::Wasm => {
+        AssertedModuleType::JavaScriptOrWasm
+      }
       ModuleType::Json => AssertedModuleType::Json,
+      ModuleType::Text => AssertedModuleType::Text,
+      ModuleType::Bytes => AssertedModuleType::Bytes,
     }
   }
 }
@@ -742,14 +866,16 @@ impl std::fmt::Display for AssertedModuleType {
     match self {
       Self::JavaScriptOrWasm => write!(f, "JavaScriptOrWasm"),
       Self::Json => write!(f, "JSON"),
+      Self::Text => write!(f, "Text"),
+      Self::Bytes => write!(f, "Bytes"),
     }
   }
 }
 
 /// Describes a request for a module as parsed from the source code.
 /// Usually executable (`JavaScriptOrWasm`) is used, except when an
-/// import assertions explicitly constrains an import to JSON, in
-/// which case this will have a `AssertedModuleType::Json`.
+/// import assertions explicitly constrains an import to JSON, text or
+/// bytes, in which case this will have the matching `AssertedModuleType`.
 #[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub(crate) struct ModuleRequest {
   pub specifier: ModuleSpecifier,
@@ -802,9 +928,16 @@ pub(crate) struct ModuleMap {
   pub(crate) pending_dynamic_imports:
     FuturesUnordered<StreamFuture<RecursiveModuleLoad>>,
 
-  // This store is used temporarly, to forward parsed JSON
-  // value from `new_json_module` to `json_module_evaluation_steps`
-  json_value_store: HashMap<v8::Global<v8::Module>, v8::Global<v8::Value>>,
+  // This store is used temporarly, to forward the default export of
+  // synthetic modules (such as parsed JSON values) from `new_json_module`,
+  // `new_raw_module` and `new_wasm_module` to
+  // `synthetic_module_evaluation_steps`
+  synthetic_module_value_store:
+    HashMap<v8::Global<v8::Module>, v8::Global<v8::Value>>,
+  // Synthetic modules exporting the compiled `WebAssembly.Module` of each
+  // WebAssembly module, by module name. They are imported by the wrapper
+  // module that instantiates them and dropped once it is instantiated.
+  wasm_module_store: HashMap<String, v8::Global<v8::Module>>,
 }
 
 impl ModuleMap {
@@ -893,7 +1026,8 @@ impl ModuleMap {
       dynamic_import_map: HashMap::new(),
       preparing_dynamic_imports: FuturesUnordered::new(),
       pending_dynamic_imports: FuturesUnordered::new(),
-      json_value_store: HashMap::new(),
+      synthetic_module_value_store: HashMap::new(),
+      wasm_module_store: HashMap::new(),
     }
   }
 
@@ -944,24 +1078,125 @@ impl ModuleMap {
       }
     };
 
-    let export_names = [v8::String::new(tc_scope, "default").unwrap()];
-    let module = v8::Module::create_synthetic_module(
-      tc_scope,
-      name_str,
-      &export_names,
-      json_module_evaluation_steps,
-    );
-
-    let handle = v8::Global::<v8::Module>::new(tc_scope, module);
-    let value_handle = v8::Global::<v8::Value>::new(tc_scope, parsed_json);
-    self.json_value_store.insert(handle.clone(), value_handle);
-
+    let handle = self.new_synthetic_module(tc_scope, name_str, parsed_json);
     let id =
       self.create_module_info(name, ModuleType::Json, handle, false, vec![]);
 
     Ok(id)
   }
 
+  /// Create a text or bytes module, whose default export is the source.
+  fn new_raw_module(
+    &mut self,
+    scope: &mut v8::HandleScope,
+    name: &str,
+    module_type: ModuleType,
+    source: &[u8],
+  ) -> Result<ModuleId, ModuleError> {
+    let name_str = v8::String::new(scope, name).unwrap();
+    let value: v8::Local<v8::Value> = match module_type {
+      ModuleType::Text => {
+        let source = String::from_utf8_lossy(strip_bom(source));
+        v8::String::new(scope, &source)
+          .ok_or_else(|| {
+            ModuleError::Other(generic_error(format!(
+              "Text module \"{name}\" is too large."
+            )))
+          })?
+          .into()
+      }
+      ModuleType::Bytes => {
+        let len = source.len();
+        let backing_store =
+          v8::ArrayBuffer::new_backing_store_from_vec(source.to_vec())
+            .make_shared();
+        let buffer = v8::ArrayBuffer::with_backing_store(scope, &backing_store);
+        v8::Uint8Array::new(scope, buffer, 0, len).unwrap().into()
+      }
+      _ => unreachable!("{module_type} is not a raw module type"),
+    };
+
+    let handle = self.new_synthetic_module(scope, name_str, value);
+    let id = self.create_module_info(name, module_type, handle, false, vec![]);
+
+    Ok(id)
+  }
+
+  /// Create a WebAssembly module.
+  ///
+  /// The binary is compiled to a `WebAssembly.Module` that is exported by a
+  /// synthetic module. A wrapper ES module imports it along with the modules
+  /// it imports from, instantiates it and re-exports the exports of the
+  /// instance. The wrapper is what's registered under `name`, so the imports
+  /// of the WebAssembly module are loaded like any other part of the graph.
+  fn new_wasm_module(
+    &mut self,
+    scope: &mut v8::HandleScope,
+    main: bool,
+    name: &str,
+    source: &[u8],
+    is_dynamic_import: bool,
+  ) -> Result<ModuleId, ModuleError> {
+    let tc_scope = &mut v8::TryCatch::new(scope);
+    let maybe_compiled = v8::WasmModuleObject::compile(tc_scope, source)
+      .and_then(|module| {
+        let imports_and_exports =
+          wasm_module_imports_and_exports(tc_scope, module)?;
+        Some((module, imports_and_exports))
+      });
+    let (module, (imports, exports)) = match maybe_compiled {
+      Some(compiled) => compiled,
+      None => {
+        if tc_scope.has_caught() {
+          let exception = tc_scope.exception().unwrap();
+          let exception = v8::Global::new(tc_scope, exception);
+          return Err(ModuleError::Exception(exception));
+        }
+        return Err(ModuleError::Other(generic_error(format!(
+          "Failed to compile WebAssembly module \"{name}\"."
+        ))));
+      }
+    };
+
+    let name_str = v8::String::new(tc_scope, name).unwrap();
+    let handle = self.new_synthetic_module(tc_scope, name_str, module.into());
+    self.wasm_module_store.insert(name.to_string(), handle);
+
+    let wrapper_source = render_wasm_module_wrapper(name, &imports, &exports);
+    self.new_module_from_js_source(
+      tc_scope,
+      main,
+      ModuleType::Wasm,
+      name,
+      wrapper_source.as_bytes(),
+      is_dynamic_import,
+    )
+  }
+
+  /// Create a synthetic module whose only export is `value` as the default
+  /// export. The value is set when the module is evaluated.
+  fn new_synthetic_module(
+    &mut self,
+    scope: &mut v8::HandleScope,
+    name: v8::Local<v8::String>,
+    value: v8::Local<v8::Value>,
+  ) -> v8::Global<v8::Module> {
+    let export_names = [v8::String::new(scope, "default").unwrap()];
+    let module = v8::Module::create_synthetic_module(
+      scope,
+      name,
+      &export_names,
+      synthetic_module_evaluation_steps,
+    );
+
+    let handle = v8::Global::<v8::Module>::new(scope, module);
+    let value_handle = v8::Global::<v8::Value>::new(scope, value);
+    self
+      .synthetic_module_value_store
+      .insert(handle.clone(), value_handle);
+    handle
+  }
+
   // Create and compile an ES module.
   pub(crate) fn new_es_module(
     &mut self,
@@ -970,6 +1205,25 @@ impl ModuleMap {
     name: &str,
     source: &[u8],
     is_dynamic_import: bool,
+  ) -> Result<ModuleId, ModuleError> {
+    self.new_module_from_js_source(
+      scope,
+      main,
+      ModuleType::JavaScript,
+      name,
+      source,
+      is_dynamic_import,
+    )
+  }
+
+  fn new_module_from_js_source(
+    &mut self,
+    scope: &mut v8::HandleScope,
+    main: bool,
+    module_type: ModuleType,
+    name: &str,
+    source: &[u8],
+    is_dynamic_import: bool,
   ) -> Result<ModuleId, ModuleError> {
     let name_str = v8::String::new(scope, name).unwrap();
     let source_str =
@@ -1011,6 +1265,15 @@ impl ModuleMap {
         ImportAssertionsKind::StaticImport,
       );
 
+      // The wrapper of a WebAssembly module imports the compiled module,
+      // which is not part of the module graph.
+      if module_type == ModuleType::Wasm
+        && assertions.get("type").map(String::as_str)
+          == Some(WASM_MODULE_TYPE_ASSERTION)
+      {
+        continue;
+      }
+
       // FIXME(bartomieju): there are no stack frames if exception
       // is thrown here
       validate_import_assertions(tc_scope, &assertions);
@@ -1053,13 +1316,7 @@ impl ModuleMap {
     }
 
     let handle = v8::Global::<v8::Module>::new(tc_scope, module);
-    let id = self.create_module_info(
-      name,
-      ModuleType::JavaScript,
-      handle,
-      main,
-      requests,
-    );
+    let id = self.create_module_info(name, module_type, handle, main, requests);
 
     Ok(id)
   }
@@ -1135,6 +1392,25 @@ impl ModuleMap {
     self.handles.get(id).cloned()
   }
 
+  /// Drops the compiled WebAssembly modules whose wrapper module has been
+  /// instantiated, as they are only kept to resolve the import of the
+  /// wrapper.
+  pub(crate) fn clear_instantiated_wasm_modules(
+    &mut self,
+    scope: &mut v8::HandleScope,
+  ) {
+    let mut wasm_module_store = std::mem::take(&mut self.wasm_module_store);
+    wasm_module_store.retain(|name, _| {
+      self
+        .get_id(name, AssertedModuleType::JavaScriptOrWasm)
+        .and_then(|id| self.get_handle(id))
+        .map_or(false, |wrapper| {
+          wrapper.open(scope).get_status() == v8::ModuleStatus::Uninstantiated
+        })
+    });
+    self.wasm_module_store = wasm_module_store;
+  }
+
   pub(crate) fn get_info(
     &self,
     global: &v8::Global<v8::Module>,
@@ -1224,6 +1500,15 @@ impl ModuleMap {
     referrer: &str,
     import_assertions: HashMap<String, String>,
   ) -> Option<v8::Local<'s, v8::Module>> {
+    // The wrapper of a WebAssembly module imports the compiled module by its
+    // already resolved name.
+    if import_assertions.get("type").map(String::as_str)
+      == Some(WASM_MODULE_TYPE_ASSERTION)
+    {
+      let handle = self.wasm_module_store.get(specifier)?;
+      return Some(v8::Local::new(scope, handle));
+    }
+
     let resolved_specifier = self
       .loader
       .resolve(specifier, referrer, ResolutionKind::Import)
@@ -1772,6 +2057,114 @@ import "/a.js";
     futures::executor::block_on(receiver).unwrap().unwrap();
   }
 
+  #[test]
+  fn test_wasm_text_and_bytes_modules() {
+    struct ModsLoader;
+
+    // A WebAssembly module that imports `double` from "./double.js" and
+    // exports `add(a, b)`, which returns `double(a + b)`.
+    const MATH_WASM: &[u8] = &[
+      0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic and version
+      0x01, 0x0c, 0x02, 0x60, 0x01, 0x7f, 0x01, 0x7f, 0x60, 0x02, 0x7f, 0x7f,
+      0x01, 0x7f, // types: (i32) -> i32, (i32, i32) -> i32
+      0x02, 0x16, 0x01, 0x0b, b'.', b'/', b'd', b'o', b'u', b'b', b'l', b'e',
+      b'.', b'j', b's', 0x06, b'd', b'o', b'u', b'b', b'l', b'e', 0x00,
+      0x00, // import "./double.js" "double"
+      0x03, 0x02, 0x01, 0x01, // functions
+      0x07, 0x07, 0x01, 0x03, b'a', b'd', b'd', 0x00,
+      0x01, // export "add"
+      0x0a, 0x0b, 0x01, 0x09, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6a, 0x10, 0x00,
+      0x0b, // code
+    ];
+
+    impl ModuleLoader for ModsLoader {
+      fn resolve(
+        &self,
+        specifier: &str,
+        referrer: &str,
+        _kind: ResolutionKind,
+      ) -> Result<ModuleSpecifier, Error> {
+        Ok(resolve_import(specifier, referrer)?)
+      }
+
+      fn load(
+        &self,
+        module_specifier: &ModuleSpecifier,
+        _maybe_referrer: Option<ModuleSpecifier>,
+        _is_dyn_import: bool,
+      ) -> Pin<Box<ModuleSourceFuture>> {
+        let (code, module_type): (&[u8], _) = match module_specifier.as_str() {
+          "file:///main.js" => (
+            br#"
+              import { add } from "./math.wasm";
+              import text from "./hello.txt" assert { type: "text" };
+              import bytes from "./hello.txt" assert { type: "bytes" };
+              if (add(1, 2) !== 6) throw Error("add");
+              if (text !== "Hello world!") throw Error("text");
+              if (!(bytes instanceof Uint8Array)) throw Error("bytes");
+              if (bytes.length !== 12) throw Error("bytes length");
+            "#,
+            ModuleType::JavaScript,
+          ),
+          "file:///double.js" => (
+            b"export function double(x) { return x * 2; }",
+            ModuleType::JavaScript,
+          ),
+          "file:///math.wasm" => (MATH_WASM, ModuleType::Wasm),
+          // The loader doesn't know about text modules, the import assertion
+          // decides how the source is interpreted.
+          "file:///hello.txt" => (b"Hello world!", ModuleType::JavaScript),
+          _ => unreachable!(),
+        };
+        let module_source = ModuleSource {
+          code: code.to_vec().into_boxed_slice(),
+          module_type,
+          module_url_specified: module_specifier.to_string(),
+          module_url_found: module_specifier.to_string(),
+        };
+        async move { Ok(module_source) }.boxed()
+      }
+    }
+
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      module_loader: Some(Rc::new(ModsLoader)),
+      ..Default::default()
+    });
+
+    let spec = resolve_url("file:///main.js").unwrap();
+    let main_id =
+      futures::executor::block_on(runtime.load_main_module(&spec, None))
+        .unwrap();
+
+    let receiver = runtime.mod_evaluate(main_id);
+    futures::executor::block_on(runtime.run_event_loop(false)).unwrap();
+    futures::executor::block_on(receiver).unwrap().unwrap();
+
+    let module_map_rc = JsRuntime::module_map(runtime.v8_isolate());
+    let module_map = module_map_rc.borrow();
+    let wasm_id = module_map
+      .get_id("file:///math.wasm", AssertedModuleType::JavaScriptOrWasm)
+      .unwrap();
+    assert_eq!(
+      module_map.get_info_by_id(wasm_id).unwrap().module_type,
+      ModuleType::Wasm
+    );
+    assert_eq!(
+      module_map.get_requested_modules(wasm_id),
+      Some(&vec![ModuleRequest {
+        specifier: resolve_url("file:///double.js").unwrap(),
+        asserted_module_type: AssertedModuleType::JavaScriptOrWasm,
+      }])
+    );
+    assert!(module_map
+      .get_id("file:///hello.txt", AssertedModuleType::Text)
+      .is_some());
+    assert!(module_map
+      .get_id("file:///hello.txt", AssertedModuleType::Bytes)
+      .is_some());
+    assert!(module_map.wasm_module_store.is_empty());
+  }
+
   #[test]
   fn dyn_import_err() {
     #[derive(Clone, Default)]
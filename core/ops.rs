@@ -15,7 +15,9 @@ use futures::ready;
 use futures::task::noop_waker;
 use futures::Future;
 use serde::Serialize;
+use std::cell::Cell;
 use std::cell::RefCell;
+use std::fmt::Write as _;
 use std::ops::Deref;
 use std::ops::DerefMut;
 use std::pin::Pin;
@@ -150,6 +152,102 @@ pub fn to_op_result<R: Serialize + 'static>(
   }
 }
 
+/// The maximum number of JS stack frames in the trace of an op call.
+const OP_TRACE_FRAME_LIMIT: usize = 10;
+
+thread_local! {
+  static CURRENT_OP_TRACE: RefCell<Option<Rc<str>>> = RefCell::new(None);
+}
+
+/// Returns the JS stack trace of the op call which is running on this
+/// thread, if op tracing is enabled, see [`JsRuntime::enable_op_tracing`].
+/// It is `None` for fast calls.
+///
+/// [`JsRuntime::enable_op_tracing`]: crate::JsRuntime::enable_op_tracing
+pub fn current_op_trace() -> Option<Rc<str>> {
+  CURRENT_OP_TRACE.with(|trace| trace.borrow().clone())
+}
+
+/// Sets the stack trace of the op call which is running and returns the
+/// previous one, which must be restored when the op call returns.
+pub(crate) fn set_current_op_trace(trace: Option<Rc<str>>) -> Option<Rc<str>> {
+  CURRENT_OP_TRACE.with(|current| current.replace(trace))
+}
+
+/// Returns the JS stack trace of the op call, formatted like the frames of
+/// `Error.prototype.stack`, if op tracing is enabled.
+pub(crate) fn op_trace(
+  ctx: &OpCtx,
+  scope: &mut v8::HandleScope,
+) -> Option<Rc<str>> {
+  if !ctx.op_tracing.get() {
+    return None;
+  }
+  let stack = v8::StackTrace::current_stack_trace(scope, OP_TRACE_FRAME_LIMIT)?;
+  let mut trace = String::new();
+  for i in 0..stack.get_frame_count() {
+    let frame = match stack.get_frame(scope, i) {
+      Some(frame) => frame,
+      None => continue,
+    };
+    let location = match frame.get_script_name_or_source_url(scope) {
+      Some(name) => format!(
+        "{}:{}:{}",
+        name.to_rust_string_lossy(scope),
+        frame.get_line_number(),
+        frame.get_column()
+      ),
+      None => "<anonymous>".to_string(),
+    };
+    match frame.get_function_name(scope) {
+      Some(name) if name.length() > 0 => writeln!(
+        trace,
+        "    at {} ({})",
+        name.to_rust_string_lossy(scope),
+        location
+      ),
+      _ => writeln!(trace, "    at {location}"),
+    }
+    .unwrap();
+  }
+  let trace = trace.trim_end();
+  if trace.is_empty() {
+    return None;
+  }
+  Some(trace.into())
+}
+
+/// Sets the JS stack trace of a sync op call as the current op trace, while
+/// op tracing is enabled. The trace of the enclosing op call, if any, is
+/// restored when the guard is dropped as the op returns.
+pub struct OpTraceGuard {
+  traced: bool,
+  prev_trace: Option<Rc<str>>,
+}
+
+impl OpTraceGuard {
+  pub fn enter(ctx: &OpCtx, scope: &mut v8::HandleScope) -> Self {
+    match op_trace(ctx, scope) {
+      Some(trace) => Self {
+        traced: true,
+        prev_trace: set_current_op_trace(Some(trace)),
+      },
+      None => Self {
+        traced: false,
+        prev_trace: None,
+      },
+    }
+  }
+}
+
+impl Drop for OpTraceGuard {
+  fn drop(&mut self) {
+    if self.traced {
+      set_current_op_trace(self.prev_trace.take());
+    }
+  }
+}
+
 // TODO(@AaronO): optimize OpCtx(s) mem usage ?
 pub struct OpCtx {
   pub id: OpId,
@@ -158,6 +256,9 @@ pub struct OpCtx {
   pub runtime_state: Weak<RefCell<JsRuntimeState>>,
   // Index of the current realm into `JsRuntimeState::known_realms`.
   pub realm_idx: RealmIdx,
+  /// Whether the JS stack trace of op calls is captured. It is shared by the
+  /// ops of all realms, so it can be checked without borrowing the state.
+  pub op_tracing: Rc<Cell<bool>>,
 }
 
 /// Maintains the resources and ops inside a JS runtime.
@@ -31,6 +31,7 @@ pub(crate) fn init_builtins() -> Extension {
       op_try_close::decl(),
       op_print::decl(),
       op_resources::decl(),
+      op_resource_traces::decl(),
       op_wasm_streaming_feed::decl(),
       op_wasm_streaming_set_url::decl(),
       op_void_sync::decl(),
@@ -62,6 +63,21 @@ pub fn op_resources(state: &mut OpState) -> Vec<(ResourceId, String)> {
     .collect()
 }
 
+/// Return map of resources with id as key and the JS stack trace of where
+/// they were created as value, for the resources which were traced.
+#[op]
+pub fn op_resource_traces(state: &mut OpState) -> Vec<(ResourceId, String)> {
+  let resource_table = &state.resource_table;
+  resource_table
+    .names()
+    .filter_map(|(rid, _)| {
+      resource_table
+        .trace(rid)
+        .map(|trace| (rid, trace.to_string()))
+    })
+    .collect()
+}
+
 #[op(fast)]
 fn op_add(a: i32, b: i32) -> i32 {
   a + b
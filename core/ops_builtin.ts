@@ -30,6 +30,11 @@ export const op_resources = () => {
   console.warn("Not implemented: ops.op_resources");
 }
 
+export const op_resource_traces = (): [number, string][] => {
+  console.warn("Not implemented: ops.op_resource_traces");
+  return [];
+}
+
 export const op_wasm_streaming_feed = (...args: any[]) => {
   console.warn("Not implemented: ops.op_wasm_streaming_feed");
 }
@@ -11,6 +11,7 @@ use crate::error::not_supported;
 use crate::io::BufMutView;
 use crate::io::BufView;
 use crate::io::WriteOutcome;
+use crate::ops::current_op_trace;
 use anyhow::Error;
 use futures::Future;
 use std::any::type_name;
@@ -18,6 +19,7 @@ use std::any::Any;
 use std::any::TypeId;
 use std::borrow::Cow;
 use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::iter::Iterator;
 use std::pin::Pin;
 use std::rc::Rc;
@@ -216,10 +218,15 @@ pub type ResourceId = u32;
 ///
 /// Each resource is identified through a _resource ID (rid)_, which acts as
 /// the key in the map.
+///
+/// When tracing is enabled, the table also remembers the JS stack trace of the
+/// op call which created each resource, for reporting leaked resources.
 #[derive(Default)]
 pub struct ResourceTable {
   index: BTreeMap<ResourceId, Rc<dyn Resource>>,
   next_rid: ResourceId,
+  /// The creation traces of the resources, if tracing is enabled.
+  traces: Option<HashMap<ResourceId, Rc<str>>>,
 }
 
 impl ResourceTable {
@@ -248,6 +255,11 @@ impl ResourceTable {
     let rid = self.next_rid;
     let removed_resource = self.index.insert(rid, resource);
     assert!(removed_resource.is_none());
+    if let Some(traces) = &mut self.traces {
+      if let Some(trace) = current_op_trace() {
+        traces.insert(rid, trace);
+      }
+    }
     self.next_rid += 1;
     rid
   }
@@ -300,6 +312,7 @@ impl ResourceTable {
   pub fn take<T: Resource>(&mut self, rid: ResourceId) -> Result<Rc<T>, Error> {
     let resource = self.get::<T>(rid)?;
     self.index.remove(&rid);
+    self.remove_trace(rid);
     Ok(resource)
   }
 
@@ -315,7 +328,9 @@ impl ResourceTable {
     &mut self,
     rid: ResourceId,
   ) -> Result<Rc<dyn Resource>, Error> {
-    self.index.remove(&rid).ok_or_else(bad_resource_id)
+    let resource = self.index.remove(&rid).ok_or_else(bad_resource_id)?;
+    self.remove_trace(rid);
+    Ok(resource)
   }
 
   /// Removes the resource with the given `rid` from the resource table. If the
@@ -325,11 +340,10 @@ impl ResourceTable {
   /// may implement the `close()` method to perform clean-ups such as canceling
   /// ops.
   pub fn close(&mut self, rid: ResourceId) -> Result<(), Error> {
-    self
-      .index
-      .remove(&rid)
-      .ok_or_else(bad_resource_id)
-      .map(|resource| resource.close())
+    let resource = self.index.remove(&rid).ok_or_else(bad_resource_id)?;
+    self.remove_trace(rid);
+    resource.close();
+    Ok(())
   }
 
   /// Returns an iterator that yields a `(id, name)` pair for every resource
@@ -350,6 +364,36 @@ impl ResourceTable {
       .iter()
       .map(|(&id, resource)| (id, resource.name()))
   }
+
+  /// Starts recording the JS stack trace of the op call which creates each
+  /// resource from now on, see [`ResourceTable::trace`]. The traces are
+  /// captured by the runtime while op tracing is enabled with
+  /// [`JsRuntime::enable_op_tracing`], which has a significant performance
+  /// cost, so this is meant for debugging leaked resources, e.g. in the
+  /// resource sanitizer of `deno test`.
+  ///
+  /// [`JsRuntime::enable_op_tracing`]: crate::JsRuntime::enable_op_tracing
+  pub fn enable_tracing(&mut self) {
+    self.traces.get_or_insert_with(HashMap::new);
+  }
+
+  pub fn is_tracing_enabled(&self) -> bool {
+    self.traces.is_some()
+  }
+
+  /// Returns the JS stack trace of the op call which created the resource
+  /// with the given `rid`. This is `None` if tracing wasn't enabled at that
+  /// time, or the resource was created outside of an op call, or by a fast
+  /// call.
+  pub fn trace(&self, rid: ResourceId) -> Option<&str> {
+    self.traces.as_ref()?.get(&rid).map(|trace| &**trace)
+  }
+
+  fn remove_trace(&mut self, rid: ResourceId) {
+    if let Some(traces) = &mut self.traces {
+      traces.remove(&rid);
+    }
+  }
 }
 
 #[macro_export]
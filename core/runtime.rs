@@ -27,6 +27,7 @@ use crate::PromiseId;
 use anyhow::Error;
 use futures::channel::oneshot;
 use futures::future::poll_fn;
+use futures::future::Either;
 use futures::future::Future;
 use futures::future::FutureExt;
 use futures::stream::FuturesUnordered;
@@ -34,6 +35,7 @@ use futures::stream::StreamExt;
 use futures::task::AtomicWaker;
 use smallvec::SmallVec;
 use std::any::Any;
+use std::cell::Cell;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::collections::HashSet;
@@ -181,6 +183,9 @@ pub struct JsRuntimeState {
   pub(crate) pending_ops: FuturesUnordered<PendingOpFuture>,
   pub(crate) have_unpolled_ops: bool,
   pub(crate) op_state: Rc<RefCell<OpState>>,
+  /// Whether the JS stack trace of op calls is captured, shared with the
+  /// `OpCtx` of each op.
+  pub(crate) op_tracing: Rc<Cell<bool>>,
   pub(crate) shared_array_buffer_store: Option<SharedArrayBufferStore>,
   pub(crate) compiled_wasm_module_store: Option<CompiledWasmModuleStore>,
   /// The error that was passed to an `op_dispatch_exception` call.
@@ -384,6 +389,7 @@ impl JsRuntime {
       // SAFETY: we just asserted that layout has non-0 size.
       unsafe { std::alloc::alloc(layout) as *mut _ };
 
+    let op_tracing = Rc::new(Cell::new(false));
     let state_rc = Rc::new(RefCell::new(JsRuntimeState {
       pending_dyn_mod_evaluate: vec![],
       pending_mod_evaluate: None,
@@ -397,6 +403,7 @@ impl JsRuntime {
       shared_array_buffer_store: options.shared_array_buffer_store,
       compiled_wasm_module_store: options.compiled_wasm_module_store,
       op_state: op_state.clone(),
+      op_tracing: op_tracing.clone(),
       waker: AtomicWaker::new(),
       have_unpolled_ops: false,
       dispatched_exceptions: Default::default(),
@@ -416,6 +423,7 @@ impl JsRuntime {
         runtime_state: weak.clone(),
         decl: Rc::new(decl),
         realm_idx: 0,
+        op_tracing: op_tracing.clone(),
       })
       .collect::<Vec<_>>()
       .into_boxed_slice();
@@ -723,6 +731,7 @@ impl JsRuntime {
           decl: op_ctx.decl.clone(),
           runtime_state: op_ctx.runtime_state.clone(),
           realm_idx,
+          op_tracing: op_ctx.op_tracing.clone(),
         })
         .collect();
 
@@ -950,6 +959,14 @@ impl JsRuntime {
     state.op_state.clone()
   }
 
+  /// Starts capturing the JS stack trace of each op call, which is available
+  /// with [`current_op_trace`](crate::current_op_trace) while the op runs
+  /// and recorded for the resources it creates if the resource table traces
+  /// them. This has a significant performance cost.
+  pub fn enable_op_tracing(&mut self) {
+    self.state.borrow().op_tracing.set(true);
+  }
+
   /// Executes traditional JavaScript code (traditional = not ES modules).
   ///
   /// The execution takes place on the current global context, so it is possible
@@ -1608,6 +1625,10 @@ impl JsRuntime {
       return Err(v8::Global::new(tc_scope, exception));
     }
 
+    module_map_rc
+      .borrow_mut()
+      .clear_instantiated_wasm_modules(tc_scope);
+
     Ok(())
   }
 
@@ -2660,6 +2681,20 @@ pub fn queue_async_op(
     Some(scope.get_current_context())
   );
 
+  // the op call is the current op trace while the op is polled
+  let op = match op_trace(ctx, scope) {
+    Some(trace) => {
+      let mut op = Box::pin(op);
+      Either::Left(poll_fn(move |cx| {
+        let prev_trace = set_current_op_trace(Some(trace.clone()));
+        let poll = op.as_mut().poll(cx);
+        set_current_op_trace(prev_trace);
+        poll
+      }))
+    }
+    None => Either::Right(op),
+  };
+
   match OpCall::eager(op) {
     // This calls promise.resolve() before the control goes back to userland JS. It works something
     // along the lines of:
@@ -3965,6 +4000,153 @@ assertEquals(1, notify_return_value);
     runtime.run_event_loop(false).await.unwrap();
   }
 
+  #[tokio::test]
+  async fn test_op_traces() {
+    #[derive(Default)]
+    struct Traces(Vec<Option<String>>);
+
+    #[op]
+    fn op_record_trace(state: &mut OpState) {
+      let trace = crate::current_op_trace().map(|trace| trace.to_string());
+      state.borrow_mut::<Traces>().0.push(trace);
+    }
+
+    #[op]
+    async fn op_record_trace_async(state: Rc<RefCell<OpState>>) {
+      // the trace is recorded after the op call returned
+      tokio::task::yield_now().await;
+      let trace = crate::current_op_trace().map(|trace| trace.to_string());
+      state.borrow_mut().borrow_mut::<Traces>().0.push(trace);
+    }
+
+    let extension = Extension::builder("test_ext")
+      .ops(vec![op_record_trace::decl(), op_record_trace_async::decl()])
+      .state(|state| {
+        state.put(Traces::default());
+        Ok(())
+      })
+      .build();
+
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      extensions: vec![extension],
+      ..Default::default()
+    });
+
+    runtime
+      .execute_script("untraced.js", "Deno.core.ops.op_record_trace()")
+      .unwrap();
+    runtime.enable_op_tracing();
+    runtime
+      .execute_script(
+        "op_traces.js",
+        r#"
+Deno.core.initializeAsyncOps();
+function recordTrace() {
+  Deno.core.ops.op_record_trace();
+}
+function recordTraceAsync() {
+  Deno.core.ops.op_record_trace_async();
+}
+recordTrace();
+recordTraceAsync();
+"#,
+      )
+      .unwrap();
+    runtime.run_event_loop(false).await.unwrap();
+    assert_eq!(crate::current_op_trace(), None);
+
+    let state = runtime.op_state();
+    let state = state.borrow();
+    let traces = &state.borrow::<Traces>().0;
+    assert_eq!(traces.len(), 3);
+    assert_eq!(traces[0], None);
+    let trace = traces[1].as_deref().unwrap();
+    assert!(
+      trace.starts_with("    at recordTrace (op_traces.js:4:"),
+      "{trace}"
+    );
+    let trace = traces[2].as_deref().unwrap();
+    assert!(
+      trace.contains("    at recordTraceAsync (op_traces.js:7:"),
+      "{trace}"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_resource_traces() {
+    struct TestResource;
+
+    impl crate::Resource for TestResource {}
+
+    #[op]
+    fn op_create_resource(state: &mut OpState) -> crate::ResourceId {
+      state.resource_table.add(TestResource)
+    }
+
+    #[op]
+    async fn op_create_resource_async(
+      state: Rc<RefCell<OpState>>,
+    ) -> crate::ResourceId {
+      // the resource is created after the op call returned
+      tokio::task::yield_now().await;
+      state.borrow_mut().resource_table.add(TestResource)
+    }
+
+    let extension = Extension::builder("test_ext")
+      .ops(vec![
+        op_create_resource::decl(),
+        op_create_resource_async::decl(),
+      ])
+      .build();
+
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      extensions: vec![extension],
+      ..Default::default()
+    });
+
+    runtime
+      .execute_script("untraced.js", "Deno.core.ops.op_create_resource()")
+      .unwrap();
+    runtime.enable_op_tracing();
+    runtime
+      .op_state()
+      .borrow_mut()
+      .resource_table
+      .enable_tracing();
+    runtime
+      .execute_script(
+        "resource_traces.js",
+        r#"
+Deno.core.initializeAsyncOps();
+function createResource() {
+  Deno.core.ops.op_create_resource();
+}
+function createResourceAsync() {
+  Deno.core.ops.op_create_resource_async();
+}
+createResource();
+createResourceAsync();
+"#,
+      )
+      .unwrap();
+    runtime.run_event_loop(false).await.unwrap();
+
+    let state = runtime.op_state();
+    let state = state.borrow();
+    let resource_table = &state.resource_table;
+    assert_eq!(resource_table.trace(0), None);
+    let trace = resource_table.trace(1).unwrap();
+    assert!(
+      trace.starts_with("    at createResource (resource_traces.js:4:"),
+      "{trace}"
+    );
+    let trace = resource_table.trace(2).unwrap();
+    assert!(
+      trace.contains("    at createResourceAsync (resource_traces.js:7:"),
+      "{trace}"
+    );
+  }
+
   #[tokio::test]
   async fn test_sync_op_serialize_object_with_numbers_as_keys() {
     #[op]
@@ -0,0 +1,530 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+"use strict";
+
+import { primordials } from '../../core/00_primordials.js';
+import * as core from '../../core/01_core.js';
+import { forgivingBase64Decode, forgivingBase64Encode } from '../web/00_infra.js';
+const {
+  ArrayIsArray,
+  ArrayPrototypeMap,
+  ArrayPrototypePush,
+  ArrayPrototypeReverse,
+  ArrayPrototypeShift,
+  BigInt,
+  DataView,
+  DataViewPrototypeGetFloat64,
+  DataViewPrototypeSetFloat64,
+  MathMin,
+  Number,
+  ObjectPrototypeIsPrototypeOf,
+  RangeError,
+  Symbol,
+  SymbolAsyncIterator,
+  TypeError,
+  TypedArrayPrototypeSet,
+  TypedArrayPrototypeSubarray,
+  Uint8Array,
+  Uint8ArrayPrototype,
+} = primordials;
+
+// Key parts are encoded so that comparing the encoded keys byte by byte
+// orders them like the tuples:
+// Uint8Array < string < number < bigint < boolean.
+const BYTES = 0x01;
+const STRING = 0x02;
+const NUMBER = 0x21;
+const NEGATIVE_BIGINT = 0x22;
+const POSITIVE_BIGINT = 0x23;
+const FALSE = 0x26;
+const TRUE = 0x27;
+
+export type KvKeyPart = Uint8Array | string | number | bigint | boolean;
+export type KvKey = KvKeyPart[];
+
+class KeyWriter {
+  #buf = new Uint8Array(64);
+  #len = 0;
+
+  #reserve(n: number) {
+    if (this.#len + n <= this.#buf.length) return;
+    let size = this.#buf.length * 2;
+    while (size < this.#len + n) size *= 2;
+    const buf = new Uint8Array(size);
+    TypedArrayPrototypeSet(buf, TypedArrayPrototypeSubarray(this.#buf, 0, this.#len));
+    this.#buf = buf;
+  }
+
+  byte(b: number) {
+    this.#reserve(1);
+    this.#buf[this.#len++] = b;
+  }
+
+  bytes(bytes: Uint8Array) {
+    this.#reserve(bytes.length);
+    TypedArrayPrototypeSet(this.#buf, bytes, this.#len);
+    this.#len += bytes.length;
+  }
+
+  /** Writes the bytes with 0x00 escaped as 0x00 0xff, followed by 0x00. */
+  escaped(bytes: Uint8Array) {
+    for (let i = 0; i < bytes.length; ++i) {
+      this.byte(bytes[i]);
+      if (bytes[i] === 0x00) this.byte(0xff);
+    }
+    this.byte(0x00);
+  }
+
+  finish(): Uint8Array {
+    return TypedArrayPrototypeSubarray(this.#buf, 0, this.#len);
+  }
+}
+
+function bigintToBytes(n: bigint): Uint8Array {
+  const bytes = [];
+  while (n > 0n) {
+    ArrayPrototypePush(bytes, Number(n & 0xffn));
+    n >>= 8n;
+  }
+  if (bytes.length > 255) {
+    throw new RangeError("BigInt key part is too large");
+  }
+  return new Uint8Array(ArrayPrototypeReverse(bytes));
+}
+
+export function encodeKey(key: KvKey): Uint8Array {
+  if (!ArrayIsArray(key)) {
+    throw new TypeError("Key must be an array");
+  }
+  const writer = new KeyWriter();
+  for (let i = 0; i < key.length; ++i) {
+    const part = key[i];
+    if (ObjectPrototypeIsPrototypeOf(Uint8ArrayPrototype, part)) {
+      writer.byte(BYTES);
+      writer.escaped(part as Uint8Array);
+    } else if (typeof part === "string") {
+      writer.byte(STRING);
+      writer.escaped(core.encode(part));
+    } else if (typeof part === "number") {
+      const buf = new Uint8Array(8);
+      DataViewPrototypeSetFloat64(new DataView(buf.buffer), 0, part);
+      if (buf[0] & 0x80) {
+        // negative numbers sort in reverse order of their magnitude
+        for (let j = 0; j < 8; ++j) buf[j] ^= 0xff;
+      } else {
+        buf[0] ^= 0x80;
+      }
+      writer.byte(NUMBER);
+      writer.bytes(buf);
+    } else if (typeof part === "bigint") {
+      if (part < 0n) {
+        const magnitude = bigintToBytes(-part);
+        writer.byte(NEGATIVE_BIGINT);
+        writer.byte(255 - magnitude.length);
+        for (let j = 0; j < magnitude.length; ++j) {
+          writer.byte(magnitude[j] ^ 0xff);
+        }
+      } else {
+        const magnitude = bigintToBytes(part);
+        writer.byte(POSITIVE_BIGINT);
+        writer.byte(magnitude.length);
+        writer.bytes(magnitude);
+      }
+    } else if (typeof part === "boolean") {
+      writer.byte(part ? TRUE : FALSE);
+    } else {
+      throw new TypeError(
+        `Invalid key part at index ${i}: expected Uint8Array, string, number, bigint or boolean`,
+      );
+    }
+  }
+  return writer.finish();
+}
+
+function readEscaped(buf: Uint8Array, pos: number): [Uint8Array, number] {
+  const bytes = [];
+  while (pos < buf.length) {
+    const b = buf[pos++];
+    if (b === 0x00) {
+      if (buf[pos] === 0xff) {
+        pos++;
+      } else {
+        return [new Uint8Array(bytes), pos];
+      }
+    }
+    ArrayPrototypePush(bytes, b);
+  }
+  throw new TypeError("Invalid key: unterminated key part");
+}
+
+export function decodeKey(buf: Uint8Array): KvKey {
+  const key = [];
+  let pos = 0;
+  while (pos < buf.length) {
+    const type = buf[pos++];
+    switch (type) {
+      case BYTES: {
+        let bytes;
+        [bytes, pos] = readEscaped(buf, pos);
+        ArrayPrototypePush(key, bytes);
+        break;
+      }
+      case STRING: {
+        let bytes;
+        [bytes, pos] = readEscaped(buf, pos);
+        ArrayPrototypePush(key, core.decode(bytes));
+        break;
+      }
+      case NUMBER: {
+        const bytes = new Uint8Array(8);
+        TypedArrayPrototypeSet(bytes, TypedArrayPrototypeSubarray(buf, pos, pos + 8));
+        pos += 8;
+        if (bytes[0] & 0x80) {
+          bytes[0] ^= 0x80;
+        } else {
+          for (let j = 0; j < 8; ++j) bytes[j] ^= 0xff;
+        }
+        ArrayPrototypePush(
+          key,
+          DataViewPrototypeGetFloat64(new DataView(bytes.buffer), 0),
+        );
+        break;
+      }
+      case NEGATIVE_BIGINT:
+      case POSITIVE_BIGINT: {
+        const negative = type === NEGATIVE_BIGINT;
+        const len = negative ? 255 - buf[pos++] : buf[pos++];
+        let n = 0n;
+        for (let j = 0; j < len; ++j) {
+          const b = negative ? buf[pos++] ^ 0xff : buf[pos++];
+          n = (n << 8n) | BigInt(b);
+        }
+        ArrayPrototypePush(key, negative ? -n : n);
+        break;
+      }
+      case FALSE:
+        ArrayPrototypePush(key, false);
+        break;
+      case TRUE:
+        ArrayPrototypePush(key, true);
+        break;
+      default:
+        throw new TypeError(`Invalid key: unknown key part type ${type}`);
+    }
+  }
+  return key;
+}
+
+function concat(a: Uint8Array, b: number): Uint8Array {
+  const buf = new Uint8Array(a.length + 1);
+  TypedArrayPrototypeSet(buf, a);
+  buf[a.length] = b;
+  return buf;
+}
+
+function startsWith(key: Uint8Array, prefix: Uint8Array): boolean {
+  if (key.length < prefix.length) return false;
+  for (let i = 0; i < prefix.length; ++i) {
+    if (key[i] !== prefix[i]) return false;
+  }
+  return true;
+}
+
+function compareBytes(a: Uint8Array, b: Uint8Array): number {
+  const len = MathMin(a.length, b.length);
+  for (let i = 0; i < len; ++i) {
+    if (a[i] !== b[i]) return a[i] - b[i];
+  }
+  return a.length - b.length;
+}
+
+function serializeValue(value: unknown): Uint8Array {
+  return core.serialize(value, {}, (err) => {
+    throw new TypeError(`Value is not serializable: ${err}`);
+  });
+}
+
+function deserializeValue(value: Uint8Array): unknown {
+  return core.deserialize(value);
+}
+
+export interface KvEntry {
+  key: KvKey;
+  value: unknown;
+  versionstamp: string | null;
+}
+
+function toEntry([key, value, versionstamp]: [Uint8Array, Uint8Array, string]): KvEntry {
+  return {
+    key: decodeKey(key),
+    value: deserializeValue(value),
+    versionstamp,
+  };
+}
+
+const _rid = Symbol("[[rid]]");
+const illegalConstructorKey = Symbol("illegalConstructorKey");
+
+export async function openKv(path?: string) {
+  const rid = await core.opAsync("op_kv_database_open", path ?? null);
+  return new Kv(illegalConstructorKey, rid);
+}
+
+export class Kv {
+  // @ts-ignore
+  [_rid]: number;
+
+  constructor(key = undefined, rid = undefined) {
+    if (key !== illegalConstructorKey) {
+      throw new TypeError("Illegal constructor");
+    }
+    this[_rid] = rid;
+  }
+
+  atomic() {
+    return new AtomicOperation(this[_rid]);
+  }
+
+  async get(key: KvKey, _opts?: { consistency?: "strong" | "eventual" }) {
+    const start = encodeKey(key);
+    const [entries] = await core.opAsync(
+      "op_kv_snapshot_read",
+      this[_rid],
+      [[start, concat(start, 0x00), 1, false]],
+    );
+    if (entries.length === 0) {
+      return { key, value: null, versionstamp: null };
+    }
+    return toEntry(entries[0]);
+  }
+
+  async getMany(keys: KvKey[], _opts?: { consistency?: "strong" | "eventual" }) {
+    const ranges = ArrayPrototypeMap(keys, (key) => {
+      const start = encodeKey(key);
+      return [start, concat(start, 0x00), 1, false];
+    });
+    const results = await core.opAsync(
+      "op_kv_snapshot_read",
+      this[_rid],
+      ranges,
+    );
+    return ArrayPrototypeMap(results, (entries, i) => {
+      if (entries.length === 0) {
+        return { key: keys[i], value: null, versionstamp: null };
+      }
+      return toEntry(entries[0]);
+    });
+  }
+
+  async set(key: KvKey, value: unknown) {
+    const result = await this.atomic().set(key, value).commit();
+    if (!result.ok) throw new TypeError("Failed to set value");
+    return result;
+  }
+
+  async delete(key: KvKey) {
+    const result = await this.atomic().delete(key).commit();
+    if (!result.ok) throw new TypeError("Failed to delete value");
+  }
+
+  list(selector: KvListSelector, options: KvListOptions = {}) {
+    if (options.limit !== undefined && options.limit <= 0) {
+      throw new RangeError("limit must be positive");
+    }
+    let batchSize = options.batchSize ?? (options.limit ?? 100);
+    if (batchSize <= 0) throw new RangeError("batchSize must be positive");
+    batchSize = MathMin(batchSize, 500);
+    return new KvListIterator(illegalConstructorKey, {
+      rid: this[_rid],
+      selector,
+      limit: options.limit,
+      cursor: options.cursor,
+      reverse: options.reverse ?? false,
+      batchSize,
+    });
+  }
+
+  close() {
+    core.close(this[_rid]);
+  }
+}
+
+export type KvListSelector =
+  | { prefix: KvKey }
+  | { prefix: KvKey; start: KvKey }
+  | { prefix: KvKey; end: KvKey }
+  | { start: KvKey; end: KvKey };
+
+export interface KvListOptions {
+  limit?: number;
+  cursor?: string;
+  reverse?: boolean;
+  consistency?: "strong" | "eventual";
+  batchSize?: number;
+}
+
+/** Returns the `[start, end)` range of encoded keys matched by the selector. */
+function selectorToRange(selector: KvListSelector): [Uint8Array, Uint8Array] {
+  // @ts-ignore
+  const { prefix, start, end } = selector;
+  if (prefix !== undefined) {
+    const encodedPrefix = encodeKey(prefix);
+    const firstKey = concat(encodedPrefix, 0x00);
+    const lastKey = concat(encodedPrefix, 0xff);
+    if (start !== undefined && end !== undefined) {
+      throw new TypeError("Selector can not specify both 'start' and 'end' with 'prefix'");
+    }
+    if (start !== undefined) {
+      const encodedStart = encodeKey(start);
+      if (!startsWith(encodedStart, encodedPrefix)) {
+        throw new TypeError("Start key is not in the keyspace defined by prefix");
+      }
+      return [encodedStart, lastKey];
+    }
+    if (end !== undefined) {
+      const encodedEnd = encodeKey(end);
+      if (!startsWith(encodedEnd, encodedPrefix)) {
+        throw new TypeError("End key is not in the keyspace defined by prefix");
+      }
+      return [firstKey, encodedEnd];
+    }
+    return [firstKey, lastKey];
+  }
+  if (start !== undefined && end !== undefined) {
+    return [encodeKey(start), encodeKey(end)];
+  }
+  throw new TypeError("Selector must specify either 'prefix' or both 'start' and 'end'");
+}
+
+export class KvListIterator {
+  #rid: number;
+  #start: Uint8Array;
+  #end: Uint8Array;
+  #remaining: number;
+  #reverse: boolean;
+  #batchSize: number;
+  #entries: [Uint8Array, Uint8Array, string][] = [];
+  #done = false;
+  #cursor: string | undefined = undefined;
+
+  constructor(key, { rid, selector, limit, cursor, reverse, batchSize }) {
+    if (key !== illegalConstructorKey) {
+      throw new TypeError("Illegal constructor");
+    }
+    this.#rid = rid;
+    [this.#start, this.#end] = selectorToRange(selector);
+    this.#remaining = limit ?? Infinity;
+    this.#reverse = reverse;
+    this.#batchSize = batchSize;
+    if (cursor !== undefined) {
+      // The cursor is the last key that was returned, so continue after it.
+      const lastKey = forgivingBase64Decode(cursor);
+      if (reverse) {
+        if (compareBytes(lastKey, this.#end) < 0) this.#end = lastKey;
+      } else {
+        const next = concat(lastKey, 0x00);
+        if (compareBytes(next, this.#start) > 0) this.#start = next;
+      }
+    }
+  }
+
+  /** The cursor to continue listing after the last entry that was returned. */
+  get cursor(): string {
+    if (this.#cursor === undefined) {
+      throw new TypeError("Cannot get cursor before first iteration");
+    }
+    return this.#cursor;
+  }
+
+  async next(): Promise<IteratorResult<KvEntry>> {
+    if (this.#entries.length === 0 && !this.#done) {
+      await this.#fetchBatch();
+    }
+    const entry = ArrayPrototypeShift(this.#entries);
+    if (entry === undefined) {
+      this.#cursor ??= "";
+      return { done: true, value: undefined };
+    }
+    this.#cursor = forgivingBase64Encode(entry[0]);
+    return { done: false, value: toEntry(entry) };
+  }
+
+  async #fetchBatch() {
+    const limit = MathMin(this.#batchSize, this.#remaining);
+    if (limit <= 0 || compareBytes(this.#start, this.#end) >= 0) {
+      this.#done = true;
+      return;
+    }
+    const [entries] = await core.opAsync(
+      "op_kv_snapshot_read",
+      this.#rid,
+      [[this.#start, this.#end, limit, this.#reverse]],
+    );
+    this.#entries = entries;
+    this.#remaining -= entries.length;
+    if (entries.length < limit) {
+      this.#done = true;
+    } else {
+      const lastKey = entries[entries.length - 1][0];
+      if (this.#reverse) {
+        this.#end = lastKey;
+      } else {
+        this.#start = concat(lastKey, 0x00);
+      }
+    }
+  }
+
+  [SymbolAsyncIterator]() {
+    return this;
+  }
+}
+
+export interface AtomicCheck {
+  key: KvKey;
+  versionstamp: string | null;
+}
+
+export class AtomicOperation {
+  #rid: number;
+  #checks: [Uint8Array, string | null][] = [];
+  #mutations: [Uint8Array, string, Uint8Array | null][] = [];
+
+  constructor(rid: number) {
+    this.#rid = rid;
+  }
+
+  check(...checks: AtomicCheck[]) {
+    for (const check of checks) {
+      ArrayPrototypePush(this.#checks, [
+        encodeKey(check.key),
+        check.versionstamp,
+      ]);
+    }
+    return this;
+  }
+
+  set(key: KvKey, value: unknown) {
+    ArrayPrototypePush(this.#mutations, [
+      encodeKey(key),
+      "set",
+      serializeValue(value),
+    ]);
+    return this;
+  }
+
+  delete(key: KvKey) {
+    ArrayPrototypePush(this.#mutations, [encodeKey(key), "delete", null]);
+    return this;
+  }
+
+  async commit() {
+    const versionstamp = await core.opAsync(
+      "op_kv_atomic_write",
+      this.#rid,
+      this.#checks,
+      this.#mutations,
+    );
+    if (versionstamp === null) return { ok: false };
+    return { ok: true, versionstamp };
+  }
+}
@@ -0,0 +1,364 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+mod sqlite;
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::path::Path;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::include_js_files;
+use deno_core::op;
+use deno_core::Extension;
+use deno_core::OpState;
+use deno_core::Resource;
+use deno_core::ResourceId;
+use deno_core::ZeroCopyBuf;
+
+pub use sqlite::SqliteDb;
+
+const MAX_WRITE_KEY_SIZE_BYTES: usize = 2048;
+// range selectors can contain 0x00 or 0xff suffixes
+const MAX_READ_KEY_SIZE_BYTES: usize = MAX_WRITE_KEY_SIZE_BYTES + 1;
+const MAX_VALUE_SIZE_BYTES: usize = 65536;
+const MAX_READ_RANGES: usize = 10;
+const MAX_READ_ENTRIES: usize = 1000;
+const MAX_CHECKS: usize = 10;
+const MAX_MUTATIONS: usize = 1000;
+
+/// The file in the default storage directory that databases opened without a
+/// path are stored in.
+const DEFAULT_DATABASE_FILE_NAME: &str = "kv.sqlite3";
+
+/// A versionstamp identifies the write that last changed a key. Versionstamps
+/// of later writes compare greater.
+pub type Versionstamp = [u8; 10];
+
+pub struct ReadRange {
+  /// The first key of the range, inclusive.
+  pub start: Vec<u8>,
+  /// The end of the range, exclusive.
+  pub end: Vec<u8>,
+  pub limit: u32,
+  pub reverse: bool,
+}
+
+pub struct KvEntry {
+  pub key: Vec<u8>,
+  pub value: Vec<u8>,
+  pub versionstamp: Versionstamp,
+}
+
+/// A check that the versionstamp of a key matches, or that the key does not
+/// exist when `versionstamp` is `None`.
+pub struct KvCheck {
+  pub key: Vec<u8>,
+  pub versionstamp: Option<Versionstamp>,
+}
+
+pub enum MutationKind {
+  Set(Vec<u8>),
+  Delete,
+}
+
+pub struct KvMutation {
+  pub key: Vec<u8>,
+  pub kind: MutationKind,
+}
+
+pub struct AtomicWrite {
+  pub checks: Vec<KvCheck>,
+  pub mutations: Vec<KvMutation>,
+}
+
+pub trait KvPermissions {
+  fn check_read(&mut self, p: &Path, api_name: &str) -> Result<(), AnyError>;
+  fn check_write(&mut self, p: &Path, api_name: &str) -> Result<(), AnyError>;
+}
+
+pub struct Unstable(pub bool);
+
+/// The directory databases opened without a path are stored in, which is
+/// specific to the origin of the main module.
+#[derive(Clone)]
+struct DefaultStorageDir(Option<PathBuf>);
+
+pub fn init<P: KvPermissions + 'static>(
+  default_storage_dir: Option<PathBuf>,
+  unstable: bool,
+) -> Extension {
+  Extension::builder(env!("CARGO_PKG_NAME"))
+    .dependencies(vec!["deno_web"])
+    .js(include_js_files!(
+      prefix "internal:ext/kv",
+      "01_db.js",
+    ))
+    .ops(vec![
+      op_kv_database_open::decl::<P>(),
+      op_kv_snapshot_read::decl(),
+      op_kv_atomic_write::decl(),
+    ])
+    .state(move |state| {
+      state.put(Unstable(unstable));
+      state.put(DefaultStorageDir(default_storage_dir.clone()));
+      Ok(())
+    })
+    .build()
+}
+
+/// Returns the class of errors from the SQLite database backing a KV store.
+pub fn get_error_class_name(e: &AnyError) -> Option<&'static str> {
+  e.downcast_ref::<rusqlite::Error>().map(|_| "Error")
+}
+
+fn check_unstable(state: &OpState, api_name: &str) {
+  let unstable = state.borrow::<Unstable>();
+
+  if !unstable.0 {
+    eprintln!(
+      "Unstable API '{api_name}'. The --unstable flag must be provided."
+    );
+    std::process::exit(70);
+  }
+}
+
+struct DatabaseResource {
+  db: SqliteDb,
+}
+
+impl Resource for DatabaseResource {
+  fn name(&self) -> Cow<str> {
+    "database".into()
+  }
+}
+
+#[op]
+async fn op_kv_database_open<P>(
+  state: Rc<RefCell<OpState>>,
+  path: Option<String>,
+) -> Result<ResourceId, AnyError>
+where
+  P: KvPermissions + 'static,
+{
+  let path = {
+    let mut state = state.borrow_mut();
+    check_unstable(&state, "Deno.openKv");
+    match path.as_deref() {
+      Some(":memory:") => None,
+      Some(path) => {
+        let path = Path::new(path);
+        let permissions = state.borrow_mut::<P>();
+        permissions.check_read(path, "Deno.openKv")?;
+        permissions.check_write(path, "Deno.openKv")?;
+        Some(path.to_path_buf())
+      }
+      None => {
+        let dir = state.borrow::<DefaultStorageDir>().0.clone().ok_or_else(
+          || {
+            type_error(
+              "Deno.openKv() requires a path when there is no storage location for the main module.",
+            )
+          },
+        )?;
+        Some(dir.join(DEFAULT_DATABASE_FILE_NAME))
+      }
+    }
+  };
+
+  let db = tokio::task::spawn_blocking(move || SqliteDb::open(path.as_deref()))
+    .await??;
+  let rid = state
+    .borrow_mut()
+    .resource_table
+    .add(DatabaseResource { db });
+  Ok(rid)
+}
+
+/// A range to read, as `[start, end, limit, reverse]`.
+type SnapshotReadRange = (ZeroCopyBuf, ZeroCopyBuf, u32, bool);
+
+/// An entry that was read, as `[key, value, versionstamp]`.
+type ToV8KvEntry = (ZeroCopyBuf, ZeroCopyBuf, String);
+
+#[op]
+async fn op_kv_snapshot_read(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+  ranges: Vec<SnapshotReadRange>,
+) -> Result<Vec<Vec<ToV8KvEntry>>, AnyError> {
+  let db = state
+    .borrow()
+    .resource_table
+    .get::<DatabaseResource>(rid)?
+    .db
+    .clone();
+
+  if ranges.len() > MAX_READ_RANGES {
+    return Err(type_error(format!(
+      "too many ranges (max {MAX_READ_RANGES})"
+    )));
+  }
+  let mut total_entries = 0usize;
+  let ranges = ranges
+    .into_iter()
+    .map(|(start, end, limit, reverse)| {
+      check_read_key_size(&start)?;
+      check_read_key_size(&end)?;
+      total_entries += limit as usize;
+      Ok(ReadRange {
+        start: start.to_vec(),
+        end: end.to_vec(),
+        limit,
+        reverse,
+      })
+    })
+    .collect::<Result<Vec<_>, AnyError>>()?;
+  if total_entries > MAX_READ_ENTRIES {
+    return Err(type_error(format!(
+      "too many entries (max {MAX_READ_ENTRIES})"
+    )));
+  }
+
+  let results = db.snapshot_read(ranges).await?;
+  Ok(
+    results
+      .into_iter()
+      .map(|entries| {
+        entries
+          .into_iter()
+          .map(|entry| {
+            (
+              entry.key.into(),
+              entry.value.into(),
+              encode_versionstamp(&entry.versionstamp),
+            )
+          })
+          .collect()
+      })
+      .collect(),
+  )
+}
+
+/// A check, as `[key, versionstamp]`.
+type V8KvCheck = (ZeroCopyBuf, Option<String>);
+
+/// A mutation, as `[key, kind, value]`, where `kind` is `"set"` or
+/// `"delete"`.
+type V8KvMutation = (ZeroCopyBuf, String, Option<ZeroCopyBuf>);
+
+#[op]
+async fn op_kv_atomic_write(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+  checks: Vec<V8KvCheck>,
+  mutations: Vec<V8KvMutation>,
+) -> Result<Option<String>, AnyError> {
+  let db = state
+    .borrow()
+    .resource_table
+    .get::<DatabaseResource>(rid)?
+    .db
+    .clone();
+
+  if checks.len() > MAX_CHECKS {
+    return Err(type_error(format!("too many checks (max {MAX_CHECKS})")));
+  }
+  if mutations.len() > MAX_MUTATIONS {
+    return Err(type_error(format!(
+      "too many mutations (max {MAX_MUTATIONS})"
+    )));
+  }
+
+  let checks = checks
+    .into_iter()
+    .map(|(key, versionstamp)| {
+      check_write_key_size(&key)?;
+      let versionstamp = match versionstamp {
+        Some(versionstamp) => Some(decode_versionstamp(&versionstamp)?),
+        None => None,
+      };
+      Ok(KvCheck {
+        key: key.to_vec(),
+        versionstamp,
+      })
+    })
+    .collect::<Result<Vec<_>, AnyError>>()?;
+  let mutations = mutations
+    .into_iter()
+    .map(|(key, kind, value)| {
+      check_write_key_size(&key)?;
+      let kind = match (kind.as_str(), value) {
+        ("set", Some(value)) => {
+          check_value_size(&value)?;
+          MutationKind::Set(value.to_vec())
+        }
+        ("delete", None) => MutationKind::Delete,
+        ("set", None) => {
+          return Err(type_error("set mutation requires a value"))
+        }
+        ("delete", Some(_)) => {
+          return Err(type_error("delete mutation does not take a value"))
+        }
+        _ => return Err(type_error(format!("invalid mutation '{kind}'"))),
+      };
+      Ok(KvMutation {
+        key: key.to_vec(),
+        kind,
+      })
+    })
+    .collect::<Result<Vec<_>, AnyError>>()?;
+
+  let versionstamp = db.atomic_write(AtomicWrite { checks, mutations }).await?;
+  Ok(versionstamp.as_ref().map(encode_versionstamp))
+}
+
+fn check_read_key_size(key: &[u8]) -> Result<(), AnyError> {
+  if key.len() > MAX_READ_KEY_SIZE_BYTES {
+    Err(type_error(format!(
+      "key too large for read (max {MAX_READ_KEY_SIZE_BYTES} bytes)"
+    )))
+  } else {
+    Ok(())
+  }
+}
+
+fn check_write_key_size(key: &[u8]) -> Result<(), AnyError> {
+  if key.len() > MAX_WRITE_KEY_SIZE_BYTES {
+    Err(type_error(format!(
+      "key too large for write (max {MAX_WRITE_KEY_SIZE_BYTES} bytes)"
+    )))
+  } else {
+    Ok(())
+  }
+}
+
+fn check_value_size(value: &[u8]) -> Result<(), AnyError> {
+  if value.len() > MAX_VALUE_SIZE_BYTES {
+    Err(type_error(format!(
+      "value too large (max {MAX_VALUE_SIZE_BYTES} bytes)"
+    )))
+  } else {
+    Ok(())
+  }
+}
+
+/// Versionstamps are exposed to JavaScript as 20 character hex strings.
+fn encode_versionstamp(versionstamp: &Versionstamp) -> String {
+  versionstamp.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn decode_versionstamp(s: &str) -> Result<Versionstamp, AnyError> {
+  let invalid = || type_error(format!("invalid versionstamp '{s}'"));
+  if s.len() != 20 || !s.is_ascii() {
+    return Err(invalid());
+  }
+  let mut versionstamp = [0; 10];
+  for (i, byte) in versionstamp.iter_mut().enumerate() {
+    *byte =
+      u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?;
+  }
+  Ok(versionstamp)
+}
@@ -0,0 +1,213 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use rusqlite::params;
+use rusqlite::Connection;
+use rusqlite::OptionalExtension;
+use rusqlite::Transaction;
+
+use crate::AtomicWrite;
+use crate::KvEntry;
+use crate::MutationKind;
+use crate::ReadRange;
+use crate::Versionstamp;
+
+const STATEMENT_INC_AND_GET_DATA_VERSION: &str =
+  "UPDATE data_version SET version = version + 1 WHERE k = 0 RETURNING version";
+const STATEMENT_KV_RANGE_SCAN: &str =
+  "SELECT k, v, version FROM kv WHERE k >= ? AND k < ? ORDER BY k ASC LIMIT ?";
+const STATEMENT_KV_RANGE_SCAN_REVERSE: &str =
+  "SELECT k, v, version FROM kv WHERE k >= ? AND k < ? ORDER BY k DESC LIMIT ?";
+const STATEMENT_KV_POINT_GET_VERSION_ONLY: &str =
+  "SELECT version FROM kv WHERE k = ?";
+const STATEMENT_KV_POINT_SET: &str =
+  "INSERT INTO kv (k, v, version) VALUES (:k, :v, :version) ON CONFLICT(k) DO UPDATE SET v = :v, version = :version";
+const STATEMENT_KV_POINT_DELETE: &str = "DELETE FROM kv WHERE k = ?";
+
+const STATEMENT_CREATE_MIGRATION_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS migration_state (
+  k INTEGER PRIMARY KEY NOT NULL,
+  version INTEGER NOT NULL
+)
+";
+
+const MIGRATIONS: [&str; 1] = ["
+CREATE TABLE data_version (
+  k INTEGER PRIMARY KEY NOT NULL,
+  version INTEGER NOT NULL
+);
+INSERT INTO data_version (k, version) VALUES (0, 0);
+CREATE TABLE kv (
+  k BLOB PRIMARY KEY NOT NULL,
+  v BLOB NOT NULL,
+  version INTEGER NOT NULL
+) WITHOUT ROWID;
+"];
+
+/// A database stored in SQLite, either in a file or in memory.
+#[derive(Clone)]
+pub struct SqliteDb {
+  conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteDb {
+  /// Opens the database at `path`, creating it if it doesn't exist. If no
+  /// path is given, the database only lives in memory.
+  pub fn open(path: Option<&Path>) -> Result<Self, AnyError> {
+    let mut conn = match path {
+      Some(path) => {
+        if let Some(parent) = path.parent() {
+          if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+          }
+        }
+        let conn = Connection::open(path)?;
+        // Enable write-ahead-logging and tweak some other stuff.
+        let initial_pragmas = "
+          -- enable write-ahead-logging mode
+          PRAGMA journal_mode=WAL;
+          PRAGMA synchronous=NORMAL;
+          PRAGMA temp_store=memory;
+          PRAGMA page_size=4096;
+          PRAGMA mmap_size=6000000;
+          PRAGMA optimize;
+        ";
+        conn.execute_batch(initial_pragmas)?;
+        conn
+      }
+      None => Connection::open_in_memory()?,
+    };
+    conn.set_prepared_statement_cache_capacity(128);
+
+    {
+      let tx = conn.transaction()?;
+      tx.execute(STATEMENT_CREATE_MIGRATION_TABLE, [])?;
+      let current_version: usize = tx
+        .query_row(
+          "SELECT version FROM migration_state WHERE k = 0",
+          [],
+          |row| row.get(0),
+        )
+        .optional()?
+        .unwrap_or(0);
+      for (i, migration) in MIGRATIONS.iter().enumerate() {
+        let version = i + 1;
+        if version > current_version {
+          tx.execute_batch(migration)?;
+          tx.execute(
+            "REPLACE INTO migration_state (k, version) VALUES (0, ?)",
+            [version],
+          )?;
+        }
+      }
+      tx.commit()?;
+    }
+
+    Ok(Self {
+      conn: Arc::new(Mutex::new(conn)),
+    })
+  }
+
+  /// Reads all the ranges from a single consistent snapshot of the database.
+  pub async fn snapshot_read(
+    &self,
+    ranges: Vec<ReadRange>,
+  ) -> Result<Vec<Vec<KvEntry>>, AnyError> {
+    let conn = self.conn.clone();
+    tokio::task::spawn_blocking(move || {
+      let mut conn = conn.lock();
+      let tx = conn.transaction()?;
+      let mut results = Vec::with_capacity(ranges.len());
+      for range in ranges {
+        let mut stmt = tx.prepare_cached(if range.reverse {
+          STATEMENT_KV_RANGE_SCAN_REVERSE
+        } else {
+          STATEMENT_KV_RANGE_SCAN
+        })?;
+        let entries = stmt
+          .query_map(params![range.start, range.end, range.limit], |row| {
+            let key: Vec<u8> = row.get(0)?;
+            let value: Vec<u8> = row.get(1)?;
+            let version: i64 = row.get(2)?;
+            Ok(KvEntry {
+              key,
+              value,
+              versionstamp: version_to_versionstamp(version),
+            })
+          })?
+          .collect::<Result<Vec<_>, _>>()?;
+        results.push(entries);
+      }
+      tx.commit()?;
+      Ok(results)
+    })
+    .await?
+  }
+
+  /// Applies the mutations if all the checks pass. Returns the versionstamp
+  /// of the write, or `None` if a check failed and nothing was written.
+  pub async fn atomic_write(
+    &self,
+    write: AtomicWrite,
+  ) -> Result<Option<Versionstamp>, AnyError> {
+    let conn = self.conn.clone();
+    tokio::task::spawn_blocking(move || {
+      let mut conn = conn.lock();
+      let tx = conn.transaction()?;
+
+      for check in write.checks {
+        let current_versionstamp = get_versionstamp(&tx, &check.key)?;
+        if current_versionstamp != check.versionstamp {
+          return Ok(None);
+        }
+      }
+
+      let version: i64 = tx
+        .prepare_cached(STATEMENT_INC_AND_GET_DATA_VERSION)?
+        .query_row([], |row| row.get(0))?;
+
+      for mutation in write.mutations {
+        match mutation.kind {
+          MutationKind::Set(value) => {
+            tx.prepare_cached(STATEMENT_KV_POINT_SET)?.execute(params![
+              mutation.key,
+              value,
+              version
+            ])?;
+          }
+          MutationKind::Delete => {
+            tx.prepare_cached(STATEMENT_KV_POINT_DELETE)?
+              .execute(params![mutation.key])?;
+          }
+        }
+      }
+
+      tx.commit()?;
+      Ok(Some(version_to_versionstamp(version)))
+    })
+    .await?
+  }
+}
+
+fn get_versionstamp(
+  tx: &Transaction,
+  key: &[u8],
+) -> Result<Option<Versionstamp>, AnyError> {
+  let version: Option<i64> = tx
+    .prepare_cached(STATEMENT_KV_POINT_GET_VERSION_ONLY)?
+    .query_row([key], |row| row.get(0))
+    .optional()?;
+  Ok(version.map(version_to_versionstamp))
+}
+
+/// The versionstamp of a write is the big-endian version of the database
+/// after the write, padded to 10 bytes.
+fn version_to_versionstamp(version: i64) -> Versionstamp {
+  let mut versionstamp = [0; 10];
+  versionstamp[..8].copy_from_slice(&version.to_be_bytes());
+  versionstamp
+}
@@ -7,8 +7,12 @@ import * as core from '../../core/01_core.js';
 import * as ops from '../../ops/index.js';
 
 const {
+  ArrayPrototypeFindIndex,
   ArrayPrototypePush,
   ArrayPrototypeShift,
+  ArrayPrototypeSplice,
+  DatePrototypeToString,
+  Error,
   FunctionPrototypeCall,
   Map,
   MapPrototypeDelete,
@@ -19,7 +23,10 @@ const {
   Uint32Array,
   // deno-lint-ignore camelcase
   NumberPOSITIVE_INFINITY,
+  ObjectAssign,
+  ObjectSetPrototypeOf,
   PromisePrototypeThen,
+  ReflectConstruct,
   SafeArrayIterator,
   SymbolFor,
   TypeError,
@@ -33,6 +40,9 @@ import { converters } from '../webidl/00_webidl.js';
 const hrU8 = new Uint8Array(8);
 const hr = new Uint32Array(hrU8.buffer);
 export function opNow() {
+  if (fakeClock !== null) {
+    return fakeClock.now;
+  }
   ops.op_now(hrU8);
   return (hr[0] * 1000 + hr[1] / 1e6);
 }
@@ -73,12 +83,20 @@ export function handleTimerMacrotask() {
 
 // ---------------------------------------------------------------------------
 
+interface TimerInfo {
+  cancelRid: number;
+  isRef: boolean;
+  promiseId: number;
+  /** Whether the timer runs on the fake clock instead of an `op_sleep`. */
+  fake: boolean;
+}
+
 /**
  * The keys in this map correspond to the key ID's in the spec's map of active
  * timers. The values are the timeout's cancel rid.
  *
  */
-const activeTimers = new Map<number, { cancelRid: number, isRef: boolean, promiseId: number }>();
+const activeTimers = new Map<number, TimerInfo>();
 
 let nextId = 1;
 
@@ -91,12 +109,13 @@ function initializeTimer(
   args: Array<any>,
   repeat: boolean,
   prevId?: number,
+  fake = false,
 ): number {
   // 2. If previousId was given, let id be previousId; otherwise, let
   // previousId be an implementation-defined integer than is greater than zero
   // and does not already exist in global's map of active timers.
   let id: number;
-  let timerInfo: { cancelRid: number, isRef: boolean, promiseId: number };
+  let timerInfo: TimerInfo;
   if (prevId !== undefined) {
     // `prevId` is only passed for follow-up calls on intervals
     assert(repeat);
@@ -106,8 +125,9 @@ function initializeTimer(
     // TODO(@andreubotella): Deal with overflow.
     // https://github.com/whatwg/html/issues/7358
     id = nextId++;
-    const cancelRid = ops.op_timer_handle();
-    timerInfo = { cancelRid, isRef: true, promiseId: -1 };
+    // fake timers don't sleep, so they need no cancel handle
+    const cancelRid = fake ? -1 : ops.op_timer_handle();
+    timerInfo = { cancelRid, isRef: true, promiseId: -1, fake };
 
     // Step 4 in "run steps after a timeout".
     MapPrototypeSet(activeTimers, id, timerInfo);
@@ -165,7 +185,9 @@ function initializeTimer(
         }
       } else {
         // 6. Otherwise, remove global's map of active timers[id].
-        core.tryClose(timerInfo.cancelRid);
+        if (!timerInfo.fake) {
+          core.tryClose(timerInfo.cancelRid);
+        }
         MapPrototypeDelete(activeTimers, id);
       }
     },
@@ -179,11 +201,15 @@ function initializeTimer(
   // the timer task source given global to run task.
   // 13. Run steps after a timeout given global, "setTimeout/setInterval",
   // timeout, completionStep, and id.
-  runAfterTimeout(
-    () => ArrayPrototypePush(timerTasks, task),
-    timeout,
-    timerInfo,
-  );
+  if (timerInfo.fake) {
+    scheduleFakeTimer(id, task, timeout);
+  } else {
+    runAfterTimeout(
+      () => ArrayPrototypePush(timerTasks, task),
+      timeout,
+      timerInfo,
+    );
+  }
 
   return id;
 }
@@ -209,7 +235,7 @@ const scheduledTimers = { head: null as ScheduledTimer | null, tail: null as Sch
  * @param millis
  * @param timerInfo
  */
-function runAfterTimeout(cb: () => void, millis: number, timerInfo: { cancelRid: number, isRef: boolean, promiseId: number }) {
+function runAfterTimeout(cb: () => void, millis: number, timerInfo: TimerInfo) {
   const cancelRid = timerInfo.cancelRid;
   const sleepPromise = core.opAsync("op_sleep", millis, cancelRid);
   timerInfo.promiseId =
@@ -327,7 +353,11 @@ export function clearTimeout(id = 0) {
   id = converters.long(id);
   const timerInfo = MapPrototypeGet(activeTimers, id);
   if (timerInfo !== undefined) {
-    core.tryClose(timerInfo.cancelRid);
+    if (timerInfo.fake) {
+      removeFakeTimer(id);
+    } else {
+      core.tryClose(timerInfo.cancelRid);
+    }
     MapPrototypeDelete(activeTimers, id);
   }
 }
@@ -339,7 +369,7 @@ export function clearInterval(id = 0) {
 
 export function refTimer(id: number) {
   const timerInfo = MapPrototypeGet(activeTimers, id);
-  if (timerInfo === undefined || timerInfo.isRef) {
+  if (timerInfo === undefined || timerInfo.fake || timerInfo.isRef) {
     return;
   }
   timerInfo.isRef = true;
@@ -348,9 +378,248 @@ export function refTimer(id: number) {
 
 export function unrefTimer(id: number) {
   const timerInfo = MapPrototypeGet(activeTimers, id);
-  if (timerInfo === undefined || !timerInfo.isRef) {
+  if (timerInfo === undefined || timerInfo.fake || !timerInfo.isRef) {
     return;
   }
   timerInfo.isRef = false;
   core.unrefOp(timerInfo.promiseId);
 }
+
+
+// ---------------------------------------------------------------------------
+
+/**
+ * A timer scheduled on the fake clock. It runs when the clock is advanced past
+ * its due time, instead of after an `op_sleep`.
+ */
+interface FakeTimer {
+  id: number;
+  due: number;
+  task: { action: () => void; nestingLevel: number; };
+}
+
+/**
+ * The clock of `Deno.TestContext#useFakeTime()`. While it is installed,
+ * `performance.now()` returns `now`, `Date` is offset from it by
+ * `dateOffset`, and the global timer functions schedule fake timers on it.
+ */
+export interface FakeClock {
+  now: number;
+  dateOffset: number;
+  /** The fake timers, ordered by due time and then by scheduling order. */
+  timers: FakeTimer[];
+  /** The globals replaced while the clock is installed. */
+  globals: {
+    Date: DateConstructor;
+    setTimeout: typeof setTimeout;
+    setInterval: typeof setInterval;
+    clearTimeout: typeof clearTimeout;
+    clearInterval: typeof clearInterval;
+  };
+}
+
+/**
+ * The number of timers run by a single `tick()` or `runAll()` after which
+ * they are assumed to reschedule themselves forever.
+ */
+const FAKE_TIMER_LIMIT = 10_000;
+
+let fakeClock: FakeClock | null = null;
+
+function scheduleFakeTimer(
+  id: number,
+  task: { action: () => void; nestingLevel: number; },
+  millis: number,
+) {
+  const clock = fakeClock!;
+  const due = clock.now + millis;
+  // timers with the same due time run in the order they were scheduled
+  let index = ArrayPrototypeFindIndex(clock.timers, (timer) => timer.due > due);
+  if (index === -1) {
+    index = clock.timers.length;
+  }
+  ArrayPrototypeSplice(clock.timers, index, 0, { id, due, task });
+}
+
+function removeFakeTimer(id: number) {
+  if (fakeClock === null) {
+    return;
+  }
+  const index = ArrayPrototypeFindIndex(
+    fakeClock.timers,
+    (timer) => timer.id === id,
+  );
+  if (index !== -1) {
+    ArrayPrototypeSplice(fakeClock.timers, index, 1);
+  }
+}
+
+/**
+ * Runs the fake timers which are due at `time` at the latest, advancing the
+ * clock to the due time of each one before running it.
+ */
+function runFakeTimers(clock: FakeClock, time: number) {
+  let count = 0;
+  while (clock.timers.length > 0 && clock.timers[0].due <= time) {
+    if (++count > FAKE_TIMER_LIMIT) {
+      throw new Error(
+        `Aborting after running ${FAKE_TIMER_LIMIT} timers, assuming an infinite loop.`,
+      );
+    }
+    const { due, task } = ArrayPrototypeShift(clock.timers);
+    clock.now = due;
+    const prevNestingLevel = timerNestingLevel;
+    timerNestingLevel = task.nestingLevel;
+    try {
+      task.action();
+    } finally {
+      timerNestingLevel = prevNestingLevel;
+    }
+  }
+}
+
+function createFakeGlobals(clock: FakeClock) {
+  const RealDate = clock.globals.Date;
+
+  function Date(...args) {
+    const dateNow = clock.now + clock.dateOffset;
+    if (new.target === undefined) {
+      return DatePrototypeToString(new RealDate(dateNow));
+    }
+    return ReflectConstruct(
+      RealDate,
+      args.length === 0 ? [dateNow] : args,
+      new.target,
+    );
+  }
+  Date.prototype = RealDate.prototype;
+  Date.now = () => clock.now + clock.dateOffset;
+  // inherit `Date.parse()` and `Date.UTC()`
+  ObjectSetPrototypeOf(Date, RealDate);
+
+  // timers created through these after the clock was restored are real
+  return {
+    Date,
+    setTimeout(callback, timeout = 0, ...args) {
+      if (fakeClock !== clock) {
+        return FunctionPrototypeCall(
+          clock.globals.setTimeout,
+          globalThis,
+          callback,
+          timeout,
+          ...new SafeArrayIterator(args),
+        );
+      }
+      checkThis(this);
+      if (typeof callback !== "function") {
+        callback = converters.DOMString(callback);
+      }
+      timeout = converters.long(timeout);
+
+      return initializeTimer(callback, timeout, args, false, undefined, true);
+    },
+    setInterval(callback, timeout = 0, ...args) {
+      if (fakeClock !== clock) {
+        return FunctionPrototypeCall(
+          clock.globals.setInterval,
+          globalThis,
+          callback,
+          timeout,
+          ...new SafeArrayIterator(args),
+        );
+      }
+      checkThis(this);
+      if (typeof callback !== "function") {
+        callback = converters.DOMString(callback);
+      }
+      timeout = converters.long(timeout);
+
+      return initializeTimer(callback, timeout, args, true, undefined, true);
+    },
+    clearTimeout(id = 0) {
+      if (MapPrototypeGet(activeTimers, id)?.fake) {
+        clearTimeout(id);
+      } else {
+        FunctionPrototypeCall(clock.globals.clearTimeout, globalThis, id);
+      }
+    },
+    clearInterval(id = 0) {
+      if (MapPrototypeGet(activeTimers, id)?.fake) {
+        clearInterval(id);
+      } else {
+        FunctionPrototypeCall(clock.globals.clearInterval, globalThis, id);
+      }
+    },
+  };
+}
+
+function assertFakeClockInstalled(clock: FakeClock) {
+  if (fakeClock !== clock) {
+    throw new Error("The fake time has already been restored.");
+  }
+}
+
+/**
+ * Freezes the clock at `dateNow` milliseconds since the epoch and replaces
+ * the global `Date` and timer functions with ones driven by the fake clock,
+ * until it is restored. Timers scheduled before keep running in real time.
+ */
+export function installFakeClock(dateNow: number): FakeClock {
+  if (fakeClock !== null) {
+    throw new Error(
+      "Time is already faked. Restore the current fake time before faking it again.",
+    );
+  }
+  const now = opNow();
+  const clock: FakeClock = {
+    now,
+    dateOffset: dateNow - now,
+    timers: [],
+    globals: {
+      Date: globalThis.Date,
+      setTimeout: globalThis.setTimeout,
+      setInterval: globalThis.setInterval,
+      clearTimeout: globalThis.clearTimeout,
+      clearInterval: globalThis.clearInterval,
+    },
+  };
+  fakeClock = clock;
+  ObjectAssign(globalThis, createFakeGlobals(clock));
+  return clock;
+}
+
+/**
+ * Advances the fake clock by `millis`, running the timers which become due
+ * on the way.
+ */
+export function tickFakeClock(clock: FakeClock, millis: number) {
+  assertFakeClockInstalled(clock);
+  const time = clock.now + millis;
+  runFakeTimers(clock, time);
+  clock.now = time;
+}
+
+/**
+ * Advances the fake clock until no timers are left, including the ones
+ * scheduled by the timers which run.
+ */
+export function runAllFakeTimers(clock: FakeClock) {
+  assertFakeClockInstalled(clock);
+  runFakeTimers(clock, NumberPOSITIVE_INFINITY);
+}
+
+/**
+ * Cancels the pending fake timers and restores the real clock and globals.
+ * Does nothing if the clock was already restored.
+ */
+export function restoreFakeClock(clock: FakeClock) {
+  if (fakeClock !== clock) {
+    return;
+  }
+  for (const { id } of new SafeArrayIterator(clock.timers)) {
+    MapPrototypeDelete(activeTimers, id);
+  }
+  clock.timers = [];
+  fakeClock = null;
+  ObjectAssign(globalThis, clock.globals);
+}
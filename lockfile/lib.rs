@@ -203,7 +203,7 @@ impl Lockfile {
   pub fn check_or_insert_remote(
     &mut self,
     specifier: &str,
-    code: &str,
+    code: impl AsRef<[u8]>,
   ) -> bool {
     if !(specifier.starts_with("http:") || specifier.starts_with("https:")) {
       return true;
@@ -232,9 +232,13 @@ impl Lockfile {
 
   /// Checks the given module is included, if so verify the checksum. If module
   /// is not included, insert it.
-  fn check_or_insert(&mut self, specifier: &str, code: &str) -> bool {
+  fn check_or_insert(
+    &mut self,
+    specifier: &str,
+    code: impl AsRef<[u8]>,
+  ) -> bool {
     if let Some(lockfile_checksum) = self.content.remote.get(specifier) {
-      let compiled_checksum = gen_checksum(&[code.as_bytes()]);
+      let compiled_checksum = gen_checksum(&[code]);
       lockfile_checksum == &compiled_checksum
     } else {
       self.insert(specifier, code);
@@ -242,8 +246,8 @@ impl Lockfile {
     }
   }
 
-  fn insert(&mut self, specifier: &str, code: &str) {
-    let checksum = gen_checksum(&[code.as_bytes()]);
+  fn insert(&mut self, specifier: &str, code: impl AsRef<[u8]>) {
+    let checksum = gen_checksum(&[code]);
     self.content.remote.insert(specifier.to_string(), checksum);
     self.has_content_changed = true;
   }
@@ -358,6 +358,8 @@ fn codegen_v8_sync(
       };
 
       #fast_error_handler
+      // makes this call the current op trace while op tracing is enabled
+      let _op_trace = #core::_ops::OpTraceGuard::enter(ctx, scope);
       #arg_decls
 
       let result = Self::call::<#type_params>(#args_head #args_tail);
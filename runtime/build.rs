@@ -95,6 +95,24 @@ mod not_docs {
     }
   }
 
+  impl deno_kv::KvPermissions for Permissions {
+    fn check_read(
+      &mut self,
+      _p: &Path,
+      _api_name: &str,
+    ) -> Result<(), deno_core::error::AnyError> {
+      unreachable!("snapshotting!")
+    }
+
+    fn check_write(
+      &mut self,
+      _p: &Path,
+      _api_name: &str,
+    ) -> Result<(), deno_core::error::AnyError> {
+      unreachable!("snapshotting!")
+    }
+  }
+
   impl deno_net::NetPermissions for Permissions {
     fn check_net<T: AsRef<str>>(
       &mut self,
@@ -135,6 +153,7 @@ mod not_docs {
       deno_cache::init::<SqliteBackedCache>(None),
       deno_websocket::init::<Permissions>("".to_owned(), None, None),
       deno_webstorage::init(None),
+      deno_kv::init::<Permissions>(None, false), // No --unstable
       deno_crypto::init(None),
       deno_webgpu::init(false),
       deno_broadcast_channel::init(
@@ -0,0 +1,56 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use super::FileSystemRc;
+
+use deno_core::error::type_error;
+use deno_core::futures::FutureExt;
+use deno_core::futures::TryFutureExt;
+use deno_core::url::Url;
+use deno_core::CancelFuture;
+use deno_core::CancelHandle;
+use deno_core::OpState;
+use deno_fetch::reqwest;
+use deno_fetch::CancelableResponseFuture;
+use deno_fetch::FetchHandler;
+use deno_fetch::FetchRequestBodyResource;
+use std::rc::Rc;
+
+/// Reads `file:` URLs passed to `fetch` from the [`super::FileSystem`] in the
+/// op state, so they see the same files as the `Deno.*` file system APIs.
+#[derive(Clone)]
+pub struct FileSystemFetchHandler;
+
+impl FetchHandler for FileSystemFetchHandler {
+  fn fetch_file(
+    &self,
+    state: &mut OpState,
+    url: Url,
+  ) -> (
+    CancelableResponseFuture,
+    Option<FetchRequestBodyResource>,
+    Option<Rc<CancelHandle>>,
+  ) {
+    let fs = state.borrow::<FileSystemRc>().clone();
+    let cancel_handle = CancelHandle::new_rc();
+    let response_fut = async move {
+      let path = url.to_file_path()?;
+      let data = tokio::task::spawn_blocking(move || fs.read_file(&path))
+        .await
+        .map_err(|_| ())?
+        .map_err(|_| ())?;
+      let response = http::Response::builder()
+        .status(reqwest::StatusCode::OK)
+        .body(reqwest::Body::from(data))
+        .map_err(|_| ())?
+        .into();
+      Ok::<_, ()>(response)
+    }
+    .map_err(move |_| {
+      type_error("NetworkError when attempting to fetch resource.")
+    })
+    .or_cancel(&cancel_handle)
+    .boxed_local();
+
+    (response_fut, None, Some(cancel_handle))
+  }
+}
@@ -0,0 +1,910 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use super::File;
+use super::FileSystem;
+use super::FileTime;
+use super::FsDirEntry;
+use super::FsFileType;
+use super::FsStat;
+use super::OpenOptions;
+
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use std::collections::HashMap;
+use std::io;
+use std::io::ErrorKind;
+use std::io::SeekFrom;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+const S_IFREG: u32 = 0o100000;
+const S_IFDIR: u32 = 0o040000;
+const S_IFLNK: u32 = 0o120000;
+
+/// Maximum number of symlinks followed while resolving a path, like Linux.
+const MAX_SYMLINK_HOPS: usize = 40;
+
+/// A file system that lives entirely in memory. Cloning it shares the
+/// underlying state.
+///
+/// It starts out with an empty root directory and a `/tmp` directory, and
+/// the current working directory is the root.
+#[derive(Clone)]
+pub struct InMemoryFs {
+  state: Arc<Mutex<State>>,
+}
+
+impl Default for InMemoryFs {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl InMemoryFs {
+  pub fn new() -> Self {
+    let mut state = State {
+      cwd: PathBuf::from("/"),
+      umask: 0o022,
+      next_ino: 1,
+      entries: HashMap::new(),
+    };
+    let root_meta = state.new_meta(0o755);
+    state
+      .entries
+      .insert(PathBuf::from("/"), Entry::Dir(root_meta));
+    let tmp_meta = state.new_meta(0o777);
+    state
+      .entries
+      .insert(PathBuf::from("/tmp"), Entry::Dir(tmp_meta));
+    Self {
+      state: Arc::new(Mutex::new(state)),
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+struct Meta {
+  ino: u64,
+  /// Permission bits, without the file type.
+  mode: u32,
+  uid: u32,
+  gid: u32,
+  // In milliseconds since the epoch.
+  atime: u64,
+  mtime: u64,
+  birthtime: u64,
+}
+
+#[derive(Debug)]
+struct FileData {
+  data: Vec<u8>,
+  meta: Meta,
+  nlink: u64,
+}
+
+impl FileData {
+  fn stat(&self) -> FsStat {
+    FsStat {
+      is_file: true,
+      size: self.data.len() as u64,
+      nlink: self.nlink,
+      mode: S_IFREG | self.meta.mode,
+      blocks: (self.data.len() as u64 + 511) / 512,
+      ..meta_stat(&self.meta)
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+enum Entry {
+  /// Hard links to the same file share the data.
+  File(Arc<Mutex<FileData>>),
+  Dir(Meta),
+  Symlink(PathBuf, Meta),
+}
+
+impl Entry {
+  fn stat(&self) -> FsStat {
+    match self {
+      Entry::File(file) => file.lock().stat(),
+      Entry::Dir(meta) => FsStat {
+        is_directory: true,
+        mode: S_IFDIR | meta.mode,
+        nlink: 1,
+        ..meta_stat(meta)
+      },
+      Entry::Symlink(target, meta) => FsStat {
+        is_symlink: true,
+        size: target.as_os_str().len() as u64,
+        mode: S_IFLNK | meta.mode,
+        nlink: 1,
+        ..meta_stat(meta)
+      },
+    }
+  }
+
+  fn with_meta_mut<R>(&mut self, f: impl FnOnce(&mut Meta) -> R) -> R {
+    match self {
+      Entry::File(file) => f(&mut file.lock().meta),
+      Entry::Dir(meta) | Entry::Symlink(_, meta) => f(meta),
+    }
+  }
+}
+
+fn meta_stat(meta: &Meta) -> FsStat {
+  FsStat {
+    mtime: Some(meta.mtime),
+    atime: Some(meta.atime),
+    birthtime: Some(meta.birthtime),
+    ino: meta.ino,
+    uid: meta.uid,
+    gid: meta.gid,
+    blksize: 4096,
+    ..Default::default()
+  }
+}
+
+struct State {
+  cwd: PathBuf,
+  umask: u32,
+  next_ino: u64,
+  entries: HashMap<PathBuf, Entry>,
+}
+
+impl State {
+  fn new_meta(&mut self, mode: u32) -> Meta {
+    let ino = self.next_ino;
+    self.next_ino += 1;
+    let now = now_msec();
+    Meta {
+      ino,
+      mode: mode & 0o7777,
+      uid: 0,
+      gid: 0,
+      atime: now,
+      mtime: now,
+      birthtime: now,
+    }
+  }
+
+  /// Makes the path absolute and removes `.` and `..` components without
+  /// following symlinks.
+  fn normalize(&self, path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in self.cwd.join(path).components() {
+      match component {
+        Component::Prefix(_) | Component::RootDir => normalized.push(component),
+        Component::CurDir => {}
+        Component::ParentDir => {
+          normalized.pop();
+        }
+        Component::Normal(name) => normalized.push(name),
+      }
+    }
+    normalized
+  }
+
+  /// Resolves the symlinks in the path. The last component is only followed
+  /// when `follow_last` is set. The resolved path might not exist.
+  fn resolve(&self, path: &Path, follow_last: bool) -> io::Result<PathBuf> {
+    let mut path = self.normalize(path);
+    let mut hops = 0;
+    'outer: loop {
+      let mut resolved = PathBuf::new();
+      let mut components = path.components().peekable();
+      while let Some(component) = components.next() {
+        resolved.push(component);
+        let is_last = components.peek().is_none();
+        if let Some(Entry::Symlink(target, _)) = self.entries.get(&resolved) {
+          if is_last && !follow_last {
+            break;
+          }
+          hops += 1;
+          if hops > MAX_SYMLINK_HOPS {
+            return Err(io::Error::new(
+              ErrorKind::Other,
+              "Too many levels of symbolic links",
+            ));
+          }
+          let parent = resolved.parent().unwrap_or(&resolved);
+          let mut next = self.normalize(&parent.join(target));
+          let rest = components.collect::<PathBuf>();
+          if !rest.as_os_str().is_empty() {
+            next.push(rest);
+          }
+          path = next;
+          continue 'outer;
+        }
+      }
+      return Ok(resolved);
+    }
+  }
+
+  fn get(&self, path: &Path) -> io::Result<&Entry> {
+    self.entries.get(path).ok_or_else(not_found)
+  }
+
+  fn get_mut(&mut self, path: &Path) -> io::Result<&mut Entry> {
+    self.entries.get_mut(path).ok_or_else(not_found)
+  }
+
+  fn get_file(&self, path: &Path) -> io::Result<Arc<Mutex<FileData>>> {
+    match self.get(path)? {
+      Entry::File(file) => Ok(file.clone()),
+      Entry::Dir(_) => Err(is_a_directory()),
+      Entry::Symlink(..) => Err(not_found()),
+    }
+  }
+
+  /// Checks that the path doesn't exist and its parent is a directory.
+  fn check_new(&self, path: &Path) -> io::Result<()> {
+    if self.entries.contains_key(path) {
+      return Err(io::Error::new(ErrorKind::AlreadyExists, "File exists"));
+    }
+    self.check_parent(path)
+  }
+
+  fn check_parent(&self, path: &Path) -> io::Result<()> {
+    match path.parent().map(|parent| self.get(parent)) {
+      Some(Ok(Entry::Dir(_))) => Ok(()),
+      Some(Ok(_)) => Err(not_a_directory()),
+      Some(Err(err)) => Err(err),
+      // The root always exists.
+      None => Err(io::Error::new(ErrorKind::AlreadyExists, "File exists")),
+    }
+  }
+
+  fn has_children(&self, path: &Path) -> bool {
+    self.entries.keys().any(|key| key.parent() == Some(path))
+  }
+
+  fn create_file(
+    &mut self,
+    path: PathBuf,
+    mode: u32,
+  ) -> io::Result<Arc<Mutex<FileData>>> {
+    self.check_new(&path)?;
+    let meta = self.new_meta(mode & !self.umask);
+    let file = Arc::new(Mutex::new(FileData {
+      data: Vec::new(),
+      meta,
+      nlink: 1,
+    }));
+    self.entries.insert(path, Entry::File(file.clone()));
+    Ok(file)
+  }
+
+  fn mkdir(&mut self, path: PathBuf, mode: u32) -> io::Result<()> {
+    self.check_new(&path)?;
+    let meta = self.new_meta(mode & !self.umask);
+    self.entries.insert(path, Entry::Dir(meta));
+    Ok(())
+  }
+
+  fn remove_entry(&mut self, path: &Path) {
+    if let Some(Entry::File(file)) = self.entries.remove(path) {
+      let mut file = file.lock();
+      file.nlink = file.nlink.saturating_sub(1);
+    }
+  }
+}
+
+fn now_msec() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|t| t.as_millis() as u64)
+    .unwrap_or(0)
+}
+
+fn filetime_to_msec(time: FileTime) -> u64 {
+  (time.unix_seconds() as u64) * 1000 + (time.nanoseconds() as u64) / 1_000_000
+}
+
+fn not_found() -> io::Error {
+  io::Error::new(ErrorKind::NotFound, "No such file or directory")
+}
+
+fn not_a_directory() -> io::Error {
+  io::Error::new(ErrorKind::Other, "Not a directory")
+}
+
+fn is_a_directory() -> io::Error {
+  io::Error::new(ErrorKind::Other, "Is a directory")
+}
+
+fn bad_file_descriptor() -> io::Error {
+  io::Error::new(ErrorKind::Other, "Bad file descriptor")
+}
+
+impl FileSystem for InMemoryFs {
+  fn cwd(&self) -> io::Result<PathBuf> {
+    Ok(self.state.lock().cwd.clone())
+  }
+
+  fn chdir(&self, path: &Path) -> io::Result<()> {
+    let mut state = self.state.lock();
+    let path = state.resolve(path, true)?;
+    match state.get(&path)? {
+      Entry::Dir(_) => {
+        state.cwd = path;
+        Ok(())
+      }
+      _ => Err(not_a_directory()),
+    }
+  }
+
+  fn tmp_dir(&self) -> io::Result<PathBuf> {
+    Ok(PathBuf::from("/tmp"))
+  }
+
+  fn umask(&self, mask: Option<u32>) -> Result<u32, AnyError> {
+    let mut state = self.state.lock();
+    let prev = state.umask;
+    if let Some(mask) = mask {
+      state.umask = mask & 0o777;
+    }
+    Ok(prev)
+  }
+
+  fn open(
+    &self,
+    path: &Path,
+    options: OpenOptions,
+  ) -> io::Result<Box<dyn File>> {
+    let mut state = self.state.lock();
+    let path = state.resolve(path, true)?;
+    let file = match state.entries.get(&path) {
+      Some(_) if options.create_new => {
+        return Err(io::Error::new(ErrorKind::AlreadyExists, "File exists"))
+      }
+      Some(_) => {
+        let file = state.get_file(&path)?;
+        if options.truncate && options.write {
+          let mut data = file.lock();
+          data.data.clear();
+          data.meta.mtime = now_msec();
+        }
+        file
+      }
+      None if options.create || options.create_new => {
+        state.create_file(path, options.mode.unwrap_or(0o666))?
+      }
+      None => return Err(not_found()),
+    };
+    Ok(Box::new(InMemoryFile {
+      file,
+      position: 0,
+      read: options.read,
+      write: options.write || options.append,
+      append: options.append,
+    }))
+  }
+
+  fn mkdir(&self, path: &Path, recursive: bool, mode: u32) -> io::Result<()> {
+    let mut state = self.state.lock();
+    let path = state.resolve(path, false)?;
+    if !recursive {
+      return state.mkdir(path, mode);
+    }
+    let mut current = PathBuf::new();
+    for component in path.components() {
+      current.push(component);
+      let resolved = state.resolve(&current, true)?;
+      match state.entries.get(&resolved) {
+        Some(Entry::Dir(_)) => {}
+        Some(_) => return Err(not_a_directory()),
+        None => state.mkdir(resolved, mode)?,
+      }
+    }
+    Ok(())
+  }
+
+  fn chmod(&self, path: &Path, mode: u32) -> Result<(), AnyError> {
+    let mut state = self.state.lock();
+    let path = state.resolve(path, true)?;
+    state
+      .get_mut(&path)?
+      .with_meta_mut(|meta| meta.mode = mode & 0o7777);
+    Ok(())
+  }
+
+  fn chown(
+    &self,
+    path: &Path,
+    uid: Option<u32>,
+    gid: Option<u32>,
+  ) -> Result<(), AnyError> {
+    let mut state = self.state.lock();
+    let path = state.resolve(path, true)?;
+    state.get_mut(&path)?.with_meta_mut(|meta| {
+      if let Some(uid) = uid {
+        meta.uid = uid;
+      }
+      if let Some(gid) = gid {
+        meta.gid = gid;
+      }
+    });
+    Ok(())
+  }
+
+  fn remove(&self, path: &Path, recursive: bool) -> io::Result<()> {
+    let mut state = self.state.lock();
+    let path = state.resolve(path, false)?;
+    if let Entry::Dir(_) = state.get(&path)? {
+      if path.parent().is_none() {
+        return Err(io::Error::new(
+          ErrorKind::PermissionDenied,
+          "Operation not permitted",
+        ));
+      }
+      if !recursive && state.has_children(&path) {
+        return Err(io::Error::new(ErrorKind::Other, "Directory not empty"));
+      }
+      let descendants = state
+        .entries
+        .keys()
+        .filter(|key| key.starts_with(&path))
+        .cloned()
+        .collect::<Vec<_>>();
+      for descendant in descendants {
+        state.remove_entry(&descendant);
+      }
+    } else {
+      state.remove_entry(&path);
+    }
+    Ok(())
+  }
+
+  fn copy_file(&self, from: &Path, to: &Path) -> io::Result<()> {
+    let mut state = self.state.lock();
+    let from = state.resolve(from, true)?;
+    let to = state.resolve(to, true)?;
+    let (data, mode) = {
+      let from_file = state.get_file(&from)?;
+      let from_file = from_file.lock();
+      (from_file.data.clone(), from_file.meta.mode)
+    };
+    let to_file = if state.entries.contains_key(&to) {
+      state.get_file(&to)?
+    } else {
+      state.create_file(to, mode)?
+    };
+    let mut to_file = to_file.lock();
+    to_file.data = data;
+    to_file.meta.mode = mode;
+    to_file.meta.mtime = now_msec();
+    Ok(())
+  }
+
+  fn stat(&self, path: &Path) -> io::Result<FsStat> {
+    let state = self.state.lock();
+    let path = state.resolve(path, true)?;
+    Ok(state.get(&path)?.stat())
+  }
+
+  fn lstat(&self, path: &Path) -> io::Result<FsStat> {
+    let state = self.state.lock();
+    let path = state.resolve(path, false)?;
+    Ok(state.get(&path)?.stat())
+  }
+
+  fn realpath(&self, path: &Path) -> io::Result<PathBuf> {
+    let state = self.state.lock();
+    let path = state.resolve(path, true)?;
+    state.get(&path)?;
+    Ok(path)
+  }
+
+  fn read_dir(&self, path: &Path) -> io::Result<Vec<FsDirEntry>> {
+    let state = self.state.lock();
+    let path = state.resolve(path, true)?;
+    if !matches!(state.get(&path)?, Entry::Dir(_)) {
+      return Err(not_a_directory());
+    }
+    let mut entries = state
+      .entries
+      .iter()
+      .filter(|(key, _)| key.parent() == Some(path.as_path()))
+      .filter_map(|(key, entry)| {
+        Some(FsDirEntry {
+          name: key.file_name()?.to_str()?.to_string(),
+          is_file: matches!(entry, Entry::File(_)),
+          is_directory: matches!(entry, Entry::Dir(_)),
+          is_symlink: matches!(entry, Entry::Symlink(..)),
+        })
+      })
+      .collect::<Vec<_>>();
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(entries)
+  }
+
+  fn rename(&self, oldpath: &Path, newpath: &Path) -> io::Result<()> {
+    let mut state = self.state.lock();
+    let oldpath = state.resolve(oldpath, false)?;
+    let newpath = state.resolve(newpath, false)?;
+    let is_dir = matches!(state.get(&oldpath)?, Entry::Dir(_));
+    if oldpath == newpath {
+      return Ok(());
+    }
+    if is_dir && newpath.starts_with(&oldpath) {
+      return Err(io::Error::new(ErrorKind::InvalidInput, "Invalid argument"));
+    }
+    match state.entries.get(&newpath) {
+      Some(Entry::Dir(_)) if !is_dir => return Err(is_a_directory()),
+      Some(Entry::Dir(_)) if state.has_children(&newpath) => {
+        return Err(io::Error::new(ErrorKind::Other, "Directory not empty"))
+      }
+      Some(_) if is_dir => return Err(not_a_directory()),
+      Some(_) => state.remove_entry(&newpath),
+      None => state.check_parent(&newpath)?,
+    }
+    let moved = state
+      .entries
+      .keys()
+      .filter(|key| key.starts_with(&oldpath))
+      .cloned()
+      .collect::<Vec<_>>();
+    for key in moved {
+      let entry = state.entries.remove(&key).unwrap();
+      let suffix = key.strip_prefix(&oldpath).unwrap();
+      let new_key = if suffix.as_os_str().is_empty() {
+        newpath.clone()
+      } else {
+        newpath.join(suffix)
+      };
+      state.entries.insert(new_key, entry);
+    }
+    Ok(())
+  }
+
+  fn link(&self, oldpath: &Path, newpath: &Path) -> io::Result<()> {
+    let mut state = self.state.lock();
+    let oldpath = state.resolve(oldpath, false)?;
+    let newpath = state.resolve(newpath, false)?;
+    let entry = match state.get(&oldpath)? {
+      Entry::Dir(_) => {
+        return Err(io::Error::new(
+          ErrorKind::PermissionDenied,
+          "Operation not permitted",
+        ))
+      }
+      entry => entry.clone(),
+    };
+    state.check_new(&newpath)?;
+    if let Entry::File(file) = &entry {
+      file.lock().nlink += 1;
+    }
+    state.entries.insert(newpath, entry);
+    Ok(())
+  }
+
+  fn symlink(
+    &self,
+    oldpath: &Path,
+    newpath: &Path,
+    _file_type: Option<FsFileType>,
+  ) -> Result<(), AnyError> {
+    let mut state = self.state.lock();
+    let newpath = state.resolve(newpath, false)?;
+    state.check_new(&newpath)?;
+    let meta = state.new_meta(0o777);
+    state
+      .entries
+      .insert(newpath, Entry::Symlink(oldpath.to_path_buf(), meta));
+    Ok(())
+  }
+
+  fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+    let state = self.state.lock();
+    let path = state.resolve(path, false)?;
+    match state.get(&path)? {
+      Entry::Symlink(target, _) => Ok(target.clone()),
+      _ => Err(io::Error::new(ErrorKind::InvalidInput, "Invalid argument")),
+    }
+  }
+
+  fn truncate(&self, path: &Path, len: u64) -> io::Result<()> {
+    let state = self.state.lock();
+    let path = state.resolve(path, true)?;
+    let file = state.get_file(&path)?;
+    let mut file = file.lock();
+    file.data.resize(len as usize, 0);
+    file.meta.mtime = now_msec();
+    Ok(())
+  }
+
+  fn utime(
+    &self,
+    path: &Path,
+    atime: FileTime,
+    mtime: FileTime,
+  ) -> io::Result<()> {
+    let mut state = self.state.lock();
+    let path = state.resolve(path, true)?;
+    state.get_mut(&path)?.with_meta_mut(|meta| {
+      meta.atime = filetime_to_msec(atime);
+      meta.mtime = filetime_to_msec(mtime);
+    });
+    Ok(())
+  }
+}
+
+struct InMemoryFile {
+  file: Arc<Mutex<FileData>>,
+  position: u64,
+  read: bool,
+  write: bool,
+  append: bool,
+}
+
+impl File for InMemoryFile {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    if !self.read {
+      return Err(bad_file_descriptor());
+    }
+    let mut file = self.file.lock();
+    let start = (self.position as usize).min(file.data.len());
+    let n = buf.len().min(file.data.len() - start);
+    buf[..n].copy_from_slice(&file.data[start..start + n]);
+    file.meta.atime = now_msec();
+    self.position += n as u64;
+    Ok(n)
+  }
+
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    if !self.write {
+      return Err(bad_file_descriptor());
+    }
+    let mut file = self.file.lock();
+    if self.append {
+      self.position = file.data.len() as u64;
+    }
+    let start = self.position as usize;
+    let end = start + buf.len();
+    if file.data.len() < end {
+      file.data.resize(end, 0);
+    }
+    file.data[start..end].copy_from_slice(buf);
+    file.meta.mtime = now_msec();
+    self.position = end as u64;
+    Ok(buf.len())
+  }
+
+  fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+    let (base, offset) = match pos {
+      SeekFrom::Start(offset) => {
+        self.position = offset;
+        return Ok(offset);
+      }
+      SeekFrom::End(offset) => (self.file.lock().data.len() as u64, offset),
+      SeekFrom::Current(offset) => (self.position, offset),
+    };
+    let position = if offset >= 0 {
+      base.checked_add(offset as u64)
+    } else {
+      base.checked_sub(offset.unsigned_abs())
+    };
+    match position {
+      Some(position) => {
+        self.position = position;
+        Ok(position)
+      }
+      None => Err(io::Error::new(
+        ErrorKind::InvalidInput,
+        "invalid seek to a negative or overflowing position",
+      )),
+    }
+  }
+
+  fn sync_data(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+
+  fn sync_all(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+
+  fn stat(&mut self) -> io::Result<FsStat> {
+    Ok(self.file.lock().stat())
+  }
+
+  fn lock(&mut self, _exclusive: bool) -> io::Result<()> {
+    // There are no other processes sharing the file system.
+    Ok(())
+  }
+
+  fn unlock(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+
+  fn truncate(&mut self, len: u64) -> io::Result<()> {
+    if !self.write {
+      return Err(bad_file_descriptor());
+    }
+    let mut file = self.file.lock();
+    file.data.resize(len as usize, 0);
+    file.meta.mtime = now_msec();
+    Ok(())
+  }
+
+  fn utime(&mut self, atime: FileTime, mtime: FileTime) -> io::Result<()> {
+    let mut file = self.file.lock();
+    file.meta.atime = filetime_to_msec(atime);
+    file.meta.mtime = filetime_to_msec(mtime);
+    Ok(())
+  }
+
+  fn try_clone(&self) -> io::Result<Box<dyn File>> {
+    Ok(Box::new(InMemoryFile {
+      file: self.file.clone(),
+      position: self.position,
+      read: self.read,
+      write: self.write,
+      append: self.append,
+    }))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn read_to_string(fs: &InMemoryFs, path: &str) -> String {
+    String::from_utf8(fs.read_file(Path::new(path)).unwrap()).unwrap()
+  }
+
+  #[test]
+  fn write_and_read_file() {
+    let fs = InMemoryFs::new();
+    let path = Path::new("/hello.txt");
+    fs.write_file(path, OpenOptions::write(true, false, false, None), b"hi")
+      .unwrap();
+    fs.write_file(path, OpenOptions::write(true, true, false, None), b"!")
+      .unwrap();
+    assert_eq!(read_to_string(&fs, "/hello.txt"), "hi!");
+
+    let stat = fs.stat(path).unwrap();
+    assert!(stat.is_file);
+    assert_eq!(stat.size, 3);
+    assert_eq!(stat.mode, S_IFREG | 0o644);
+
+    let err = fs
+      .write_file(path, OpenOptions::write(true, false, true, None), b"")
+      .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+    let err = fs.read_file(Path::new("/missing.txt")).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+  }
+
+  #[test]
+  fn file_handle_seek() {
+    let fs = InMemoryFs::new();
+    let mut options = OpenOptions::write(true, false, false, None);
+    options.read = true;
+    let mut file = fs.open(Path::new("/a.txt"), options).unwrap();
+    file.write_all(b"hello world").unwrap();
+    assert_eq!(file.seek(SeekFrom::Start(6)).unwrap(), 6);
+    let mut buf = [0; 5];
+    assert_eq!(file.read(&mut buf).unwrap(), 5);
+    assert_eq!(&buf, b"world");
+    assert_eq!(file.seek(SeekFrom::End(-5)).unwrap(), 6);
+    assert!(file.seek(SeekFrom::Current(-7)).is_err());
+    file.truncate(5).unwrap();
+    assert_eq!(read_to_string(&fs, "/a.txt"), "hello");
+  }
+
+  #[test]
+  fn mkdir_and_read_dir() {
+    let fs = InMemoryFs::new();
+    let err = fs.mkdir(Path::new("/a/b"), false, 0o777).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    fs.mkdir(Path::new("/a/b/c"), true, 0o777).unwrap();
+    fs.mkdir(Path::new("/a/b"), true, 0o777).unwrap();
+    fs.write_file(
+      Path::new("/a/file.txt"),
+      OpenOptions::write(true, false, false, None),
+      b"",
+    )
+    .unwrap();
+
+    let entries = fs.read_dir(Path::new("/a")).unwrap();
+    let names = entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["b", "file.txt"]);
+    assert!(entries[0].is_directory);
+    assert!(entries[1].is_file);
+    assert_eq!(fs.stat(Path::new("/a/b")).unwrap().mode, S_IFDIR | 0o755);
+  }
+
+  #[test]
+  fn relative_paths_use_cwd() {
+    let fs = InMemoryFs::new();
+    fs.mkdir(Path::new("/project/src"), true, 0o777).unwrap();
+    fs.chdir(Path::new("/project")).unwrap();
+    fs.write_file(
+      Path::new("./src/../main.ts"),
+      OpenOptions::write(true, false, false, None),
+      b"main",
+    )
+    .unwrap();
+    assert_eq!(fs.cwd().unwrap(), PathBuf::from("/project"));
+    assert_eq!(read_to_string(&fs, "/project/main.ts"), "main");
+    assert!(fs.chdir(Path::new("main.ts")).is_err());
+  }
+
+  #[test]
+  fn symlinks() {
+    let fs = InMemoryFs::new();
+    fs.mkdir(Path::new("/dir"), false, 0o777).unwrap();
+    fs.write_file(
+      Path::new("/dir/target.txt"),
+      OpenOptions::write(true, false, false, None),
+      b"target",
+    )
+    .unwrap();
+    fs.symlink(Path::new("dir"), Path::new("/link"), None)
+      .unwrap();
+    fs.symlink(Path::new("/loop"), Path::new("/loop"), None)
+      .unwrap();
+
+    assert_eq!(read_to_string(&fs, "/link/target.txt"), "target");
+    assert!(fs.stat(Path::new("/link")).unwrap().is_directory);
+    assert!(fs.lstat(Path::new("/link")).unwrap().is_symlink);
+    assert_eq!(fs.read_link(Path::new("/link")).unwrap(), Path::new("dir"));
+    assert_eq!(
+      fs.realpath(Path::new("/link/target.txt")).unwrap(),
+      PathBuf::from("/dir/target.txt")
+    );
+    assert!(fs.stat(Path::new("/loop")).is_err());
+
+    fs.remove(Path::new("/link"), false).unwrap();
+    assert!(fs.stat(Path::new("/dir/target.txt")).is_ok());
+  }
+
+  #[test]
+  fn hard_links_share_data() {
+    let fs = InMemoryFs::new();
+    fs.write_file(
+      Path::new("/a.txt"),
+      OpenOptions::write(true, false, false, None),
+      b"a",
+    )
+    .unwrap();
+    fs.link(Path::new("/a.txt"), Path::new("/b.txt")).unwrap();
+    assert_eq!(fs.stat(Path::new("/a.txt")).unwrap().nlink, 2);
+    fs.write_file(
+      Path::new("/b.txt"),
+      OpenOptions::write(false, true, false, None),
+      b"b",
+    )
+    .unwrap();
+    assert_eq!(read_to_string(&fs, "/a.txt"), "ab");
+    fs.remove(Path::new("/a.txt"), false).unwrap();
+    assert_eq!(fs.stat(Path::new("/b.txt")).unwrap().nlink, 1);
+  }
+
+  #[test]
+  fn rename_and_remove_dir() {
+    let fs = InMemoryFs::new();
+    fs.mkdir(Path::new("/a/b"), true, 0o777).unwrap();
+    fs.write_file(
+      Path::new("/a/b/c.txt"),
+      OpenOptions::write(true, false, false, None),
+      b"c",
+    )
+    .unwrap();
+    fs.rename(Path::new("/a"), Path::new("/z")).unwrap();
+    assert!(fs.stat(Path::new("/a")).is_err());
+    assert_eq!(read_to_string(&fs, "/z/b/c.txt"), "c");
+
+    let err = fs.remove(Path::new("/z"), false).unwrap_err();
+    assert_eq!(err.to_string(), "Directory not empty");
+    fs.remove(Path::new("/z"), true).unwrap();
+    let names = fs.read_dir(Path::new("/")).unwrap();
+    assert_eq!(names.len(), 1);
+    assert_eq!(names[0].name, "tmp");
+  }
+}
@@ -0,0 +1,333 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! The file system used by the `Deno.*` file system APIs.
+//!
+//! All file system ops go through the [`FileSystem`] stored in the op state,
+//! which allows embedders to sandbox or virtualize file access by passing
+//! their own implementation in `WorkerOptions::fs`. [`RealFs`] uses the file
+//! system of the OS and [`InMemoryFs`] keeps everything in memory, which is
+//! mostly useful for tests.
+
+mod fetch_handler;
+mod in_memory_fs;
+mod real_fs;
+
+pub use fetch_handler::FileSystemFetchHandler;
+pub use in_memory_fs::InMemoryFs;
+pub use real_fs::RealFs;
+
+use deno_core::error::AnyError;
+use std::fs::File as StdFile;
+use std::io;
+use std::io::SeekFrom;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+pub use filetime::FileTime;
+
+pub type FileSystemRc = Arc<dyn FileSystem>;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpenOptions {
+  pub read: bool,
+  pub write: bool,
+  pub create: bool,
+  pub truncate: bool,
+  pub append: bool,
+  pub create_new: bool,
+  /// The permissions of the file when it's created. Defaults to `0o666`
+  /// (before applying the umask).
+  pub mode: Option<u32>,
+}
+
+impl OpenOptions {
+  pub fn read() -> Self {
+    Self {
+      read: true,
+      ..Default::default()
+    }
+  }
+
+  pub fn write(
+    create: bool,
+    append: bool,
+    create_new: bool,
+    mode: Option<u32>,
+  ) -> Self {
+    Self {
+      read: false,
+      write: true,
+      create,
+      truncate: !append,
+      append,
+      create_new,
+      mode,
+    }
+  }
+}
+
+/// The type of the target of a symlink, which is required on Windows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsFileType {
+  File,
+  Directory,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FsStat {
+  pub is_file: bool,
+  pub is_directory: bool,
+  pub is_symlink: bool,
+  pub size: u64,
+  // In milliseconds, like JavaScript. Available on both Unix or Windows.
+  pub mtime: Option<u64>,
+  pub atime: Option<u64>,
+  pub birthtime: Option<u64>,
+  // Following are only valid under Unix.
+  pub dev: u64,
+  pub ino: u64,
+  pub mode: u32,
+  pub nlink: u64,
+  pub uid: u32,
+  pub gid: u32,
+  pub rdev: u64,
+  pub blksize: u64,
+  pub blocks: u64,
+}
+
+impl FsStat {
+  pub fn from_std(metadata: std::fs::Metadata) -> Self {
+    // Unix stat member (number types only). 0 if not on unix.
+    macro_rules! usm {
+      ($member:ident) => {{
+        #[cfg(unix)]
+        {
+          metadata.$member()
+        }
+        #[cfg(not(unix))]
+        {
+          0
+        }
+      }};
+    }
+
+    #[cfg(unix)]
+    use std::os::unix::fs::MetadataExt;
+
+    FsStat {
+      is_file: metadata.is_file(),
+      is_directory: metadata.is_dir(),
+      is_symlink: metadata.file_type().is_symlink(),
+      size: metadata.len(),
+      mtime: to_msec(metadata.modified()),
+      atime: to_msec(metadata.accessed()),
+      birthtime: to_msec(metadata.created()),
+      dev: usm!(dev),
+      ino: usm!(ino),
+      mode: usm!(mode),
+      nlink: usm!(nlink),
+      uid: usm!(uid),
+      gid: usm!(gid),
+      rdev: usm!(rdev),
+      blksize: usm!(blksize),
+      blocks: usm!(blocks),
+    }
+  }
+}
+
+fn to_msec(maybe_time: Result<SystemTime, io::Error>) -> Option<u64> {
+  match maybe_time {
+    Ok(time) => Some(
+      time
+        .duration_since(UNIX_EPOCH)
+        .map(|t| t.as_millis() as u64)
+        .unwrap_or_else(|err| err.duration().as_millis() as u64),
+    ),
+    Err(_) => None,
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct FsDirEntry {
+  pub name: String,
+  pub is_file: bool,
+  pub is_directory: bool,
+  pub is_symlink: bool,
+}
+
+/// A file opened with [`FileSystem::open`].
+pub trait File: Send {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>;
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize>;
+
+  fn write_all(&mut self, mut buf: &[u8]) -> io::Result<()> {
+    while !buf.is_empty() {
+      match self.write(buf)? {
+        0 => return Err(io::ErrorKind::WriteZero.into()),
+        n => buf = &buf[n..],
+      }
+    }
+    Ok(())
+  }
+
+  fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+    let start = buf.len();
+    let mut chunk = [0; 64 * 1024];
+    loop {
+      match self.read(&mut chunk)? {
+        0 => return Ok(buf.len() - start),
+        n => buf.extend_from_slice(&chunk[..n]),
+      }
+    }
+  }
+
+  fn seek(&mut self, pos: SeekFrom) -> io::Result<u64>;
+  fn sync_data(&mut self) -> io::Result<()>;
+  fn sync_all(&mut self) -> io::Result<()>;
+  fn stat(&mut self) -> io::Result<FsStat>;
+  fn lock(&mut self, exclusive: bool) -> io::Result<()>;
+  fn unlock(&mut self) -> io::Result<()>;
+  fn truncate(&mut self, len: u64) -> io::Result<()>;
+  fn utime(&mut self, atime: FileTime, mtime: FileTime) -> io::Result<()>;
+  fn try_clone(&self) -> io::Result<Box<dyn File>>;
+
+  /// The OS file backing this file, which is needed by APIs like passing the
+  /// file to a subprocess or using it as a TTY.
+  fn as_std_file(&mut self) -> Option<&mut StdFile> {
+    None
+  }
+}
+
+impl File for StdFile {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    io::Read::read(self, buf)
+  }
+
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    io::Write::write(self, buf)
+  }
+
+  fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+    io::Write::write_all(self, buf)
+  }
+
+  fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+    io::Read::read_to_end(self, buf)
+  }
+
+  fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+    io::Seek::seek(self, pos)
+  }
+
+  fn sync_data(&mut self) -> io::Result<()> {
+    StdFile::sync_data(self)
+  }
+
+  fn sync_all(&mut self) -> io::Result<()> {
+    StdFile::sync_all(self)
+  }
+
+  fn stat(&mut self) -> io::Result<FsStat> {
+    self.metadata().map(FsStat::from_std)
+  }
+
+  fn lock(&mut self, exclusive: bool) -> io::Result<()> {
+    use fs3::FileExt;
+    if exclusive {
+      self.lock_exclusive()
+    } else {
+      self.lock_shared()
+    }
+  }
+
+  fn unlock(&mut self) -> io::Result<()> {
+    fs3::FileExt::unlock(self)
+  }
+
+  fn truncate(&mut self, len: u64) -> io::Result<()> {
+    self.set_len(len)
+  }
+
+  fn utime(&mut self, atime: FileTime, mtime: FileTime) -> io::Result<()> {
+    filetime::set_file_handle_times(self, Some(atime), Some(mtime))
+  }
+
+  fn try_clone(&self) -> io::Result<Box<dyn File>> {
+    Ok(Box::new(StdFile::try_clone(self)?))
+  }
+
+  fn as_std_file(&mut self) -> Option<&mut StdFile> {
+    Some(self)
+  }
+}
+
+/// The operations behind the `Deno.*` file system APIs. Permissions are
+/// checked by the ops before calling into the file system.
+///
+/// Relative paths are resolved against [`FileSystem::cwd`].
+pub trait FileSystem: Send + Sync {
+  fn cwd(&self) -> io::Result<PathBuf>;
+  fn chdir(&self, path: &Path) -> io::Result<()>;
+  fn tmp_dir(&self) -> io::Result<PathBuf>;
+  /// Sets the umask when a mask is provided and returns the previous one.
+  fn umask(&self, mask: Option<u32>) -> Result<u32, AnyError>;
+
+  fn open(
+    &self,
+    path: &Path,
+    options: OpenOptions,
+  ) -> io::Result<Box<dyn File>>;
+  fn mkdir(&self, path: &Path, recursive: bool, mode: u32) -> io::Result<()>;
+  fn chmod(&self, path: &Path, mode: u32) -> Result<(), AnyError>;
+  fn chown(
+    &self,
+    path: &Path,
+    uid: Option<u32>,
+    gid: Option<u32>,
+  ) -> Result<(), AnyError>;
+  fn remove(&self, path: &Path, recursive: bool) -> io::Result<()>;
+  fn copy_file(&self, from: &Path, to: &Path) -> io::Result<()>;
+
+  fn stat(&self, path: &Path) -> io::Result<FsStat>;
+  fn lstat(&self, path: &Path) -> io::Result<FsStat>;
+  fn realpath(&self, path: &Path) -> io::Result<PathBuf>;
+  fn read_dir(&self, path: &Path) -> io::Result<Vec<FsDirEntry>>;
+
+  fn rename(&self, oldpath: &Path, newpath: &Path) -> io::Result<()>;
+  fn link(&self, oldpath: &Path, newpath: &Path) -> io::Result<()>;
+  fn symlink(
+    &self,
+    oldpath: &Path,
+    newpath: &Path,
+    file_type: Option<FsFileType>,
+  ) -> Result<(), AnyError>;
+  fn read_link(&self, path: &Path) -> io::Result<PathBuf>;
+
+  fn truncate(&self, path: &Path, len: u64) -> io::Result<()>;
+  fn utime(
+    &self,
+    path: &Path,
+    atime: FileTime,
+    mtime: FileTime,
+  ) -> io::Result<()>;
+
+  fn write_file(
+    &self,
+    path: &Path,
+    options: OpenOptions,
+    data: &[u8],
+  ) -> io::Result<()> {
+    let mut file = self.open(path, options)?;
+    file.write_all(data)
+  }
+
+  fn read_file(&self, path: &Path) -> io::Result<Vec<u8>> {
+    let mut file = self.open(path, OpenOptions::read())?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)?;
+    Ok(buf)
+  }
+}
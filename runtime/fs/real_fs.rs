@@ -0,0 +1,393 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use super::File;
+use super::FileSystem;
+use super::FileTime;
+use super::FsDirEntry;
+use super::FsFileType;
+use super::FsStat;
+use super::OpenOptions;
+use crate::fs_util::canonicalize_path;
+
+use deno_core::error::AnyError;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+#[cfg(not(unix))]
+use deno_core::error::generic_error;
+#[cfg(not(unix))]
+use deno_core::error::not_supported;
+
+/// The file system of the OS.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RealFs;
+
+impl FileSystem for RealFs {
+  fn cwd(&self) -> io::Result<PathBuf> {
+    std::env::current_dir()
+  }
+
+  fn chdir(&self, path: &Path) -> io::Result<()> {
+    std::env::set_current_dir(path)
+  }
+
+  fn tmp_dir(&self) -> io::Result<PathBuf> {
+    Ok(std::env::temp_dir())
+  }
+
+  fn umask(&self, mask: Option<u32>) -> Result<u32, AnyError> {
+    // TODO implement umask for Windows
+    // see https://github.com/nodejs/node/blob/master/src/node_process_methods.cc
+    // and https://docs.microsoft.com/fr-fr/cpp/c-runtime-library/reference/umask?view=vs-2019
+    #[cfg(not(unix))]
+    {
+      let _ = mask; // avoid unused warning.
+      Err(not_supported())
+    }
+    #[cfg(unix)]
+    {
+      use nix::sys::stat::mode_t;
+      use nix::sys::stat::umask;
+      use nix::sys::stat::Mode;
+      let r = if let Some(mask) = mask {
+        // If mask provided, return previous.
+        umask(Mode::from_bits_truncate(mask as mode_t))
+      } else {
+        // If no mask provided, we query the current. Requires two syscalls.
+        let prev = umask(Mode::from_bits_truncate(0o777));
+        let _ = umask(prev);
+        prev
+      };
+      #[cfg(target_os = "linux")]
+      {
+        Ok(r.bits())
+      }
+      #[cfg(target_os = "macos")]
+      {
+        Ok(r.bits() as u32)
+      }
+    }
+  }
+
+  fn open(
+    &self,
+    path: &Path,
+    options: OpenOptions,
+  ) -> io::Result<Box<dyn File>> {
+    let std_file = open_options(options).open(path)?;
+    Ok(Box::new(std_file))
+  }
+
+  fn mkdir(&self, path: &Path, recursive: bool, mode: u32) -> io::Result<()> {
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(recursive);
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::DirBuilderExt;
+      builder.mode(mode);
+    }
+    #[cfg(not(unix))]
+    let _ = mode; // avoid unused warning
+    builder.create(path)
+  }
+
+  fn chmod(&self, path: &Path, _mode: u32) -> Result<(), AnyError> {
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+      let permissions = PermissionsExt::from_mode(_mode);
+      std::fs::set_permissions(path, permissions)?;
+      Ok(())
+    }
+    // TODO Implement chmod for Windows (#4357)
+    #[cfg(not(unix))]
+    {
+      // Still check file/dir exists on Windows
+      let _metadata = std::fs::metadata(path)?;
+      Err(not_supported())
+    }
+  }
+
+  fn chown(
+    &self,
+    path: &Path,
+    #[cfg_attr(windows, allow(unused_variables))] uid: Option<u32>,
+    #[cfg_attr(windows, allow(unused_variables))] gid: Option<u32>,
+  ) -> Result<(), AnyError> {
+    #[cfg(unix)]
+    {
+      use crate::errors::get_nix_error_class;
+      use deno_core::error::custom_error;
+      use nix::unistd::chown;
+      use nix::unistd::Gid;
+      use nix::unistd::Uid;
+      let nix_uid = uid.map(Uid::from_raw);
+      let nix_gid = gid.map(Gid::from_raw);
+      chown(path, nix_uid, nix_gid).map_err(|err| {
+        custom_error(
+          get_nix_error_class(&err),
+          format!("{}, chown '{}'", err.desc(), path.display()),
+        )
+      })?;
+      Ok(())
+    }
+    // TODO Implement chown for Windows
+    #[cfg(not(unix))]
+    {
+      let _ = path; // avoid unused warning
+      Err(generic_error("Not implemented"))
+    }
+  }
+
+  fn remove(&self, path: &Path, recursive: bool) -> io::Result<()> {
+    #[cfg(not(unix))]
+    use std::os::windows::prelude::MetadataExt;
+
+    let metadata = std::fs::symlink_metadata(path)?;
+
+    let file_type = metadata.file_type();
+    if file_type.is_file() {
+      std::fs::remove_file(path)
+    } else if recursive {
+      std::fs::remove_dir_all(path)
+    } else if file_type.is_symlink() {
+      #[cfg(unix)]
+      {
+        std::fs::remove_file(path)
+      }
+      #[cfg(not(unix))]
+      {
+        use winapi::um::winnt::FILE_ATTRIBUTE_DIRECTORY;
+        if metadata.file_attributes() & FILE_ATTRIBUTE_DIRECTORY != 0 {
+          std::fs::remove_dir(path)
+        } else {
+          std::fs::remove_file(path)
+        }
+      }
+    } else if file_type.is_dir() {
+      std::fs::remove_dir(path)
+    } else {
+      // pipes, sockets, etc...
+      std::fs::remove_file(path)
+    }
+  }
+
+  fn copy_file(&self, from: &Path, to: &Path) -> io::Result<()> {
+    // On *nix, Rust reports non-existent `from` as ErrorKind::InvalidInput
+    // See https://github.com/rust-lang/rust/issues/54800
+    // Once the issue is resolved, we should remove this workaround.
+    if cfg!(unix) && !from.is_file() {
+      return Err(io::Error::new(io::ErrorKind::NotFound, "File not found"));
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+      use libc::clonefile;
+      use libc::stat;
+      use libc::unlink;
+      use std::ffi::CString;
+      use std::io::Read;
+      use std::os::unix::ffi::OsStrExt;
+      use std::os::unix::fs::OpenOptionsExt;
+      use std::os::unix::fs::PermissionsExt;
+
+      let from_str = CString::new(from.as_os_str().as_bytes()).unwrap();
+      let to_str = CString::new(to.as_os_str().as_bytes()).unwrap();
+
+      // SAFETY: `from` and `to` are valid C strings.
+      // std::fs::copy does open() + fcopyfile() on macOS. We try to use
+      // clonefile() instead, which is more efficient.
+      unsafe {
+        let mut st = std::mem::zeroed();
+        let ret = stat(from_str.as_ptr(), &mut st);
+        if ret != 0 {
+          return Err(io::Error::last_os_error());
+        }
+
+        if st.st_size > 128 * 1024 {
+          // Try unlink. If it fails, we are going to try clonefile() anyway.
+          let _ = unlink(to_str.as_ptr());
+          // Matches rust stdlib behavior for io::copy.
+          // https://github.com/rust-lang/rust/blob/3fdd578d72a24d4efc2fe2ad18eec3b6ba72271e/library/std/src/sys/unix/fs.rs#L1613-L1616
+          if clonefile(from_str.as_ptr(), to_str.as_ptr(), 0) == 0 {
+            return Ok(());
+          }
+        } else {
+          // Do a regular copy. fcopyfile() is an overkill for < 128KB
+          // files.
+          let mut buf = [0u8; 128 * 1024];
+          let mut from_file = std::fs::File::open(from)?;
+          let perm = from_file.metadata()?.permissions();
+
+          let mut to_file = std::fs::OpenOptions::new()
+            // create the file with the correct mode right away
+            .mode(perm.mode())
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(to)?;
+          let writer_metadata = to_file.metadata()?;
+          if writer_metadata.is_file() {
+            // Set the correct file permissions, in case the file already existed.
+            // Don't set the permissions on already existing non-files like
+            // pipes/FIFOs or device nodes.
+            to_file.set_permissions(perm)?;
+          }
+          loop {
+            let nread = from_file.read(&mut buf)?;
+            if nread == 0 {
+              break;
+            }
+            to_file.write_all(&buf[..nread])?;
+          }
+          return Ok(());
+        }
+      }
+
+      // clonefile() failed, fall back to std::fs::copy().
+    }
+
+    // returns size of from as u64 (we ignore)
+    std::fs::copy(from, to)?;
+    Ok(())
+  }
+
+  fn stat(&self, path: &Path) -> io::Result<FsStat> {
+    std::fs::metadata(path).map(FsStat::from_std)
+  }
+
+  fn lstat(&self, path: &Path) -> io::Result<FsStat> {
+    std::fs::symlink_metadata(path).map(FsStat::from_std)
+  }
+
+  fn realpath(&self, path: &Path) -> io::Result<PathBuf> {
+    // corresponds to the realpath on Unix and
+    // CreateFile and GetFinalPathNameByHandle on Windows
+    canonicalize_path(path)
+  }
+
+  fn read_dir(&self, path: &Path) -> io::Result<Vec<FsDirEntry>> {
+    let entries = std::fs::read_dir(path)?
+      .filter_map(|entry| {
+        let entry = entry.ok()?;
+        // Not all filenames can be encoded as UTF-8. Skip those for now.
+        let name = entry.file_name().into_string().ok()?;
+        let file_type = entry.file_type();
+        Some(FsDirEntry {
+          name,
+          is_file: file_type.as_ref().map_or(false, |t| t.is_file()),
+          is_directory: file_type.as_ref().map_or(false, |t| t.is_dir()),
+          is_symlink: file_type.as_ref().map_or(false, |t| t.is_symlink()),
+        })
+      })
+      .collect();
+    Ok(entries)
+  }
+
+  fn rename(&self, oldpath: &Path, newpath: &Path) -> io::Result<()> {
+    std::fs::rename(oldpath, newpath)
+  }
+
+  fn link(&self, oldpath: &Path, newpath: &Path) -> io::Result<()> {
+    std::fs::hard_link(oldpath, newpath)
+  }
+
+  fn symlink(
+    &self,
+    oldpath: &Path,
+    newpath: &Path,
+    _file_type: Option<FsFileType>,
+  ) -> Result<(), AnyError> {
+    #[cfg(unix)]
+    {
+      std::os::unix::fs::symlink(oldpath, newpath)?;
+      Ok(())
+    }
+    #[cfg(not(unix))]
+    {
+      use deno_core::error::type_error;
+      use std::os::windows::fs::symlink_dir;
+      use std::os::windows::fs::symlink_file;
+
+      let file_type = match _file_type {
+        Some(file_type) => file_type,
+        None => match std::fs::metadata(oldpath) {
+          Ok(metadata) if metadata.is_dir() => FsFileType::Directory,
+          Ok(_) => FsFileType::File,
+          Err(_) => return Err(type_error("you must pass a `options` argument for non-existent target path in windows".to_string())),
+        },
+      };
+      match file_type {
+        FsFileType::File => symlink_file(oldpath, newpath)?,
+        FsFileType::Directory => symlink_dir(oldpath, newpath)?,
+      };
+      Ok(())
+    }
+  }
+
+  fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+    std::fs::read_link(path)
+  }
+
+  fn truncate(&self, path: &Path, len: u64) -> io::Result<()> {
+    let f = std::fs::OpenOptions::new().write(true).open(path)?;
+    f.set_len(len)
+  }
+
+  fn utime(
+    &self,
+    path: &Path,
+    atime: FileTime,
+    mtime: FileTime,
+  ) -> io::Result<()> {
+    filetime::set_file_times(path, atime, mtime)
+  }
+
+  fn write_file(
+    &self,
+    path: &Path,
+    options: OpenOptions,
+    data: &[u8],
+  ) -> io::Result<()> {
+    let mut std_file = open_options(options).open(path)?;
+
+    // need to chmod the file if it already exists and a mode is specified
+    #[cfg(unix)]
+    if let Some(mode) = options.mode {
+      use std::os::unix::fs::PermissionsExt;
+      let permissions = PermissionsExt::from_mode(mode & 0o777);
+      std_file.set_permissions(permissions)?;
+    }
+
+    std_file.write_all(data)
+  }
+
+  fn read_file(&self, path: &Path) -> io::Result<Vec<u8>> {
+    std::fs::read(path)
+  }
+}
+
+fn open_options(options: OpenOptions) -> std::fs::OpenOptions {
+  let mut open_options = std::fs::OpenOptions::new();
+  if let Some(mode) = options.mode {
+    // mode only used if creating the file on Unix
+    // if not specified, defaults to 0o666
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::OpenOptionsExt;
+      open_options.mode(mode & 0o777);
+    }
+    #[cfg(not(unix))]
+    let _ = mode; // avoid unused warning
+  }
+  open_options
+    .read(options.read)
+    .write(options.write)
+    .create(options.create)
+    .truncate(options.truncate)
+    .append(options.append)
+    .create_new(options.create_new);
+  open_options
+}
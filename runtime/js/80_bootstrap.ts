@@ -60,6 +60,7 @@ import {
   flash,
   broadcastChannel,
   webStorage,
+  kv,
   crypto,
   internals,
 } from './index.js';
@@ -202,6 +203,9 @@ export const __bootstrap = {
   // packages/deno/runtime/src/ext/webstorage/01_webstorage.ts
   webStorage,
 
+  // packages/deno/runtime/src/ext/kv/01_db.ts
+  kv,
+
   // packages/deno/runtime/src/ext/crypto/00_crypto.ts
   crypto,
 };
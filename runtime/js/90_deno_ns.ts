@@ -154,4 +154,8 @@ export const denoNsUnstable = {
   serve: __bootstrap.flash.serve,
   upgradeHttp: __bootstrap.http.upgradeHttp,
   upgradeHttpRaw: __bootstrap.flash.upgradeHttpRaw,
+  openKv: __bootstrap.kv.openKv,
+  Kv: __bootstrap.kv.Kv,
+  AtomicOperation: __bootstrap.kv.AtomicOperation,
+  KvListIterator: __bootstrap.kv.KvListIterator,
 };
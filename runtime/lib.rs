@@ -9,6 +9,7 @@ pub use deno_fetch;
 pub use deno_ffi;
 pub use deno_flash;
 pub use deno_http;
+pub use deno_kv;
 pub use deno_napi;
 pub use deno_net;
 pub use deno_node;
@@ -23,6 +24,7 @@ pub use deno_webstorage;
 pub mod colors;
 pub mod errors;
 pub mod fmt_errors;
+pub mod fs;
 pub mod fs_util;
 pub mod inspector_server;
 pub mod js;
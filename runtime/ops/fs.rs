@@ -2,9 +2,12 @@
 // Some deserializer fields are only used on Unix and Windows build fails without it
 use super::io::StdFileResource;
 use super::utils::into_string;
-use crate::fs_util::canonicalize_path;
+use crate::fs::FileSystem;
+use crate::fs::FileSystemRc;
+use crate::fs::FileTime;
+use crate::fs::FsFileType;
+use crate::fs::FsStat;
 use crate::permissions::PermissionsContainer;
-use deno_core::error::custom_error;
 use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::op;
@@ -22,26 +25,13 @@ use serde::Serialize;
 use std::borrow::Cow;
 use std::cell::RefCell;
 use std::convert::From;
-use std::env::current_dir;
-use std::env::set_current_dir;
-use std::env::temp_dir;
-use std::io;
 use std::io::Error;
-use std::io::Seek;
 use std::io::SeekFrom;
-use std::io::Write;
 use std::path::Path;
 use std::path::PathBuf;
 use std::rc::Rc;
-use std::time::SystemTime;
-use std::time::UNIX_EPOCH;
 
-#[cfg(not(unix))]
-use deno_core::error::generic_error;
-#[cfg(not(unix))]
-use deno_core::error::not_supported;
-
-pub fn init() -> Extension {
+pub fn init(fs: FileSystemRc) -> Extension {
   Extension::builder("deno_fs")
     .ops(vec![
       op_open_sync::decl(),
@@ -104,9 +94,25 @@ pub fn init() -> Extension {
       op_readfile_async::decl(),
       op_readfile_text_async::decl(),
     ])
+    .state(move |state| {
+      state.put::<FileSystemRc>(fs.clone());
+      Ok(())
+    })
     .build()
 }
 
+/// Adds context to the error if it's an io error, like the `err_mapper`
+/// closures do for the file system methods returning `io::Result`.
+fn map_any_err(
+  err: AnyError,
+  err_mapper: impl FnOnce(Error) -> Error,
+) -> AnyError {
+  match err.downcast::<Error>() {
+    Ok(err) => err_mapper(err).into(),
+    Err(err) => err,
+  }
+}
+
 #[derive(Deserialize, Default, Debug)]
 #[serde(rename_all = "camelCase")]
 #[serde(default)]
@@ -126,35 +132,18 @@ fn open_helper(
   mode: Option<u32>,
   options: Option<&OpenOptions>,
   api_name: &str,
-) -> Result<(PathBuf, std::fs::OpenOptions), AnyError> {
+) -> Result<(PathBuf, crate::fs::OpenOptions), AnyError> {
   let path = Path::new(path).to_path_buf();
 
-  let mut open_options = std::fs::OpenOptions::new();
-
-  if let Some(mode) = mode {
-    // mode only used if creating the file on Unix
-    // if not specified, defaults to 0o666
-    #[cfg(unix)]
-    {
-      use std::os::unix::fs::OpenOptionsExt;
-      open_options.mode(mode & 0o777);
-    }
-    #[cfg(not(unix))]
-    let _ = mode; // avoid unused warning
-  }
-
   let permissions = state.borrow_mut::<PermissionsContainer>();
 
-  match options {
+  let open_options = match options {
     None => {
       permissions.check_read(&path, api_name)?;
-      open_options
-        .read(true)
-        .create(false)
-        .write(false)
-        .truncate(false)
-        .append(false)
-        .create_new(false);
+      crate::fs::OpenOptions {
+        mode,
+        ..crate::fs::OpenOptions::read()
+      }
     }
     Some(options) => {
       if options.read {
@@ -165,15 +154,17 @@ fn open_helper(
         permissions.check_write(&path, api_name)?;
       }
 
-      open_options
-        .read(options.read)
-        .create(options.create)
-        .write(options.write)
-        .truncate(options.truncate)
-        .append(options.append)
-        .create_new(options.create_new);
+      crate::fs::OpenOptions {
+        read: options.read,
+        write: options.write,
+        create: options.create,
+        truncate: options.truncate,
+        append: options.append,
+        create_new: options.create_new,
+        mode,
+      }
     }
-  }
+  };
 
   Ok((path, open_options))
 }
@@ -187,10 +178,11 @@ fn op_open_sync(
 ) -> Result<ResourceId, AnyError> {
   let (path, open_options) =
     open_helper(state, &path, mode, options.as_ref(), "Deno.openSync()")?;
-  let std_file = open_options.open(&path).map_err(|err| {
+  let fs = state.borrow::<FileSystemRc>().clone();
+  let file = fs.open(&path, open_options).map_err(|err| {
     Error::new(err.kind(), format!("{}, open '{}'", err, path.display()))
   })?;
-  let resource = StdFileResource::fs_file(std_file);
+  let resource = StdFileResource::fs_file(file);
   let rid = state.resource_table.add(resource);
   Ok(rid)
 }
@@ -209,13 +201,14 @@ async fn op_open_async(
     options.as_ref(),
     "Deno.open()",
   )?;
-  let std_file = tokio::task::spawn_blocking(move || {
-    open_options.open(&path).map_err(|err| {
+  let fs = state.borrow().borrow::<FileSystemRc>().clone();
+  let file = tokio::task::spawn_blocking(move || {
+    fs.open(&path, open_options).map_err(|err| {
       Error::new(err.kind(), format!("{}, open '{}'", err, path.display()))
     })
   })
   .await?;
-  let resource = StdFileResource::fs_file(std_file?);
+  let resource = StdFileResource::fs_file(file?);
   let rid = state.borrow_mut().resource_table.add(resource);
   Ok(rid)
 }
@@ -253,7 +246,8 @@ fn op_write_file_sync(
     Some(&write_open_options(create, append, create_new)),
     "Deno.writeFileSync()",
   )?;
-  write_file(&path, open_options, mode, data)
+  let fs = state.borrow::<FileSystemRc>().clone();
+  write_file(&*fs, &path, open_options, data)
 }
 
 #[op]
@@ -282,8 +276,9 @@ async fn op_write_file_async(
     Some(&write_open_options(create, append, create_new)),
     "Deno.writeFile()",
   )?;
+  let fs = state.borrow().borrow::<FileSystemRc>().clone();
   let write_future = tokio::task::spawn_blocking(move || {
-    write_file(&path, open_options, mode, data)
+    write_file(&*fs, &path, open_options, data)
   });
   if let Some(cancel_handle) = cancel_handle {
     write_future.or_cancel(cancel_handle).await???;
@@ -294,28 +289,14 @@ async fn op_write_file_async(
 }
 
 fn write_file(
+  fs: &dyn FileSystem,
   path: &Path,
-  open_options: std::fs::OpenOptions,
-  _mode: Option<u32>,
+  open_options: crate::fs::OpenOptions,
   data: ZeroCopyBuf,
 ) -> Result<(), AnyError> {
-  let mut std_file = open_options.open(path).map_err(|err| {
+  fs.write_file(path, open_options, &data).map_err(|err| {
     Error::new(err.kind(), format!("{}, open '{}'", err, path.display()))
   })?;
-
-  // need to chmod the file if it already exists and a mode is specified
-  #[cfg(unix)]
-  if let Some(mode) = _mode {
-    use std::os::unix::fs::PermissionsExt;
-    let permissions = PermissionsExt::from_mode(mode & 0o777);
-    std_file
-      .set_permissions(permissions)
-      .map_err(|err: Error| {
-        Error::new(err.kind(), format!("{}, chmod '{}'", err, path.display()))
-      })?;
-  }
-
-  std_file.write_all(&data)?;
   Ok(())
 }
 
@@ -347,8 +328,8 @@ fn seek_helper(args: SeekArgs) -> Result<(u32, SeekFrom), AnyError> {
 #[op]
 fn op_seek_sync(state: &mut OpState, args: SeekArgs) -> Result<u64, AnyError> {
   let (rid, seek_from) = seek_helper(args)?;
-  StdFileResource::with_file(state, rid, |std_file| {
-    std_file.seek(seek_from).map_err(AnyError::from)
+  StdFileResource::with_fs_file(state, rid, |file| {
+    file.seek(seek_from).map_err(AnyError::from)
   })
 }
 
@@ -359,8 +340,8 @@ async fn op_seek_async(
 ) -> Result<u64, AnyError> {
   let (rid, seek_from) = seek_helper(args)?;
 
-  StdFileResource::with_file_blocking_task(state, rid, move |std_file| {
-    std_file.seek(seek_from).map_err(AnyError::from)
+  StdFileResource::with_fs_file_blocking_task(state, rid, move |file| {
+    file.seek(seek_from).map_err(AnyError::from)
   })
   .await
 }
@@ -370,8 +351,8 @@ fn op_fdatasync_sync(
   state: &mut OpState,
   rid: ResourceId,
 ) -> Result<(), AnyError> {
-  StdFileResource::with_file(state, rid, |std_file| {
-    std_file.sync_data().map_err(AnyError::from)
+  StdFileResource::with_fs_file(state, rid, |file| {
+    file.sync_data().map_err(AnyError::from)
   })
 }
 
@@ -380,16 +361,16 @@ async fn op_fdatasync_async(
   state: Rc<RefCell<OpState>>,
   rid: ResourceId,
 ) -> Result<(), AnyError> {
-  StdFileResource::with_file_blocking_task(state, rid, move |std_file| {
-    std_file.sync_data().map_err(AnyError::from)
+  StdFileResource::with_fs_file_blocking_task(state, rid, move |file| {
+    file.sync_data().map_err(AnyError::from)
   })
   .await
 }
 
 #[op]
 fn op_fsync_sync(state: &mut OpState, rid: ResourceId) -> Result<(), AnyError> {
-  StdFileResource::with_file(state, rid, |std_file| {
-    std_file.sync_all().map_err(AnyError::from)
+  StdFileResource::with_fs_file(state, rid, |file| {
+    file.sync_all().map_err(AnyError::from)
   })
 }
 
@@ -398,8 +379,8 @@ async fn op_fsync_async(
   state: Rc<RefCell<OpState>>,
   rid: ResourceId,
 ) -> Result<(), AnyError> {
-  StdFileResource::with_file_blocking_task(state, rid, move |std_file| {
-    std_file.sync_all().map_err(AnyError::from)
+  StdFileResource::with_fs_file_blocking_task(state, rid, move |file| {
+    file.sync_all().map_err(AnyError::from)
   })
   .await
 }
@@ -410,10 +391,10 @@ fn op_fstat_sync(
   rid: ResourceId,
   out_buf: &mut [u32],
 ) -> Result<(), AnyError> {
-  let metadata = StdFileResource::with_file(state, rid, |std_file| {
-    std_file.metadata().map_err(AnyError::from)
+  let stat = StdFileResource::with_fs_file(state, rid, |file| {
+    file.stat().map_err(AnyError::from)
   })?;
-  let stat = get_stat(metadata);
+  let stat = SerializableStat::from(stat);
   stat.write(out_buf);
   Ok(())
 }
@@ -422,13 +403,13 @@ fn op_fstat_sync(
 async fn op_fstat_async(
   state: Rc<RefCell<OpState>>,
   rid: ResourceId,
-) -> Result<FsStat, AnyError> {
-  let metadata =
-    StdFileResource::with_file_blocking_task(state, rid, move |std_file| {
-      std_file.metadata().map_err(AnyError::from)
+) -> Result<SerializableStat, AnyError> {
+  let stat =
+    StdFileResource::with_fs_file_blocking_task(state, rid, move |file| {
+      file.stat().map_err(AnyError::from)
     })
     .await?;
-  Ok(get_stat(metadata))
+  Ok(SerializableStat::from(stat))
 }
 
 #[op]
@@ -437,15 +418,10 @@ fn op_flock_sync(
   rid: ResourceId,
   exclusive: bool,
 ) -> Result<(), AnyError> {
-  use fs3::FileExt;
   super::check_unstable(state, "Deno.flockSync");
 
-  StdFileResource::with_file(state, rid, |std_file| {
-    if exclusive {
-      std_file.lock_exclusive()?;
-    } else {
-      std_file.lock_shared()?;
-    }
+  StdFileResource::with_fs_file(state, rid, |file| {
+    file.lock(exclusive)?;
     Ok(())
   })
 }
@@ -456,15 +432,10 @@ async fn op_flock_async(
   rid: ResourceId,
   exclusive: bool,
 ) -> Result<(), AnyError> {
-  use fs3::FileExt;
   super::check_unstable2(&state, "Deno.flock");
 
-  StdFileResource::with_file_blocking_task(state, rid, move |std_file| {
-    if exclusive {
-      std_file.lock_exclusive()?;
-    } else {
-      std_file.lock_shared()?;
-    }
+  StdFileResource::with_fs_file_blocking_task(state, rid, move |file| {
+    file.lock(exclusive)?;
     Ok(())
   })
   .await
@@ -475,11 +446,10 @@ fn op_funlock_sync(
   state: &mut OpState,
   rid: ResourceId,
 ) -> Result<(), AnyError> {
-  use fs3::FileExt;
   super::check_unstable(state, "Deno.funlockSync");
 
-  StdFileResource::with_file(state, rid, |std_file| {
-    std_file.unlock()?;
+  StdFileResource::with_fs_file(state, rid, |file| {
+    file.unlock()?;
     Ok(())
   })
 }
@@ -489,11 +459,10 @@ async fn op_funlock_async(
   state: Rc<RefCell<OpState>>,
   rid: ResourceId,
 ) -> Result<(), AnyError> {
-  use fs3::FileExt;
   super::check_unstable2(&state, "Deno.funlock");
 
-  StdFileResource::with_file_blocking_task(state, rid, move |std_file| {
-    std_file.unlock()?;
+  StdFileResource::with_fs_file_blocking_task(state, rid, move |file| {
+    file.unlock()?;
     Ok(())
   })
   .await
@@ -502,37 +471,7 @@ async fn op_funlock_async(
 #[op]
 fn op_umask(state: &mut OpState, mask: Option<u32>) -> Result<u32, AnyError> {
   super::check_unstable(state, "Deno.umask");
-  // TODO implement umask for Windows
-  // see https://github.com/nodejs/node/blob/master/src/node_process_methods.cc
-  // and https://docs.microsoft.com/fr-fr/cpp/c-runtime-library/reference/umask?view=vs-2019
-  #[cfg(not(unix))]
-  {
-    let _ = mask; // avoid unused warning.
-    Err(not_supported())
-  }
-  #[cfg(unix)]
-  {
-    use nix::sys::stat::mode_t;
-    use nix::sys::stat::umask;
-    use nix::sys::stat::Mode;
-    let r = if let Some(mask) = mask {
-      // If mask provided, return previous.
-      umask(Mode::from_bits_truncate(mask as mode_t))
-    } else {
-      // If no mask provided, we query the current. Requires two syscalls.
-      let prev = umask(Mode::from_bits_truncate(0o777));
-      let _ = umask(prev);
-      prev
-    };
-    #[cfg(target_os = "linux")]
-    {
-      Ok(r.bits())
-    }
-    #[cfg(target_os = "macos")]
-    {
-      Ok(r.bits() as u32)
-    }
-  }
+  state.borrow::<FileSystemRc>().umask(mask)
 }
 
 #[op]
@@ -541,7 +480,7 @@ fn op_chdir(state: &mut OpState, directory: String) -> Result<(), AnyError> {
   state
     .borrow_mut::<PermissionsContainer>()
     .check_read(&d, "Deno.chdir()")?;
-  set_current_dir(&d).map_err(|err| {
+  state.borrow::<FileSystemRc>().chdir(&d).map_err(|err| {
     Error::new(err.kind(), format!("{err}, chdir '{directory}'"))
   })?;
   Ok(())
@@ -563,14 +502,8 @@ fn op_mkdir_sync(state: &mut OpState, args: MkdirArgs) -> Result<(), AnyError> {
     .borrow_mut::<PermissionsContainer>()
     .check_write(&path, "Deno.mkdirSync()")?;
   debug!("op_mkdir {} {:o} {}", path.display(), mode, args.recursive);
-  let mut builder = std::fs::DirBuilder::new();
-  builder.recursive(args.recursive);
-  #[cfg(unix)]
-  {
-    use std::os::unix::fs::DirBuilderExt;
-    builder.mode(mode);
-  }
-  builder.create(&path).map_err(|err| {
+  let fs = state.borrow::<FileSystemRc>();
+  fs.mkdir(&path, args.recursive, mode).map_err(|err| {
     Error::new(err.kind(), format!("{}, mkdir '{}'", err, path.display()))
   })?;
   Ok(())
@@ -584,23 +517,17 @@ async fn op_mkdir_async(
   let path = Path::new(&args.path).to_path_buf();
   let mode = args.mode.unwrap_or(0o777) & 0o777;
 
-  {
+  let fs = {
     let mut state = state.borrow_mut();
     state
       .borrow_mut::<PermissionsContainer>()
       .check_write(&path, "Deno.mkdir()")?;
-  }
+    state.borrow::<FileSystemRc>().clone()
+  };
 
   tokio::task::spawn_blocking(move || {
     debug!("op_mkdir {} {:o} {}", path.display(), mode, args.recursive);
-    let mut builder = std::fs::DirBuilder::new();
-    builder.recursive(args.recursive);
-    #[cfg(unix)]
-    {
-      use std::os::unix::fs::DirBuilderExt;
-      builder.mode(mode);
-    }
-    builder.create(&path).map_err(|err| {
+    fs.mkdir(&path, args.recursive, mode).map_err(|err| {
       Error::new(err.kind(), format!("{}, mkdir '{}'", err, path.display()))
     })?;
     Ok(())
@@ -621,7 +548,7 @@ fn op_chmod_sync(
   state
     .borrow_mut::<PermissionsContainer>()
     .check_write(path, "Deno.chmodSync()")?;
-  raw_chmod(path, mode)
+  raw_chmod(&**state.borrow::<FileSystemRc>(), path, mode)
 }
 
 #[op]
@@ -633,111 +560,65 @@ async fn op_chmod_async(
   let path = Path::new(&path).to_path_buf();
   let mode = mode & 0o777;
 
-  {
+  let fs = {
     let mut state = state.borrow_mut();
     state
       .borrow_mut::<PermissionsContainer>()
       .check_write(&path, "Deno.chmod()")?;
-  }
+    state.borrow::<FileSystemRc>().clone()
+  };
 
-  tokio::task::spawn_blocking(move || raw_chmod(&path, mode))
+  tokio::task::spawn_blocking(move || raw_chmod(&*fs, &path, mode))
     .await
     .unwrap()
 }
 
-fn raw_chmod(path: &Path, _raw_mode: u32) -> Result<(), AnyError> {
-  let err_mapper = |err: Error| {
-    Error::new(err.kind(), format!("{}, chmod '{}'", err, path.display()))
-  };
-  #[cfg(unix)]
-  {
-    use std::os::unix::fs::PermissionsExt;
-    let permissions = PermissionsExt::from_mode(_raw_mode);
-    std::fs::set_permissions(path, permissions).map_err(err_mapper)?;
-    Ok(())
-  }
-  // TODO Implement chmod for Windows (#4357)
-  #[cfg(not(unix))]
-  {
-    // Still check file/dir exists on Windows
-    let _metadata = std::fs::metadata(path).map_err(err_mapper)?;
-    Err(not_supported())
-  }
+fn raw_chmod(
+  fs: &dyn FileSystem,
+  path: &Path,
+  mode: u32,
+) -> Result<(), AnyError> {
+  fs.chmod(path, mode).map_err(|err| {
+    map_any_err(err, |err| {
+      Error::new(err.kind(), format!("{}, chmod '{}'", err, path.display()))
+    })
+  })
 }
 
 #[op]
 fn op_chown_sync(
   state: &mut OpState,
   path: String,
-  #[cfg_attr(windows, allow(unused_variables))] uid: Option<u32>,
-  #[cfg_attr(windows, allow(unused_variables))] gid: Option<u32>,
+  uid: Option<u32>,
+  gid: Option<u32>,
 ) -> Result<(), AnyError> {
   let path = Path::new(&path).to_path_buf();
   state
     .borrow_mut::<PermissionsContainer>()
     .check_write(&path, "Deno.chownSync()")?;
-  #[cfg(unix)]
-  {
-    use crate::errors::get_nix_error_class;
-    use nix::unistd::chown;
-    use nix::unistd::Gid;
-    use nix::unistd::Uid;
-    let nix_uid = uid.map(Uid::from_raw);
-    let nix_gid = gid.map(Gid::from_raw);
-    chown(&path, nix_uid, nix_gid).map_err(|err| {
-      custom_error(
-        get_nix_error_class(&err),
-        format!("{}, chown '{}'", err.desc(), path.display()),
-      )
-    })?;
-    Ok(())
-  }
-  // TODO Implement chown for Windows
-  #[cfg(not(unix))]
-  {
-    Err(generic_error("Not implemented"))
-  }
+  state.borrow::<FileSystemRc>().chown(&path, uid, gid)
 }
 
 #[op]
 async fn op_chown_async(
   state: Rc<RefCell<OpState>>,
   path: String,
-  #[cfg_attr(windows, allow(unused_variables))] uid: Option<u32>,
-  #[cfg_attr(windows, allow(unused_variables))] gid: Option<u32>,
+  uid: Option<u32>,
+  gid: Option<u32>,
 ) -> Result<(), AnyError> {
   let path = Path::new(&path).to_path_buf();
 
-  {
+  let fs = {
     let mut state = state.borrow_mut();
     state
       .borrow_mut::<PermissionsContainer>()
       .check_write(&path, "Deno.chown()")?;
-  }
+    state.borrow::<FileSystemRc>().clone()
+  };
 
-  tokio::task::spawn_blocking(move || {
-    #[cfg(unix)]
-    {
-      use crate::errors::get_nix_error_class;
-      use nix::unistd::chown;
-      use nix::unistd::Gid;
-      use nix::unistd::Uid;
-      let nix_uid = uid.map(Uid::from_raw);
-      let nix_gid = gid.map(Gid::from_raw);
-      chown(&path, nix_uid, nix_gid).map_err(|err| {
-        custom_error(
-          get_nix_error_class(&err),
-          format!("{}, chown '{}'", err.desc(), path.display()),
-        )
-      })?;
-      Ok(())
-    }
-    // TODO Implement chown for Windows
-    #[cfg(not(unix))]
-    Err(not_supported())
-  })
-  .await
-  .unwrap()
+  tokio::task::spawn_blocking(move || fs.chown(&path, uid, gid))
+    .await
+    .unwrap()
 }
 
 #[op]
@@ -752,37 +633,10 @@ fn op_remove_sync(
     .borrow_mut::<PermissionsContainer>()
     .check_write(&path, "Deno.removeSync()")?;
 
-  #[cfg(not(unix))]
-  use std::os::windows::prelude::MetadataExt;
-
-  let err_mapper = |err: Error| {
+  let fs = state.borrow::<FileSystemRc>();
+  fs.remove(&path, recursive).map_err(|err| {
     Error::new(err.kind(), format!("{}, remove '{}'", err, path.display()))
-  };
-  let metadata = std::fs::symlink_metadata(&path).map_err(err_mapper)?;
-
-  let file_type = metadata.file_type();
-  if file_type.is_file() {
-    std::fs::remove_file(&path).map_err(err_mapper)?;
-  } else if recursive {
-    std::fs::remove_dir_all(&path).map_err(err_mapper)?;
-  } else if file_type.is_symlink() {
-    #[cfg(unix)]
-    std::fs::remove_file(&path).map_err(err_mapper)?;
-    #[cfg(not(unix))]
-    {
-      use winapi::um::winnt::FILE_ATTRIBUTE_DIRECTORY;
-      if metadata.file_attributes() & FILE_ATTRIBUTE_DIRECTORY != 0 {
-        std::fs::remove_dir(&path).map_err(err_mapper)?;
-      } else {
-        std::fs::remove_file(&path).map_err(err_mapper)?;
-      }
-    }
-  } else if file_type.is_dir() {
-    std::fs::remove_dir(&path).map_err(err_mapper)?;
-  } else {
-    // pipes, sockets, etc...
-    std::fs::remove_file(&path).map_err(err_mapper)?;
-  }
+  })?;
   Ok(())
 }
 
@@ -794,51 +648,39 @@ async fn op_remove_async(
 ) -> Result<(), AnyError> {
   let path = PathBuf::from(&path);
 
-  {
+  let fs = {
     let mut state = state.borrow_mut();
     state
       .borrow_mut::<PermissionsContainer>()
       .check_write(&path, "Deno.remove()")?;
-  }
+    state.borrow::<FileSystemRc>().clone()
+  };
 
   tokio::task::spawn_blocking(move || {
-    #[cfg(not(unix))]
-    use std::os::windows::prelude::MetadataExt;
-    let err_mapper = |err: Error| {
-      Error::new(err.kind(), format!("{}, remove '{}'", err, path.display()))
-    };
-    let metadata = std::fs::symlink_metadata(&path).map_err(err_mapper)?;
-
     debug!("op_remove_async {} {}", path.display(), recursive);
-    let file_type = metadata.file_type();
-    if file_type.is_file() {
-      std::fs::remove_file(&path).map_err(err_mapper)?;
-    } else if recursive {
-      std::fs::remove_dir_all(&path).map_err(err_mapper)?;
-    } else if file_type.is_symlink() {
-      #[cfg(unix)]
-      std::fs::remove_file(&path).map_err(err_mapper)?;
-      #[cfg(not(unix))]
-      {
-        use winapi::um::winnt::FILE_ATTRIBUTE_DIRECTORY;
-        if metadata.file_attributes() & FILE_ATTRIBUTE_DIRECTORY != 0 {
-          std::fs::remove_dir(&path).map_err(err_mapper)?;
-        } else {
-          std::fs::remove_file(&path).map_err(err_mapper)?;
-        }
-      }
-    } else if file_type.is_dir() {
-      std::fs::remove_dir(&path).map_err(err_mapper)?;
-    } else {
-      // pipes, sockets, etc...
-      std::fs::remove_file(&path).map_err(err_mapper)?;
-    }
+    fs.remove(&path, recursive).map_err(|err| {
+      Error::new(err.kind(), format!("{}, remove '{}'", err, path.display()))
+    })?;
     Ok(())
   })
   .await
   .unwrap()
 }
 
+fn copy_file(
+  fs: &dyn FileSystem,
+  from: &Path,
+  to: &Path,
+) -> Result<(), AnyError> {
+  fs.copy_file(from, to).map_err(|err| {
+    Error::new(
+      err.kind(),
+      format!("{}, copy '{}' -> '{}'", err, from.display(), to.display()),
+    )
+  })?;
+  Ok(())
+}
+
 #[op]
 fn op_copy_file_sync(
   state: &mut OpState,
@@ -852,103 +694,7 @@ fn op_copy_file_sync(
   permissions.check_read(&from_path, "Deno.copyFileSync()")?;
   permissions.check_write(&to_path, "Deno.copyFileSync()")?;
 
-  // On *nix, Rust reports non-existent `from` as ErrorKind::InvalidInput
-  // See https://github.com/rust-lang/rust/issues/54800
-  // Once the issue is resolved, we should remove this workaround.
-  if cfg!(unix) && !from_path.is_file() {
-    return Err(custom_error(
-      "NotFound",
-      format!(
-        "File not found, copy '{}' -> '{}'",
-        from_path.display(),
-        to_path.display()
-      ),
-    ));
-  }
-
-  let err_mapper = |err: Error| {
-    Error::new(
-      err.kind(),
-      format!(
-        "{}, copy '{}' -> '{}'",
-        err,
-        from_path.display(),
-        to_path.display()
-      ),
-    )
-  };
-
-  #[cfg(target_os = "macos")]
-  {
-    use libc::clonefile;
-    use libc::stat;
-    use libc::unlink;
-    use std::ffi::CString;
-    use std::io::Read;
-    use std::os::unix::fs::OpenOptionsExt;
-    use std::os::unix::fs::PermissionsExt;
-
-    let from = CString::new(from).unwrap();
-    let to = CString::new(to).unwrap();
-
-    // SAFETY: `from` and `to` are valid C strings.
-    // std::fs::copy does open() + fcopyfile() on macOS. We try to use
-    // clonefile() instead, which is more efficient.
-    unsafe {
-      let mut st = std::mem::zeroed();
-      let ret = stat(from.as_ptr(), &mut st);
-      if ret != 0 {
-        return Err(err_mapper(Error::last_os_error()).into());
-      }
-
-      if st.st_size > 128 * 1024 {
-        // Try unlink. If it fails, we are going to try clonefile() anyway.
-        let _ = unlink(to.as_ptr());
-        // Matches rust stdlib behavior for io::copy.
-        // https://github.com/rust-lang/rust/blob/3fdd578d72a24d4efc2fe2ad18eec3b6ba72271e/library/std/src/sys/unix/fs.rs#L1613-L1616
-        if clonefile(from.as_ptr(), to.as_ptr(), 0) == 0 {
-          return Ok(());
-        }
-      } else {
-        // Do a regular copy. fcopyfile() is an overkill for < 128KB
-        // files.
-        let mut buf = [0u8; 128 * 1024];
-        let mut from_file =
-          std::fs::File::open(&from_path).map_err(err_mapper)?;
-        let perm = from_file.metadata().map_err(err_mapper)?.permissions();
-
-        let mut to_file = std::fs::OpenOptions::new()
-          // create the file with the correct mode right away
-          .mode(perm.mode())
-          .write(true)
-          .create(true)
-          .truncate(true)
-          .open(&to_path)
-          .map_err(err_mapper)?;
-        let writer_metadata = to_file.metadata()?;
-        if writer_metadata.is_file() {
-          // Set the correct file permissions, in case the file already existed.
-          // Don't set the permissions on already existing non-files like
-          // pipes/FIFOs or device nodes.
-          to_file.set_permissions(perm)?;
-        }
-        loop {
-          let nread = from_file.read(&mut buf).map_err(err_mapper)?;
-          if nread == 0 {
-            break;
-          }
-          to_file.write_all(&buf[..nread]).map_err(err_mapper)?;
-        }
-        return Ok(());
-      }
-    }
-
-    // clonefile() failed, fall back to std::fs::copy().
-  }
-
-  // returns size of from as u64 (we ignore)
-  std::fs::copy(&from_path, &to_path).map_err(err_mapper)?;
-  Ok(())
+  copy_file(&**state.borrow::<FileSystemRc>(), &from_path, &to_path)
 }
 
 #[op]
@@ -960,53 +706,17 @@ async fn op_copy_file_async(
   let from = PathBuf::from(&from);
   let to = PathBuf::from(&to);
 
-  {
+  let fs = {
     let mut state = state.borrow_mut();
     let permissions = state.borrow_mut::<PermissionsContainer>();
     permissions.check_read(&from, "Deno.copyFile()")?;
     permissions.check_write(&to, "Deno.copyFile()")?;
-  }
-
-  tokio::task::spawn_blocking(move || {
-    // On *nix, Rust reports non-existent `from` as ErrorKind::InvalidInput
-    // See https://github.com/rust-lang/rust/issues/54800
-    // Once the issue is resolved, we should remove this workaround.
-    if cfg!(unix) && !from.is_file() {
-      return Err(custom_error(
-        "NotFound",
-        format!(
-          "File not found, copy '{}' -> '{}'",
-          from.display(),
-          to.display()
-        ),
-      ));
-    }
-
-    let err_mapper = |err: Error| {
-      Error::new(
-        err.kind(),
-        format!("{}, copy '{}' -> '{}'", err, from.display(), to.display()),
-      )
-    };
-    // returns size of from as u64 (we ignore)
-    std::fs::copy(&from, &to).map_err(err_mapper)?;
-    Ok(())
-  })
-  .await
-  .unwrap()
-}
+    state.borrow::<FileSystemRc>().clone()
+  };
 
-fn to_msec(maybe_time: Result<SystemTime, io::Error>) -> (u64, bool) {
-  match maybe_time {
-    Ok(time) => (
-      time
-        .duration_since(UNIX_EPOCH)
-        .map(|t| t.as_millis() as u64)
-        .unwrap_or_else(|err| err.duration().as_millis() as u64),
-      true,
-    ),
-    Err(_) => (0, false),
-  }
+  tokio::task::spawn_blocking(move || copy_file(&*fs, &from, &to))
+    .await
+    .unwrap()
 }
 
 macro_rules! create_struct_writer {
@@ -1035,7 +745,7 @@ macro_rules! create_struct_writer {
 }
 
 create_struct_writer! {
-  pub struct FsStat {
+  pub struct SerializableStat {
     is_file: bool,
     is_directory: bool,
     is_symlink: bool,
@@ -1060,50 +770,30 @@ create_struct_writer! {
   }
 }
 
-#[inline(always)]
-fn get_stat(metadata: std::fs::Metadata) -> FsStat {
-  // Unix stat member (number types only). 0 if not on unix.
-  macro_rules! usm {
-    ($member:ident) => {{
-      #[cfg(unix)]
-      {
-        metadata.$member()
-      }
-      #[cfg(not(unix))]
-      {
-        0
-      }
-    }};
-  }
-
-  #[cfg(unix)]
-  use std::os::unix::fs::MetadataExt;
-  let (mtime, mtime_set) = to_msec(metadata.modified());
-  let (atime, atime_set) = to_msec(metadata.accessed());
-  let (birthtime, birthtime_set) = to_msec(metadata.created());
-
-  FsStat {
-    is_file: metadata.is_file(),
-    is_directory: metadata.is_dir(),
-    is_symlink: metadata.file_type().is_symlink(),
-    size: metadata.len(),
-    // In milliseconds, like JavaScript. Available on both Unix or Windows.
-    mtime_set,
-    mtime,
-    atime_set,
-    atime,
-    birthtime_set,
-    birthtime,
-    // Following are only valid under Unix.
-    dev: usm!(dev),
-    ino: usm!(ino),
-    mode: usm!(mode),
-    nlink: usm!(nlink),
-    uid: usm!(uid),
-    gid: usm!(gid),
-    rdev: usm!(rdev),
-    blksize: usm!(blksize),
-    blocks: usm!(blocks),
+impl From<FsStat> for SerializableStat {
+  #[inline(always)]
+  fn from(stat: FsStat) -> Self {
+    SerializableStat {
+      is_file: stat.is_file,
+      is_directory: stat.is_directory,
+      is_symlink: stat.is_symlink,
+      size: stat.size,
+      mtime_set: stat.mtime.is_some(),
+      mtime: stat.mtime.unwrap_or(0),
+      atime_set: stat.atime.is_some(),
+      atime: stat.atime.unwrap_or(0),
+      birthtime_set: stat.birthtime.is_some(),
+      birthtime: stat.birthtime.unwrap_or(0),
+      dev: stat.dev,
+      ino: stat.ino,
+      mode: stat.mode,
+      nlink: stat.nlink,
+      uid: stat.uid,
+      gid: stat.gid,
+      rdev: stat.rdev,
+      blksize: stat.blksize,
+      blocks: stat.blocks,
+    }
   }
 }
 
@@ -1114,6 +804,22 @@ pub struct StatArgs {
   lstat: bool,
 }
 
+fn stat(
+  fs: &dyn FileSystem,
+  path: &Path,
+  lstat: bool,
+) -> Result<SerializableStat, AnyError> {
+  let err_mapper = |err: Error| {
+    Error::new(err.kind(), format!("{}, stat '{}'", err, path.display()))
+  };
+  let stat = if lstat {
+    fs.lstat(path).map_err(err_mapper)?
+  } else {
+    fs.stat(path).map_err(err_mapper)?
+  };
+  Ok(SerializableStat::from(stat))
+}
+
 #[op]
 fn op_stat_sync(
   state: &mut OpState,
@@ -1125,16 +831,8 @@ fn op_stat_sync(
   state
     .borrow_mut::<PermissionsContainer>()
     .check_read(&path, "Deno.statSync()")?;
-  let err_mapper = |err: Error| {
-    Error::new(err.kind(), format!("{}, stat '{}'", err, path.display()))
-  };
-  let metadata = if lstat {
-    std::fs::symlink_metadata(&path).map_err(err_mapper)?
-  } else {
-    std::fs::metadata(&path).map_err(err_mapper)?
-  };
 
-  let stat = get_stat(metadata);
+  let stat = stat(&**state.borrow::<FileSystemRc>(), &path, lstat)?;
   stat.write(out_buf);
 
   Ok(())
@@ -1144,28 +842,21 @@ fn op_stat_sync(
 async fn op_stat_async(
   state: Rc<RefCell<OpState>>,
   args: StatArgs,
-) -> Result<FsStat, AnyError> {
+) -> Result<SerializableStat, AnyError> {
   let path = PathBuf::from(&args.path);
   let lstat = args.lstat;
 
-  {
+  let fs = {
     let mut state = state.borrow_mut();
     state
       .borrow_mut::<PermissionsContainer>()
       .check_read(&path, "Deno.stat()")?;
-  }
+    state.borrow::<FileSystemRc>().clone()
+  };
 
   tokio::task::spawn_blocking(move || {
     debug!("op_stat_async {} {}", path.display(), lstat);
-    let err_mapper = |err: Error| {
-      Error::new(err.kind(), format!("{}, stat '{}'", err, path.display()))
-    };
-    let metadata = if lstat {
-      std::fs::symlink_metadata(&path).map_err(err_mapper)?
-    } else {
-      std::fs::metadata(&path).map_err(err_mapper)?
-    };
-    Ok(get_stat(metadata))
+    stat(&*fs, &path, lstat)
   })
   .await
   .unwrap()
@@ -1178,20 +869,15 @@ fn op_realpath_sync(
 ) -> Result<String, AnyError> {
   let path = PathBuf::from(&path);
 
+  let fs = state.borrow::<FileSystemRc>().clone();
   let permissions = state.borrow_mut::<PermissionsContainer>();
   permissions.check_read(&path, "Deno.realPathSync()")?;
   if path.is_relative() {
-    permissions.check_read_blind(
-      &current_dir()?,
-      "CWD",
-      "Deno.realPathSync()",
-    )?;
+    permissions.check_read_blind(&fs.cwd()?, "CWD", "Deno.realPathSync()")?;
   }
 
   debug!("op_realpath_sync {}", path.display());
-  // corresponds to the realpath on Unix and
-  // CreateFile and GetFinalPathNameByHandle on Windows
-  let realpath = canonicalize_path(&path)?;
+  let realpath = fs.realpath(&path)?;
   let realpath_str = into_string(realpath.into_os_string())?;
   Ok(realpath_str)
 }
@@ -1203,24 +889,20 @@ async fn op_realpath_async(
 ) -> Result<String, AnyError> {
   let path = PathBuf::from(&path);
 
-  {
+  let fs = {
     let mut state = state.borrow_mut();
+    let fs = state.borrow::<FileSystemRc>().clone();
     let permissions = state.borrow_mut::<PermissionsContainer>();
     permissions.check_read(&path, "Deno.realPath()")?;
     if path.is_relative() {
-      permissions.check_read_blind(
-        &current_dir()?,
-        "CWD",
-        "Deno.realPath()",
-      )?;
+      permissions.check_read_blind(&fs.cwd()?, "CWD", "Deno.realPath()")?;
     }
-  }
+    fs
+  };
 
   tokio::task::spawn_blocking(move || {
     debug!("op_realpath_async {}", path.display());
-    // corresponds to the realpath on Unix and
-    // CreateFile and GetFinalPathNameByHandle on Windows
-    let realpath = canonicalize_path(&path)?;
+    let realpath = fs.realpath(&path)?;
     let realpath_str = into_string(realpath.into_os_string())?;
     Ok(realpath_str)
   })
@@ -1237,6 +919,26 @@ pub struct DirEntry {
   is_symlink: bool,
 }
 
+fn read_dir(
+  fs: &dyn FileSystem,
+  path: &Path,
+) -> Result<Vec<DirEntry>, AnyError> {
+  let entries = fs
+    .read_dir(path)
+    .map_err(|err| {
+      Error::new(err.kind(), format!("{}, readdir '{}'", err, path.display()))
+    })?
+    .into_iter()
+    .map(|entry| DirEntry {
+      name: entry.name,
+      is_file: entry.is_file,
+      is_directory: entry.is_directory,
+      is_symlink: entry.is_symlink,
+    })
+    .collect();
+  Ok(entries)
+}
+
 #[op]
 fn op_read_dir_sync(
   state: &mut OpState,
@@ -1249,34 +951,7 @@ fn op_read_dir_sync(
     .check_read(&path, "Deno.readDirSync()")?;
 
   debug!("op_read_dir_sync {}", path.display());
-  let err_mapper = |err: Error| {
-    Error::new(err.kind(), format!("{}, readdir '{}'", err, path.display()))
-  };
-  let entries: Vec<_> = std::fs::read_dir(&path)
-    .map_err(err_mapper)?
-    .filter_map(|entry| {
-      let entry = entry.unwrap();
-      // Not all filenames can be encoded as UTF-8. Skip those for now.
-      if let Ok(name) = into_string(entry.file_name()) {
-        Some(DirEntry {
-          name,
-          is_file: entry
-            .file_type()
-            .map_or(false, |file_type| file_type.is_file()),
-          is_directory: entry
-            .file_type()
-            .map_or(false, |file_type| file_type.is_dir()),
-          is_symlink: entry
-            .file_type()
-            .map_or(false, |file_type| file_type.is_symlink()),
-        })
-      } else {
-        None
-      }
-    })
-    .collect();
-
-  Ok(entries)
+  read_dir(&**state.borrow::<FileSystemRc>(), &path)
 }
 
 #[op]
@@ -1285,47 +960,40 @@ async fn op_read_dir_async(
   path: String,
 ) -> Result<Vec<DirEntry>, AnyError> {
   let path = PathBuf::from(&path);
-  {
+  let fs = {
     let mut state = state.borrow_mut();
     state
       .borrow_mut::<PermissionsContainer>()
       .check_read(&path, "Deno.readDir()")?;
-  }
+    state.borrow::<FileSystemRc>().clone()
+  };
   tokio::task::spawn_blocking(move || {
     debug!("op_read_dir_async {}", path.display());
-    let err_mapper = |err: Error| {
-      Error::new(err.kind(), format!("{}, readdir '{}'", err, path.display()))
-    };
-    let entries: Vec<_> = std::fs::read_dir(&path)
-      .map_err(err_mapper)?
-      .filter_map(|entry| {
-        let entry = entry.unwrap();
-        // Not all filenames can be encoded as UTF-8. Skip those for now.
-        if let Ok(name) = into_string(entry.file_name()) {
-          Some(DirEntry {
-            name,
-            is_file: entry
-              .file_type()
-              .map_or(false, |file_type| file_type.is_file()),
-            is_directory: entry
-              .file_type()
-              .map_or(false, |file_type| file_type.is_dir()),
-            is_symlink: entry
-              .file_type()
-              .map_or(false, |file_type| file_type.is_symlink()),
-          })
-        } else {
-          None
-        }
-      })
-      .collect();
-
-    Ok(entries)
+    read_dir(&*fs, &path)
   })
   .await
   .unwrap()
 }
 
+fn rename(
+  fs: &dyn FileSystem,
+  oldpath: &Path,
+  newpath: &Path,
+) -> Result<(), AnyError> {
+  fs.rename(oldpath, newpath).map_err(|err| {
+    Error::new(
+      err.kind(),
+      format!(
+        "{}, rename '{}' -> '{}'",
+        err,
+        oldpath.display(),
+        newpath.display()
+      ),
+    )
+  })?;
+  Ok(())
+}
+
 #[op]
 fn op_rename_sync(
   state: &mut OpState,
@@ -1340,19 +1008,7 @@ fn op_rename_sync(
   permissions.check_write(&oldpath, "Deno.renameSync()")?;
   permissions.check_write(&newpath, "Deno.renameSync()")?;
 
-  let err_mapper = |err: Error| {
-    Error::new(
-      err.kind(),
-      format!(
-        "{}, rename '{}' -> '{}'",
-        err,
-        oldpath.display(),
-        newpath.display()
-      ),
-    )
-  };
-  std::fs::rename(&oldpath, &newpath).map_err(err_mapper)?;
-  Ok(())
+  rename(&**state.borrow::<FileSystemRc>(), &oldpath, &newpath)
 }
 
 #[op]
@@ -1363,30 +1019,36 @@ async fn op_rename_async(
 ) -> Result<(), AnyError> {
   let oldpath = PathBuf::from(&oldpath);
   let newpath = PathBuf::from(&newpath);
-  {
+  let fs = {
     let mut state = state.borrow_mut();
     let permissions = state.borrow_mut::<PermissionsContainer>();
     permissions.check_read(&oldpath, "Deno.rename()")?;
     permissions.check_write(&oldpath, "Deno.rename()")?;
     permissions.check_write(&newpath, "Deno.rename()")?;
-  }
-  tokio::task::spawn_blocking(move || {
-    let err_mapper = |err: Error| {
-      Error::new(
-        err.kind(),
-        format!(
-          "{}, rename '{}' -> '{}'",
-          err,
-          oldpath.display(),
-          newpath.display()
-        ),
-      )
-    };
-    std::fs::rename(&oldpath, &newpath).map_err(err_mapper)?;
-    Ok(())
-  })
-  .await
-  .unwrap()
+    state.borrow::<FileSystemRc>().clone()
+  };
+  tokio::task::spawn_blocking(move || rename(&*fs, &oldpath, &newpath))
+    .await
+    .unwrap()
+}
+
+fn link(
+  fs: &dyn FileSystem,
+  oldpath: &Path,
+  newpath: &Path,
+) -> Result<(), AnyError> {
+  fs.link(oldpath, newpath).map_err(|err| {
+    Error::new(
+      err.kind(),
+      format!(
+        "{}, link '{}' -> '{}'",
+        err,
+        oldpath.display(),
+        newpath.display()
+      ),
+    )
+  })?;
+  Ok(())
 }
 
 #[op]
@@ -1404,19 +1066,7 @@ fn op_link_sync(
   permissions.check_read(&newpath, "Deno.linkSync()")?;
   permissions.check_write(&newpath, "Deno.linkSync()")?;
 
-  let err_mapper = |err: Error| {
-    Error::new(
-      err.kind(),
-      format!(
-        "{}, link '{}' -> '{}'",
-        err,
-        oldpath.display(),
-        newpath.display()
-      ),
-    )
-  };
-  std::fs::hard_link(&oldpath, &newpath).map_err(err_mapper)?;
-  Ok(())
+  link(&**state.borrow::<FileSystemRc>(), &oldpath, &newpath)
 }
 
 #[op]
@@ -1428,32 +1078,51 @@ async fn op_link_async(
   let oldpath = PathBuf::from(&oldpath);
   let newpath = PathBuf::from(&newpath);
 
-  {
+  let fs = {
     let mut state = state.borrow_mut();
     let permissions = state.borrow_mut::<PermissionsContainer>();
     permissions.check_read(&oldpath, "Deno.link()")?;
     permissions.check_write(&oldpath, "Deno.link()")?;
     permissions.check_read(&newpath, "Deno.link()")?;
     permissions.check_write(&newpath, "Deno.link()")?;
+    state.borrow::<FileSystemRc>().clone()
+  };
+
+  tokio::task::spawn_blocking(move || link(&*fs, &oldpath, &newpath))
+    .await
+    .unwrap()
+}
+
+fn symlink_file_type(
+  file_type: Option<String>,
+) -> Result<Option<FsFileType>, AnyError> {
+  match file_type.as_deref() {
+    Some("file") => Ok(Some(FsFileType::File)),
+    Some("dir") => Ok(Some(FsFileType::Directory)),
+    Some(_) => Err(type_error("unsupported type")),
+    None => Ok(None),
   }
+}
 
-  tokio::task::spawn_blocking(move || {
-    let err_mapper = |err: Error| {
+fn symlink(
+  fs: &dyn FileSystem,
+  oldpath: &Path,
+  newpath: &Path,
+  file_type: Option<FsFileType>,
+) -> Result<(), AnyError> {
+  fs.symlink(oldpath, newpath, file_type).map_err(|err| {
+    map_any_err(err, |err| {
       Error::new(
         err.kind(),
         format!(
-          "{}, link '{}' -> '{}'",
+          "{}, symlink '{}' -> '{}'",
           err,
           oldpath.display(),
           newpath.display()
         ),
       )
-    };
-    std::fs::hard_link(&oldpath, &newpath).map_err(err_mapper)?;
-    Ok(())
+    })
   })
-  .await
-  .unwrap()
 }
 
 #[op]
@@ -1465,6 +1134,7 @@ fn op_symlink_sync(
 ) -> Result<(), AnyError> {
   let oldpath = PathBuf::from(&oldpath);
   let newpath = PathBuf::from(&newpath);
+  let file_type = symlink_file_type(_type)?;
 
   state
     .borrow_mut::<PermissionsContainer>()
@@ -1473,50 +1143,12 @@ fn op_symlink_sync(
     .borrow_mut::<PermissionsContainer>()
     .check_read_all("Deno.symlinkSync()")?;
 
-  let err_mapper = |err: Error| {
-    Error::new(
-      err.kind(),
-      format!(
-        "{}, symlink '{}' -> '{}'",
-        err,
-        oldpath.display(),
-        newpath.display()
-      ),
-    )
-  };
-  #[cfg(unix)]
-  {
-    use std::os::unix::fs::symlink;
-    symlink(&oldpath, &newpath).map_err(err_mapper)?;
-    Ok(())
-  }
-  #[cfg(not(unix))]
-  {
-    use std::os::windows::fs::symlink_dir;
-    use std::os::windows::fs::symlink_file;
-
-    match _type {
-      Some(ty) => match ty.as_ref() {
-        "file" => symlink_file(&oldpath, &newpath).map_err(err_mapper)?,
-        "dir" => symlink_dir(&oldpath, &newpath).map_err(err_mapper)?,
-        _ => return Err(type_error("unsupported type")),
-      },
-      None => {
-        let old_meta = std::fs::metadata(&oldpath);
-        match old_meta {
-          Ok(metadata) => {
-            if metadata.is_file() {
-              symlink_file(&oldpath, &newpath).map_err(err_mapper)?
-            } else if metadata.is_dir() {
-              symlink_dir(&oldpath, &newpath).map_err(err_mapper)?
-            }
-          }
-          Err(_) => return Err(type_error("you must pass a `options` argument for non-existent target path in windows".to_string())),
-        }
-      }
-    };
-    Ok(())
-  }
+  symlink(
+    &**state.borrow::<FileSystemRc>(),
+    &oldpath,
+    &newpath,
+    file_type,
+  )
 }
 
 #[op]
@@ -1528,8 +1160,9 @@ async fn op_symlink_async(
 ) -> Result<(), AnyError> {
   let oldpath = PathBuf::from(&oldpath);
   let newpath = PathBuf::from(&newpath);
+  let file_type = symlink_file_type(_type)?;
 
-  {
+  let fs = {
     let mut state = state.borrow_mut();
     state
       .borrow_mut::<PermissionsContainer>()
@@ -1537,57 +1170,30 @@ async fn op_symlink_async(
     state
       .borrow_mut::<PermissionsContainer>()
       .check_read_all("Deno.symlink()")?;
-  }
+    state.borrow::<FileSystemRc>().clone()
+  };
 
   tokio::task::spawn_blocking(move || {
-    let err_mapper = |err: Error| {
-      Error::new(
-        err.kind(),
-        format!(
-          "{}, symlink '{}' -> '{}'",
-          err,
-          oldpath.display(),
-          newpath.display()
-        ),
-      )
-    };
-    #[cfg(unix)]
-    {
-      use std::os::unix::fs::symlink;
-      symlink(&oldpath, &newpath).map_err(err_mapper)?;
-      Ok(())
-    }
-    #[cfg(not(unix))]
-    {
-      use std::os::windows::fs::{symlink_dir, symlink_file};
-
-      match _type {
-        Some(ty) => match ty.as_ref() {
-          "file" => symlink_file(&oldpath, &newpath).map_err(err_mapper)?,
-          "dir" => symlink_dir(&oldpath, &newpath).map_err(err_mapper)?,
-          _ => return Err(type_error("unsupported type")),
-        },
-        None => {
-          let old_meta = std::fs::metadata(&oldpath);
-          match old_meta {
-            Ok(metadata) => {
-              if metadata.is_file() {
-                symlink_file(&oldpath, &newpath).map_err(err_mapper)?
-              } else if metadata.is_dir() {
-                symlink_dir(&oldpath, &newpath).map_err(err_mapper)?
-              }
-            }
-            Err(_) => return Err(type_error("you must pass a `options` argument for non-existent target path in windows".to_string())),
-          }
-        }
-      };
-      Ok(())
-    }
+    symlink(&*fs, &oldpath, &newpath, file_type)
   })
   .await
   .unwrap()
 }
 
+fn read_link(fs: &dyn FileSystem, path: &Path) -> Result<String, AnyError> {
+  let target = fs
+    .read_link(path)
+    .map_err(|err| {
+      Error::new(
+        err.kind(),
+        format!("{}, readlink '{}'", err, path.display()),
+      )
+    })?
+    .into_os_string();
+  let targetstr = into_string(target)?;
+  Ok(targetstr)
+}
+
 #[op]
 fn op_read_link_sync(
   state: &mut OpState,
@@ -1600,17 +1206,7 @@ fn op_read_link_sync(
     .check_read(&path, "Deno.readLink()")?;
 
   debug!("op_read_link_value {}", path.display());
-  let err_mapper = |err: Error| {
-    Error::new(
-      err.kind(),
-      format!("{}, readlink '{}'", err, path.display()),
-    )
-  };
-  let target = std::fs::read_link(&path)
-    .map_err(err_mapper)?
-    .into_os_string();
-  let targetstr = into_string(target)?;
-  Ok(targetstr)
+  read_link(&**state.borrow::<FileSystemRc>(), &path)
 }
 
 #[op]
@@ -1619,25 +1215,16 @@ async fn op_read_link_async(
   path: String,
 ) -> Result<String, AnyError> {
   let path = PathBuf::from(&path);
-  {
+  let fs = {
     let mut state = state.borrow_mut();
     state
       .borrow_mut::<PermissionsContainer>()
       .check_read(&path, "Deno.readLink()")?;
-  }
+    state.borrow::<FileSystemRc>().clone()
+  };
   tokio::task::spawn_blocking(move || {
     debug!("op_read_link_async {}", path.display());
-    let err_mapper = |err: Error| {
-      Error::new(
-        err.kind(),
-        format!("{}, readlink '{}'", err, path.display()),
-      )
-    };
-    let target = std::fs::read_link(&path)
-      .map_err(err_mapper)?
-      .into_os_string();
-    let targetstr = into_string(target)?;
-    Ok(targetstr)
+    read_link(&*fs, &path)
   })
   .await
   .unwrap()
@@ -1650,8 +1237,8 @@ fn op_ftruncate_sync(
   len: i32,
 ) -> Result<(), AnyError> {
   let len = len as u64;
-  StdFileResource::with_file(state, rid, |std_file| {
-    std_file.set_len(len).map_err(AnyError::from)
+  StdFileResource::with_fs_file(state, rid, |file| {
+    file.truncate(len).map_err(AnyError::from)
   })?;
   Ok(())
 }
@@ -1664,13 +1251,27 @@ async fn op_ftruncate_async(
 ) -> Result<(), AnyError> {
   let len = len as u64;
 
-  StdFileResource::with_file_blocking_task(state, rid, move |std_file| {
-    std_file.set_len(len)?;
+  StdFileResource::with_fs_file_blocking_task(state, rid, move |file| {
+    file.truncate(len)?;
     Ok(())
   })
   .await
 }
 
+fn truncate(
+  fs: &dyn FileSystem,
+  path: &Path,
+  len: u64,
+) -> Result<(), AnyError> {
+  fs.truncate(path, len).map_err(|err| {
+    Error::new(
+      err.kind(),
+      format!("{}, truncate '{}'", err, path.display()),
+    )
+  })?;
+  Ok(())
+}
+
 #[op]
 fn op_truncate_sync(
   state: &mut OpState,
@@ -1684,18 +1285,7 @@ fn op_truncate_sync(
     .check_write(&path, "Deno.truncateSync()")?;
 
   debug!("op_truncate_sync {} {}", path.display(), len);
-  let err_mapper = |err: Error| {
-    Error::new(
-      err.kind(),
-      format!("{}, truncate '{}'", err, path.display()),
-    )
-  };
-  let f = std::fs::OpenOptions::new()
-    .write(true)
-    .open(&path)
-    .map_err(err_mapper)?;
-  f.set_len(len).map_err(err_mapper)?;
-  Ok(())
+  truncate(&**state.borrow::<FileSystemRc>(), &path, len)
 }
 
 #[op]
@@ -1706,67 +1296,45 @@ async fn op_truncate_async(
 ) -> Result<(), AnyError> {
   let path = PathBuf::from(&path);
 
-  {
+  let fs = {
     let mut state = state.borrow_mut();
     state
       .borrow_mut::<PermissionsContainer>()
       .check_write(&path, "Deno.truncate()")?;
-  }
+    state.borrow::<FileSystemRc>().clone()
+  };
   tokio::task::spawn_blocking(move || {
     debug!("op_truncate_async {} {}", path.display(), len);
-    let err_mapper = |err: Error| {
-      Error::new(
-        err.kind(),
-        format!("{}, truncate '{}'", err, path.display()),
-      )
-    };
-    let f = std::fs::OpenOptions::new()
-      .write(true)
-      .open(&path)
-      .map_err(err_mapper)?;
-    f.set_len(len).map_err(err_mapper)?;
-    Ok(())
+    truncate(&*fs, &path, len)
   })
   .await
   .unwrap()
 }
 
 fn make_temp(
-  dir: Option<&Path>,
+  fs: &dyn FileSystem,
+  dir: &Path,
   prefix: Option<&str>,
   suffix: Option<&str>,
   is_dir: bool,
 ) -> std::io::Result<PathBuf> {
   let prefix_ = prefix.unwrap_or("");
   let suffix_ = suffix.unwrap_or("");
-  let mut buf: PathBuf = match dir {
-    Some(p) => p.to_path_buf(),
-    None => temp_dir(),
-  }
-  .join("_");
+  let mut buf: PathBuf = dir.join("_");
   let mut rng = thread_rng();
   loop {
     let unique = rng.gen::<u32>();
     buf.set_file_name(format!("{prefix_}{unique:08x}{suffix_}"));
     let r = if is_dir {
-      #[allow(unused_mut)]
-      let mut builder = std::fs::DirBuilder::new();
-      #[cfg(unix)]
-      {
-        use std::os::unix::fs::DirBuilderExt;
-        builder.mode(0o700);
-      }
-      builder.create(buf.as_path())
+      fs.mkdir(buf.as_path(), false, 0o700)
     } else {
-      let mut open_options = std::fs::OpenOptions::new();
-      open_options.write(true).create_new(true);
-      #[cfg(unix)]
-      {
-        use std::os::unix::fs::OpenOptionsExt;
-        open_options.mode(0o600);
-      }
-      open_options.open(buf.as_path())?;
-      Ok(())
+      let open_options = crate::fs::OpenOptions {
+        write: true,
+        create_new: true,
+        mode: Some(0o600),
+        ..Default::default()
+      };
+      fs.open(buf.as_path(), open_options).map(|_| ())
     };
     match r {
       Err(ref e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
@@ -1784,26 +1352,41 @@ pub struct MakeTempArgs {
   suffix: Option<String>,
 }
 
+/// Checks the write permission for the directory a temp file or directory is
+/// created in, which defaults to the temp dir of the file system.
+fn make_temp_check(
+  state: &mut OpState,
+  dir: Option<String>,
+  api_name: &str,
+) -> Result<(FileSystemRc, PathBuf), AnyError> {
+  let fs = state.borrow::<FileSystemRc>().clone();
+  let dir = match dir {
+    Some(dir) => PathBuf::from(dir),
+    None => fs.tmp_dir()?,
+  };
+  state
+    .borrow_mut::<PermissionsContainer>()
+    .check_write(&dir, api_name)?;
+  Ok((fs, dir))
+}
+
 #[op]
 fn op_make_temp_dir_sync(
   state: &mut OpState,
   args: MakeTempArgs,
 ) -> Result<String, AnyError> {
-  let dir = args.dir.map(|s| PathBuf::from(&s));
   let prefix = args.prefix.map(String::from);
   let suffix = args.suffix.map(String::from);
 
-  state.borrow_mut::<PermissionsContainer>().check_write(
-    dir.clone().unwrap_or_else(temp_dir).as_path(),
-    "Deno.makeTempDirSync()",
-  )?;
+  let (fs, dir) = make_temp_check(state, args.dir, "Deno.makeTempDirSync()")?;
 
   // TODO(piscisaureus): use byte vector for paths, not a string.
   // See https://github.com/denoland/deno/issues/627.
   // We can't assume that paths are always valid utf8 strings.
   let path = make_temp(
+    &*fs,
+    &dir,
     // Converting Option<String> to Option<&str>
-    dir.as_deref(),
     prefix.as_deref(),
     suffix.as_deref(),
     true,
@@ -1818,23 +1401,18 @@ async fn op_make_temp_dir_async(
   state: Rc<RefCell<OpState>>,
   args: MakeTempArgs,
 ) -> Result<String, AnyError> {
-  let dir = args.dir.map(|s| PathBuf::from(&s));
   let prefix = args.prefix.map(String::from);
   let suffix = args.suffix.map(String::from);
-  {
-    let mut state = state.borrow_mut();
-    state.borrow_mut::<PermissionsContainer>().check_write(
-      dir.clone().unwrap_or_else(temp_dir).as_path(),
-      "Deno.makeTempDir()",
-    )?;
-  }
+  let (fs, dir) =
+    make_temp_check(&mut state.borrow_mut(), args.dir, "Deno.makeTempDir()")?;
   tokio::task::spawn_blocking(move || {
     // TODO(piscisaureus): use byte vector for paths, not a string.
     // See https://github.com/denoland/deno/issues/627.
     // We can't assume that paths are always valid utf8 strings.
     let path = make_temp(
+      &*fs,
+      &dir,
       // Converting Option<String> to Option<&str>
-      dir.as_deref(),
       prefix.as_deref(),
       suffix.as_deref(),
       true,
@@ -1852,21 +1430,18 @@ fn op_make_temp_file_sync(
   state: &mut OpState,
   args: MakeTempArgs,
 ) -> Result<String, AnyError> {
-  let dir = args.dir.map(|s| PathBuf::from(&s));
   let prefix = args.prefix.map(String::from);
   let suffix = args.suffix.map(String::from);
 
-  state.borrow_mut::<PermissionsContainer>().check_write(
-    dir.clone().unwrap_or_else(temp_dir).as_path(),
-    "Deno.makeTempFileSync()",
-  )?;
+  let (fs, dir) = make_temp_check(state, args.dir, "Deno.makeTempFileSync()")?;
 
   // TODO(piscisaureus): use byte vector for paths, not a string.
   // See https://github.com/denoland/deno/issues/627.
   // We can't assume that paths are always valid utf8 strings.
   let path = make_temp(
+    &*fs,
+    &dir,
     // Converting Option<String> to Option<&str>
-    dir.as_deref(),
     prefix.as_deref(),
     suffix.as_deref(),
     false,
@@ -1881,23 +1456,18 @@ async fn op_make_temp_file_async(
   state: Rc<RefCell<OpState>>,
   args: MakeTempArgs,
 ) -> Result<String, AnyError> {
-  let dir = args.dir.map(|s| PathBuf::from(&s));
   let prefix = args.prefix.map(String::from);
   let suffix = args.suffix.map(String::from);
-  {
-    let mut state = state.borrow_mut();
-    state.borrow_mut::<PermissionsContainer>().check_write(
-      dir.clone().unwrap_or_else(temp_dir).as_path(),
-      "Deno.makeTempFile()",
-    )?;
-  }
+  let (fs, dir) =
+    make_temp_check(&mut state.borrow_mut(), args.dir, "Deno.makeTempFile()")?;
   tokio::task::spawn_blocking(move || {
     // TODO(piscisaureus): use byte vector for paths, not a string.
     // See https://github.com/denoland/deno/issues/627.
     // We can't assume that paths are always valid utf8 strings.
     let path = make_temp(
+      &*fs,
+      &dir,
       // Converting Option<String> to Option<&str>
-      dir.as_deref(),
       prefix.as_deref(),
       suffix.as_deref(),
       false,
@@ -1919,12 +1489,11 @@ fn op_futime_sync(
   mtime_secs: i64,
   mtime_nanos: u32,
 ) -> Result<(), AnyError> {
-  let atime = filetime::FileTime::from_unix_time(atime_secs, atime_nanos);
-  let mtime = filetime::FileTime::from_unix_time(mtime_secs, mtime_nanos);
+  let atime = FileTime::from_unix_time(atime_secs, atime_nanos);
+  let mtime = FileTime::from_unix_time(mtime_secs, mtime_nanos);
 
-  StdFileResource::with_file(state, rid, |std_file| {
-    filetime::set_file_handle_times(std_file, Some(atime), Some(mtime))
-      .map_err(AnyError::from)
+  StdFileResource::with_fs_file(state, rid, |file| {
+    file.utime(atime, mtime).map_err(AnyError::from)
   })?;
 
   Ok(())
@@ -1939,16 +1508,28 @@ async fn op_futime_async(
   mtime_secs: i64,
   mtime_nanos: u32,
 ) -> Result<(), AnyError> {
-  let atime = filetime::FileTime::from_unix_time(atime_secs, atime_nanos);
-  let mtime = filetime::FileTime::from_unix_time(mtime_secs, mtime_nanos);
+  let atime = FileTime::from_unix_time(atime_secs, atime_nanos);
+  let mtime = FileTime::from_unix_time(mtime_secs, mtime_nanos);
 
-  StdFileResource::with_file_blocking_task(state, rid, move |std_file| {
-    filetime::set_file_handle_times(std_file, Some(atime), Some(mtime))?;
+  StdFileResource::with_fs_file_blocking_task(state, rid, move |file| {
+    file.utime(atime, mtime)?;
     Ok(())
   })
   .await
 }
 
+fn utime(
+  fs: &dyn FileSystem,
+  path: &Path,
+  atime: FileTime,
+  mtime: FileTime,
+) -> Result<(), AnyError> {
+  fs.utime(path, atime, mtime).map_err(|err| {
+    Error::new(err.kind(), format!("{}, utime '{}'", err, path.display()))
+  })?;
+  Ok(())
+}
+
 #[op]
 fn op_utime_sync(
   state: &mut OpState,
@@ -1959,16 +1540,13 @@ fn op_utime_sync(
   mtime_nanos: u32,
 ) -> Result<(), AnyError> {
   let path = PathBuf::from(&path);
-  let atime = filetime::FileTime::from_unix_time(atime_secs, atime_nanos);
-  let mtime = filetime::FileTime::from_unix_time(mtime_secs, mtime_nanos);
+  let atime = FileTime::from_unix_time(atime_secs, atime_nanos);
+  let mtime = FileTime::from_unix_time(mtime_secs, mtime_nanos);
 
   state
     .borrow_mut::<PermissionsContainer>()
     .check_write(&path, "Deno.utime()")?;
-  filetime::set_file_times(&path, atime, mtime).map_err(|err| {
-    Error::new(err.kind(), format!("{}, utime '{}'", err, path.display()))
-  })?;
-  Ok(())
+  utime(&**state.borrow::<FileSystemRc>(), &path, atime, mtime)
 }
 
 #[op]
@@ -1981,27 +1559,25 @@ async fn op_utime_async(
   mtime_nanos: u32,
 ) -> Result<(), AnyError> {
   let path = PathBuf::from(&path);
-  let atime = filetime::FileTime::from_unix_time(atime_secs, atime_nanos);
-  let mtime = filetime::FileTime::from_unix_time(mtime_secs, mtime_nanos);
+  let atime = FileTime::from_unix_time(atime_secs, atime_nanos);
+  let mtime = FileTime::from_unix_time(mtime_secs, mtime_nanos);
 
-  state
-    .borrow_mut()
-    .borrow_mut::<PermissionsContainer>()
-    .check_write(&path, "Deno.utime()")?;
+  let fs = {
+    let mut state = state.borrow_mut();
+    state
+      .borrow_mut::<PermissionsContainer>()
+      .check_write(&path, "Deno.utime()")?;
+    state.borrow::<FileSystemRc>().clone()
+  };
 
-  tokio::task::spawn_blocking(move || {
-    filetime::set_file_times(&path, atime, mtime).map_err(|err| {
-      Error::new(err.kind(), format!("{}, utime '{}'", err, path.display()))
-    })?;
-    Ok(())
-  })
-  .await
-  .unwrap()
+  tokio::task::spawn_blocking(move || utime(&*fs, &path, atime, mtime))
+    .await
+    .unwrap()
 }
 
 #[op]
 fn op_cwd(state: &mut OpState) -> Result<String, AnyError> {
-  let path = current_dir()?;
+  let path = state.borrow::<FileSystemRc>().cwd()?;
   state
     .borrow_mut::<PermissionsContainer>()
     .check_read_blind(&path, "CWD", "Deno.cwd()")?;
@@ -2018,7 +1594,8 @@ fn op_readfile_sync(
   state
     .borrow_mut::<PermissionsContainer>()
     .check_read(path, "Deno.readFileSync()")?;
-  Ok(std::fs::read(path)?.into())
+  let fs = state.borrow::<FileSystemRc>();
+  Ok(fs.read_file(path)?.into())
 }
 
 #[op]
@@ -2030,7 +1607,8 @@ fn op_readfile_text_sync(
   state
     .borrow_mut::<PermissionsContainer>()
     .check_read(path, "Deno.readTextFileSync()")?;
-  Ok(string_from_utf8_lossy(std::fs::read(path)?))
+  let fs = state.borrow::<FileSystemRc>();
+  Ok(string_from_utf8_lossy(fs.read_file(path)?))
 }
 
 #[op]
@@ -2039,16 +1617,17 @@ async fn op_readfile_async(
   path: String,
   cancel_rid: Option<ResourceId>,
 ) -> Result<ZeroCopyBuf, AnyError> {
-  {
+  let fs = {
     let path = Path::new(&path);
     let mut state = state.borrow_mut();
     state
       .borrow_mut::<PermissionsContainer>()
       .check_read(path, "Deno.readFile()")?;
-  }
+    state.borrow::<FileSystemRc>().clone()
+  };
   let fut = tokio::task::spawn_blocking(move || {
     let path = Path::new(&path);
-    Ok(std::fs::read(path).map(ZeroCopyBuf::from)?)
+    Ok(fs.read_file(path).map(ZeroCopyBuf::from)?)
   });
   if let Some(cancel_rid) = cancel_rid {
     let cancel_handle = state
@@ -2068,16 +1647,17 @@ async fn op_readfile_text_async(
   path: String,
   cancel_rid: Option<ResourceId>,
 ) -> Result<String, AnyError> {
-  {
+  let fs = {
     let path = Path::new(&path);
     let mut state = state.borrow_mut();
     state
       .borrow_mut::<PermissionsContainer>()
       .check_read(path, "Deno.readTextFile()")?;
-  }
+    state.borrow::<FileSystemRc>().clone()
+  };
   let fut = tokio::task::spawn_blocking(move || {
     let path = Path::new(&path);
-    Ok(string_from_utf8_lossy(std::fs::read(path)?))
+    Ok(string_from_utf8_lossy(fs.read_file(path)?))
   });
   if let Some(cancel_rid) = cancel_rid {
     let cancel_handle = state
@@ -1,5 +1,8 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use crate::fs::File;
+
+use deno_core::error::not_supported;
 use deno_core::error::resource_unavailable;
 use deno_core::error::AnyError;
 use deno_core::op;
@@ -131,9 +134,9 @@ pub fn init_stdio(stdio: Stdio) -> Extension {
         match stdio.stdin {
           StdioPipe::Inherit => StdFileResourceInner {
             kind: StdFileResourceKind::Stdin,
-            file: STDIN_HANDLE.try_clone().unwrap(),
+            file: Box::new(STDIN_HANDLE.try_clone().unwrap()),
           },
-          StdioPipe::File(pipe) => StdFileResourceInner::file(pipe),
+          StdioPipe::File(pipe) => StdFileResourceInner::file(Box::new(pipe)),
         },
         "stdin",
       ));
@@ -143,9 +146,9 @@ pub fn init_stdio(stdio: Stdio) -> Extension {
         match stdio.stdout {
           StdioPipe::Inherit => StdFileResourceInner {
             kind: StdFileResourceKind::Stdout,
-            file: STDOUT_HANDLE.try_clone().unwrap(),
+            file: Box::new(STDOUT_HANDLE.try_clone().unwrap()),
           },
-          StdioPipe::File(pipe) => StdFileResourceInner::file(pipe),
+          StdioPipe::File(pipe) => StdFileResourceInner::file(Box::new(pipe)),
         },
         "stdout",
       ));
@@ -155,9 +158,9 @@ pub fn init_stdio(stdio: Stdio) -> Extension {
         match stdio.stderr {
           StdioPipe::Inherit => StdFileResourceInner {
             kind: StdFileResourceKind::Stderr,
-            file: STDERR_HANDLE.try_clone().unwrap(),
+            file: Box::new(STDERR_HANDLE.try_clone().unwrap()),
           },
-          StdioPipe::File(pipe) => StdFileResourceInner::file(pipe),
+          StdioPipe::File(pipe) => StdFileResourceInner::file(Box::new(pipe)),
         },
         "stderr",
       ));
@@ -319,19 +322,30 @@ enum StdFileResourceKind {
 
 struct StdFileResourceInner {
   kind: StdFileResourceKind,
-  file: StdFile,
+  file: Box<dyn File>,
 }
 
 impl StdFileResourceInner {
-  pub fn file(fs_file: StdFile) -> Self {
+  pub fn file(fs_file: Box<dyn File>) -> Self {
     StdFileResourceInner {
       kind: StdFileResourceKind::File,
       file: fs_file,
     }
   }
 
-  pub fn with_file<R>(&mut self, f: impl FnOnce(&mut StdFile) -> R) -> R {
-    f(&mut self.file)
+  pub fn with_file<R>(
+    &mut self,
+    f: impl FnOnce(&mut StdFile) -> Result<R, AnyError>,
+  ) -> Result<R, AnyError> {
+    // Files from a virtual file system have no OS file backing them.
+    match self.file.as_std_file() {
+      Some(std_file) => f(std_file),
+      None => Err(not_supported()),
+    }
+  }
+
+  pub fn with_fs_file<R>(&mut self, f: impl FnOnce(&mut dyn File) -> R) -> R {
+    f(self.file.as_mut())
   }
 
   pub fn try_clone(&self) -> Result<Self, std::io::Error> {
@@ -452,7 +466,7 @@ impl StdFileResource {
     }
   }
 
-  pub fn fs_file(fs_file: StdFile) -> Self {
+  pub fn fs_file(fs_file: Box<dyn File>) -> Self {
     Self {
       cell: RefCell::new(Some(StdFileResourceCellValue {
         inner: StdFileResourceInner::file(fs_file),
@@ -586,6 +600,39 @@ impl StdFileResource {
     })
   }
 
+  /// Like [`StdFileResource::with_file`], but also works for files that are
+  /// not backed by an OS file.
+  pub fn with_fs_file<F, R>(
+    state: &mut OpState,
+    rid: ResourceId,
+    f: F,
+  ) -> Result<R, AnyError>
+  where
+    F: FnOnce(&mut dyn File) -> Result<R, AnyError>,
+  {
+    Self::with_resource(state, rid, move |resource| {
+      resource.with_inner_and_metadata(move |inner, _| inner.with_fs_file(f))
+    })
+  }
+
+  pub async fn with_fs_file_blocking_task<F, R: Send + 'static>(
+    state: Rc<RefCell<OpState>>,
+    rid: ResourceId,
+    f: F,
+  ) -> Result<R, AnyError>
+  where
+    F: (FnOnce(&mut dyn File) -> Result<R, AnyError>) + Send + 'static,
+  {
+    let resource = state
+      .borrow_mut()
+      .resource_table
+      .get::<StdFileResource>(rid)?;
+
+    resource
+      .with_inner_blocking_task(move |inner| inner.with_fs_file(f))
+      .await
+  }
+
   pub async fn with_file_blocking_task<F, R: Send + 'static>(
     state: Rc<RefCell<OpState>>,
     rid: ResourceId,
@@ -619,10 +666,10 @@ impl StdFileResource {
   ) -> Result<std::process::Stdio, AnyError> {
     Self::with_resource(state, rid, |resource| {
       resource.with_inner_and_metadata(|inner, _| match inner.kind {
-        StdFileResourceKind::File => {
-          let file = inner.file.try_clone()?;
+        StdFileResourceKind::File => inner.with_file(|file| {
+          let file = file.try_clone()?;
           Ok(file.into())
-        }
+        }),
         _ => Ok(std::process::Stdio::inherit()),
       })
     })
@@ -661,7 +708,7 @@ impl Resource for StdFileResource {
     use std::os::unix::io::AsRawFd;
     self
       .with_inner_and_metadata(move |std_file, _| {
-        Ok(std_file.with_file(|f| f.as_raw_fd()))
+        std_file.with_file(|f| Ok(f.as_raw_fd()))
       })
       .ok()
   }
@@ -156,6 +156,7 @@ fn op_set_exit_code(state: &mut OpState, code: i32) {
 #[op]
 fn op_exit(state: &mut OpState) {
   let code = state.borrow::<ExitCode>().get();
+  crate::permissions::print_learned_permissions();
   std::process::exit(code)
 }
 
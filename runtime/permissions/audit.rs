@@ -0,0 +1,119 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::Context;
+use deno_core::current_op_trace;
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use once_cell::sync::Lazy;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+static PERMISSION_AUDIT_LOG: Lazy<Mutex<Option<File>>> =
+  Lazy::new(|| Mutex::new(None));
+
+// Checked before the log is locked, so permission checks stay cheap when
+// there is no audit log.
+static PERMISSION_AUDIT_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Writes a JSON record of every permission check, from any worker, to the
+/// file at `path`, one record per line. The file is truncated first. The
+/// workers capture the JS stack trace of op calls while the log is written,
+/// for the location of the code which caused each check.
+pub fn set_permission_audit_log(path: &Path) -> Result<(), AnyError> {
+  let file = File::create(path).with_context(|| {
+    format!(
+      "Failed to create permission audit log \"{}\"",
+      path.display()
+    )
+  })?;
+  *PERMISSION_AUDIT_LOG.lock() = Some(file);
+  PERMISSION_AUDIT_ENABLED.store(true, Ordering::Relaxed);
+  Ok(())
+}
+
+#[inline(always)]
+pub(crate) fn is_audit_enabled() -> bool {
+  PERMISSION_AUDIT_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Logs a permission check. The descriptor is the resolved path, host, etc.
+/// which was checked, or `None` for checks of a whole permission.
+pub(crate) fn audit_permission_check(
+  name: &str,
+  api_name: Option<&str>,
+  descriptor: Option<String>,
+  granted: bool,
+  prompted: bool,
+) {
+  let time = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_millis())
+    .unwrap_or(0);
+  let record = json!({
+    "time": time,
+    "kind": name,
+    "descriptor": descriptor,
+    "api": api_name,
+    "result": if granted { "granted" } else { "denied" },
+    "prompted": prompted,
+    "location": current_op_trace().as_deref().and_then(call_site_location),
+  });
+  let mut line = serde_json::to_string(&record).unwrap();
+  line.push('\n');
+
+  let mut maybe_file = PERMISSION_AUDIT_LOG.lock();
+  if let Some(file) = maybe_file.as_mut() {
+    // Written unbuffered, so the log is complete even if the process exits
+    // without unwinding.
+    if let Err(err) = file.write_all(line.as_bytes()) {
+      log::warn!("Failed to write permission audit log: {err}");
+    }
+  }
+}
+
+/// Returns the location of the innermost frame of the op call trace that is
+/// not internal code, e.g. `file:///main.js:1:6`.
+fn call_site_location(trace: &str) -> Option<String> {
+  trace
+    .lines()
+    .filter_map(|frame| frame.trim_start().strip_prefix("at "))
+    .map(|frame| match frame.strip_suffix(')') {
+      Some(frame) => frame.rsplit_once(" (").map_or(frame, |(_, loc)| loc),
+      None => frame,
+    })
+    .find(|location| !location.trim_start_matches('[').starts_with("internal:"))
+    .map(ToString::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_call_site_location() {
+    let trace = concat!(
+      "    at readTextFileSync (internal:runtime/js/40_read_file.js:18:14)\n",
+      "    at readConfig (file:///main.js:3:8)\n",
+      "    at file:///main.js:6:1",
+    );
+    assert_eq!(
+      call_site_location(trace),
+      Some("file:///main.js:3:8".to_string())
+    );
+    assert_eq!(
+      call_site_location("    at file:///main.js:1:6"),
+      Some("file:///main.js:1:6".to_string())
+    );
+    assert_eq!(
+      call_site_location("    at op (internal:core/01_core.js:10:3)"),
+      None
+    );
+  }
+}
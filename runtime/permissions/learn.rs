@@ -0,0 +1,374 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use super::EnvVarName;
+use super::NetDescriptor;
+use crate::colors;
+use crate::fs_util::resolve_from_cwd;
+use deno_core::anyhow::anyhow;
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use once_cell::sync::Lazy;
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+static LEARNED_PERMISSIONS: Lazy<Mutex<Option<LearnedPermissions>>> =
+  Lazy::new(|| Mutex::new(None));
+
+// The config file that the learned permissions are written into.
+static LEARNED_PERMISSIONS_CONFIG: Lazy<Mutex<Option<PathBuf>>> =
+  Lazy::new(|| Mutex::new(None));
+
+// Checked before the learned permissions are locked, so permission checks
+// stay cheap when not learning.
+static PERMISSIONS_LEARNING: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+  static LEARNING_SUSPENDED: Cell<bool> = Cell::new(false);
+}
+
+/// The descriptors of one permission that were checked.
+#[derive(Debug, Default, Eq, PartialEq)]
+struct LearnedDescriptors<T: Ord> {
+  /// Whether the whole permission was checked, eg. by `Deno.env.toObject()`.
+  all: bool,
+  descriptors: BTreeSet<T>,
+}
+
+impl<T: Ord> LearnedDescriptors<T> {
+  fn insert(&mut self, descriptor: Option<T>) {
+    match descriptor {
+      Some(descriptor) => {
+        self.descriptors.insert(descriptor);
+      }
+      None => self.all = true,
+    }
+  }
+
+  fn to_flag(&self, name: &str, values: Vec<String>) -> Option<String> {
+    if self.all {
+      Some(format!("--allow-{name}"))
+    } else if values.is_empty() {
+      None
+    } else {
+      Some(format!("--allow-{name}={}", values.join(",")))
+    }
+  }
+}
+
+/// Every distinct descriptor that was checked while learning.
+#[derive(Debug, Default, Eq, PartialEq)]
+struct LearnedPermissions {
+  read: LearnedDescriptors<PathBuf>,
+  write: LearnedDescriptors<PathBuf>,
+  net: LearnedDescriptors<String>,
+  env: LearnedDescriptors<String>,
+  sys: LearnedDescriptors<String>,
+  run: LearnedDescriptors<String>,
+  ffi: LearnedDescriptors<PathBuf>,
+  hrtime: bool,
+}
+
+impl LearnedPermissions {
+  /// The tightest `--allow-*` flags that grant every learned descriptor.
+  /// Paths are collapsed to common directories.
+  fn to_flags(&self) -> Vec<String> {
+    let paths = |descriptors: &LearnedDescriptors<PathBuf>| {
+      collapse_paths(&descriptors.descriptors)
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+    };
+    let strings = |descriptors: &LearnedDescriptors<String>| {
+      descriptors.descriptors.iter().cloned().collect::<Vec<_>>()
+    };
+    let mut flags = vec![
+      self.read.to_flag("read", paths(&self.read)),
+      self.write.to_flag("write", paths(&self.write)),
+      self.net.to_flag("net", strings(&self.net)),
+      self.env.to_flag("env", strings(&self.env)),
+      self.sys.to_flag("sys", strings(&self.sys)),
+      self.run.to_flag("run", strings(&self.run)),
+      self.ffi.to_flag("ffi", paths(&self.ffi)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
+    if self.hrtime {
+      flags.push("--allow-hrtime".to_string());
+    }
+    flags
+  }
+}
+
+/// Starts recording the descriptors of every permission check, from any
+/// worker. Permissions should be granted to everything while learning.
+///
+/// When `config_path` is given, the learned permissions are also written into
+/// the `"permissions"` field of that config file.
+pub fn start_learning_permissions(config_path: Option<PathBuf>) {
+  *LEARNED_PERMISSIONS.lock() = Some(LearnedPermissions::default());
+  *LEARNED_PERMISSIONS_CONFIG.lock() = config_path;
+  PERMISSIONS_LEARNING.store(true, Ordering::Relaxed);
+}
+
+/// Stops learning and prints the learned permissions as `--allow-*` flags.
+/// Does nothing when not learning, so it is safe to call more than once.
+pub fn print_learned_permissions() {
+  PERMISSIONS_LEARNING.store(false, Ordering::Relaxed);
+  let learned = match LEARNED_PERMISSIONS.lock().take() {
+    Some(learned) => learned,
+    None => return,
+  };
+  let flags = learned.to_flags();
+  if flags.is_empty() {
+    eprintln!("{} none", colors::green("Learned permissions:"));
+  } else {
+    eprintln!(
+      "{} {}",
+      colors::green("Learned permissions:"),
+      flags.join(" ")
+    );
+  }
+  if let Some(config_path) = LEARNED_PERMISSIONS_CONFIG.lock().take() {
+    if let Err(err) = write_learned_permissions(&config_path, &flags) {
+      eprintln!(
+        "{}: Failed to write learned permissions to {}: {}",
+        colors::red_bold("error"),
+        config_path.display(),
+        err
+      );
+    }
+  }
+}
+
+fn write_learned_permissions(
+  config_path: &Path,
+  flags: &[String],
+) -> Result<(), AnyError> {
+  let text = match std::fs::read_to_string(config_path) {
+    Ok(text) => Some(text),
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+    Err(err) => return Err(err.into()),
+  };
+  let text = set_config_permissions(text.as_deref(), flags)?;
+  std::fs::write(config_path, text)?;
+  Ok(())
+}
+
+/// Sets the `"permissions"` field of the config file `text` to `flags`.
+fn set_config_permissions(
+  text: Option<&str>,
+  flags: &[String],
+) -> Result<String, AnyError> {
+  let mut config = match text {
+    Some(text) => jsonc_parser::parse_to_serde_value(text, &Default::default())
+      .map_err(|err| anyhow!("Unable to parse the config file: {}", err))?
+      .unwrap_or_else(|| json!({})),
+    None => json!({}),
+  };
+  let object = match config.as_object_mut() {
+    Some(object) => object,
+    None => bail!("the config file should be a JSON object"),
+  };
+  object.insert("permissions".to_string(), json!(flags));
+  let mut text = serde_json::to_string_pretty(&config)?;
+  text.push('\n');
+  Ok(text)
+}
+
+/// Runs `f` without recording its permission checks. Used for module loads,
+/// which are checked against the internal allow-all root permissions as well
+/// as the program's own.
+pub(crate) fn without_learning<R>(f: impl FnOnce() -> R) -> R {
+  let suspended = LEARNING_SUSPENDED.with(|s| s.replace(true));
+  let result = f();
+  LEARNING_SUSPENDED.with(|s| s.set(suspended));
+  result
+}
+
+#[inline(always)]
+fn is_learning() -> bool {
+  PERMISSIONS_LEARNING.load(Ordering::Relaxed)
+    && !LEARNING_SUSPENDED.with(|s| s.get())
+}
+
+fn learn(f: impl FnOnce(&mut LearnedPermissions)) {
+  if let Some(learned) = LEARNED_PERMISSIONS.lock().as_mut() {
+    f(learned);
+  }
+}
+
+fn resolve_path(path: &Path) -> PathBuf {
+  resolve_from_cwd(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+// The `learn_*` functions record a checked descriptor, or a check of the
+// whole permission for `None`.
+
+#[inline]
+pub(crate) fn learn_read(path: Option<&Path>) {
+  if is_learning() {
+    let path = path.map(resolve_path);
+    learn(|learned| learned.read.insert(path));
+  }
+}
+
+#[inline]
+pub(crate) fn learn_write(path: Option<&Path>) {
+  if is_learning() {
+    let path = path.map(resolve_path);
+    learn(|learned| learned.write.insert(path));
+  }
+}
+
+#[inline]
+pub(crate) fn learn_net(host: Option<&NetDescriptor>) {
+  if is_learning() {
+    let host = host.map(ToString::to_string);
+    learn(|learned| learned.net.insert(host));
+  }
+}
+
+#[inline]
+pub(crate) fn learn_env(env: Option<&str>) {
+  if is_learning() {
+    let env = env.map(|env| EnvVarName::new(env).as_ref().to_string());
+    learn(|learned| learned.env.insert(env));
+  }
+}
+
+#[inline]
+pub(crate) fn learn_sys(kind: Option<&str>) {
+  if is_learning() {
+    let kind = kind.map(ToString::to_string);
+    learn(|learned| learned.sys.insert(kind));
+  }
+}
+
+#[inline]
+pub(crate) fn learn_run(cmd: Option<&str>) {
+  if is_learning() {
+    let cmd = cmd.map(ToString::to_string);
+    learn(|learned| learned.run.insert(cmd));
+  }
+}
+
+#[inline]
+pub(crate) fn learn_ffi(path: Option<&Path>) {
+  if is_learning() {
+    let path = path.map(resolve_path);
+    learn(|learned| learned.ffi.insert(path));
+  }
+}
+
+#[inline]
+pub(crate) fn learn_hrtime() {
+  if is_learning() {
+    learn(|learned| learned.hrtime = true);
+  }
+}
+
+/// Replaces paths that share a parent directory with that directory, then
+/// drops paths that are inside another path of the list.
+fn collapse_paths(paths: &BTreeSet<PathBuf>) -> Vec<PathBuf> {
+  let mut by_parent = BTreeMap::<Option<&Path>, Vec<&PathBuf>>::new();
+  for path in paths {
+    by_parent.entry(path.parent()).or_default().push(path);
+  }
+  let mut collapsed = BTreeSet::new();
+  for (parent, children) in by_parent {
+    match parent {
+      Some(parent) if children.len() > 1 => {
+        collapsed.insert(parent.to_path_buf());
+      }
+      _ => collapsed.extend(children.into_iter().cloned()),
+    }
+  }
+  collapsed
+    .iter()
+    .filter(|path| {
+      !collapsed
+        .iter()
+        .any(|other| other != *path && path.starts_with(other))
+    })
+    .cloned()
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_collapse_paths() {
+    let paths = ["/a/b/c.txt", "/a/b/d.txt", "/a/b/e/f.txt", "/g/h.txt"]
+      .iter()
+      .map(PathBuf::from)
+      .collect();
+    assert_eq!(
+      collapse_paths(&paths),
+      vec![PathBuf::from("/a/b"), PathBuf::from("/g/h.txt")]
+    );
+  }
+
+  #[test]
+  fn test_learned_permissions_to_flags() {
+    let mut learned = LearnedPermissions::default();
+    assert!(learned.to_flags().is_empty());
+
+    learned.read.insert(Some(PathBuf::from("/a/b.txt")));
+    learned.read.insert(Some(PathBuf::from("/a/c.txt")));
+    learned.write.insert(Some(PathBuf::from("/d/e.txt")));
+    learned.net.insert(Some("deno.land:443".to_string()));
+    learned.net.insert(Some("127.0.0.1:4545".to_string()));
+    learned.env.insert(Some("HOME".to_string()));
+    learned.env.insert(None);
+    learned.hrtime = true;
+    assert_eq!(
+      learned.to_flags(),
+      vec![
+        "--allow-read=/a",
+        "--allow-write=/d/e.txt",
+        "--allow-net=127.0.0.1:4545,deno.land:443",
+        "--allow-env",
+        "--allow-hrtime",
+      ]
+    );
+  }
+
+  #[test]
+  fn test_set_config_permissions() {
+    let flags = vec!["--allow-read=/a".to_string(), "--allow-env".to_string()];
+    assert_eq!(
+      set_config_permissions(None, &flags).unwrap(),
+      "{\n  \"permissions\": [\n    \"--allow-read=/a\",\n    \"--allow-env\"\n  ]\n}\n"
+    );
+
+    let text = r#"{
+      // comment
+      "tasks": { "start": "deno run main.ts" },
+      "permissions": ["--allow-net"]
+    }"#;
+    let config: serde_json::Value = serde_json::from_str(
+      &set_config_permissions(Some(text), &flags).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+      config,
+      json!({
+        "tasks": { "start": "deno run main.ts" },
+        "permissions": ["--allow-read=/a", "--allow-env"],
+      })
+    );
+
+    assert!(set_config_permissions(Some("[]"), &flags).is_err());
+  }
+}
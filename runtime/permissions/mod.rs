@@ -26,11 +26,27 @@ use std::str::FromStr;
 use std::string::ToString;
 use std::sync::Arc;
 
+mod audit;
+mod learn;
 mod prompter;
+use audit::audit_permission_check;
+use learn::learn_env;
+use learn::learn_ffi;
+use learn::learn_hrtime;
+use learn::learn_net;
+use learn::learn_read;
+use learn::learn_run;
+use learn::learn_sys;
+use learn::learn_write;
+use learn::without_learning;
 use prompter::permission_prompt;
 use prompter::PromptResponse;
 use prompter::PERMISSION_EMOJI;
 
+pub(crate) use audit::is_audit_enabled;
+pub use audit::set_permission_audit_log;
+pub use learn::print_learned_permissions;
+pub use learn::start_learning_permissions;
 pub use prompter::set_prompt_callbacks;
 pub use prompter::PromptCallback;
 
@@ -83,15 +99,23 @@ impl PermissionState {
   }
 
   /// Check the permission state. bool is whether a prompt was issued.
+  /// `descriptor` is the checked descriptor for the audit log, if any.
   #[inline]
   fn check(
     self,
     name: &str,
     api_name: Option<&str>,
     info: Option<&str>,
+    descriptor: Option<&str>,
     prompt: bool,
   ) -> (Result<(), AnyError>, bool) {
-    self.check2(name, api_name, || info.map(|s| s.to_string()), prompt)
+    self.check2(
+      name,
+      api_name,
+      || info.map(|s| s.to_string()),
+      || descriptor.map(|s| s.to_string()),
+      prompt,
+    )
   }
 
   #[inline]
@@ -100,11 +124,12 @@ impl PermissionState {
     name: &str,
     api_name: Option<&str>,
     info: impl Fn() -> Option<String>,
+    descriptor: impl FnOnce() -> Option<String>,
     prompt: bool,
   ) -> (Result<(), AnyError>, bool) {
-    match self {
+    let (result, prompted) = match self {
       PermissionState::Granted => {
-        Self::log_perm_access(name, info);
+        Self::log_perm_access(name, &info);
         (Ok(()), false)
       }
       PermissionState::Prompt if prompt => {
@@ -114,14 +139,24 @@ impl PermissionState {
           info().map_or(String::new(), |info| { format!(" to {info}") }),
         );
         if PromptResponse::Allow == permission_prompt(&msg, name, api_name) {
-          Self::log_perm_access(name, info);
+          Self::log_perm_access(name, &info);
           (Ok(()), true)
         } else {
-          (Err(Self::error(name, info)), true)
+          (Err(Self::error(name, &info)), true)
         }
       }
-      _ => (Err(Self::error(name, info)), false),
+      _ => (Err(Self::error(name, &info)), false),
+    };
+    if is_audit_enabled() {
+      audit_permission_check(
+        name,
+        api_name,
+        descriptor(),
+        result.is_ok(),
+        prompted,
+      );
     }
+    (result, prompted)
   }
 }
 
@@ -179,8 +214,10 @@ impl UnitPermission {
   }
 
   pub fn check(&mut self) -> Result<(), AnyError> {
+    // `hrtime` is the only unit permission.
+    learn_hrtime();
     let (result, prompted) =
-      self.state.check(self.name, None, None, self.prompt);
+      self.state.check(self.name, None, None, None, self.prompt);
     if prompted {
       if result.is_ok() {
         self.state = PermissionState::Granted;
@@ -421,10 +458,12 @@ impl UnaryPermission<ReadDescriptor> {
     path: &Path,
     api_name: Option<&str>,
   ) -> Result<(), AnyError> {
+    learn_read(Some(path));
     let (result, prompted) = self.query(Some(path)).check2(
       self.name,
       api_name,
       || Some(format!("\"{}\"", path.to_path_buf().display())),
+      || Some(path_descriptor(path)),
       self.prompt,
     );
     if prompted {
@@ -447,11 +486,13 @@ impl UnaryPermission<ReadDescriptor> {
     display: &str,
     api_name: &str,
   ) -> Result<(), AnyError> {
+    learn_read(Some(path));
     let resolved_path = resolve_from_cwd(path)?;
     let (result, prompted) = self.query(Some(&resolved_path)).check(
       self.name,
       Some(api_name),
       Some(&format!("<{display}>")),
+      Some(&resolved_path.display().to_string()),
       self.prompt,
     );
     if prompted {
@@ -466,10 +507,14 @@ impl UnaryPermission<ReadDescriptor> {
   }
 
   pub fn check_all(&mut self, api_name: Option<&str>) -> Result<(), AnyError> {
-    let (result, prompted) =
-      self
-        .query(None)
-        .check(self.name, api_name, Some("all"), self.prompt);
+    learn_read(None);
+    let (result, prompted) = self.query(None).check(
+      self.name,
+      api_name,
+      Some("all"),
+      None,
+      self.prompt,
+    );
     if prompted {
       if result.is_ok() {
         self.global_state = PermissionState::Granted;
@@ -594,10 +639,12 @@ impl UnaryPermission<WriteDescriptor> {
     path: &Path,
     api_name: Option<&str>,
   ) -> Result<(), AnyError> {
+    learn_write(Some(path));
     let (result, prompted) = self.query(Some(path)).check2(
       self.name,
       api_name,
       || Some(format!("\"{}\"", path.to_path_buf().display())),
+      || Some(path_descriptor(path)),
       self.prompt,
     );
     if prompted {
@@ -613,10 +660,14 @@ impl UnaryPermission<WriteDescriptor> {
   }
 
   pub fn check_all(&mut self, api_name: Option<&str>) -> Result<(), AnyError> {
-    let (result, prompted) =
-      self
-        .query(None)
-        .check(self.name, api_name, Some("all"), self.prompt);
+    learn_write(None);
+    let (result, prompted) = self.query(None).check(
+      self.name,
+      api_name,
+      Some("all"),
+      None,
+      self.prompt,
+    );
     if prompted {
       if result.is_ok() {
         self.global_state = PermissionState::Granted;
@@ -756,10 +807,12 @@ impl UnaryPermission<NetDescriptor> {
     api_name: Option<&str>,
   ) -> Result<(), AnyError> {
     let new_host = NetDescriptor::new(&host);
+    learn_net(Some(&new_host));
     let (result, prompted) = self.query(Some(host)).check(
       self.name,
       api_name,
       Some(&format!("\"{new_host}\"")),
+      Some(&new_host.to_string()),
       self.prompt,
     );
     if prompted {
@@ -787,10 +840,13 @@ impl UnaryPermission<NetDescriptor> {
       Some(port) => format!("{hostname}:{port}"),
     };
     let host = &(&hostname, url.port_or_known_default());
+    let descriptor = NetDescriptor::new(&host);
+    learn_net(Some(&descriptor));
     let (result, prompted) = self.query(Some(host)).check(
       self.name,
       api_name,
       Some(&format!("\"{display_host}\"")),
+      Some(&descriptor.to_string()),
       self.prompt,
     );
     if prompted {
@@ -805,10 +861,14 @@ impl UnaryPermission<NetDescriptor> {
   }
 
   pub fn check_all(&mut self) -> Result<(), AnyError> {
-    let (result, prompted) =
-      self
-        .query::<&str>(None)
-        .check(self.name, None, Some("all"), self.prompt);
+    learn_net(None);
+    let (result, prompted) = self.query::<&str>(None).check(
+      self.name,
+      None,
+      Some("all"),
+      None,
+      self.prompt,
+    );
     if prompted {
       if result.is_ok() {
         self.global_state = PermissionState::Granted;
@@ -915,10 +975,12 @@ impl UnaryPermission<EnvDescriptor> {
   }
 
   pub fn check(&mut self, env: &str) -> Result<(), AnyError> {
+    learn_env(Some(env));
     let (result, prompted) = self.query(Some(env)).check(
       self.name,
       None,
       Some(&format!("\"{env}\"")),
+      Some(env),
       self.prompt,
     );
     if prompted {
@@ -933,10 +995,11 @@ impl UnaryPermission<EnvDescriptor> {
   }
 
   pub fn check_all(&mut self) -> Result<(), AnyError> {
+    learn_env(None);
     let (result, prompted) =
       self
         .query(None)
-        .check(self.name, None, Some("all"), self.prompt);
+        .check(self.name, None, Some("all"), None, self.prompt);
     if prompted {
       if result.is_ok() {
         self.global_state = PermissionState::Granted;
@@ -1041,10 +1104,12 @@ impl UnaryPermission<SysDescriptor> {
     kind: &str,
     api_name: Option<&str>,
   ) -> Result<(), AnyError> {
+    learn_sys(Some(kind));
     let (result, prompted) = self.query(Some(kind)).check(
       self.name,
       api_name,
       Some(&format!("\"{kind}\"")),
+      Some(kind),
       self.prompt,
     );
     if prompted {
@@ -1059,10 +1124,11 @@ impl UnaryPermission<SysDescriptor> {
   }
 
   pub fn check_all(&mut self) -> Result<(), AnyError> {
+    learn_sys(None);
     let (result, prompted) =
       self
         .query(None)
-        .check(self.name, None, Some("all"), self.prompt);
+        .check(self.name, None, Some("all"), None, self.prompt);
     if prompted {
       if result.is_ok() {
         self.global_state = PermissionState::Granted;
@@ -1184,10 +1250,12 @@ impl UnaryPermission<RunDescriptor> {
     cmd: &str,
     api_name: Option<&str>,
   ) -> Result<(), AnyError> {
+    learn_run(Some(cmd));
     let (result, prompted) = self.query(Some(cmd)).check(
       self.name,
       api_name,
       Some(&format!("\"{cmd}\"")),
+      Some(cmd),
       self.prompt,
     );
     if prompted {
@@ -1206,10 +1274,14 @@ impl UnaryPermission<RunDescriptor> {
   }
 
   pub fn check_all(&mut self, api_name: Option<&str>) -> Result<(), AnyError> {
-    let (result, prompted) =
-      self
-        .query(None)
-        .check(self.name, api_name, Some("all"), self.prompt);
+    learn_run(None);
+    let (result, prompted) = self.query(None).check(
+      self.name,
+      api_name,
+      Some("all"),
+      None,
+      self.prompt,
+    );
     if prompted {
       if result.is_ok() {
         self.global_state = PermissionState::Granted;
@@ -1326,12 +1398,14 @@ impl UnaryPermission<FfiDescriptor> {
   }
 
   pub fn check(&mut self, path: Option<&Path>) -> Result<(), AnyError> {
+    learn_ffi(path);
     if let Some(path) = path {
       let (resolved_path, display_path) = resolved_and_display_path(path);
       let (result, prompted) = self.query(Some(&resolved_path)).check(
         self.name,
         None,
         Some(&format!("\"{}\"", display_path.display())),
+        Some(&resolved_path.display().to_string()),
         self.prompt,
       );
 
@@ -1347,7 +1421,9 @@ impl UnaryPermission<FfiDescriptor> {
       result
     } else {
       let (result, prompted) =
-        self.query(None).check(self.name, None, None, self.prompt);
+        self
+          .query(None)
+          .check(self.name, None, None, None, self.prompt);
 
       if prompted {
         if result.is_ok() {
@@ -1362,10 +1438,11 @@ impl UnaryPermission<FfiDescriptor> {
   }
 
   pub fn check_all(&mut self) -> Result<(), AnyError> {
+    learn_ffi(None);
     let (result, prompted) =
       self
         .query(None)
-        .check(self.name, None, Some("all"), self.prompt);
+        .check(self.name, None, Some("all"), None, self.prompt);
     if prompted {
       if result.is_ok() {
         self.global_state = PermissionState::Granted;
@@ -1604,14 +1681,16 @@ impl Permissions {
   ) -> Result<(), AnyError> {
     match specifier.scheme() {
       "file" => match specifier.to_file_path() {
-        Ok(path) => self.read.check(&path, Some("import()")),
+        Ok(path) => {
+          without_learning(|| self.read.check(&path, Some("import()")))
+        }
         Err(_) => Err(uri_error(format!(
           "Invalid file path.\n  Specifier: {specifier}"
         ))),
       },
       "data" => Ok(()),
       "blob" => Ok(()),
-      _ => self.net.check_url(specifier, Some("import()")),
+      _ => without_learning(|| self.net.check_url(specifier, Some("import()"))),
     }
   }
 }
@@ -1732,6 +1811,26 @@ impl deno_node::NodePermissions for PermissionsContainer {
   }
 }
 
+impl deno_kv::KvPermissions for PermissionsContainer {
+  #[inline(always)]
+  fn check_read(
+    &mut self,
+    path: &Path,
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    self.0.lock().read.check(path, Some(api_name))
+  }
+
+  #[inline(always)]
+  fn check_write(
+    &mut self,
+    path: &Path,
+    api_name: &str,
+  ) -> Result<(), AnyError> {
+    self.0.lock().write.check(path, Some(api_name))
+  }
+}
+
 impl deno_net::NetPermissions for PermissionsContainer {
   #[inline(always)]
   fn check_net<T: AsRef<str>>(
@@ -1903,6 +2002,15 @@ pub fn resolve_ffi_allowlist(
 /// Arbitrary helper. Resolves the path from CWD, and also gets a path that
 /// can be displayed without leaking the CWD when not allowed.
 #[inline]
+/// Returns the path resolved against the current directory, as logged in the
+/// permission audit log.
+fn path_descriptor(path: &Path) -> String {
+  match resolve_from_cwd(path) {
+    Ok(resolved_path) => resolved_path.display().to_string(),
+    Err(_) => path.display().to_string(),
+  }
+}
+
 fn resolved_and_display_path(path: &Path) -> (PathBuf, PathBuf) {
   let resolved_path = resolve_from_cwd(path).unwrap();
   let display_path = path.to_path_buf();
@@ -1,9 +1,12 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 use crate::colors;
+use crate::fs::FileSystemFetchHandler;
+use crate::fs::FileSystemRc;
 use crate::inspector_server::InspectorServer;
 use crate::js;
 use crate::ops;
 use crate::ops::io::Stdio;
+use crate::permissions::is_audit_enabled;
 use crate::permissions::PermissionsContainer;
 use crate::tokio_util::run_local;
 use crate::worker::FormatJsErrorFn;
@@ -344,6 +347,8 @@ pub struct WebWorkerOptions {
   pub compiled_wasm_module_store: Option<CompiledWasmModuleStore>,
   pub cache_storage_dir: Option<std::path::PathBuf>,
   pub stdio: Stdio,
+  /// The file system used by the `Deno.*` file system APIs.
+  pub fs: FileSystemRc,
 }
 
 impl WebWorker {
@@ -399,7 +404,7 @@ impl WebWorker {
         unsafely_ignore_certificate_errors: options
           .unsafely_ignore_certificate_errors
           .clone(),
-        file_fetch_handler: Rc::new(deno_fetch::FsFetchHandler),
+        file_fetch_handler: Rc::new(FileSystemFetchHandler),
         ..Default::default()
       }),
       deno_cache::init::<SqliteBackedCache>(create_cache),
@@ -409,6 +414,7 @@ impl WebWorker {
         options.unsafely_ignore_certificate_errors.clone(),
       ),
       deno_webstorage::init(None).disable(),
+      deno_kv::init::<PermissionsContainer>(None, unstable),
       deno_broadcast_channel::init(options.broadcast_channel.clone(), unstable),
       deno_crypto::init(options.seed),
       deno_webgpu::init(unstable),
@@ -425,7 +431,7 @@ impl WebWorker {
       ),
       // Extensions providing Deno.* features
       ops::fs_events::init(),
-      ops::fs::init(),
+      ops::fs::init(options.fs.clone()),
       ops::io::init(),
       ops::io::init_stdio(options.stdio),
       deno_tls::init(),
@@ -468,6 +474,11 @@ impl WebWorker {
       ..Default::default()
     });
 
+    // the permission audit log records where each check was made
+    if is_audit_enabled() {
+      js_runtime.enable_op_tracing();
+    }
+
     if let Some(server) = options.maybe_inspector_server.clone() {
       server.register_inspector(
         main_module.to_string(),
@@ -34,10 +34,14 @@ use deno_tls::rustls::RootCertStore;
 use deno_web::BlobStore;
 use log::debug;
 
+use crate::fs::FileSystemFetchHandler;
+use crate::fs::FileSystemRc;
+use crate::fs::RealFs;
 use crate::inspector_server::InspectorServer;
 use crate::js;
 use crate::ops;
 use crate::ops::io::Stdio;
+use crate::permissions::is_audit_enabled;
 use crate::permissions::PermissionsContainer;
 use crate::BootstrapOptions;
 
@@ -142,6 +146,8 @@ pub struct WorkerOptions {
   /// `WebAssembly.Module` objects cannot be serialized.
   pub compiled_wasm_module_store: Option<CompiledWasmModuleStore>,
   pub stdio: Stdio,
+  /// The file system used by the `Deno.*` file system APIs.
+  pub fs: FileSystemRc,
 }
 
 impl Default for WorkerOptions {
@@ -178,6 +184,7 @@ impl Default for WorkerOptions {
       startup_snapshot: Default::default(),
       bootstrap: Default::default(),
       stdio: Default::default(),
+      fs: Arc::new(RealFs),
     }
   }
 }
@@ -232,7 +239,7 @@ impl MainWorker {
         unsafely_ignore_certificate_errors: options
           .unsafely_ignore_certificate_errors
           .clone(),
-        file_fetch_handler: Rc::new(deno_fetch::FsFetchHandler),
+        file_fetch_handler: Rc::new(FileSystemFetchHandler),
         ..Default::default()
       }),
       deno_cache::init::<SqliteBackedCache>(create_cache),
@@ -242,6 +249,10 @@ impl MainWorker {
         options.unsafely_ignore_certificate_errors.clone(),
       ),
       deno_webstorage::init(options.origin_storage_dir.clone()),
+      deno_kv::init::<PermissionsContainer>(
+        options.origin_storage_dir.clone(),
+        unstable,
+      ),
       deno_broadcast_channel::init(options.broadcast_channel.clone(), unstable),
       deno_crypto::init(options.seed),
       deno_webgpu::init(unstable),
@@ -257,7 +268,7 @@ impl MainWorker {
       ),
       ops::spawn::init(),
       ops::fs_events::init(),
-      ops::fs::init(),
+      ops::fs::init(options.fs.clone()),
       ops::io::init(),
       ops::io::init_stdio(options.stdio),
       deno_tls::init(),
@@ -299,6 +310,11 @@ impl MainWorker {
       ..Default::default()
     });
 
+    // the permission audit log records where each check was made
+    if is_audit_enabled() {
+      js_runtime.enable_op_tracing();
+    }
+
     if let Some(server) = options.maybe_inspector_server.clone() {
       server.register_inspector(
         main_module.to_string(),